@@ -0,0 +1,118 @@
+//! Copy pipeline benchmarks on generated temp data.
+//!
+//! The data set can be tuned with environment variables:
+//! - `LIBRECARD_BENCH_FILES`: number of files in the `copy_dirs` source tree (default 16)
+//! - `LIBRECARD_BENCH_FILE_MIB`: size of each file in MiB (default 8)
+//! - `LIBRECARD_BENCH_DESTINATIONS`: benchmark 1..=N destinations (default 3)
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use librecard_core::backend::{
+    CopyOptions, JobHandle, Progress, SourcePlan, SpaceHandling, copy_dirs, read_file_copy_batch,
+};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+use tokio::sync::watch;
+
+fn env_or(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Writes `size` bytes of incompressible pseudo-random data, so filesystem compression and
+/// deduplication don't skew the results.
+fn write_file(path: &Path, size: usize, seed: u64) {
+    let mut state = seed | 1;
+    let data: Vec<u8> = (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect();
+    std::fs::write(path, data).unwrap();
+}
+
+fn create_destinations(count: usize) -> (Vec<TempDir>, Vec<PathBuf>) {
+    let dirs: Vec<TempDir> = (0..count).map(|_| TempDir::new().unwrap()).collect();
+    let paths = dirs.iter().map(|d| d.path().to_path_buf()).collect();
+    (dirs, paths)
+}
+
+fn bench_read_file_copy_batch(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let file_size = env_or("LIBRECARD_BENCH_FILE_MIB", 8) * 1024 * 1024;
+    let max_destinations = env_or("LIBRECARD_BENCH_DESTINATIONS", 3);
+
+    let source = TempDir::new().unwrap();
+    let source_file = source.path().join("clip.bin");
+    write_file(&source_file, file_size, 42);
+
+    let mut group = c.benchmark_group("read_file_copy_batch");
+    group.throughput(Throughput::Bytes(file_size as u64));
+    for destination_count in 1..=max_destinations {
+        let (_dirs, destinations) = create_destinations(destination_count);
+        let dest_paths: Vec<PathBuf> = destinations.iter().map(|d| d.join("clip.bin")).collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("destinations", destination_count),
+            &dest_paths,
+            |b, dest_paths| {
+                b.to_async(&runtime)
+                    .iter(|| read_file_copy_batch(&source_file, dest_paths.clone(), None));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_copy_dirs(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let file_count = env_or("LIBRECARD_BENCH_FILES", 16);
+    let file_size = env_or("LIBRECARD_BENCH_FILE_MIB", 8) * 1024 * 1024;
+    let max_destinations = env_or("LIBRECARD_BENCH_DESTINATIONS", 3);
+
+    let source = TempDir::new().unwrap();
+    for i in 0..file_count {
+        let dir = source.path().join(format!("DCIM/{:03}", i % 4));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_file(&dir.join(format!("C{:04}.MP4", i)), file_size, i as u64);
+    }
+
+    let mut group = c.benchmark_group("copy_dirs");
+    group.throughput(Throughput::Bytes((file_count * file_size) as u64));
+    group.sample_size(10);
+    for destination_count in 1..=max_destinations {
+        let (_dirs, destinations) = create_destinations(destination_count);
+
+        group.bench_with_input(
+            BenchmarkId::new("destinations", destination_count),
+            &destinations,
+            |b, destinations| {
+                b.to_async(&runtime).iter(|| async {
+                    // Keep the receiver alive for the whole copy
+                    let (tx, _rx) = watch::channel(Progress::default());
+                    let (warnings, _) = watch::channel(Vec::new());
+                    copy_dirs(
+                        source.path(),
+                        destinations,
+                        &[],
+                        SourcePlan::default(),
+                        SpaceHandling::default(),
+                        CopyOptions::default(),
+                        JobHandle::new(tx, warnings),
+                    )
+                    .await
+                    .unwrap()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_read_file_copy_batch, bench_copy_dirs);
+criterion_main!(benches);
@@ -1,290 +1,10022 @@
-﻿use csv::Writer;
+use csv::Writer;
+use futures::StreamExt;
 use futures::future::join_all;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest as _, Sha256};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt;
 use std::hash::Hasher;
-use std::path::{Path, PathBuf};
+use std::io::Write;
+use std::ops::{ControlFlow, Range};
+use std::path::{Component, Path, PathBuf, Prefix};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use sysinfo::Disks;
 use tokio::fs::File;
-use tokio::io::{self, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::watch;
+use tokio::io::{self, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio::{join, spawn};
-use twox_hash::XxHash3_64;
+use tokio_util::sync::CancellationToken;
+use twox_hash::{XxHash3_64, XxHash64};
+use unicode_normalization::{UnicodeNormalization, is_nfc};
 
 pub type SizeResult = io::Result<u64>;
 
+/// Error used internally to unwind a walk or hash that is no longer wanted.
+fn stopped_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "Stopped before completion")
+}
+
 fn collect_results<T, E>(vec: Vec<Result<T, E>>) -> Result<Vec<T>, E> {
     vec.into_iter().collect()
 }
 
+/// The form of `path` to hand to the OS. On Windows, an absolute path becomes an extended-length
+/// path (`\\?\C:\...` or `\\?\UNC\server\share\...`), which isn't limited to 260 characters;
+/// camera folder trees under a long destination easily exceed that. Paths are only converted
+/// when they are opened, so every path the backend hands around, and every `strip_prefix` on
+/// them, keeps the form the user gave. Elsewhere the path is returned as is.
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    {
+        use std::ffi::OsString;
+        use std::path::Prefix;
+
+        // Built as a string, since joining a path with a drive or share onto one replaces it
+        let mut components = path.components();
+        let mut long = OsString::from(r"\\?\");
+        match components.next() {
+            Some(Component::Prefix(prefix)) if path.has_root() => match prefix.kind() {
+                Prefix::Disk(_) => long.push(prefix.as_os_str()),
+                Prefix::UNC(server, share) => {
+                    long.push(r"UNC\");
+                    long.push(server);
+                    long.push("\\");
+                    long.push(share);
+                }
+                // Already verbatim, or a device path
+                _ => return Cow::Borrowed(path),
+            },
+            _ => return Cow::Borrowed(path),
+        }
+        long.push("\\");
+
+        // Verbatim paths skip normalization, so `/`, `.` and `..` must be resolved here
+        let mut long = PathBuf::from(long);
+        for component in components {
+            match component {
+                Component::ParentDir => {
+                    long.pop();
+                }
+                Component::Normal(name) => long.push(name),
+                _ => {}
+            }
+        }
+        Cow::Owned(long)
+    }
+    #[cfg(not(windows))]
+    Cow::Borrowed(path)
+}
+
+/// `path` with its names in Unicode NFC. macOS stores names decomposed (NFD) and most other
+/// systems keep whatever form they are given, so the same name can reach a destination as
+/// different bytes. Paths are compared and reported in this form; I/O uses the name on disk.
+pub fn nfc_path(path: &Path) -> Cow<'_, Path> {
+    match path.to_str() {
+        Some(name) if !is_nfc(name) => Cow::Owned(PathBuf::from(name.nfc().collect::<String>())),
+        // Names that aren't Unicode can't be normalized
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Finds `path` on disk when its names are stored in another Unicode normalization than the one
+/// asked for. Reads each directory on the way down, so this is only worth it once a plain lookup
+/// has failed.
+fn find_by_nfc(path: &Path) -> Option<PathBuf> {
+    let name = nfc_path(Path::new(path.file_name()?)).into_owned();
+    let parent = path.parent()?;
+    let parent = match std::fs::metadata(long_path(parent)) {
+        Ok(_) => parent.to_path_buf(),
+        Err(_) => find_by_nfc(parent)?,
+    };
+    std::fs::read_dir(long_path(&parent))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.file_name())
+        .find(|found| nfc_path(Path::new(found)) == name.as_path())
+        .map(|found| parent.join(found))
+}
+
 pub fn flatten_dir_files_recur(base_dir: &Path, dir: &Path) -> io::Result<Vec<PathBuf>> {
     let mut files = Vec::new();
-    for entry in std::fs::read_dir(dir)? {
+    flatten_dir_files_below(base_dir, dir, 0, None, &mut files, &mut Vec::new())?;
+    Ok(files)
+}
+
+/// Adds the files under `dir`, which is `depth` levels below `base_dir`, to `files`. Folders more
+/// than `max_depth` levels down are added to `pruned` instead of entered.
+fn flatten_dir_files_below(
+    base_dir: &Path,
+    dir: &Path,
+    depth: usize,
+    max_depth: Option<usize>,
+    files: &mut Vec<PathBuf>,
+    pruned: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    for entry in std::fs::read_dir(long_path(dir))? {
         let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() {
-            files.extend(flatten_dir_files_recur(base_dir, &path)?);
-        } else {
-            let relative_path = path.strip_prefix(base_dir).unwrap().to_path_buf();
-            files.push(relative_path);
+        // Joined onto `dir` rather than taken from the entry, which has the extended form
+        let path = dir.join(entry.file_name());
+        let relative_path = || path.strip_prefix(base_dir).unwrap().to_path_buf();
+        if std::fs::metadata(long_path(&path)).is_ok_and(|m| m.is_dir()) {
+            match max_depth.is_some_and(|max| depth >= max) {
+                true => pruned.push(relative_path()),
+                false => {
+                    flatten_dir_files_below(base_dir, &path, depth + 1, max_depth, files, pruned)?
+                }
+            }
+        } else if entry.file_name() != OFFLOAD_MARKER {
+            files.push(relative_path());
         }
     }
-    Ok(files)
+    Ok(())
 }
 
 pub fn flatten_dir_files(base_dir: &Path) -> io::Result<Vec<PathBuf>> {
     flatten_dir_files_recur(base_dir, base_dir)
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Progress {
-    pub total: usize,
-    pub completed: usize,
+/// Like [`flatten_dir_files`], but folders more than `max_depth` levels below `base_dir` aren't
+/// entered. Returns the files and the folders left out, both relative to `base_dir`.
+pub fn flatten_dir_files_within(
+    base_dir: &Path,
+    max_depth: Option<usize>,
+) -> io::Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let (mut files, mut pruned) = (Vec::new(), Vec::new());
+    flatten_dir_files_below(base_dir, base_dir, 0, max_depth, &mut files, &mut pruned)?;
+    Ok((files, pruned))
 }
 
-impl Progress {
-    pub fn mut_increment(&mut self) {
-        self.completed += 1;
-    }
+/// How far a scan of a source goes, so that picking the wrong folder, e.g. a home folder, doesn't
+/// walk millions of files before anyone notices. The default has no bounds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScanLimits {
+    /// Folder levels below the source the scan goes into; 0 only takes the files at its root.
+    /// Deeper folders are left out and reported.
+    pub max_depth: Option<usize>,
+    /// Files the scan stops at, for the user to confirm that a source this large is meant.
+    pub max_files: Option<usize>,
 }
 
-impl Default for Progress {
-    fn default() -> Self {
-        Progress {
-            total: 0,
-            completed: 0,
+/// Default for [`ScanLimits::max_files`] when listing a source that was just picked. Far more
+/// files than a card holds.
+pub const DEFAULT_SCAN_FILE_LIMIT: usize = 200_000;
+
+/// What a scan of a source found, in path order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScanEntry {
+    File(PathBuf, FileStamp),
+    /// A folder, relative to the source, below [`ScanLimits::max_depth`] that wasn't entered.
+    Pruned(PathBuf),
+    /// The scan stopped at [`ScanLimits::max_files`] with files left. Nothing follows.
+    Capped,
+}
+
+/// Size and modification time of a file, to notice it changing while it is copied.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Only a cloud placeholder, whose contents are downloaded when it is read.
+    pub placeholder: bool,
+}
+
+impl FileStamp {
+    fn from_metadata(metadata: &std::fs::Metadata) -> Self {
+        FileStamp {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            placeholder: is_placeholder(metadata),
         }
     }
-}
 
-pub async fn read_file_copy_batch<P: AsRef<Path>>(
-    source_path: P,
-    dest_paths: Vec<PathBuf>,
-) -> SizeResult {
-    // Open the source file
-    let mut source_file = File::open(&source_path).await?;
+    /// Whether the file may have been written since it had the `earlier` stamp. Downloading a
+    /// placeholder isn't a change of its contents.
+    fn changed_since(&self, earlier: &FileStamp) -> bool {
+        self.size != earlier.size || self.modified != earlier.modified
+    }
 
-    // Open all destination files
-    let mut dest_files = Vec::with_capacity(dest_paths.len());
-    for path in dest_paths {
-        dest_files.push(File::create(&path).await?);
+    /// Stamps the file now, or returns `None` if it can't be read.
+    pub async fn of(path: &Path) -> Option<FileStamp> {
+        let metadata = tokio::fs::metadata(long_path(path)).await.ok()?;
+        Some(FileStamp::from_metadata(&metadata))
     }
+}
 
-    let mut total_bytes = 0;
+/// Whether the file is a placeholder of a cloud drive (OneDrive, iCloud Drive, Dropbox) that
+/// isn't stored locally. Other platforms don't mark them, so nothing counts as one there.
+fn is_placeholder(metadata: &std::fs::Metadata) -> bool {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+        const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+        const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+        metadata.file_attributes()
+            & (FILE_ATTRIBUTE_OFFLINE
+                | FILE_ATTRIBUTE_RECALL_ON_OPEN
+                | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+            != 0
+    }
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::macos::fs::MetadataExt;
+        const SF_DATALESS: u32 = 0x4000_0000;
+        metadata.st_flags() & SF_DATALESS != 0
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        let _ = metadata;
+        false
+    }
+}
 
-    // Rotated buffers for concurrent read/write
-    const BUFFER_SIZE: usize = 1024 * 1024; // 1MB
-    let mut buffer1 = vec![0u8; BUFFER_SIZE];
-    let mut buffer2 = vec![0u8; BUFFER_SIZE];
-    let mut read_buffer = &mut buffer1;
-    let mut write_buffer = &mut buffer2;
+/// Walks `base_dir` on a blocking thread within `limits` and streams the relative file paths with
+/// their stamps as they are found, along with the folders the limits left out. The walk ends
+/// after the first error, which is sent as the last item, or once the receiver is dropped.
+pub fn scan_dir_files(
+    base_dir: &Path,
+    limits: ScanLimits,
+) -> mpsc::UnboundedReceiver<io::Result<ScanEntry>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let base_dir = base_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut found = 0;
+        if let Err(e) = scan_dir_files_recur(&base_dir, &base_dir, 0, limits, &mut found, &tx) {
+            // Fails only if the receiver is gone, which has nothing left to report to
+            let _ = tx.send(Err(e));
+        }
+    });
+    rx
+}
 
-    // Read first chunk into write_buffer
-    let mut bytes_read = source_file.read(read_buffer).await?;
-    if bytes_read == 0 {
-        return Ok(0); // Edge case: empty file
+/// Walks `dir`, which is `depth` levels below `base_dir`, counting the files sent in `found`.
+/// Breaks once the file limit is reached.
+fn scan_dir_files_recur(
+    base_dir: &Path,
+    dir: &Path,
+    depth: usize,
+    limits: ScanLimits,
+    found: &mut usize,
+    tx: &mpsc::UnboundedSender<io::Result<ScanEntry>>,
+) -> io::Result<ControlFlow<()>> {
+    // read_dir order depends on the filesystem; sorting makes runs repeatable
+    let mut paths = std::fs::read_dir(long_path(dir))?
+        .map(|entry| entry.map(|entry| dir.join(entry.file_name())))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    for path in paths {
+        let relative_path = || path.strip_prefix(base_dir).unwrap().to_path_buf();
+        let entry = match std::fs::metadata(long_path(&path)) {
+            Ok(metadata) if metadata.is_dir() => {
+                if limits.max_depth.is_some_and(|max| depth >= max) {
+                    ScanEntry::Pruned(relative_path())
+                } else if scan_dir_files_recur(base_dir, &path, depth + 1, limits, found, tx)?
+                    .is_break()
+                {
+                    return Ok(ControlFlow::Break(()));
+                } else {
+                    continue;
+                }
+            }
+            _ if path.file_name() == Some(OFFLOAD_MARKER.as_ref()) => continue,
+            _ if limits.max_files == Some(*found) => {
+                let _ = tx.send(Ok(ScanEntry::Capped));
+                return Ok(ControlFlow::Break(()));
+            }
+            metadata => {
+                // A file we can't stat still gets copied, so the error surfaces there
+                let stamp = metadata.map_or_else(
+                    |_| FileStamp::default(),
+                    |metadata| FileStamp::from_metadata(&metadata),
+                );
+                *found += 1;
+                ScanEntry::File(relative_path(), stamp)
+            }
+        };
+        if tx.send(Ok(entry)).is_err() {
+            return Err(stopped_error());
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Joins a user-supplied sub-root (e.g. a top-level folder added by another tool) onto a
+/// destination root. Only plain components are kept, so `..` or an absolute path can't escape
+/// the root. An archive is written whole, so it takes no sub-root.
+pub fn join_subroot(root: &Path, subroot: &str) -> PathBuf {
+    let mut path = root.to_path_buf();
+    if DestinationKind::of(root).is_archive() {
+        return path;
+    }
+    for component in Path::new(subroot.trim()).components() {
+        if let Component::Normal(part) = component {
+            path.push(part);
+        }
     }
+    path
+}
+
+/// Picks the first `Session NNN` folder name that exists under none of the destinations, so cards
+/// with the same layout (e.g. `DCIM/100CANON`) don't overwrite each other.
+pub fn next_session_folder(dest: &[PathBuf]) -> String {
+    (1..)
+        .map(|n| format!("Session {n:03}"))
+        .find(|name| dest.iter().all(|d| !long_path(&d.join(name)).exists()))
+        .unwrap()
+}
 
+/// Resolves symlinks and `..` so that aliases of one directory compare equal. The destination may
+/// not exist yet, so its deepest existing ancestor is canonicalized and the rest appended.
+pub fn canonicalize_destination(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut missing = Vec::new();
     loop {
-        // Data from read_buffer from the last loop goes to write_buffer, and write_buffer from the last loop
-        // is overwritten
-        std::mem::swap(&mut read_buffer, &mut write_buffer);
+        if let Ok(canonical) = std::fs::canonicalize(existing) {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |path, name| path.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
 
-        let mut write_futures = Vec::with_capacity(dest_files.len());
-        for file in &mut dest_files {
-            write_futures.push(file.write_all(&write_buffer[..bytes_read]));
+/// How a destination holds the files copied to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DestinationKind {
+    /// A folder tree mirroring the source.
+    Folder,
+    /// A single ZIP file. Entries are stored uncompressed, in ZIP64 form where a file needs it.
+    Zip,
+    /// A single tar file, with GNU headers for long paths.
+    Tar,
+}
+
+impl DestinationKind {
+    /// The kind of the destination at `path`, told by its name: `Proxies.zip` and `Proxies.tar`
+    /// are archives, anything else is a folder.
+    pub fn of(path: &Path) -> Self {
+        match path
+            .extension()
+            .map(|extension| extension.to_ascii_lowercase())
+        {
+            Some(extension) if extension == "zip" => DestinationKind::Zip,
+            Some(extension) if extension == "tar" => DestinationKind::Tar,
+            _ => DestinationKind::Folder,
         }
-        let write_futures = join_all(write_futures);
+    }
 
-        let read_future = source_file.read(read_buffer);
+    pub fn is_archive(self) -> bool {
+        self != DestinationKind::Folder
+    }
+}
+
+/// The destinations among `dest` that are folders, in order.
+fn folders(dest: &[PathBuf]) -> Vec<PathBuf> {
+    dest.iter()
+        .filter(|root| !DestinationKind::of(root).is_archive())
+        .cloned()
+        .collect()
+}
 
-        // Execute read and write futures concurrently
-        let (read_result, write_results) = join!(read_future, write_futures);
+/// Whether a destination can be written to, as far as can be told before the copy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reachability {
+    Reachable,
+    /// The folder, or the share or drive it is on, doesn't answer or doesn't exist.
+    Unreachable,
+    /// The share or folder is there but refuses the user.
+    AccessDenied,
+}
 
-        // Check for write errors
-        for result in write_results {
-            result?;
+/// Checks that the deepest existing folder of `path` can be listed. Folders that don't exist yet
+/// are created by the copy, so only the share, drive or folder above them has to be there. Paths
+/// on a share (`\\server\share\...`) are checked the same way, which tells a server that is
+/// down apart from one that refuses the login.
+pub async fn check_reachable(path: &Path) -> Reachability {
+    // An archive is a file, so its folder is what has to be reachable
+    let skip = usize::from(DestinationKind::of(path).is_archive());
+    for dir in path
+        .ancestors()
+        .skip(skip)
+        .filter(|dir| !dir.as_os_str().is_empty())
+    {
+        match tokio::fs::read_dir(long_path(dir)).await {
+            Ok(_) => return Reachability::Reachable,
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                return Reachability::AccessDenied;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(_) => return Reachability::Unreachable,
         }
+    }
+    Reachability::Unreachable
+}
 
-        bytes_read = read_result?; // Might not be BUFFER_SIZE if the upcoming read will hit EOF
-        if bytes_read == 0 {
-            break; // EOF
+/// Checks that the copy can write to `path` by creating and removing a small file in its deepest
+/// existing folder: a folder that lists fine may still be on a read-only volume. Returns the
+/// probe file if it was written but couldn't be removed, as some shares allow creating files but
+/// not deleting them.
+pub fn probe_writable(path: &Path) -> io::Result<Option<PathBuf>> {
+    // An archive is a file, so its folder is what is written to
+    let skip = usize::from(DestinationKind::of(path).is_archive());
+    let dir = path
+        .ancestors()
+        .skip(skip)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .find(|dir| std::fs::metadata(long_path(dir)).is_ok_and(|m| m.is_dir()))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No folder of the path exists"))?;
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos());
+    let probe = dir.join(format!(".librecard-probe-{}-{nanos}", std::process::id()));
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(long_path(&probe))?;
+    let written = file.write_all(b"LibreCard");
+    drop(file);
+    let removed = std::fs::remove_file(long_path(&probe));
+    written?;
+    Ok(removed.err().map(|_| probe))
+}
+
+/// Finds destinations that resolve to the same location as an earlier one, as
+/// `(earlier, duplicate)` index pairs.
+pub fn find_duplicate_destinations(dest: &[PathBuf]) -> Vec<(usize, usize)> {
+    let canonical: Vec<_> = dest.iter().map(|d| canonicalize_destination(d)).collect();
+    (0..canonical.len())
+        .filter_map(|i| {
+            canonical[..i]
+                .iter()
+                .position(|earlier| *earlier == canonical[i])
+                .map(|earlier| (earlier, i))
+        })
+        .collect()
+}
+
+/// Finds destinations that are the source or lie inside it, by index. A copy there would need
+/// room for itself and copy its own output on the next job.
+pub fn find_nested_destinations(source: &Path, dest: &[PathBuf]) -> Vec<usize> {
+    let source = canonicalize_destination(source);
+    dest.iter()
+        .enumerate()
+        .filter(|(_, d)| canonicalize_destination(d).starts_with(&source))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Volume {
+    /// The label the volume was formatted with, e.g. `EOS_DIGITAL`.
+    pub label: String,
+    /// The filesystem's serial number or UUID, e.g. `1234-ABCD` for a FAT card, where the OS
+    /// tells it. Unlike the label, cameras of one model don't give every card the same one.
+    #[serde(default)]
+    pub serial: Option<String>,
+    pub mount_point: PathBuf,
+}
+
+impl Volume {
+    /// Whether this is the volume with `label` and `serial`, either of which may be left empty to
+    /// match any. Labels are compared without regard to case, as Windows does, and serials
+    /// without regard to case or dashes, so `1234abcd` finds `1234-ABCD`. Nothing matches when
+    /// both are empty.
+    pub fn matches(&self, label: &str, serial: &str) -> bool {
+        let (label, serial) = (label.trim(), serial.trim());
+        if label.is_empty() && serial.is_empty() {
+            return false;
         }
-        total_bytes += bytes_read as u64;
+        let serial_digits = |serial: &str| -> String {
+            serial
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .map(|c| c.to_ascii_uppercase())
+                .collect()
+        };
+        (label.is_empty() || self.label.to_lowercase() == label.to_lowercase())
+            && (serial.is_empty()
+                || self
+                    .serial
+                    .as_deref()
+                    .is_some_and(|own| serial_digits(own) == serial_digits(serial)))
     }
+}
 
-    // Flush all destination files
-    for file in &mut dest_files {
-        file.flush().await?;
+/// Filesystem types of network shares, as the OS reports them in lowercase.
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "cifs",
+    "smb",
+    "smb2",
+    "smb3",
+    "smbfs",
+    "nfs",
+    "nfs4",
+    "afpfs",
+    "webdav",
+    "davfs",
+    "fuse.sshfs",
+];
+
+/// Whether `path` is on a network share: a UNC path, or a volume mounted with a network
+/// filesystem. This queries the OS synchronously.
+pub fn is_network_path(path: &Path) -> bool {
+    if let Some(Component::Prefix(prefix)) = path.components().next()
+        && matches!(prefix.kind(), Prefix::UNC(..) | Prefix::VerbatimUNC(..))
+    {
+        return true;
     }
+    Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .is_some_and(|disk| {
+            let filesystem = disk.file_system().to_string_lossy().to_lowercase();
+            NETWORK_FILESYSTEMS.contains(&filesystem.as_str())
+        })
+}
 
-    Ok(total_bytes)
+/// Size and free space of a mounted volume.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VolumeSpace {
+    pub mount_point: PathBuf,
+    pub available: u64,
+    pub total: u64,
 }
 
-pub async fn copy_dirs(
-    source: &PathBuf,
-    dest: &Vec<PathBuf>,
-    tx: watch::Sender<Progress>,
-) -> SizeResult {
-    let files = flatten_dir_files(source)?;
-    let total_files = files.len();
-    let mut progress = Progress {
-        total: total_files,
-        completed: 0,
+impl VolumeSpace {
+    /// How much of the free space may be filled before `margin` is reached.
+    pub fn allowed(&self, margin: Option<SpaceMargin>) -> u64 {
+        let reserved = margin.map_or(0, |margin| margin.reserved(self.total));
+        self.available.saturating_sub(reserved)
+    }
+}
+
+/// Space on the volume `path` is on, if it is a mounted volume the OS lists. This queries the OS
+/// synchronously.
+pub fn volume_space(path: &Path) -> Option<VolumeSpace> {
+    Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| VolumeSpace {
+            mount_point: disk.mount_point().to_path_buf(),
+            available: disk.available_space(),
+            total: disk.total_space(),
+        })
+}
+
+/// Destinations, by index, on the same volume as `source`. Copying there reads and writes one
+/// drive, which is slow on a spinning disk and no protection against losing it. Partitions of
+/// one disk count as different volumes. This queries the OS synchronously.
+pub fn destinations_on_source_volume(source: &Path, dest: &[PathBuf]) -> Vec<usize> {
+    let mount_point =
+        |path: &Path| volume_space(&canonicalize_destination(path)).map(|space| space.mount_point);
+    let Some(source) = mount_point(source) else {
+        return Vec::new();
     };
-    let mut total_bytes = 0;
+    dest.iter()
+        .enumerate()
+        .filter(|(_, d)| mount_point(d).as_ref() == Some(&source))
+        .map(|(i, _)| i)
+        .collect()
+}
 
-    for file in files {
-        let source_path = source.join(&file);
-        let dest_paths: Vec<_> = dest.iter().map(|d| d.join(&file)).collect();
+/// Free space to leave on each destination volume.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpaceMargin {
+    /// A share of the volume's size, e.g. 5 to never fill it beyond 95%.
+    Percent(f64),
+    /// A fixed amount, in GiB.
+    Gib(u64),
+}
 
-        // Create destination directories if they don't exist
-        for dest_path in &dest_paths {
-            if let Some(parent) = dest_path.parent() {
-                tokio::fs::create_dir_all(parent).await?;
+impl SpaceMargin {
+    /// Bytes the margin keeps free on a volume of `total` bytes.
+    pub fn reserved(self, total: u64) -> u64 {
+        match self {
+            SpaceMargin::Percent(percent) => {
+                (total as f64 * percent.clamp(0.0, 100.0) / 100.0) as u64
             }
+            SpaceMargin::Gib(gib) => gib.saturating_mul(1024 * 1024 * 1024),
         }
+    }
+}
 
-        total_bytes += read_file_copy_batch(&source_path, dest_paths).await?;
+/// How a job is expected to fit on a destination.
+#[derive(Clone, Debug)]
+pub struct SpaceForecast {
+    pub destination: PathBuf,
+    /// Bytes the job writes to the destination's volume. Destinations on the same volume share
+    /// it, so each counts what all of them write.
+    pub required: u64,
+    /// The volume, if the OS lists it.
+    pub space: Option<VolumeSpace>,
+    /// Bytes the margin keeps free there.
+    pub reserved: u64,
+}
+
+impl SpaceForecast {
+    /// How much the job may write there.
+    pub fn allowed(&self) -> Option<u64> {
+        let space = self.space.as_ref()?;
+        Some(space.available.saturating_sub(self.reserved))
+    }
 
-        progress.mut_increment();
-        tx.send(progress).unwrap();
+    /// Whether the job leaves the margin free. A volume of unknown size is assumed to fit.
+    pub fn fits(&self) -> bool {
+        self.allowed()
+            .is_none_or(|allowed| self.required <= allowed)
+    }
+
+    /// Free space left once the job is done, or how much is missing.
+    pub fn left_after(&self) -> Option<Result<u64, u64>> {
+        let available = self.space.as_ref()?.available;
+        Some(match available.checked_sub(self.required) {
+            Some(left) => Ok(left),
+            None => Err(self.required - available),
+        })
     }
-    Ok(total_bytes)
 }
 
-#[derive(Clone, Debug)]
-pub struct ChecksumReport(pub Vec<ChecksumReportSingleFile>);
+/// Forecasts the space of each destination, given the bytes the job writes to it. This queries
+/// the OS synchronously.
+pub fn forecast_space(
+    destinations: &[(PathBuf, u64)],
+    margin: Option<SpaceMargin>,
+) -> Vec<SpaceForecast> {
+    let spaces: Vec<Option<VolumeSpace>> = destinations
+        .iter()
+        .map(|(destination, _)| volume_space(destination))
+        .collect();
+    spaces
+        .iter()
+        .map(|space| {
+            let required = match space {
+                Some(space) => destinations
+                    .iter()
+                    .zip(&spaces)
+                    .filter(|(_, other)| {
+                        other.as_ref().map(|other| &other.mount_point) == Some(&space.mount_point)
+                    })
+                    .map(|((_, bytes), _)| bytes)
+                    .sum(),
+                None => 0,
+            };
+            (space.clone(), required)
+        })
+        .zip(destinations)
+        .map(|((space, required), (destination, bytes))| SpaceForecast {
+            destination: destination.clone(),
+            required: required.max(*bytes),
+            reserved: match (&space, margin) {
+                (Some(space), Some(margin)) => margin.reserved(space.total),
+                _ => 0,
+            },
+            space,
+        })
+        .collect()
+}
 
-#[derive(Clone, Debug)]
-pub struct ChecksumReportSingleFile {
-    pub source: (PathBuf, u64),
-    pub destinations: Vec<(PathBuf, u64)>,
+/// Bytes of `files`, by path relative to the source with their size, that a copy to
+/// `destination` needn't find room for as they are already there. Only a copy that keeps
+/// existing files, i.e. with [`CopyOptions::skip_existing`], leaves any out: a file already there
+/// with the source's size takes no more space. Everything else is written, and archives are
+/// written anew. This queries the file system synchronously.
+pub fn bytes_in_place(destination: &Path, files: &[(PathBuf, u64)], skip_existing: bool) -> u64 {
+    if !skip_existing || DestinationKind::of(destination) != DestinationKind::Folder {
+        return 0;
+    }
+    files
+        .iter()
+        .filter_map(|(file, size)| {
+            let existing = std::fs::metadata(long_path(&destination.join(file))).ok()?;
+            (existing.is_file() && existing.len() == *size).then_some(*size)
+        })
+        .sum()
 }
 
-impl ChecksumReportSingleFile {
-    pub fn consistent(&self) -> bool {
-        let source_hash = self.source.1;
-        self.destinations.iter().all(|(_, d)| *d == source_hash)
+/// Whether a write failed because the volume, or the user's quota on it, is full.
+fn is_out_of_space(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded
+    )
+}
+
+/// Whether another program held the file at the time, which on Windows a virus scanner or the
+/// search indexer does for a moment after a file is written (ERROR_SHARING_VIOLATION or
+/// ERROR_LOCK_VIOLATION). Other platforms don't lock files this way.
+fn is_sharing_violation(error: &io::Error) -> bool {
+    #[cfg(windows)]
+    {
+        const ERROR_SHARING_VIOLATION: i32 = 32;
+        const ERROR_LOCK_VIOLATION: i32 = 33;
+        matches!(
+            error.raw_os_error(),
+            Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+        )
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = error;
+        false
     }
 }
 
-impl ChecksumReport {
-    pub fn total_files(&self) -> usize {
-        self.0.len()
+/// How many more times an operation on a file another program holds is tried.
+const SHARING_RETRIES: u32 = 5;
+
+/// Wait before the first retry of an operation on a file another program holds, which grows with
+/// each retry.
+const SHARING_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Runs `operation` on the file at `path`, trying again a few times, a moment apart, while
+/// another program holds the file. Each time is logged, naming the file, so whatever holds it
+/// can be found.
+async fn retry_shared<T, F, Fut>(path: &Path, mut operation: F) -> io::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = io::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation().await {
+            Err(e) if is_sharing_violation(&e) && attempt < SHARING_RETRIES => {
+                attempt += 1;
+                tracing::warn!(
+                    attempt,
+                    "{} is in use by another program, trying again: {e}",
+                    path.display()
+                );
+                tokio::time::sleep(SHARING_RETRY_DELAY * attempt).await;
+            }
+            result => return result,
+        }
     }
+}
 
-    pub fn count_errors(&self) -> usize {
-        self.0.iter().filter(|file| !file.consistent()).count()
+/// Blocking version of [`retry_shared`].
+fn retry_shared_blocking<T>(
+    path: &Path,
+    mut operation: impl FnMut() -> io::Result<T>,
+) -> io::Result<T> {
+    let mut attempt = 0;
+    loop {
+        match operation() {
+            Err(e) if is_sharing_violation(&e) && attempt < SHARING_RETRIES => {
+                attempt += 1;
+                tracing::warn!(
+                    attempt,
+                    "{} is in use by another program, trying again: {e}",
+                    path.display()
+                );
+                std::thread::sleep(SHARING_RETRY_DELAY * attempt);
+            }
+            result => return result,
+        }
     }
 }
 
-pub async fn hash_dirs(
-    source: &PathBuf,
-    dest: &Vec<PathBuf>,
-    files: &Vec<PathBuf>,
-    tx: watch::Sender<Progress>,
-) -> io::Result<ChecksumReport> {
-    let mut report = Vec::new();
-    let mut progress = Progress {
-        total: files.len(),
-        completed: 0,
-    };
-    tx.send(progress).unwrap();
+/// Options that open a file for reading even while other programs have it open to read, write
+/// or delete it, as a virus scanner might.
+fn shared_read_options() -> std::fs::OpenOptions {
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true);
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        const FILE_SHARE_READ: u32 = 0x1;
+        const FILE_SHARE_WRITE: u32 = 0x2;
+        const FILE_SHARE_DELETE: u32 = 0x4;
+        options.share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE);
+    }
+    options
+}
 
-    for file in files {
-        let source_path = source.join(file);
-        let dest_paths: Vec<_> = dest.iter().map(|d| d.join(file)).collect();
-        let source_path_clone = source_path.clone();
-        let dest_paths_clone = dest_paths.clone();
+/// Opens the file at `path` to read it, e.g. to verify a copy, sharing it with other programs
+/// and waiting out one that holds it.
+async fn open_shared(path: &Path) -> io::Result<File> {
+    let options = tokio::fs::OpenOptions::from(shared_read_options());
+    retry_shared(path, || options.open(long_path(path))).await
+}
 
-        // Take advantage of multiple cores, just in case.
-        let source_hash_future = spawn(async move { compute_file_hash(&source_path_clone).await });
-        let dest_hash_futures: Vec<_> = dest_paths_clone
-            .into_iter()
-            .map(|dest_path| spawn(async move { compute_file_hash(dest_path).await }))
-            .collect();
-        let dest_hash_futures = join_all(dest_hash_futures);
+/// Blocking version of [`open_shared`].
+fn open_shared_blocking(path: &Path) -> io::Result<std::fs::File> {
+    retry_shared_blocking(path, || shared_read_options().open(long_path(path)))
+}
 
-        // Execute the futures concurrently
-        let (source_hash_result, dest_hash_results) = join!(source_hash_future, dest_hash_futures);
-        // Remove JoinError
-        let source_hash_result = source_hash_result?;
-        let dest_hash_results = collect_results(dest_hash_results)?;
+/// Lists the currently mounted volumes. This queries the OS synchronously.
+pub fn list_volumes() -> Vec<Volume> {
+    Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .map(|disk| {
+            let (label, serial) = volume_identity(disk.name(), disk.mount_point());
+            Volume {
+                label,
+                serial,
+                mount_point: disk.mount_point().to_path_buf(),
+            }
+        })
+        .collect()
+}
 
-        let mut destination_hashes = Vec::new();
-        for (dest_path, dest_hash_result) in dest_paths.iter().zip(dest_hash_results) {
-            destination_hashes.push((dest_path.clone(), dest_hash_result?));
+/// The label and serial of the volume `disk` names, mounted at `mount_point`. Linux names the
+/// device instead, e.g. `/dev/sdb1`, so its label and UUID are looked up among the links udev
+/// keeps for it; macOS already names the volume by its label.
+fn volume_identity(disk: &OsStr, mount_point: &Path) -> (String, Option<String>) {
+    #[cfg(target_os = "linux")]
+    {
+        let _ = mount_point;
+        let device = std::fs::canonicalize(disk).ok();
+        // udev writes characters a file name can't hold, spaces included, as \xNN
+        let linked = |folder: &str| -> Option<String> {
+            std::fs::read_dir(folder)
+                .ok()?
+                .flatten()
+                .find(|link| device.is_some() && std::fs::canonicalize(link.path()).ok() == device)
+                .map(|link| unescape_udev(&link.file_name().to_string_lossy()))
+        };
+        (
+            linked("/dev/disk/by-label").unwrap_or_default(),
+            linked("/dev/disk/by-uuid"),
+        )
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        #[link(name = "kernel32")]
+        unsafe extern "system" {
+            fn GetVolumeInformationW(
+                root: *const u16,
+                name: *mut u16,
+                name_size: u32,
+                serial: *mut u32,
+                max_component_length: *mut u32,
+                flags: *mut u32,
+                filesystem_name: *mut u16,
+                filesystem_name_size: u32,
+            ) -> i32;
         }
 
-        report.push(ChecksumReportSingleFile {
-            source: (source_path, source_hash_result?),
-            destinations: destination_hashes,
-        });
+        let _ = disk;
+        // The root must end in a backslash, as the mount point of a drive already does
+        let root: Vec<u16> = mount_point
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        let mut name = [0u16; 261];
+        let mut serial = 0u32;
+        let found = unsafe {
+            GetVolumeInformationW(
+                root.as_ptr(),
+                name.as_mut_ptr(),
+                name.len() as u32,
+                &mut serial,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        if found == 0 {
+            return (String::new(), None);
+        }
+        let length = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+        (
+            String::from_utf16_lossy(&name[..length]),
+            Some(format!("{:04X}-{:04X}", serial >> 16, serial & 0xFFFF)),
+        )
+    }
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = mount_point;
+        (disk.to_string_lossy().into_owned(), None)
+    }
+}
 
-        progress.mut_increment();
-        tx.send(progress).unwrap();
+/// A name from the links under `/dev/disk`, with the `\xNN` escapes udev writes turned back into
+/// the bytes they stand for.
+#[cfg(target_os = "linux")]
+fn unescape_udev(name: &str) -> String {
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut rest = name.as_bytes();
+    while let Some((&byte, after)) = rest.split_first() {
+        let escaped = after
+            .strip_prefix(b"x")
+            .filter(|_| byte == b'\\')
+            .and_then(|hex| hex.get(..2))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(unescaped) => {
+                bytes.push(unescaped);
+                rest = &after[3..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = after;
+            }
+        }
     }
-    Ok(ChecksumReport(report))
+    String::from_utf8_lossy(&bytes).into_owned()
 }
 
-pub async fn compute_file_hash<P: AsRef<Path>>(path: P) -> io::Result<u64> {
-    let file = File::open(path).await?;
-    let mut reader = BufReader::new(file);
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JobPhase {
+    /// Only enumerating the source; nothing has been copied yet.
+    Scanning,
+    #[default]
+    Running,
+    /// Every file is copied, and the copies are being synced to the destinations' drives.
+    Finalizing,
+}
 
-    // Create the hasher
-    let mut hasher = XxHash3_64::default();
+/// Identifies one job, from the copy through its verification, in what it leaves behind: the
+/// reports, the offload markers, the job history and the log. A random (version 4) UUID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct JobId(u128);
 
-    const CHUNK_SIZE: usize = 1024 * 1024; // 1MB
-    let mut buffer = vec![0; CHUNK_SIZE];
+impl JobId {
+    pub fn generate() -> JobId {
+        let mut bytes = [0; 16];
+        // The clock still keeps the ids of different jobs apart without the OS's randomness
+        if getrandom::fill(&mut bytes).is_err() {
+            let since = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
+            bytes = since.map_or(0, |since| since.as_nanos()).to_be_bytes();
+        }
+        let bits = u128::from_be_bytes(bytes);
+        // The version, then the variant of RFC 9562
+        JobId(bits & !(0xF << 76) & !(0x3 << 62) | 0x4 << 76 | 0x2 << 62)
+    }
 
-    loop {
-        let bytes_read = reader.read(&mut buffer).await?;
-        if bytes_read == 0 {
-            // EOF reached
-            break;
+    /// The first eight digits, enough to tell jobs apart at a glance.
+    pub fn short(self) -> String {
+        format!("{:08x}", self.0 >> 96)
+    }
+}
+
+impl fmt::Display for JobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = format!("{:032x}", self.0);
+        write!(
+            f,
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+}
+
+impl FromStr for JobId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<JobId, String> {
+        let hex = s.replace('-', "");
+        match hex.len() {
+            32 => u128::from_str_radix(&hex, 16)
+                .map(JobId)
+                .map_err(|e| format!("Invalid job id {s}: {e}")),
+            _ => Err(format!("Invalid job id {s}")),
         }
+    }
+}
 
-        // Since memory read is far faster than disk IO, and xxHash3 has roughly the same throughput as memory read,
-        // we can assume it is not a long enough task to spawn_blocking
-        hasher.write(&buffer[..bytes_read]);
+impl From<JobId> for String {
+    fn from(id: JobId) -> String {
+        id.to_string()
     }
+}
 
-    // Return the final hash
-    Ok(hasher.finish())
+impl TryFrom<String> for JobId {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<JobId, String> {
+        s.parse()
+    }
 }
 
-impl ChecksumReport {
-    pub fn export_report<P: AsRef<Path>>(&self, to_file: P) -> Result<(), Box<dyn Error>> {
-        let file = std::fs::File::create(to_file)?;
-        let mut writer = Writer::from_writer(file);
-        let mut header: Vec<String> = vec![
-            "Consistent".to_owned(),
-            "Source".to_owned(),
-            "Source Hash".to_owned(),
-        ];
-        let row0 = &self.0[0];
-        for i in 0..row0.destinations.len() {
-            header.push(format!("Destination File {}", i + 1));
-            header.push(format!("Destination Hash {}", i + 1));
+#[derive(Clone, Debug, Default)]
+pub struct Progress {
+    pub phase: JobPhase,
+    pub total_files: usize,
+    pub completed_files: usize,
+    /// Size of all files found so far.
+    pub total_bytes: u64,
+    pub completed_bytes: u64,
+    /// Size of the files copied so far. Files that were skipped or failed aren't counted.
+    pub copied_bytes: u64,
+    /// Source file being copied or hashed, relative to the source.
+    pub current_file: Option<PathBuf>,
+    /// Size of the file being copied or hashed, and how much of it is done.
+    pub current_file_size: u64,
+    pub current_file_bytes: u64,
+    /// Files that matched, and that didn't, when verifying while copying.
+    pub verified_files: usize,
+    pub failed_files: usize,
+    /// The source is still being enumerated, so the totals may grow.
+    pub scanning: bool,
+    /// Warnings so far; the feed may hold only the latest of them.
+    pub warnings: usize,
+    /// How far the copy is at each destination, in the job's order. Empty when verifying.
+    pub destinations: Vec<DestinationProgress>,
+    /// The copy has moved no data for a while. Cleared once it moves again.
+    pub stall: Option<Stall>,
+    /// Files of the job and how many are done, by [group](file_group).
+    pub groups: BTreeMap<String, GroupProgress>,
+}
+
+/// How far the files of one [group](file_group) are.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GroupProgress {
+    pub total_files: usize,
+    pub completed_files: usize,
+}
+
+/// The group of a file at the source root, which has no folder to go by.
+pub const ROOT_GROUP: &str = "ROOT";
+
+/// The group a file is shown and reported in: the first folder of its path `relative` to the
+/// source, e.g. the roll or day folder of a card, or [`ROOT_GROUP`] right at the root.
+pub fn file_group(relative: &Path) -> String {
+    let mut folders = relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)));
+    match (folders.next(), folders.next()) {
+        (Some(first), Some(_)) => first.as_os_str().to_string_lossy().into_owned(),
+        _ => ROOT_GROUP.to_owned(),
+    }
+}
+
+/// A copy that has been waiting on a read or a write for longer than it should.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stall {
+    /// Time since data last moved, in whole seconds.
+    pub idle: Duration,
+    /// Destinations, in the job's order, with a write that hasn't returned. Empty when the copy
+    /// is waiting on the source.
+    pub destinations: Vec<usize>,
+}
+
+/// The files a destination takes and how many of their bytes are written.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DestinationProgress {
+    /// Size of the files found so far that go to this destination, less those it won't get after
+    /// all, e.g. duplicates and files it already had.
+    pub total_bytes: u64,
+    pub written_bytes: u64,
+    /// Files written there that the OS may still hold in its cache, not yet synced to the drive.
+    pub pending_files: usize,
+    pub state: DestinationState,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum DestinationState {
+    #[default]
+    Writing,
+    /// Every file for the destination is written, but not all of them are synced to its drive
+    /// yet; unplugging it now could lose them.
+    Finalizing,
+    /// Every file for the destination is written and synced, and the source is fully scanned.
+    Done,
+    /// A write failed, with the error.
+    Failed(String),
+}
+
+impl Progress {
+    pub fn complete_file(&mut self, bytes: u64) {
+        self.completed_files += 1;
+        self.completed_bytes += bytes;
+        self.current_file_bytes = 0;
+    }
+
+    /// Counts `file`, relative to the source, in its group's total.
+    fn add_to_group(&mut self, file: &Path) {
+        self.groups.entry(file_group(file)).or_default().total_files += 1;
+    }
+
+    /// Counts `file`, relative to the source, as done in its group.
+    fn complete_in_group(&mut self, file: &Path) {
+        self.groups
+            .entry(file_group(file))
+            .or_default()
+            .completed_files += 1;
+    }
+
+    /// Bytes done, counting what is done of the current file.
+    pub fn done_bytes(&self) -> u64 {
+        self.completed_bytes + self.current_file_bytes.min(self.current_file_size)
+    }
+
+    /// Bytes copied, counting what is written of the current file. Once the copy is done this is
+    /// the summary's `total_bytes`.
+    pub fn copied_so_far(&self) -> u64 {
+        self.copied_bytes + self.current_file_bytes.min(self.current_file_size)
+    }
+
+    pub fn file_fraction(&self) -> f32 {
+        if self.total_files == 0 {
+            0.0
+        } else {
+            self.completed_files as f32 / self.total_files as f32
         }
-        writer.write_record(header)?;
+    }
 
-        for row in &self.0 {
-            let mut record: Vec<String> = vec![
-                if row.consistent() {
-                    "Y".to_owned()
-                } else {
-                    "N".to_owned()
-                },
-                row.source.0.to_string_lossy().into_owned(),
-                format!("{:X}", row.source.1).to_owned(),
-            ];
-            for dest in &row.destinations {
-                record.push(dest.0.to_string_lossy().into_owned());
-                record.push(format!("{:X}", dest.1).to_owned());
+    /// Share of bytes done, which tracks elapsed time far better than the file count when file
+    /// sizes vary.
+    pub fn byte_fraction(&self) -> f32 {
+        if self.total_bytes == 0 {
+            self.file_fraction()
+        } else {
+            self.done_bytes() as f32 / self.total_bytes as f32
+        }
+    }
+
+    /// Accounts a file of `size` at the destinations that wanted it: added to the bytes written
+    /// where it was `written`, and dropped from the total where it won't be. An empty `written`
+    /// means it went nowhere.
+    fn settle_destinations(&mut self, wanted: &[bool], written: &[bool], size: u64) {
+        for (index, (destination, wanted)) in self.destinations.iter_mut().zip(wanted).enumerate() {
+            match (wanted, written.get(index).copied().unwrap_or(false)) {
+                (true, true) => destination.written_bytes += size,
+                (true, false) => {
+                    destination.total_bytes = destination.total_bytes.saturating_sub(size)
+                }
+                (false, _) => {}
+            }
+        }
+        self.mark_caught_up();
+    }
+
+    /// Marks the destinations with nothing left to write as done, once no more files can turn up.
+    fn mark_caught_up(&mut self) {
+        if self.scanning {
+            return;
+        }
+        for destination in &mut self.destinations {
+            if destination.state == DestinationState::Writing
+                && destination.written_bytes >= destination.total_bytes
+            {
+                destination.state = match destination.pending_files {
+                    0 => DestinationState::Done,
+                    _ => DestinationState::Finalizing,
+                };
+            }
+        }
+    }
+}
+
+/// A batch copy failed at one of its destinations, given by its index in the batch.
+#[derive(Debug)]
+struct DestinationFailed {
+    index: usize,
+    path: PathBuf,
+    error: io::Error,
+}
+
+impl fmt::Display for DestinationFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl Error for DestinationFailed {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Ties `error` to the destination at `index` of a batch, keeping its kind.
+fn destination_failed(index: usize, path: &Path, error: io::Error) -> io::Error {
+    io::Error::new(
+        error.kind(),
+        DestinationFailed {
+            index,
+            path: path.to_path_buf(),
+            error,
+        },
+    )
+}
+
+/// Marks the destination a failed batch copy broke off at, so the view can show which one it was
+/// before the job ends with the error. `written` tells which destinations were in the batch.
+fn report_failed_destination(
+    tx: &watch::Sender<Progress>,
+    progress: &mut Progress,
+    written: &[bool],
+    error: &io::Error,
+) {
+    let Some(index) = failed_destination(error) else {
+        return;
+    };
+    let destination = written
+        .iter()
+        .enumerate()
+        .filter(|(_, written)| **written)
+        .nth(index)
+        .and_then(|(destination, _)| progress.destinations.get_mut(destination));
+    if let Some(destination) = destination {
+        destination.state = DestinationState::Failed(error.to_string());
+        tx.send_replace(progress.clone());
+    }
+}
+
+/// The index in its batch of the destination `error` happened at, if it was a destination's.
+fn failed_destination(error: &io::Error) -> Option<usize> {
+    error
+        .get_ref()?
+        .downcast_ref::<DestinationFailed>()
+        .map(|failed| failed.index)
+}
+
+/// Copies the file at `source_path` to each of `dest_paths` at once, reading it only once. With
+/// a `sink`, each chunk is reported to it as it is written.
+pub async fn read_file_copy_batch<P: AsRef<Path>>(
+    source_path: P,
+    dest_paths: Vec<PathBuf>,
+    sink: Option<&dyn CopyProgressSink>,
+) -> SizeResult {
+    let activity = CopyActivity::new(dest_paths.len());
+    let copy = read_file_copy_batch_hashed(
+        source_path,
+        dest_paths,
+        &mut [],
+        BatchOptions::default(),
+        None,
+        &activity,
+        sink,
+    )
+    .await?;
+    Ok(copy.size)
+}
+
+/// Receives each chunk of a batch copy as it is written, for callers embedding the backend that
+/// need more than the job's [`Progress`].
+///
+/// For each file, [`CopyProgressSink::chunk`] is called once per chunk, in order, from the task
+/// running the copy, after every destination has finished writing the chunk, waits for space
+/// included. The chunks of a copy that succeeds add up to its size; an empty file has none. A
+/// chunk whose write fails the copy is still reported, with the destination
+/// [`ChunkStatus::Failed`], just before the error is returned. A destination the copy goes on
+/// without is reported failed in that chunk and every one after it. Opening, flushing and reading
+/// back the destinations aren't reported. The copy waits for the call, so it should return
+/// quickly.
+pub trait CopyProgressSink: Send + Sync {
+    fn chunk(&self, progress: &ChunkProgress<'_>);
+}
+
+impl<F: Fn(&ChunkProgress<'_>) + Send + Sync> CopyProgressSink for F {
+    fn chunk(&self, progress: &ChunkProgress<'_>) {
+        self(progress)
+    }
+}
+
+/// Stands in for a sink when the caller has none.
+struct NoProgress;
+
+impl CopyProgressSink for NoProgress {
+    fn chunk(&self, _: &ChunkProgress<'_>) {}
+}
+
+/// A chunk of a batch copy, as reported to a [`CopyProgressSink`].
+#[derive(Clone, Debug)]
+pub struct ChunkProgress<'a> {
+    /// The source file, as the copy was given it.
+    pub file: &'a Path,
+    /// Bytes in this chunk.
+    pub bytes: u64,
+    /// Size of the file when the copy opened it.
+    pub file_bytes: u64,
+    /// Each destination of the batch, in order.
+    pub destinations: Vec<ChunkStatus>,
+}
+
+impl ChunkProgress<'_> {
+    /// Reports the chunk with the destination at `index` failed by `error`, and returns the
+    /// error to fail the copy with.
+    fn fail(
+        &mut self,
+        sink: &dyn CopyProgressSink,
+        index: usize,
+        path: &Path,
+        error: io::Error,
+    ) -> io::Error {
+        self.destinations[index] = ChunkStatus::Failed(error.to_string());
+        sink.chunk(self);
+        destination_failed(index, path, error)
+    }
+}
+
+/// What became of a chunk at one destination.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChunkStatus {
+    Written,
+    /// The destination ran out of space and was given up on, at this chunk or before.
+    Dropped,
+    /// The write failed with this error, at this chunk or, if the copy went on without the
+    /// destination, before.
+    Failed(String),
+}
+
+/// What `read_file_copy_batch_hashed` found while copying a file.
+#[derive(Clone, Debug)]
+pub struct BatchCopy {
+    pub size: u64,
+    /// Hash of the source as it streamed through, if an algorithm was given.
+    pub source_hash: Option<Digest>,
+    /// Hash of each destination, read back through the handle it was written with, if asked for.
+    /// Archive entries aren't read back this way.
+    pub read_back: Vec<Digest>,
+    /// A destination dropped while the file was written to it, and the copy resumed once it was
+    /// back.
+    pub reconnected: bool,
+    /// Reads of the source that only succeeded when tried again.
+    pub read_retries: Vec<ReadRetry>,
+    /// Ranges of the source that couldn't be read in salvage mode and were written as zeros.
+    pub lost: Vec<Range<u64>>,
+    /// Destinations, by their index in the batch, that ran out of space and were given up on.
+    /// Their partial copies are removed, and they have no read-back hash.
+    pub dropped: Vec<usize>,
+    /// Destinations, by their index in the batch, that failed and were left behind with the
+    /// error they failed with, if the copy was to go on without them. Their partial copies are
+    /// removed, and they have no read-back hash.
+    pub failed: Vec<(usize, String)>,
+    /// When each destination's copy was complete, by its index in the batch. `None` where it was
+    /// dropped, and empty if nothing was written.
+    pub finished_at: Vec<Option<SystemTime>>,
+}
+
+impl BatchCopy {
+    /// A copy of `size` bytes that went without reading the source.
+    fn unread(size: u64) -> Self {
+        BatchCopy {
+            size,
+            source_hash: None,
+            read_back: Vec::new(),
+            reconnected: false,
+            read_retries: Vec::new(),
+            lost: Vec::new(),
+            dropped: Vec::new(),
+            failed: Vec::new(),
+            finished_at: Vec::new(),
+        }
+    }
+}
+
+/// A file copied in salvage mode although parts of the source couldn't be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Salvaged {
+    /// Byte ranges of the source that couldn't be read. The copies have zeros there, so the rest
+    /// of the file keeps its offsets.
+    pub lost: Vec<Range<u64>>,
+    /// Hash of the content as copied, zeros included. The card can't be read again to check the
+    /// copies, so they are verified against this instead.
+    pub hash: Digest,
+}
+
+impl Salvaged {
+    pub fn bytes_lost(&self) -> u64 {
+        bytes_in(&self.lost)
+    }
+}
+
+fn bytes_in(ranges: &[Range<u64>]) -> u64 {
+    ranges.iter().map(|range| range.end - range.start).sum()
+}
+
+/// A read that failed and then succeeded when tried again from the same offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadRetry {
+    pub offset: u64,
+    /// Attempts after the first one that failed.
+    pub attempts: u32,
+}
+
+/// How many more times a failed read of a file is tried, unless configured.
+pub const DEFAULT_READ_RETRIES: u32 = 3;
+
+/// Reads the next chunk of `reader`, which is at `offset`. A failing card often returns a marginal
+/// sector on a later attempt, so a failed read is tried again from `offset` up to `retries` times.
+/// Returns the bytes read and the attempts it took after the first; once the retries are used up,
+/// the last error is returned.
+async fn read_retrying<R>(
+    reader: &mut R,
+    offset: u64,
+    buffer: &mut [u8],
+    retries: u32,
+) -> io::Result<(usize, u32)>
+where
+    R: AsyncReadExt + AsyncSeekExt + Unpin,
+{
+    let mut error = match reader.read(buffer).await {
+        Ok(bytes_read) => return Ok((bytes_read, 0)),
+        Err(e) => e,
+    };
+    for attempt in 1..=retries {
+        tracing::info!(offset, attempt, "Read failed, retrying: {error}");
+        let retried = async {
+            reader.seek(io::SeekFrom::Start(offset)).await?;
+            reader.read(buffer).await
+        };
+        match retried.await {
+            Ok(bytes_read) => return Ok((bytes_read, attempt)),
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+/// Size of the pieces a chunk that can't be read is salvaged in; only the pieces that fail are
+/// lost.
+const SALVAGE_BLOCK_SIZE: usize = 4096;
+
+/// Reads the chunk of `buffer.len()` bytes at `offset`, which failed to read as a whole, a block
+/// at a time. Blocks that fail are zero-filled and returned as lost ranges. They aren't retried,
+/// since each failed read of a dying card can take seconds. The chunk ends at `size`, the length
+/// of the file, and `reader` is left after it.
+async fn salvage_chunk<R>(
+    reader: &mut R,
+    offset: u64,
+    buffer: &mut [u8],
+    size: u64,
+) -> io::Result<(usize, Vec<Range<u64>>)>
+where
+    R: AsyncReadExt + AsyncSeekExt + Unpin,
+{
+    let len = buffer
+        .len()
+        .min(usize::try_from(size.saturating_sub(offset)).unwrap_or(usize::MAX));
+    let mut lost: Vec<Range<u64>> = Vec::new();
+    for start in (0..len).step_by(SALVAGE_BLOCK_SIZE) {
+        let block = &mut buffer[start..(start + SALVAGE_BLOCK_SIZE).min(len)];
+        let block_offset = offset + start as u64;
+        let read = async {
+            reader.seek(io::SeekFrom::Start(block_offset)).await?;
+            reader.read_exact(block).await
+        };
+        if read.await.is_err() {
+            block.fill(0);
+            let end = block_offset + block.len() as u64;
+            match lost.last_mut() {
+                Some(range) if range.end == block_offset => range.end = end,
+                _ => lost.push(block_offset..end),
+            }
+        }
+    }
+    reader
+        .seek(io::SeekFrom::Start(offset + len as u64))
+        .await?;
+    Ok((len, lost))
+}
+
+/// Reads the next chunk of the source like [`read_retrying`]. With `salvage_size`, the length of
+/// the file, a chunk that can't be read however often it is tried is salvaged instead of failing
+/// the copy. Returns the bytes read, the attempts after the first, and the ranges lost.
+async fn read_source_chunk(
+    file: &mut File,
+    offset: u64,
+    buffer: &mut [u8],
+    retries: u32,
+    salvage_size: Option<u64>,
+) -> io::Result<(usize, u32, Vec<Range<u64>>)> {
+    match read_retrying(file, offset, buffer, retries).await {
+        Ok((bytes_read, attempts)) => Ok((bytes_read, attempts, Vec::new())),
+        Err(e) => match salvage_size {
+            Some(size) => {
+                let (bytes_read, lost) = salvage_chunk(file, offset, buffer, size).await?;
+                Ok((bytes_read, retries, lost))
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Like [`read_source_chunk`], but reads until `buffer` is full or the file ends, in reads of
+/// `step` bytes. Reports the most attempts any one read took.
+async fn fill_source_chunk(
+    file: &mut File,
+    offset: u64,
+    buffer: &mut [u8],
+    step: usize,
+    retries: u32,
+    salvage_size: Option<u64>,
+) -> io::Result<(usize, u32, Vec<Range<u64>>)> {
+    let (mut filled, mut attempts, mut lost) = (0, 0, Vec::new());
+    while filled < buffer.len() {
+        let end = (filled + step).min(buffer.len());
+        let (read, tried, mut lost_now) = read_source_chunk(
+            file,
+            offset + filled as u64,
+            &mut buffer[filled..end],
+            retries,
+            salvage_size,
+        )
+        .await?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+        attempts = attempts.max(tried);
+        lost.append(&mut lost_now);
+    }
+    Ok((filled, attempts, lost))
+}
+
+/// Blocking version of [`read_retrying`].
+fn read_retrying_blocking(
+    file: &mut std::fs::File,
+    offset: u64,
+    buffer: &mut [u8],
+    retries: u32,
+) -> io::Result<(usize, u32)> {
+    use std::io::{Read, Seek};
+
+    let mut error = match file.read(buffer) {
+        Ok(bytes_read) => return Ok((bytes_read, 0)),
+        Err(e) => e,
+    };
+    for attempt in 1..=retries {
+        tracing::info!(offset, attempt, "Read failed, retrying: {error}");
+        match file
+            .seek(io::SeekFrom::Start(offset))
+            .and_then(|_| file.read(buffer))
+        {
+            Ok(bytes_read) => return Ok((bytes_read, attempt)),
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+/// How long a network destination gets to come back after a failed write, unless configured.
+pub const DEFAULT_RECONNECT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often a dropped destination is tried again while waiting for it.
+const RECONNECT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long a copy may move no data before it is reported as stalled, unless configured.
+pub const DEFAULT_STALL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often a copy is looked at for a stall.
+const STALL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When a copy last moved data and which of its destinations have a write outstanding, shared
+/// with the watch that reports a stall. Made for a job, then narrowed to each batch with
+/// [`CopyActivity::in_batch`].
+#[derive(Clone, Debug)]
+pub struct CopyActivity {
+    state: Arc<Mutex<ActivityState>>,
+    /// The job's index of each destination of the batch.
+    batch: Vec<usize>,
+}
+
+#[derive(Debug)]
+struct ActivityState {
+    /// A batch copy is underway; time spent between files, e.g. verifying, isn't a stall.
+    copying: bool,
+    moved: Instant,
+    writing: Vec<bool>,
+}
+
+impl CopyActivity {
+    pub fn new(destinations: usize) -> Self {
+        CopyActivity {
+            state: Arc::new(Mutex::new(ActivityState {
+                copying: false,
+                moved: Instant::now(),
+                writing: vec![false; destinations],
+            })),
+            batch: (0..destinations).collect(),
+        }
+    }
+
+    /// The same activity, for a batch of the destinations that are `written`.
+    pub fn in_batch(&self, written: &[bool]) -> Self {
+        CopyActivity {
+            state: self.state.clone(),
+            batch: in_batch(&self.batch, written),
+        }
+    }
+
+    /// How long nothing has moved, if that is at least `after` and a copy is underway.
+    pub fn stall(&self, after: Duration) -> Option<Stall> {
+        let state = self.state.lock().unwrap();
+        let idle = state.moved.elapsed();
+        (state.copying && idle >= after).then(|| Stall {
+            idle: Duration::from_secs(idle.as_secs()),
+            destinations: (0..state.writing.len())
+                .filter(|index| state.writing[*index])
+                .collect(),
+        })
+    }
+
+    /// Marks a batch copy as underway until the guard is dropped.
+    fn copying(&self) -> ActivityGuard<'_> {
+        let mut state = self.state.lock().unwrap();
+        state.copying = true;
+        state.moved = Instant::now();
+        ActivityGuard(self)
+    }
+
+    fn moved(&self) {
+        self.state.lock().unwrap().moved = Instant::now();
+    }
+
+    /// Marks the destination, by its index in the batch, as having a write outstanding or not.
+    fn writing(&self, index: usize, writing: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.writing[self.batch[index]] = writing;
+        if !writing {
+            state.moved = Instant::now();
+        }
+    }
+
+    /// Runs `wait`, e.g. for the user to answer, without it counting towards a stall.
+    async fn waiting<T>(&self, wait: impl Future<Output = T>) -> T {
+        self.state.lock().unwrap().copying = false;
+        let result = wait.await;
+        let mut state = self.state.lock().unwrap();
+        state.copying = true;
+        state.moved = Instant::now();
+        result
+    }
+
+    /// Runs the write to the destination at `index` of the batch, marked as outstanding.
+    async fn write<T>(&self, index: usize, write: impl Future<Output = T>) -> T {
+        self.writing(index, true);
+        let result = write.await;
+        self.writing(index, false);
+        result
+    }
+}
+
+/// Ends a batch copy's activity, however the copy ends.
+struct ActivityGuard<'a>(&'a CopyActivity);
+
+impl Drop for ActivityGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap();
+        state.copying = false;
+        state.writing.fill(false);
+    }
+}
+
+/// Publishes a stall of `activity` on `tx` once nothing has moved for `after`, and clears it once
+/// something does, until `done`.
+async fn watch_for_stalls(
+    activity: CopyActivity,
+    after: Duration,
+    tx: watch::Sender<Progress>,
+    done: CancellationToken,
+) {
+    let mut poll = tokio::time::interval(STALL_POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = poll.tick() => {}
+            _ = done.cancelled() => return,
+        }
+        let stall = activity.stall(after);
+        if let Some(stall) = &stall {
+            tracing::warn!(
+                idle = stall.idle.as_secs(),
+                destinations = ?stall.destinations,
+                "Copy stalled"
+            );
+        }
+        tx.send_if_modified(|progress| {
+            let changed = progress.stall != stall;
+            progress.stall = stall;
+            changed
+        });
+    }
+}
+
+/// How many of the usual chunks are read at once when the source and a destination share a
+/// drive.
+const SAME_DISK_CHUNKS: usize = 16;
+
+/// How [`read_file_copy_batch_hashed`] goes about copying a file.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchOptions<'a> {
+    /// Hashes the source with this as it streams through.
+    pub algorithm: Option<HashAlgorithm>,
+    /// With an `algorithm`, flushes each destination and reads it back through the same handle
+    /// before returning, so a drive that accepts writes but stores garbage fails on this file
+    /// rather than in a later pass. A read-back can still be served from the OS cache.
+    pub read_back: bool,
+    /// A destination is on the source's drive, so the file is moved in larger chunks, each read
+    /// in full before it is written, and the drive isn't made to seek between reading and writing
+    /// every megabyte.
+    pub same_disk: bool,
+    /// How long to wait for each destination, by its index in the batch, to come back after a
+    /// failed write, before resuming where it broke off. Without one, the write fails the copy.
+    pub reconnect: &'a [Option<Duration>],
+    /// A destination file that can't be opened, written or flushed is left behind and its partial
+    /// copy removed, while the others are written on. The copy only fails if every destination
+    /// does.
+    pub continue_on_error: bool,
+    /// How many more times a failed read of the source is tried.
+    pub read_retries: u32,
+    /// Once the retries are used up, the parts of the source that can't be read are written as
+    /// zeros and the copy goes on.
+    pub salvage: bool,
+    /// Cancelling it gives the copy up before its next chunk: the partial files are removed and an
+    /// `Interrupted` error is returned.
+    pub stop: Option<&'a CancellationToken>,
+}
+
+impl Default for BatchOptions<'_> {
+    fn default() -> Self {
+        BatchOptions {
+            algorithm: None,
+            read_back: false,
+            same_disk: false,
+            reconnect: &[],
+            continue_on_error: false,
+            read_retries: DEFAULT_READ_RETRIES,
+            salvage: false,
+            stop: None,
+        }
+    }
+}
+
+/// Like `read_file_copy_batch`, but also hashes the source as it streams through, so the copy can
+/// be verified without reading the source a second time, and goes about it as `options` say. A
+/// destination that runs out of space is paused on, with its file still open, until `on_full`
+/// decides; without it, the copy fails. A destination with an archive in `archives`, by its index
+/// in the batch, gets an entry of that archive instead of a file; an archive can't wait for
+/// space or reconnect, and holes are written to it as zeros. Each read and write is noted in
+/// `activity`, for telling when the copy stalls, and each chunk written is reported to `sink`.
+pub async fn read_file_copy_batch_hashed<P: AsRef<Path>>(
+    source_path: P,
+    dest_paths: Vec<PathBuf>,
+    archives: &mut [Option<&mut ArchiveWriter>],
+    options: BatchOptions<'_>,
+    mut on_full: Option<&mut SpacePrompt<'_>>,
+    activity: &CopyActivity,
+    sink: Option<&dyn CopyProgressSink>,
+) -> io::Result<BatchCopy> {
+    let BatchOptions {
+        algorithm,
+        read_back,
+        same_disk,
+        reconnect,
+        continue_on_error,
+        read_retries,
+        salvage,
+        stop,
+    } = options;
+    let _copying = activity.copying();
+    let sink = sink.unwrap_or(&NoProgress);
+    let mut hasher = algorithm.map(FileHasher::new);
+
+    // Open the source file
+    let mut source_file = File::open(long_path(source_path.as_ref())).await?;
+    let sparse = has_holes(source_path.as_ref());
+    let metadata = source_file.metadata().await?;
+    let salvage_size = salvage.then_some(metadata.len());
+
+    // Open all destination files, readable too so they can be read back
+    let mut dest_files = Vec::with_capacity(dest_paths.len());
+    let mut failed: Vec<Option<io::Error>> = dest_paths.iter().map(|_| None).collect();
+    for (index, path) in dest_paths.iter().enumerate() {
+        let sink = match archives.get_mut(index).and_then(Option::take) {
+            Some(archive) => {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                activity
+                    .write(index, archive.start_entry(path, metadata.len(), modified))
+                    .await
+                    .map_err(|e| destination_failed(index, path, e))?;
+                Sink::Archive(archive)
+            }
+            None => {
+                let opened = activity
+                    .write(
+                        index,
+                        tokio::fs::OpenOptions::new()
+                            .read(true)
+                            .write(true)
+                            .create(true)
+                            .truncate(true)
+                            .open(long_path(path)),
+                    )
+                    .await
+                    .map_err(|e| destination_failed(index, path, e));
+                match opened {
+                    Ok(file) => Sink::File(file),
+                    Err(e) if continue_on_error => {
+                        failed[index] = Some(e);
+                        Sink::Failed
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        };
+        dest_files.push(sink);
+    }
+
+    // Rotated buffers for concurrent read/write
+    const BUFFER_SIZE: usize = 1024 * 1024; // 1MB
+    let buffer_size = match same_disk {
+        true => BUFFER_SIZE * SAME_DISK_CHUNKS,
+        false => BUFFER_SIZE,
+    };
+    let mut buffer1 = vec![0u8; buffer_size];
+    let mut buffer2 = vec![0u8; buffer_size];
+    let mut read_buffer = &mut buffer1;
+    let mut write_buffer = &mut buffer2;
+    let read = async |file: &mut File, offset: u64, buffer: &mut [u8]| match same_disk {
+        true => {
+            fill_source_chunk(
+                file,
+                offset,
+                buffer,
+                BUFFER_SIZE,
+                read_retries,
+                salvage_size,
+            )
+            .await
+        }
+        false => read_source_chunk(file, offset, buffer, read_retries, salvage_size).await,
+    };
+
+    // Read first chunk into write_buffer
+    let mut retries = Vec::new();
+    let (mut bytes_read, attempts, mut lost) = read(&mut source_file, 0, read_buffer).await?;
+    activity.moved();
+    if attempts > 0 && lost.is_empty() {
+        retries.push(ReadRetry {
+            offset: 0,
+            attempts,
+        });
+    }
+    let mut total_bytes = bytes_read as u64;
+    let mut reconnected = false;
+    let mut active: Vec<bool> = failed.iter().map(Option::is_none).collect();
+
+    // An empty file has nothing to write, and there is no use reading on for no destination
+    while bytes_read > 0 && !all_failed(&failed) {
+        // A truncated copy would pass for the file in a file browser. An archive's entry is left
+        // open, and the job removes the unfinished archive.
+        if stop.is_some_and(CancellationToken::is_cancelled) {
+            let files: Vec<bool> = dest_files
+                .iter()
+                .map(|sink| matches!(sink, Sink::File(_)))
+                .collect();
+            drop(dest_files);
+            for (path, _) in dest_paths.iter().zip(files).filter(|(_, file)| *file) {
+                let _ = tokio::fs::remove_file(long_path(path)).await;
+            }
+            return Err(stopped_error());
+        }
+        // Data from read_buffer from the last loop goes to write_buffer, and write_buffer from the last loop
+        // is overwritten
+        std::mem::swap(&mut read_buffer, &mut write_buffer);
+
+        if let Some(hasher) = &mut hasher {
+            hasher.update(&write_buffer[..bytes_read]);
+        }
+
+        // Where the source has a hole, skipping ahead leaves one at the destinations too
+        let hole = sparse && write_buffer[..bytes_read].iter().all(|byte| *byte == 0);
+        let chunk = &write_buffer[..bytes_read];
+        let offset = total_bytes - bytes_read as u64;
+        let mut write_futures = Vec::with_capacity(dest_files.len());
+        let mut writing = Vec::with_capacity(dest_files.len());
+        for (index, (sink, path)) in dest_files
+            .iter_mut()
+            .zip(&dest_paths)
+            .enumerate()
+            .filter(|(index, _)| active[*index])
+        {
+            let reconnect = reconnect.get(index).copied().flatten();
+            write_futures.push(activity.write(index, async move {
+                match sink {
+                    Sink::File(file) => {
+                        write_chunk_resuming(file, path, offset, chunk, hole, reconnect).await
+                    }
+                    Sink::Archive(archive) => archive.write(chunk).await.map(|()| false),
+                    Sink::Failed => unreachable!("Failed destinations aren't written"),
+                }
+            }));
+            writing.push(index);
+        }
+        let write_futures = join_all(write_futures);
+
+        let read_future = async {
+            let read = read(&mut source_file, total_bytes, read_buffer).await;
+            activity.moved();
+            read
+        };
+
+        // Execute read and write futures concurrently, unless they would fight over one drive
+        let (read_result, write_results) = match same_disk {
+            true => {
+                let written = write_futures.await;
+                (read_future.await, written)
+            }
+            false => join!(read_future, write_futures),
+        };
+
+        // Check for write errors. A full destination waits for the user, who may free space
+        // and have the chunk written again where it started.
+        let mut report = ChunkProgress {
+            file: source_path.as_ref(),
+            bytes: bytes_read as u64,
+            file_bytes: metadata.len(),
+            destinations: active
+                .iter()
+                .zip(&failed)
+                .map(|(active, failed)| match (active, failed) {
+                    (true, _) => ChunkStatus::Written,
+                    (false, Some(e)) => ChunkStatus::Failed(e.to_string()),
+                    (false, None) => ChunkStatus::Dropped,
+                })
+                .collect(),
+        };
+        for (index, result) in writing.into_iter().zip(write_results) {
+            let path = &dest_paths[index];
+            let mut error = match result {
+                Ok(resumed) => {
+                    reconnected |= resumed;
+                    continue;
+                }
+                // Part of an entry can't be taken back from an archive
+                Err(e) if is_out_of_space(&e) && matches!(dest_files[index], Sink::File(_)) => e,
+                // A file can be given up on at one destination, but an archive would be left broken
+                Err(e) if continue_on_error && matches!(dest_files[index], Sink::File(_)) => {
+                    tracing::warn!(
+                        "Writing {} failed, going on without it: {e}",
+                        path.display()
+                    );
+                    report.destinations[index] = ChunkStatus::Failed(e.to_string());
+                    active[index] = false;
+                    failed[index] = Some(destination_failed(index, path, e));
+                    continue;
+                }
+                Err(e) => return Err(report.fail(sink, index, path, e)),
+            };
+            loop {
+                let choice = match on_full.as_deref_mut() {
+                    Some(prompt) => activity.waiting(prompt.ask(index, offset)).await,
+                    None => SpaceChoice::Abort,
+                };
+                match choice {
+                    SpaceChoice::Retry => {
+                        let Sink::File(file) = &mut dest_files[index] else {
+                            unreachable!("Only files wait for space");
+                        };
+                        let rewritten = async {
+                            file.seek(io::SeekFrom::Start(offset)).await?;
+                            write_chunk(file, chunk, hole).await
+                        };
+                        match rewritten.await {
+                            Ok(()) => break,
+                            Err(e) if is_out_of_space(&e) => error = e,
+                            Err(e) => return Err(report.fail(sink, index, path, e)),
+                        }
+                    }
+                    SpaceChoice::Drop => {
+                        active[index] = false;
+                        report.destinations[index] = ChunkStatus::Dropped;
+                        break;
+                    }
+                    SpaceChoice::Abort => return Err(report.fail(sink, index, path, error)),
+                }
+            }
+        }
+        sink.chunk(&report);
+
+        let (read, attempts, mut lost_now) = read_result?;
+        bytes_read = read; // Might not be BUFFER_SIZE if the upcoming read will hit EOF
+        if attempts > 0 && lost_now.is_empty() {
+            retries.push(ReadRetry {
+                offset: total_bytes,
+                attempts,
+            });
+        }
+        lost.append(&mut lost_now);
+        total_bytes += bytes_read as u64;
+    }
+
+    // Flush all destination files
+    let mut finished_at = vec![None; dest_files.len()];
+    for (index, (sink, path)) in dest_files.iter_mut().zip(&dest_paths).enumerate() {
+        if !active[index] {
+            continue;
+        }
+        let file = matches!(sink, Sink::File(_));
+        let flushed = async {
+            match sink {
+                Sink::File(file) => {
+                    file.flush().await?;
+                    // A hole at the end has nothing written after it to give the file its size
+                    if sparse {
+                        file.set_len(total_bytes).await?;
+                    }
+                }
+                Sink::Archive(archive) => archive.end_entry().await?,
+                Sink::Failed => unreachable!("Failed destinations aren't flushed"),
+            }
+            io::Result::Ok(())
+        };
+        match activity.write(index, flushed).await {
+            Ok(()) => finished_at[index] = Some(SystemTime::now()),
+            Err(e) if continue_on_error && file => {
+                active[index] = false;
+                failed[index] = Some(destination_failed(index, path, e));
+            }
+            Err(e) => return Err(destination_failed(index, path, e)),
+        }
+    }
+
+    let mut read_back_hashes = Vec::new();
+    if let (true, Some(algorithm)) = (read_back, algorithm) {
+        for (sink, _) in dest_files
+            .iter_mut()
+            .zip(&active)
+            .filter(|(_, active)| **active)
+        {
+            let Sink::File(file) = sink else {
+                continue;
+            };
+            file.seek(io::SeekFrom::Start(0)).await?;
+            let mut hasher = FileHasher::new(algorithm);
+            loop {
+                let bytes_read = file.read(read_buffer).await?;
+                if bytes_read == 0 {
+                    break;
+                }
+                hasher.update(&read_buffer[..bytes_read]);
+            }
+            read_back_hashes.push(hasher.finish());
+        }
+    }
+
+    // A partial copy on a destination that was given up on would pass for a whole one. One that
+    // couldn't be opened was never written.
+    let opened: Vec<bool> = dest_files
+        .iter()
+        .map(|sink| matches!(sink, Sink::File(_)))
+        .collect();
+    drop(dest_files);
+    for index in (0..active.len()).filter(|index| !active[*index] && opened[*index]) {
+        let _ = tokio::fs::remove_file(long_path(&dest_paths[index])).await;
+    }
+    if all_failed(&failed) {
+        return Err(failed
+            .into_iter()
+            .flatten()
+            .next()
+            .expect("Every destination failed"));
+    }
+    let dropped: Vec<usize> = (0..active.len())
+        .filter(|index| !active[*index] && failed[*index].is_none())
+        .collect();
+    let failed = failed
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, error)| Some((index, error?.to_string())))
+        .collect();
+
+    Ok(BatchCopy {
+        size: total_bytes,
+        source_hash: hasher.map(FileHasher::finish),
+        read_back: read_back_hashes,
+        reconnected,
+        read_retries: retries,
+        lost,
+        dropped,
+        failed,
+        finished_at,
+    })
+}
+
+/// Whether a batch has destinations, and every one of them failed.
+fn all_failed(failed: &[Option<io::Error>]) -> bool {
+    !failed.is_empty() && failed.iter().all(Option::is_some)
+}
+
+/// When the job was done with one destination.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DestinationTimes {
+    /// When the last file written there was complete.
+    pub copied_at: Option<SystemTime>,
+    /// When the last file there was verified.
+    pub verified_at: Option<SystemTime>,
+}
+
+/// Notes when each destination finished its copy of a file. `finished` is by index in the batch,
+/// which held the destinations that are `in_batch`.
+fn note_copied(times: &mut [DestinationTimes], in_batch: &[bool], finished: &[Option<SystemTime>]) {
+    let batch = times
+        .iter_mut()
+        .zip(in_batch)
+        .filter(|(_, in_batch)| **in_batch);
+    for ((times, _), finished) in batch.zip(finished) {
+        if finished.is_some() {
+            times.copied_at = *finished;
+        }
+    }
+}
+
+/// Notes that a file was just verified at the destinations that are `checked`.
+fn note_verified(times: &mut [DestinationTimes], checked: &[bool]) {
+    let now = SystemTime::now();
+    for (times, _) in times
+        .iter_mut()
+        .zip(checked)
+        .filter(|(_, checked)| **checked)
+    {
+        times.verified_at = Some(now);
+    }
+}
+
+/// Where a batch copy writes one destination's copy.
+enum Sink<'a> {
+    File(File),
+    /// An entry of an archive destination, which is only ever written front to back.
+    Archive(&'a mut ArchiveWriter),
+    /// A destination file that couldn't be opened, which the copy went on without.
+    Failed,
+}
+
+/// Writes `chunk`, or skips over it if it is a `hole`.
+async fn write_chunk(file: &mut File, chunk: &[u8], hole: bool) -> io::Result<()> {
+    match hole {
+        true => file
+            .seek(io::SeekFrom::Current(chunk.len() as i64))
+            .await
+            .map(|_| ()),
+        false => file.write_all(chunk).await,
+    }
+}
+
+/// Writes `chunk`, which starts at `offset` of the file at `path`. If that fails and a
+/// `reconnect` timeout is given, e.g. because a network share dropped, the file is reopened
+/// every few seconds until the chunk can be written again after the bytes before it. Returns
+/// whether it had to reconnect; once the timeout passes, the original error is returned.
+async fn write_chunk_resuming(
+    file: &mut File,
+    path: &Path,
+    offset: u64,
+    chunk: &[u8],
+    hole: bool,
+    reconnect: Option<Duration>,
+) -> io::Result<bool> {
+    let Err(e) = write_chunk(file, chunk, hole).await else {
+        return Ok(false);
+    };
+    let Some(timeout) = reconnect else {
+        return Err(e);
+    };
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(RECONNECT_POLL_INTERVAL).await;
+        let Ok(mut reopened) = reopen_at(path, offset).await else {
+            continue;
+        };
+        if write_chunk(&mut reopened, chunk, hole).await.is_ok() {
+            *file = reopened;
+            return Ok(true);
+        }
+    }
+    Err(e)
+}
+
+/// Reopens a partly written destination and positions it at `offset`, dropping anything after.
+/// A file that is shorter, e.g. because it ends in a hole that was only skipped over, is extended
+/// to `offset` with zeros. Verification tells if the share really lost data while it was away.
+async fn reopen_at(path: &Path, offset: u64) -> io::Result<File> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(long_path(path))
+        .await?;
+    file.set_len(offset).await?;
+    file.seek(io::SeekFrom::Start(offset)).await?;
+    Ok(file)
+}
+
+/// Whether the file has holes, i.e. ranges the filesystem doesn't store. Copying such a file
+/// densely can multiply its footprint, e.g. for disk images. Where holes can't be found this way
+/// (Windows included), files are treated as dense.
+fn has_holes(path: &Path) -> bool {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd"
+    ))]
+    {
+        use std::os::fd::AsRawFd;
+
+        let Ok(file) = std::fs::File::open(long_path(path)) else {
+            return false;
+        };
+        let Ok(len) = file.metadata().map(|metadata| metadata.len()) else {
+            return false;
+        };
+        // Without holes, the first one is the virtual hole at the end of the file
+        let first_hole = unsafe { libc::lseek(file.as_raw_fd(), 0, libc::SEEK_HOLE) };
+        first_hole >= 0 && (first_hole as u64) < len
+    }
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd"
+    )))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Chunks an archive destination may lag behind the other destinations of a batch.
+const ARCHIVE_QUEUE: usize = 4;
+
+/// Writes the files of a job into an archive destination, one entry after another, on a blocking
+/// thread of its own. The copy hands each chunk over as it reads it, so the archive fills along
+/// with the folder destinations. The archive is written next to `root` and only takes its place
+/// once finished, so a job that ends early never leaves a partial archive, nor replaces a good one,
+/// under its name.
+#[derive(Debug)]
+pub struct ArchiveWriter {
+    root: PathBuf,
+    /// Where the archive is written until it is finished.
+    partial: PathBuf,
+    commands: mpsc::Sender<ArchiveCommand>,
+    /// Gone once it was waited for after failing.
+    writer: Option<tokio::task::JoinHandle<io::Result<()>>>,
+}
+
+#[derive(Debug)]
+enum ArchiveCommand {
+    Start {
+        name: PathBuf,
+        size: u64,
+        modified: SystemTime,
+    },
+    Data(Vec<u8>),
+    /// Ends the entry, answering once everything before it is written.
+    End(oneshot::Sender<()>),
+}
+
+impl ArchiveWriter {
+    /// Creates the archive for `root`, and the folder it goes in. A file already at `root` stays
+    /// until the archive is finished.
+    pub async fn create(root: &Path, kind: DestinationKind) -> io::Result<Self> {
+        if !kind.is_archive() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Not an archive: {}", root.display()),
+            ));
+        }
+        if let Some(parent) = root.parent() {
+            tokio::fs::create_dir_all(long_path(parent)).await?;
+        }
+        ensure_not_directory(root).await?;
+        allow_overwrite(root).await?;
+        let partial = partial_archive(root);
+        let file = File::create(long_path(&partial)).await?.into_std().await;
+        let (commands, received) = mpsc::channel(ARCHIVE_QUEUE);
+        let writer = tokio::task::spawn_blocking(move || match kind {
+            DestinationKind::Tar => write_tar(file, received),
+            _ => write_zip(file, received),
+        });
+        Ok(ArchiveWriter {
+            root: root.to_path_buf(),
+            partial,
+            commands,
+            writer: Some(writer),
+        })
+    }
+
+    /// Starts the entry for `path`, a destination path under the archive's own, that holds
+    /// `size` bytes.
+    async fn start_entry(
+        &mut self,
+        path: &Path,
+        size: u64,
+        modified: SystemTime,
+    ) -> io::Result<()> {
+        let name = nfc_path(path.strip_prefix(&self.root).unwrap_or(path)).into_owned();
+        self.send(ArchiveCommand::Start {
+            name,
+            size,
+            modified,
+        })
+        .await
+    }
+
+    async fn write(&mut self, chunk: &[u8]) -> io::Result<()> {
+        self.send(ArchiveCommand::Data(chunk.to_vec())).await
+    }
+
+    async fn end_entry(&mut self) -> io::Result<()> {
+        let (done, written) = oneshot::channel();
+        self.send(ArchiveCommand::End(done)).await?;
+        match written.await {
+            Ok(()) => Ok(()),
+            Err(_) => Err(self.failure().await),
+        }
+    }
+
+    /// Writes what the archive needs after its last entry, e.g. the central directory of a ZIP
+    /// file, closes it and moves it to its `root`.
+    pub async fn finish(self) -> io::Result<()> {
+        let ArchiveWriter {
+            root,
+            partial,
+            commands,
+            writer,
+        } = self;
+        drop(commands);
+        match writer {
+            Some(writer) => writer.await??,
+            None => return Err(io::Error::other("Archive failed earlier")),
+        }
+        tokio::fs::rename(long_path(&partial), long_path(&root)).await
+    }
+
+    /// Gives up on the archive, e.g. because the job was stopped, and removes what was written of
+    /// it. A file already at `root` is left alone.
+    pub async fn abort(self) {
+        let ArchiveWriter {
+            partial,
+            commands,
+            writer,
+            ..
+        } = self;
+        drop(commands);
+        // The writer gives up on an open entry, and has to let go of the file before it is gone
+        if let Some(writer) = writer {
+            let _ = writer.await;
+        }
+        let _ = tokio::fs::remove_file(long_path(&partial)).await;
+    }
+
+    async fn send(&mut self, command: ArchiveCommand) -> io::Result<()> {
+        match self.commands.send(command).await {
+            Ok(()) => Ok(()),
+            Err(_) => Err(self.failure().await),
+        }
+    }
+
+    /// Why the writer stopped taking commands.
+    async fn failure(&mut self) -> io::Error {
+        match self.writer.take() {
+            Some(writer) => match writer.await {
+                Ok(Err(e)) => e,
+                Ok(Ok(())) => io::Error::other("Archive closed early"),
+                Err(e) => e.into(),
+            },
+            None => io::Error::other("Archive failed earlier"),
+        }
+    }
+}
+
+/// Where the archive for `root` is written until it is finished: `root` with `.part` added.
+fn partial_archive(root: &Path) -> PathBuf {
+    let mut name = root.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    root.with_file_name(name)
+}
+
+fn write_zip(file: std::fs::File, mut commands: mpsc::Receiver<ArchiveCommand>) -> io::Result<()> {
+    let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(file));
+    // Finishing the archive with an entry still open would seal its partial data as the file
+    let mut in_entry = false;
+    while let Some(command) = commands.blocking_recv() {
+        match command {
+            ArchiveCommand::Start {
+                name,
+                size,
+                modified,
+            } => {
+                let options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Stored)
+                    .large_file(size >= zip::ZIP64_BYTES_THR)
+                    .last_modified_time(zip_time(modified));
+                zip.start_file(slash_path(&name), options)?;
+                in_entry = true;
+            }
+            ArchiveCommand::Data(chunk) => zip.write_all(&chunk)?,
+            ArchiveCommand::End(done) => {
+                in_entry = false;
+                let _ = done.send(());
+            }
+        }
+    }
+    if in_entry {
+        return Err(io::Error::other("Archive entry ended early"));
+    }
+    zip.finish()?.flush()
+}
+
+/// `time` as a ZIP timestamp, in UTC. Times ZIP can't hold, before 1980 or after 2107, become
+/// its earliest.
+fn zip_time(time: SystemTime) -> zip::DateTime {
+    let ((year, month, day), seconds) = utc_date(time);
+    u16::try_from(year)
+        .ok()
+        .and_then(|year| {
+            zip::DateTime::from_date_and_time(
+                year,
+                month as u8,
+                day as u8,
+                (seconds / 3600) as u8,
+                (seconds / 60 % 60) as u8,
+                (seconds % 60) as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default()
+}
+
+fn write_tar(file: std::fs::File, mut commands: mpsc::Receiver<ArchiveCommand>) -> io::Result<()> {
+    let mut tar = tar::Builder::new(std::io::BufWriter::new(file));
+    while let Some(command) = commands.blocking_recv() {
+        let ArchiveCommand::Start {
+            name,
+            size,
+            modified,
+        } = command
+        else {
+            return Err(io::Error::other("Archive data outside an entry"));
+        };
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(size);
+        header.set_mode(0o644);
+        header.set_mtime(
+            modified
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+        );
+        let mut entry = TarEntry {
+            commands: &mut commands,
+            chunk: Vec::new(),
+            offset: 0,
+            remaining: size,
+            ended: None,
+        };
+        tar.append_data(&mut header, &name, &mut entry)?;
+        entry.finish()?;
+    }
+    tar.into_inner()?.flush()
+}
+
+/// The data of a tar entry as the copy hands it over. A tar header gives the size up front, so a
+/// file that grew while it was copied is cut, and one that shrank is padded with zeros; either
+/// way the entry won't match the source when verified.
+struct TarEntry<'a> {
+    commands: &'a mut mpsc::Receiver<ArchiveCommand>,
+    chunk: Vec<u8>,
+    offset: usize,
+    remaining: u64,
+    /// Set once the copy ended the entry.
+    ended: Option<oneshot::Sender<()>>,
+}
+
+impl std::io::Read for TarEntry<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let wanted = buf
+                .len()
+                .min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+            if wanted == 0 {
+                return Ok(0);
+            }
+            if self.offset < self.chunk.len() {
+                let n = wanted.min(self.chunk.len() - self.offset);
+                buf[..n].copy_from_slice(&self.chunk[self.offset..self.offset + n]);
+                self.offset += n;
+                self.remaining -= n as u64;
+                return Ok(n);
+            }
+            if self.ended.is_some() {
+                buf[..wanted].fill(0);
+                self.remaining -= wanted as u64;
+                return Ok(wanted);
+            }
+            self.receive()?;
+        }
+    }
+}
+
+impl TarEntry<'_> {
+    fn receive(&mut self) -> io::Result<()> {
+        match self.commands.blocking_recv() {
+            Some(ArchiveCommand::Data(chunk)) => {
+                self.chunk = chunk;
+                self.offset = 0;
+                Ok(())
+            }
+            Some(ArchiveCommand::End(done)) => {
+                self.ended = Some(done);
+                Ok(())
+            }
+            Some(ArchiveCommand::Start { .. }) | None => {
+                Err(io::Error::other("Archive entry ended early"))
+            }
+        }
+    }
+
+    /// Skips whatever the copy hands over past the entry's size, and answers the end of the entry.
+    fn finish(mut self) -> io::Result<()> {
+        while self.ended.is_none() {
+            self.receive()?;
+        }
+        if let Some(done) = self.ended {
+            let _ = done.send(());
+        }
+        Ok(())
+    }
+}
+
+/// Hashes every file in the archive at `root`, by its path in the archive. Reading stops with an
+/// `Interrupted` error once `keep_going` returns `false`.
+fn hash_archive_entries(
+    root: &Path,
+    kind: DestinationKind,
+    algorithm: HashAlgorithm,
+    keep_going: impl Fn() -> bool,
+) -> io::Result<HashMap<PathBuf, Digest>> {
+    let file = std::io::BufReader::new(std::fs::File::open(long_path(root))?);
+    let mut hashes = HashMap::new();
+    match kind {
+        DestinationKind::Tar => {
+            let mut archive = tar::Archive::new(file);
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                if entry.header().entry_type() != tar::EntryType::Regular {
+                    continue;
+                }
+                let name = entry.path()?.into_owned();
+                hashes.insert(name, hash_reader(&mut entry, algorithm, &keep_going)?);
+            }
+        }
+        _ => {
+            let mut archive = zip::ZipArchive::new(file)?;
+            for index in 0..archive.len() {
+                let mut entry = archive.by_index(index)?;
+                if entry.is_dir() {
+                    continue;
+                }
+                let name = PathBuf::from(entry.name()?.into_owned());
+                hashes.insert(name, hash_reader(&mut entry, algorithm, &keep_going)?);
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+fn hash_reader(
+    reader: &mut impl std::io::Read,
+    algorithm: HashAlgorithm,
+    keep_going: impl Fn() -> bool,
+) -> io::Result<Digest> {
+    let mut hasher = FileHasher::new(algorithm);
+    let mut buffer = vec![0; 1024 * 1024];
+    loop {
+        if !keep_going() {
+            return Err(stopped_error());
+        }
+        match reader.read(&mut buffer)? {
+            0 => return Ok(hasher.finish()),
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+}
+
+/// Reads back the archives among `dest`, `passes` times over, dropping the cached archive before
+/// each pass, for verification to look the files up in. Each destination has one set of entry
+/// hashes per pass, or none if it is a folder. An archive that isn't there has no entries.
+async fn read_archives(
+    dest: &[PathBuf],
+    algorithm: HashAlgorithm,
+    passes: u32,
+    stop: &CancellationToken,
+) -> io::Result<Vec<Vec<HashMap<PathBuf, Digest>>>> {
+    let mut archives = Vec::with_capacity(dest.len());
+    for root in dest {
+        let kind = DestinationKind::of(root);
+        let mut hashes = Vec::new();
+        if kind.is_archive() {
+            for _ in 0..passes.max(1) {
+                let (root, stop) = (root.clone(), stop.clone());
+                let entries = tokio::task::spawn_blocking(move || {
+                    drop_cached(&root);
+                    hash_archive_entries(&root, kind, algorithm, || !stop.is_cancelled())
+                })
+                .await?;
+                hashes.push(match entries {
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+                    entries => entries?,
+                });
+            }
+        }
+        archives.push(hashes);
+    }
+    Ok(archives)
+}
+
+/// What verification found for `file` in an archive, going by its entries' hashes.
+fn archive_hash(entries: &HashMap<PathBuf, Digest>, file: &Path) -> DestinationHash {
+    entries
+        .get(nfc_path(file).as_ref())
+        .map_or(DestinationHash::MissingAtDestination, |digest| {
+            DestinationHash::Hashed(digest.clone())
+        })
+}
+
+/// How a job ended, when it didn't fail.
+#[derive(Clone, Debug)]
+pub enum JobOutcome<T> {
+    Completed(T),
+    /// Stopped through the job's `stop` or `finish` token; holds the work done until then.
+    Cancelled(T),
+}
+
+/// What a caller holds to follow and steer a running copy or verification.
+#[derive(Debug)]
+pub struct JobHandle {
+    /// The job's progress, sent as it changes.
+    pub progress: watch::Sender<Progress>,
+    /// The latest of the job's warnings, up to [`WARNING_FEED_LIMIT`], each sent before the
+    /// progress that counts it.
+    pub warnings: watch::Sender<Vec<JobWarning>>,
+    /// Cancelling it ends the job as soon as it can, even partway through a file.
+    pub stop: CancellationToken,
+    /// Cancelling it ends the job once the files it is working on are done.
+    pub finish: CancellationToken,
+    /// How many files a verification works on at once, which may change while it runs. Copies
+    /// go one file at a time.
+    pub workers: watch::Receiver<usize>,
+}
+
+impl JobHandle {
+    /// A handle that reports on `progress` and `warnings`, with tokens of its own, for a job that
+    /// works on one file at a time.
+    pub fn new(
+        progress: watch::Sender<Progress>,
+        warnings: watch::Sender<Vec<JobWarning>>,
+    ) -> Self {
+        JobHandle {
+            progress,
+            warnings,
+            stop: CancellationToken::new(),
+            finish: CancellationToken::new(),
+            workers: watch::channel(1).1,
+        }
+    }
+}
+
+/// How a copy or verification ended, as the user is told. A job they stopped didn't fail, and one
+/// that finished with files that didn't make it isn't the same as a clean one either.
+#[derive(Clone, Debug)]
+pub enum JobEnd<T> {
+    Completed(T),
+    /// Finished, but with files that failed or didn't match.
+    CompletedWithWarnings(T),
+    /// Stopped by an error, worded for the user.
+    Failed(String),
+    /// Stopped through the job's `stop` or `finish` token; holds the work done until then.
+    Cancelled(T),
+}
+
+impl<T> JobEnd<T> {
+    /// Sorts how a job ended. `warned` tells whether finished work needs a second look, and
+    /// `describe` words an error for the user.
+    pub fn new(
+        result: io::Result<JobOutcome<T>>,
+        warned: impl FnOnce(&T) -> bool,
+        describe: impl FnOnce(io::Error) -> String,
+    ) -> Self {
+        match result {
+            Ok(JobOutcome::Completed(done)) => match warned(&done) {
+                true => JobEnd::CompletedWithWarnings(done),
+                false => JobEnd::Completed(done),
+            },
+            Ok(JobOutcome::Cancelled(done)) => JobEnd::Cancelled(done),
+            Err(e) => JobEnd::Failed(describe(e)),
+        }
+    }
+}
+
+/// Order in which files are copied. Sorting by size has to wait for the scan to finish.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CopyOrder {
+    /// Lexicographic by relative path.
+    #[default]
+    Path,
+    /// Makes the time estimate settle early.
+    LargestFirst,
+    /// Surfaces permission problems quickly.
+    SmallestFirst,
+}
+
+impl CopyOrder {
+    pub const ALL: [CopyOrder; 3] = [
+        CopyOrder::Path,
+        CopyOrder::LargestFirst,
+        CopyOrder::SmallestFirst,
+    ];
+}
+
+impl fmt::Display for CopyOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CopyOrder::Path => write!(f, "path"),
+            CopyOrder::LargestFirst => write!(f, "largest-first"),
+            CopyOrder::SmallestFirst => write!(f, "smallest-first"),
+        }
+    }
+}
+
+/// Where the destination hashes of a report come from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VerifyMethod {
+    /// Each destination is read back from disk and hashed.
+    #[default]
+    ReadBack,
+    /// Destinations are hashed from the bytes sent to them while copying. Nothing is read back,
+    /// so this can't catch data the media stored wrong.
+    Stream,
+    /// Each destination is read back through the handle that wrote it, right after the file is
+    /// copied, so a bad drive fails fast. The data may come from the OS cache.
+    Handle,
+}
+
+/// How far the copies of a verified job were checked, least first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VerificationLevel {
+    /// Checked as they were copied, from the bytes sent or through the handle that wrote them,
+    /// which may come from the OS cache. They are yet to be read back from the drives.
+    Copy,
+    /// Every copy was read back from its drive after the copy.
+    Archive,
+}
+
+impl fmt::Display for VerificationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationLevel::Copy => write!(f, "copy verified"),
+            VerificationLevel::Archive => write!(f, "archive verified"),
+        }
+    }
+}
+
+impl VerifyMethod {
+    pub const ALL: [VerifyMethod; 3] = [
+        VerifyMethod::ReadBack,
+        VerifyMethod::Stream,
+        VerifyMethod::Handle,
+    ];
+}
+
+impl fmt::Display for VerifyMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyMethod::ReadBack => write!(f, "read-back"),
+            VerifyMethod::Stream => write!(f, "stream"),
+            VerifyMethod::Handle => write!(f, "handle-read-back"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CopyOptions {
+    pub order: CopyOrder,
+    /// Verify each file right after it is copied, instead of in a separate pass.
+    pub verify: Option<HashOptions>,
+    /// How `verify` gets the destination hashes.
+    pub verify_method: VerifyMethod,
+    /// Copy a file once more if the source changed while it was being copied.
+    pub recopy_changed: bool,
+    /// Keep files that already exist at a destination with the source's size instead of
+    /// overwriting them. A file of another size is copied again.
+    pub skip_existing: bool,
+    /// Before keeping an existing file, hash it and the source, and copy it again if they
+    /// differ.
+    pub verify_existing: bool,
+    /// Read each source file a second time and compare hashes, to catch a failing card that
+    /// returns different data on each read.
+    pub double_read: bool,
+    /// Give copied files and folders the modification times of their originals.
+    pub preserve_timestamps: bool,
+    /// Give copied files and folders the owner and group of their originals.
+    pub preserve_ownership: bool,
+    /// How long a destination on a network share may be unreachable before a write to it fails.
+    /// `None` fails at once.
+    pub reconnect_timeout: Option<Duration>,
+    /// How many more times a failed read of the source is tried before the file fails.
+    pub read_retries: u32,
+    /// Salvage source files with parts that can't be read, rather than failing them, and hash
+    /// what was copied with this algorithm, which verification should use too.
+    pub salvage: Option<HashAlgorithm>,
+    /// Files copied more than this many times slower than the job's median rate are flagged;
+    /// `None` doesn't look.
+    pub slow_file_factor: Option<f64>,
+    /// Free space to leave on each destination. A file that would go into it is treated like a
+    /// full destination.
+    pub space_margin: Option<SpaceMargin>,
+    /// Go through the job without writing anything to the destinations.
+    pub dry_run: Option<DryRun>,
+    /// How long the copy may move no data before it is reported as stalled; `None` doesn't
+    /// watch.
+    pub stall_after: Option<Duration>,
+    /// Go on past a file that can't be copied, and past a destination that can't take a file,
+    /// listing each in [`CopySummary::failed`]. Otherwise the first failure ends the job.
+    pub continue_on_error: bool,
+}
+
+/// How a dry run stands in for copying the files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DryRun {
+    /// Only walks the job, which takes moments.
+    Walk,
+    /// Reads every source file as the copy would, which shows how long the card takes and which
+    /// files it fails to read.
+    ReadSource,
+}
+
+#[derive(Clone, Debug)]
+pub struct CopySummary {
+    pub total_bytes: u64,
+    pub order: CopyOrder,
+    /// Every copied file, in copy order.
+    pub files: Vec<CopiedFile>,
+    /// Verification of every copied file, if it was verified while copying.
+    pub report: Option<ChecksumReport>,
+    /// Copied files and folders whose owner couldn't be set to the source's.
+    pub ownership_failures: usize,
+    /// Every warning of the job, in the order they happened.
+    pub warnings: Vec<JobWarning>,
+    /// Files that took far longer than the others, in copy order.
+    pub slow_files: Vec<SlowFile>,
+    /// Modification times the job was limited to.
+    pub modified: ModifiedWindow,
+    /// The earlier offload whose files the job left out.
+    pub baseline: Option<Baseline>,
+    /// The ignore patterns whose files the job left out.
+    pub ignore: IgnoreRules,
+    /// Nothing was written: the job was a dry run.
+    pub dry_run: bool,
+    /// When the job was done with each destination, in the order of the job's destinations.
+    pub destination_times: Vec<DestinationTimes>,
+    /// Files whose copy failed, in copy order: once for each destination that failed to take a
+    /// file the others have, or once for a file that couldn't be copied at all, which is among
+    /// `files`, skipped.
+    pub failed: Vec<FailedFile>,
+    /// The whole source volume, if it was listed for the record.
+    pub volume_listing: Option<Box<VolumeListing>>,
+}
+
+impl CopySummary {
+    fn new(
+        source: &Path,
+        options: CopyOptions,
+        total_bytes: u64,
+        files: Vec<CopiedFile>,
+        verified: Vec<ChecksumReportSingleFile>,
+        destination_times: &[DestinationTimes],
+    ) -> Self {
+        let report = options.verify.map(|verify| ChecksumReport {
+            algorithm: verify.algorithm,
+            source_root: source.to_path_buf(),
+            files: verified,
+            source_changes: SourceChanges {
+                changed_during_copy: files
+                    .iter()
+                    .filter(|file| file.changed_during_copy)
+                    .map(|file| file.path.clone())
+                    .collect(),
+                unreliable_reads: files
+                    .iter()
+                    .filter(|file| file.unreliable_read)
+                    .map(|file| file.path.clone())
+                    .collect(),
+                ..Default::default()
+            },
+            copy_order: Some(options.order),
+            method: options.verify_method,
+            duplicates: duplicate_pairs(&files),
+            skipped: skipped_files(&files),
+            hard_links: hard_link_pairs(&files),
+            reconnected: reconnected_files(&files),
+            slow_files: Vec::new(),
+            baseline: None,
+            extra_files: Vec::new(),
+            stopped_at_failure: false,
+            unchecked_files: 0,
+            compared: false,
+            disabled_destinations: Vec::new(),
+            destination_times: destination_times.to_vec(),
+            hash_workers: Vec::new(),
+            job_id: None,
+            volume_listing: None,
+            folders: None,
+            finished_at: SystemTime::now(),
+        });
+        CopySummary {
+            total_bytes,
+            order: options.order,
+            files,
+            report,
+            ownership_failures: 0,
+            warnings: Vec::new(),
+            slow_files: Vec::new(),
+            modified: ModifiedWindow::default(),
+            baseline: None,
+            ignore: IgnoreRules::default(),
+            dry_run: options.dry_run.is_some(),
+            destination_times: destination_times.to_vec(),
+            failed: Vec::new(),
+            volume_listing: None,
+        }
+    }
+
+    /// How many files are missing at one destination or more because their copy failed.
+    pub fn failed_files(&self) -> usize {
+        let paths: HashSet<&Path> = self.failed.iter().map(|failure| &*failure.path).collect();
+        paths.len()
+    }
+
+    /// Writes the failed files as CSV, one row per failure, with the destination left empty
+    /// where the source couldn't be read.
+    pub fn export_failed<P: AsRef<Path>>(&self, to_file: P) -> Result<(), Box<dyn Error>> {
+        let mut writer = Writer::from_path(to_file)?;
+        writer.write_record(["Path", "Destination", "Error"])?;
+        for failure in &self.failed {
+            let destination = failure
+                .destination
+                .as_deref()
+                .map(|destination| destination.display().to_string());
+            writer.write_record([
+                &failure.path.display().to_string(),
+                &destination.unwrap_or_default(),
+                &failure.error,
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CopiedFile {
+    /// Path relative to the source.
+    pub path: PathBuf,
+    /// Bytes written to each destination.
+    pub size: u64,
+    /// The source changed while it was copied (and again on the re-copy, if enabled), so the
+    /// copy may be a torn snapshot.
+    pub changed_during_copy: bool,
+    /// A second read of the source gave a different hash or failed, so the card can't be trusted
+    /// for this file.
+    pub unreliable_read: bool,
+    /// Destinations where an existing file was kept instead of being overwritten.
+    pub kept_existing: usize,
+    /// Not copied, because it is identical to this other source file.
+    pub duplicate_of: Option<PathBuf>,
+    pub skipped: Option<SkipReason>,
+    /// Hard-linked at the destinations to the copy of this other file, like at the source.
+    pub linked_to: Option<PathBuf>,
+    /// A network destination dropped while this file was written, and the copy resumed.
+    pub reconnected: bool,
+    /// Parts of the source couldn't be read and were copied as zeros, in salvage mode.
+    pub salvaged: Option<Salvaged>,
+    /// Time spent reading the source and writing the copies, re-copies included. `None` if the
+    /// file wasn't copied, e.g. because it was linked.
+    pub transfer_time: Option<Duration>,
+}
+
+impl CopiedFile {
+    /// A file that was left out of the copy.
+    fn skipped(
+        path: PathBuf,
+        size: u64,
+        duplicate_of: Option<PathBuf>,
+        skipped: Option<SkipReason>,
+    ) -> Self {
+        CopiedFile {
+            path,
+            size,
+            changed_during_copy: false,
+            unreliable_read: false,
+            kept_existing: 0,
+            duplicate_of,
+            skipped,
+            linked_to: None,
+            reconnected: false,
+            salvaged: None,
+            transfer_time: None,
+        }
+    }
+}
+
+/// Why a source file wasn't copied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkipReason {
+    /// The user excluded it before the copy, e.g. because it couldn't be read.
+    Excluded,
+    /// The job wasn't allowed to read it, and the user chose to go on without it.
+    PermissionDenied,
+    /// A cloud placeholder the user chose not to download.
+    Placeholder,
+    /// Reading or writing it failed, and the job went on without it. [`CopySummary::failed`]
+    /// says why.
+    CopyFailed,
+}
+
+/// A source file whose copy failed, which the job went on without. What it had written of the
+/// file is removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailedFile {
+    /// Path relative to the source.
+    pub path: PathBuf,
+    /// The destination whose write failed, or `None` if the source couldn't be read.
+    pub destination: Option<PathBuf>,
+    pub error: String,
+}
+
+/// A file that had trouble without stopping the job, reported while the job runs.
+#[derive(Clone, Debug)]
+pub struct JobWarning {
+    pub at: SystemTime,
+    pub path: PathBuf,
+    pub kind: WarningKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WarningKind {
+    Skipped(SkipReason),
+    ChangedDuringCopy,
+    UnreliableRead,
+    /// A write failed and was retried once the network destination was back.
+    Reconnected,
+    /// A read of the source failed at `offset` and succeeded on attempt `attempts + 1`. The copy
+    /// is good, but the card may be failing.
+    ReadRetried {
+        offset: u64,
+        attempts: u32,
+    },
+    /// Parts of the file couldn't be read at all, and the copy has zeros there.
+    Salvaged {
+        bytes_lost: u64,
+    },
+    OwnershipNotKept,
+    TimestampNotKept,
+    /// The file doesn't match at some destination.
+    VerifyFailed,
+    /// The file was copied far slower than the job's median, which a destination drive retrying
+    /// internally often shows long before it fails a write.
+    SlowTransfer {
+        bytes_per_sec: u64,
+        median_bytes_per_sec: u64,
+    },
+    /// The copy at the destination with this index matched the source on one verification pass
+    /// but not on another.
+    UnstableMedia {
+        destination: usize,
+    },
+    /// Couldn't be written to the destination with this index, and is missing there, but was
+    /// copied to the others.
+    DestinationFailed {
+        destination: usize,
+    },
+    /// A folder below the job's depth limit, which was left out whole.
+    Pruned,
+    /// In the source now, but not copied, so not verified either.
+    AddedToSource,
+    /// Copied, but gone from the source, so it can't be verified.
+    RemovedFromSource,
+    /// Copied, but the source file has a different size now. It isn't verified.
+    ResizedOnSource,
+    /// Already at a destination with another size, e.g. cut short by an earlier job, so copied
+    /// again there instead of kept.
+    ReplacedOtherSize,
+    /// Already at a destination with the right size but other content, so copied again there.
+    ReplacedOtherContent,
+}
+
+/// How many of the latest warnings a running job keeps in its live feed. The job's result holds
+/// all of them.
+pub const WARNING_FEED_LIMIT: usize = 500;
+
+/// Collects a job's warnings and publishes the latest ones as they happen.
+struct WarningLog {
+    feed: watch::Sender<Vec<JobWarning>>,
+    all: Vec<JobWarning>,
+}
+
+impl WarningLog {
+    fn new(feed: watch::Sender<Vec<JobWarning>>) -> Self {
+        WarningLog {
+            feed,
+            all: Vec::new(),
+        }
+    }
+
+    /// Records a warning and counts it in `progress`, which the caller sends afterwards.
+    fn warn(&mut self, progress: &mut Progress, path: &Path, kind: WarningKind) {
+        tracing::warn!("{}: {kind:?}", path.display());
+        let warning = JobWarning {
+            at: SystemTime::now(),
+            path: path.to_path_buf(),
+            kind,
+        };
+        self.feed.send_modify(|feed| {
+            feed.push(warning.clone());
+            if feed.len() > WARNING_FEED_LIMIT {
+                feed.drain(..feed.len() - WARNING_FEED_LIMIT);
+            }
+        });
+        self.all.push(warning);
+        progress.warnings += 1;
+    }
+}
+
+/// Lists every file under `base_dir` without blocking the runtime, publishing the running count as
+/// a scanning phase. Cancelling `stop` returns the files found so far.
+pub async fn list_dir_files(
+    base_dir: &Path,
+    tx: &watch::Sender<Progress>,
+    stop: &CancellationToken,
+) -> io::Result<JobOutcome<Vec<PathBuf>>> {
+    let mut entries = scan_dir_files(base_dir, ScanLimits::default());
+    let mut files = Vec::new();
+    let mut progress = Progress {
+        phase: JobPhase::Scanning,
+        scanning: true,
+        ..Default::default()
+    };
+
+    while let Some(entry) = entries.recv().await {
+        if stop.is_cancelled() {
+            return Ok(JobOutcome::Cancelled(files));
+        }
+        // Without limits, the scan finds nothing but files
+        let ScanEntry::File(file, stamp) = entry? else {
+            continue;
+        };
+        files.push(file);
+        progress.total_files += 1;
+        progress.total_bytes += stamp.size;
+        tx.send_replace(progress.clone());
+    }
+    Ok(JobOutcome::Completed(files))
+}
+
+/// What the whole volume a source is on held when the job ran, for a record of what else was on
+/// the card. Nothing is hashed.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct VolumeListing {
+    /// The volume's mount point.
+    pub root: PathBuf,
+    /// Every file below `root`, relative to it, with its size. Junk files are left out.
+    pub files: Vec<(PathBuf, u64)>,
+}
+
+impl VolumeListing {
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// Lists every file on the volume `source` is on, reporting the count on `tx` as it goes. A
+/// source that isn't on a volume of its own, e.g. a folder on the system drive, can't be listed:
+/// that would walk the whole computer.
+pub async fn list_source_volume(
+    source: &Path,
+    tx: &watch::Sender<Progress>,
+    stop: &CancellationToken,
+) -> io::Result<JobOutcome<VolumeListing>> {
+    let root = {
+        let source = source.to_path_buf();
+        tokio::task::spawn_blocking(move || volume_space(&source)).await?
+    }
+    .map(|space| space.mount_point)
+    .filter(|root| root.parent().is_some())
+    .ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{} isn't on a volume of its own", source.display()),
+        )
+    })?;
+    let mut entries = scan_dir_files(&root, ScanLimits::default());
+    let mut listing = VolumeListing {
+        root,
+        files: Vec::new(),
+    };
+    let mut progress = Progress {
+        phase: JobPhase::Scanning,
+        scanning: true,
+        ..Default::default()
+    };
+    while let Some(entry) = entries.recv().await {
+        if stop.is_cancelled() {
+            return Ok(JobOutcome::Cancelled(listing));
+        }
+        let ScanEntry::File(file, stamp) = entry? else {
+            continue;
+        };
+        if is_junk_file(&file) {
+            continue;
+        }
+        listing.files.push((file, stamp.size));
+        progress.total_files += 1;
+        progress.total_bytes += stamp.size;
+        tx.send_replace(progress.clone());
+    }
+    Ok(JobOutcome::Completed(listing))
+}
+
+/// Every file under a source with its stamp at the time it was scanned.
+#[derive(Clone, Debug, Default)]
+pub struct SourceListing {
+    pub files: Vec<(PathBuf, FileStamp)>,
+    /// Folders below the scan's depth limit, which weren't entered.
+    pub pruned: Vec<PathBuf>,
+    /// The scan stopped at its file limit, so the listing is incomplete.
+    pub capped: bool,
+}
+
+impl SourceListing {
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|(_, stamp)| stamp.size).sum()
+    }
+
+    /// The files modified within `window`.
+    pub fn within(&self, window: ModifiedWindow) -> SourceListing {
+        SourceListing {
+            files: self
+                .files
+                .iter()
+                .filter(|(_, stamp)| window.contains(stamp.modified))
+                .cloned()
+                .collect(),
+            pruned: self.pruned.clone(),
+            capped: self.capped,
+        }
+    }
+
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files.iter().map(|(path, _)| path.clone()).collect()
+    }
+}
+
+/// Lists every file under `source` within `limits` with its stamp, without reporting progress.
+/// Cancelling `stop` returns the files found so far.
+pub async fn scan_source(
+    source: &Path,
+    limits: ScanLimits,
+    stop: &CancellationToken,
+) -> io::Result<JobOutcome<SourceListing>> {
+    let mut entries = scan_dir_files(source, limits);
+    let mut listing = SourceListing::default();
+    loop {
+        tokio::select! {
+            entry = entries.recv() => match entry {
+                Some(entry) => match entry? {
+                    ScanEntry::File(file, stamp) => listing.files.push((file, stamp)),
+                    ScanEntry::Pruned(folder) => listing.pruned.push(folder),
+                    ScanEntry::Capped => listing.capped = true,
+                },
+                None => return Ok(JobOutcome::Completed(listing)),
+            },
+            // Dropping the receiver stops the scan
+            _ = stop.cancelled() => return Ok(JobOutcome::Cancelled(listing)),
+        }
+    }
+}
+
+/// A source file that couldn't be opened or read before the copy.
+#[derive(Clone, Debug)]
+pub struct UnreadableFile {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// What the readability check found on the source.
+#[derive(Clone, Debug, Default)]
+pub struct Preflight {
+    /// The listing that was checked, with the stamps seen while checking it.
+    pub listing: SourceListing,
+    pub unreadable: Vec<UnreadableFile>,
+    /// Files an earlier offload already holds, which the job leaves out.
+    pub in_baseline: Vec<PathBuf>,
+    /// Files that were still being written while the source was looked at.
+    pub growing: Vec<PathBuf>,
+}
+
+/// How many files the readability check opens at once. Each check mostly waits on the card.
+const PREFLIGHT_PARALLELISM: usize = 16;
+
+/// How much of each file the readability check reads.
+const PREFLIGHT_READ_SIZE: usize = 4096;
+
+/// Opens every file in `listing` and reads its first block, so a card with unreadable files is
+/// caught before the copy starts rather than partway through. Cancelling `stop` returns the
+/// results so far.
+pub async fn check_source_readable(
+    source: &Path,
+    listing: SourceListing,
+    stop: &CancellationToken,
+) -> JobOutcome<Preflight> {
+    let checks = futures::stream::iter(listing.files)
+        .map(|(file, stamp)| async move {
+            if stop.is_cancelled() {
+                return None;
+            }
+            // Reading a placeholder would download it before the user had a say
+            if stamp.placeholder {
+                return Some((file, stamp, Ok(stamp)));
+            }
+            let path = source.join(&file);
+            let checked = async {
+                let mut handle = File::open(long_path(&path)).await?;
+                let mut block = vec![0; PREFLIGHT_READ_SIZE];
+                // A short read is fine; only a failing one matters
+                let _read = handle.read(&mut block).await?;
+                Ok::<_, io::Error>(FileStamp::from_metadata(&handle.metadata().await?))
+            };
+            Some((file, stamp, checked.await))
+        })
+        .buffered(PREFLIGHT_PARALLELISM)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut preflight = Preflight::default();
+    for (file, stamp, checked) in checks.into_iter().flatten() {
+        match checked {
+            Ok(stamp) => preflight.listing.files.push((file, stamp)),
+            Err(e) => {
+                preflight.unreadable.push(UnreadableFile {
+                    path: file.clone(),
+                    error: e.to_string(),
+                });
+                preflight.listing.files.push((file, stamp));
+            }
+        }
+    }
+    match stop.is_cancelled() {
+        true => JobOutcome::Cancelled(preflight),
+        false => JobOutcome::Completed(preflight),
+    }
+}
+
+/// How long the source is left alone between the two looks that tell whether it is still being
+/// written, unless configured.
+pub const DEFAULT_QUIESCENCE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Stamps every file in `listing` twice, `interval` apart, to catch a camera still recording to
+/// the card or a capture still writing into the folder. The listing is given the later stamps, and
+/// the files that changed size or modification time in between, or went away, are returned.
+/// Cancelling `stop` returns the files found so far.
+pub async fn find_growing_files(
+    source: &Path,
+    listing: &mut SourceListing,
+    interval: Duration,
+    stop: &CancellationToken,
+) -> JobOutcome<Vec<PathBuf>> {
+    let files = listing.paths();
+    let stamp_all = || {
+        futures::stream::iter(files.clone())
+            .map(|file| async move {
+                match stop.is_cancelled() {
+                    true => None,
+                    false => FileStamp::of(&source.join(file)).await,
+                }
+            })
+            .buffered(PREFLIGHT_PARALLELISM)
+            .collect::<Vec<_>>()
+    };
+    let first = stamp_all().await;
+    tokio::select! {
+        _ = tokio::time::sleep(interval) => {}
+        _ = stop.cancelled() => return JobOutcome::Cancelled(Vec::new()),
+    }
+    let second = stamp_all().await;
+    if stop.is_cancelled() {
+        return JobOutcome::Cancelled(Vec::new());
+    }
+
+    let mut growing = Vec::new();
+    for ((file, stamp), (before, after)) in
+        listing.files.iter_mut().zip(first.into_iter().zip(second))
+    {
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                if after.changed_since(&before) {
+                    growing.push(file.clone());
+                }
+                *stamp = after;
+            }
+            // Deleted between the looks, e.g. a temporary file of the recorder
+            (Some(_), None) => growing.push(file.clone()),
+            // Unreadable from the start is for the readability check to report
+            (None, _) => {}
+        }
+    }
+    JobOutcome::Completed(growing)
+}
+
+/// Bounds on when a source file was last modified for a job to copy it, e.g. to offload only the
+/// clips shot since yesterday evening from a card that holds several days.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ModifiedWindow {
+    /// Only files modified at or after this time.
+    pub after: Option<SystemTime>,
+    /// Only files modified before this time.
+    pub before: Option<SystemTime>,
+}
+
+impl ModifiedWindow {
+    pub fn is_unbounded(&self) -> bool {
+        self.after.is_none() && self.before.is_none()
+    }
+
+    /// Whether a file modified at `modified` falls in the window. A file without a modification
+    /// time is let through, since missing it from a backup unseen would be worse than copying it.
+    pub fn contains(&self, modified: Option<SystemTime>) -> bool {
+        let Some(modified) = modified else {
+            return true;
+        };
+        self.after.is_none_or(|after| modified >= after)
+            && self.before.is_none_or(|before| modified < before)
+    }
+}
+
+/// File at the root of a source whose patterns, in `.gitignore` syntax, name files that jobs leave
+/// out, e.g. the junk folders a camera writes.
+pub const IGNORE_FILE: &str = ".librecardignore";
+
+/// Ignore patterns for the files operating systems and file managers leave on a card, and for
+/// hidden files and folders in general.
+pub const SYSTEM_FILE_PATTERNS: [&str; 5] = [
+    ".*",
+    "Thumbs.db",
+    "desktop.ini",
+    "System Volume Information/",
+    "$RECYCLE.BIN/",
+];
+
+/// The ignore patterns a job applies to its source.
+#[derive(Clone, Debug, Default)]
+pub struct IgnoreRules {
+    /// Shared, since every job and report of the source holds the same patterns.
+    matcher: Option<Arc<Gitignore>>,
+}
+
+impl IgnoreRules {
+    /// Takes `patterns`, then reads those of `global`, then those of the [`IGNORE_FILE`] at
+    /// `source`; later patterns win where they disagree. A file that doesn't exist has no
+    /// patterns. This reads the files synchronously.
+    pub fn load(source: &Path, global: Option<&Path>, patterns: &[String]) -> io::Result<Self> {
+        let mut builder = GitignoreBuilder::new(source);
+        for pattern in patterns {
+            if let Err(e) = builder.add_line(None, pattern) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{pattern}: {e}"),
+                ));
+            }
+        }
+        let source_file = source.join(IGNORE_FILE);
+        for file in global.into_iter().chain([source_file.as_path()]) {
+            if !long_path(file).is_file() {
+                continue;
+            }
+            if let Some(e) = builder.add(long_path(file)) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {e}", file.display()),
+                ));
+            }
+        }
+        let matcher = builder.build().map_err(io::Error::other)?;
+        Ok(IgnoreRules {
+            matcher: (!matcher.is_empty()).then(|| Arc::new(matcher)),
+        })
+    }
+
+    /// Whether jobs leave out `file`, relative to the source. The ignore file itself is left out
+    /// too, unless a pattern includes it again.
+    pub fn ignores(&self, file: &Path) -> bool {
+        let matched = self
+            .matcher
+            .as_ref()
+            .map(|matcher| matcher.matched_path_or_any_parents(file, false));
+        match matched {
+            Some(matched) if matched.is_ignore() => true,
+            Some(matched) if matched.is_whitelist() => false,
+            _ => file == Path::new(IGNORE_FILE),
+        }
+    }
+}
+
+/// Source files a job leaves out.
+#[derive(Debug, Default)]
+pub struct SourcePlan {
+    /// Sets of identical files; only the first of each is copied.
+    pub duplicates: Vec<Vec<PathBuf>>,
+    /// Files the user chose to exclude, e.g. because they couldn't be read.
+    pub excluded: Vec<PathBuf>,
+    pub permission_denied: PermissionHandling,
+    /// Leave out cloud placeholders instead of downloading them.
+    pub skip_placeholders: bool,
+    /// Only copy files modified within this window. The rest are left out of the job entirely.
+    pub modified: ModifiedWindow,
+    /// An earlier offload whose files are left out of the job entirely, like those outside
+    /// `modified`.
+    pub baseline: Option<Baseline>,
+    /// Files the source's ignore patterns name are left out of the job entirely too.
+    pub ignore: IgnoreRules,
+    /// Folder levels below the source the job goes into. Deeper folders are left out, each
+    /// with a warning.
+    pub max_depth: Option<usize>,
+}
+
+/// An earlier offload of the same card, for copying only what was added to it since.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Baseline {
+    /// The report or checksum list of the earlier offload.
+    pub manifest: PathBuf,
+    /// Source files it already lists, which the job leaves out.
+    pub files: Vec<PathBuf>,
+}
+
+/// Files of `listing` that `manifest`, from an earlier offload, lists at the same path with the
+/// same size. With `compare_hashes`, or where the manifest has no size, the file is hashed with
+/// the manifest's algorithm and has to match as well; a file that can't be hashed isn't counted
+/// as listed, so it is copied again. Cancelling `stop` returns the files found so far.
+pub async fn files_in_manifest(
+    source: &Path,
+    listing: &SourceListing,
+    manifest: &Manifest,
+    compare_hashes: bool,
+    stop: &CancellationToken,
+) -> JobOutcome<Vec<PathBuf>> {
+    let algorithm = manifest.algorithm.and_then(HashAlgorithm::from_id);
+    let mut found = Vec::new();
+    for (file, stamp) in &listing.files {
+        if stop.is_cancelled() {
+            return JobOutcome::Cancelled(found);
+        }
+        let relative = normalize_manifest_path(&file.to_string_lossy());
+        let Some(entry) = manifest.entries.get(&relative) else {
+            continue;
+        };
+        if entry.size.is_some_and(|size| size != stamp.size) {
+            continue;
+        }
+        if compare_hashes || entry.size.is_none() {
+            // Hashing a placeholder would download it
+            let (Some(algorithm), false) = (algorithm, stamp.placeholder) else {
+                continue;
+            };
+            let options = HashOptions {
+                algorithm,
+                ..Default::default()
+            };
+            let stop_hash = stop.clone();
+            let hashed = compute_file_hash(source.join(file), options, move |_| {
+                !stop_hash.is_cancelled()
+            })
+            .await;
+            match hashed {
+                Err(_) if stop.is_cancelled() => return JobOutcome::Cancelled(found),
+                Ok(digest) if format!("{digest:x}") == entry.hash => {}
+                _ => continue,
+            }
+        }
+        found.push(file.clone());
+    }
+    JobOutcome::Completed(found)
+}
+
+/// The user's answer about a source file the job isn't allowed to read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PermissionChoice {
+    Skip,
+    /// Skip this file and any others the job isn't allowed to read.
+    SkipAll,
+    Abort,
+}
+
+/// How a copy handles source files it isn't allowed to read.
+#[derive(Debug, Default)]
+pub enum PermissionHandling {
+    /// End the job with the error.
+    #[default]
+    Fail,
+    SkipAll,
+    /// Publish the file on `asking` and wait for a choice on `choices`.
+    Ask {
+        asking: watch::Sender<Option<PathBuf>>,
+        choices: mpsc::UnboundedReceiver<PermissionChoice>,
+    },
+}
+
+impl PermissionHandling {
+    /// Whether to skip `file` instead of ending the job. Stopping the job while waiting for an
+    /// answer skips it, and the job ends before the next file.
+    async fn skip(&mut self, file: &Path, stop: &CancellationToken) -> bool {
+        let PermissionHandling::Ask { asking, choices } = self else {
+            return matches!(self, PermissionHandling::SkipAll);
+        };
+        asking.send_replace(Some(file.to_path_buf()));
+        let choice = tokio::select! {
+            choice = choices.recv() => choice,
+            _ = stop.cancelled() => Some(PermissionChoice::Skip),
+        };
+        asking.send_replace(None);
+        match choice {
+            Some(PermissionChoice::Skip) => true,
+            Some(PermissionChoice::SkipAll) => {
+                *self = PermissionHandling::SkipAll;
+                true
+            }
+            Some(PermissionChoice::Abort) | None => false,
+        }
+    }
+}
+
+/// The user's answer about a destination that ran out of space during a copy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpaceChoice {
+    /// Space was freed; write the rest of the file where it broke off.
+    Retry,
+    /// Go on without this destination. Its copy of the file is removed.
+    Drop,
+    Abort,
+}
+
+/// A destination that is full, for the user to decide about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FullDestination {
+    /// Index of the destination in the job.
+    pub index: usize,
+    pub path: PathBuf,
+    /// Bytes the job still has to write there.
+    pub needed: u64,
+    /// Free space left there, if the OS tells.
+    pub available: Option<u64>,
+    /// Part of the free space the safety margin keeps, which the job won't fill.
+    pub reserved: u64,
+}
+
+/// How a copy handles a destination that runs out of space.
+#[derive(Debug, Default)]
+pub enum SpaceHandling {
+    /// End the job with the error.
+    #[default]
+    Fail,
+    /// Publish the destination on `asking` and wait for a choice on `choices`.
+    Ask {
+        asking: watch::Sender<Option<FullDestination>>,
+        choices: mpsc::UnboundedReceiver<SpaceChoice>,
+    },
+}
+
+impl SpaceHandling {
+    /// What to do about the `full` destination. Stopping the job while waiting for an answer
+    /// aborts, since the file can't be finished without space.
+    async fn resolve(&mut self, full: FullDestination, stop: &CancellationToken) -> SpaceChoice {
+        let SpaceHandling::Ask { asking, choices } = self else {
+            return SpaceChoice::Abort;
+        };
+        asking.send_replace(Some(full));
+        let choice = tokio::select! {
+            choice = choices.recv() => choice,
+            _ = stop.cancelled() => None,
+        };
+        asking.send_replace(None);
+        choice.unwrap_or(SpaceChoice::Abort)
+    }
+}
+
+/// Asks about the destinations of a batch copy that fill up, through a `SpaceHandling`.
+pub struct SpacePrompt<'a> {
+    space: &'a mut SpaceHandling,
+    stop: &'a CancellationToken,
+    /// For each destination in the batch: its index in the job, its root, and what the job has
+    /// left to write there, the file in the batch included.
+    batch: Vec<(usize, PathBuf, u64)>,
+}
+
+impl<'a> SpacePrompt<'a> {
+    /// A prompt for the batch of the `written` ones of the job's `dest` roots.
+    fn new(
+        space: &'a mut SpaceHandling,
+        stop: &'a CancellationToken,
+        dest: &[PathBuf],
+        written: &[bool],
+        progress: &Progress,
+    ) -> Self {
+        let batch = (0..dest.len())
+            .filter(|index| written[*index])
+            .map(|index| {
+                let remaining = progress.destinations.get(index).map_or(0, |destination| {
+                    destination
+                        .total_bytes
+                        .saturating_sub(destination.written_bytes)
+                });
+                (index, dest[index].clone(), remaining)
+            })
+            .collect();
+        SpacePrompt { space, stop, batch }
+    }
+
+    /// What to do about the destination at index `target` of the batch, which ran out of space
+    /// after `offset` bytes of the file.
+    async fn ask(&mut self, target: usize, offset: u64) -> SpaceChoice {
+        let Some((index, root, remaining)) = self.batch.get(target).cloned() else {
+            return SpaceChoice::Abort;
+        };
+        let available = {
+            let root = root.clone();
+            tokio::task::spawn_blocking(move || volume_space(&root))
+                .await
+                .ok()
+                .flatten()
+                .map(|space| space.available)
+        };
+        let full = FullDestination {
+            index,
+            path: root,
+            needed: remaining.saturating_sub(offset),
+            available,
+            reserved: 0,
+        };
+        self.space.resolve(full, self.stop).await
+    }
+
+    /// Checks that writing `size` more bytes to each destination of the batch stays within its
+    /// budget, what it may take before the safety `margin`; `budgets` are by index in the job. A
+    /// destination that would go over is measured again, since space may have been freed
+    /// meanwhile, and then asked about like a full one. Returns the destinations, by their index
+    /// in the batch, the user gave up on.
+    async fn keep_margin(
+        &mut self,
+        margin: SpaceMargin,
+        budgets: &mut [Option<u64>],
+        size: u64,
+    ) -> io::Result<Vec<usize>> {
+        let mut dropped = Vec::new();
+        for (target, (index, root, remaining)) in self.batch.iter().enumerate() {
+            let index = *index;
+            while budgets[index].is_some_and(|budget| budget < size) {
+                let measured = {
+                    let root = root.clone();
+                    tokio::task::spawn_blocking(move || volume_space(&root)).await?
+                };
+                budgets[index] = measured.as_ref().map(|space| space.allowed(Some(margin)));
+                let Some(measured) = measured.filter(|_| budgets[index] < Some(size)) else {
+                    break;
+                };
+                let full = FullDestination {
+                    index,
+                    path: root.clone(),
+                    needed: *remaining,
+                    available: Some(measured.available),
+                    reserved: margin.reserved(measured.total),
+                };
+                match self.space.resolve(full, self.stop).await {
+                    SpaceChoice::Retry => {}
+                    SpaceChoice::Drop => {
+                        dropped.push(target);
+                        break;
+                    }
+                    SpaceChoice::Abort => {
+                        let error = io::Error::new(
+                            io::ErrorKind::StorageFull,
+                            "The file would go into the free space margin",
+                        );
+                        return Err(destination_failed(target, root, error));
+                    }
+                }
+            }
+        }
+        Ok(dropped)
+    }
+}
+
+/// How long to only scan before copying starts, so the first totals shown are meaningful.
+const SCAN_HEAD_START: Duration = Duration::from_secs(1);
+
+/// Copies every file under `source` to each destination, starting while the source is still being
+/// scanned. Cancelling the `job`'s `stop` ends the job before the next chunk, and the partial
+/// copies of the file being copied are removed. Cancelling its `finish` ends it at the next file
+/// boundary instead, once the file being copied is complete. Each file that has trouble is added
+/// to the warnings feed. A dry run goes through the same steps and progress, but never creates,
+/// writes or changes anything at the destinations.
+pub async fn copy_dirs(
+    source: &Path,
+    dest: &[PathBuf],
+    filters: &[FileFilter],
+    mut plan: SourcePlan,
+    mut space: SpaceHandling,
+    options: CopyOptions,
+    job: JobHandle,
+) -> io::Result<JobOutcome<CopySummary>> {
+    let JobHandle {
+        progress: tx,
+        warnings,
+        stop,
+        finish,
+        ..
+    } = job;
+    // Whatever would only touch the copies has nothing to work on in a dry run
+    let options = match options.dry_run {
+        Some(_) => CopyOptions {
+            verify: None,
+            recopy_changed: false,
+            preserve_timestamps: false,
+            preserve_ownership: false,
+            ..options
+        },
+        None => options,
+    };
+    let mut warnings = WarningLog::new(warnings);
+    let activity = CopyActivity::new(dest.len());
+    // Dropped with the job, however it ends
+    let _stall_watch = options.stall_after.map(|after| {
+        let done = CancellationToken::new();
+        spawn(watch_for_stalls(
+            activity.clone(),
+            after,
+            tx.clone(),
+            done.clone(),
+        ));
+        done.drop_guard()
+    });
+    let limits = ScanLimits {
+        max_depth: plan.max_depth,
+        max_files: None,
+    };
+    let mut entries = scan_dir_files(source, limits);
+    // Identical files past the first of their set are left out
+    let representatives: HashMap<&PathBuf, &PathBuf> = plan
+        .duplicates
+        .iter()
+        .flat_map(|set| set.iter().skip(1).map(move |copy| (copy, &set[0])))
+        .collect();
+    let excluded: HashSet<PathBuf> = plan.excluded.drain(..).collect();
+    let window = plan.modified;
+    let in_baseline: HashSet<PathBuf> = plan
+        .baseline
+        .iter()
+        .flat_map(|baseline| baseline.files.iter().cloned())
+        .collect();
+    let ignore = plan.ignore.clone();
+    // Files outside the date window, already offloaded or ignored aren't part of the job at all
+    let in_job = |file: &Path, stamp: &FileStamp| {
+        window.contains(stamp.modified) && !in_baseline.contains(file) && !ignore.ignores(file)
+    };
+    let mut ownership_failures = 0;
+    // Destinations the user gave up on after they ran out of space
+    let mut dropped = vec![false; dest.len()];
+    // Only network shares are worth waiting for; a local drive that fails a write is gone
+    let reconnect_timeouts: Vec<Option<Duration>> = match options.reconnect_timeout {
+        Some(timeout) => {
+            let dest = dest.to_vec();
+            tokio::task::spawn_blocking(move || {
+                dest.iter()
+                    .map(|root| is_network_path(root).then_some(timeout))
+                    .collect()
+            })
+            .await?
+        }
+        None => vec![None; dest.len()],
+    };
+    // Reading and writing one spinning drive at once makes it seek between the two
+    let on_source_disk = {
+        let (source, dest) = (source.to_path_buf(), dest.to_vec());
+        tokio::task::spawn_blocking(move || destinations_on_source_volume(&source, &dest)).await?
+    };
+    let written_to_source_disk =
+        |written: &[bool]| on_source_disk.iter().any(|&index| written[index]);
+    // What each destination may still take before the safety margin
+    let mut budgets: Vec<Option<u64>> = match options.space_margin {
+        Some(margin) => {
+            let dest = dest.to_vec();
+            tokio::task::spawn_blocking(move || {
+                dest.iter()
+                    .map(|root| Some(volume_space(root)?.allowed(Some(margin))))
+                    .collect()
+            })
+            .await?
+        }
+        None => vec![None; dest.len()],
+    };
+    // Archive destinations stay open for the whole job, taking one entry per file. A dry run
+    // leaves even the archive file alone.
+    let kinds: Vec<DestinationKind> = dest.iter().map(|root| DestinationKind::of(root)).collect();
+    let mut archives: Vec<Option<ArchiveWriter>> = Vec::with_capacity(dest.len());
+    // Files written to each destination, synced to its drive once the copy is done
+    let mut unsynced: Vec<Vec<PathBuf>> = vec![Vec::new(); dest.len()];
+    for (root, kind) in dest.iter().zip(&kinds) {
+        archives.push(match (kind.is_archive(), options.dry_run) {
+            (true, None) => Some(
+                ArchiveWriter::create(root, *kind)
+                    .await
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", root.display())))?,
+            ),
+            _ => None,
+        });
+    }
+    // Copied files with more hard links, with the destinations each was written to
+    let mut copied_links: HashMap<(u64, u64), (PathBuf, Vec<bool>)> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let mut files = Vec::new();
+    let mut verified = Vec::new();
+    let mut failed = Vec::new();
+    let mut destination_times = vec![DestinationTimes::default(); dest.len()];
+    // A job that ends early removes its unfinished archives, and still accounts for the files it
+    // got through
+    let cancelled = async |archives: Vec<Option<ArchiveWriter>>,
+                           total_bytes: u64,
+                           files: Vec<CopiedFile>,
+                           verified: Vec<ChecksumReportSingleFile>,
+                           times: &[DestinationTimes]|
+           -> io::Result<JobOutcome<CopySummary>> {
+        for archive in archives.into_iter().flatten() {
+            archive.abort().await;
+        }
+        Ok(JobOutcome::Cancelled(CopySummary::new(
+            source,
+            options,
+            total_bytes,
+            files,
+            verified,
+            times,
+        )))
+    };
+    // A copy that failed because of the source or one destination may leave the job to go on
+    // without the file
+    let mut settle_copy = async |copied: io::Result<BatchCopy>,
+                                 file: &Path,
+                                 written: &[bool],
+                                 targets: &[PathBuf],
+                                 archived: bool,
+                                 progress: &mut Progress| {
+        let e = match copied {
+            Ok(copy) => return Ok(Settled::Copied(copy)),
+            // Stopped partway through the file, whose partial copies are gone
+            Err(_) if stop.is_cancelled() => return Ok(Settled::Stopped),
+            Err(e) => e,
+        };
+        let source_path = source.join(file);
+        // Only the source matters here; a destination we can't write to fails the job
+        if e.kind() == io::ErrorKind::PermissionDenied
+            && File::open(long_path(&source_path)).await.is_err()
+        {
+            if !plan.permission_denied.skip(file, &stop).await {
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("{}: {e}", source_path.display()),
+                ));
+            }
+            return Ok(Settled::Skipped(SkipReason::PermissionDenied, None));
+        }
+        // An archive can't take back the part of the entry it was given
+        if archived || tokio::fs::metadata(long_path(source)).await.is_err() {
+            report_failed_destination(&tx, progress, written, &e);
+            return Err(e);
+        }
+        let failure = abandon_file(file, dest, written, targets, &e).await;
+        if !options.continue_on_error {
+            report_failed_destination(&tx, progress, written, &e);
+            return Err(e);
+        }
+        Ok(Settled::Skipped(SkipReason::CopyFailed, Some(failure)))
+    };
+    // The bar moves with every chunk written, not only with every file
+    let chunk_progress = |chunk: &ChunkProgress<'_>| {
+        tx.send_modify(|progress| progress.current_file_bytes += chunk.bytes);
+    };
+    // An archive is one file, open until the copy is done
+    for ((root, archive), unsynced) in dest.iter().zip(&archives).zip(&mut unsynced) {
+        if archive.is_some() {
+            unsynced.push(root.clone());
+        }
+    }
+    let mut progress = Progress {
+        phase: JobPhase::Scanning,
+        scanning: true,
+        destinations: unsynced
+            .iter()
+            .map(|unsynced| DestinationProgress {
+                pending_files: unsynced.len(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let mut total_bytes = 0;
+
+    let head_start = tokio::time::sleep(SCAN_HEAD_START);
+    tokio::pin!(head_start);
+    loop {
+        tokio::select! {
+            entry = entries.recv() => match entry {
+                Some(entry) => {
+                    enqueue(&mut queue, &mut progress, &mut warnings, filters, in_job, entry?);
+                    tx.send_replace(progress.clone());
+                }
+                None => {
+                    progress.scanning = false;
+                    break;
+                }
+            },
+            // Sorting by size needs the complete list
+            _ = &mut head_start, if options.order == CopyOrder::Path => break,
+            _ = stop.cancelled() => return cancelled(archives, 0, files, verified, &destination_times).await,
+            // Nothing is being copied yet
+            _ = finish.cancelled() => return cancelled(archives, 0, files, verified, &destination_times).await,
+        }
+    }
+    match options.order {
+        // The scanner already walks in path order
+        CopyOrder::Path => {}
+        CopyOrder::LargestFirst => queue
+            .make_contiguous()
+            .sort_by_key(|(_, stamp)| std::cmp::Reverse(stamp.size)),
+        CopyOrder::SmallestFirst => queue.make_contiguous().sort_by_key(|(_, stamp)| stamp.size),
+    }
+    progress.phase = JobPhase::Running;
+    tx.send_replace(progress.clone());
+
+    loop {
+        if stop.is_cancelled() || finish.is_cancelled() {
+            return cancelled(archives, total_bytes, files, verified, &destination_times).await;
+        }
+
+        // Take in whatever the scanner has found since the last file
+        while progress.scanning {
+            match entries.try_recv() {
+                Ok(entry) => enqueue(
+                    &mut queue,
+                    &mut progress,
+                    &mut warnings,
+                    filters,
+                    in_job,
+                    entry?,
+                ),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => progress.scanning = false,
+            }
+        }
+
+        let Some((file, stamp)) = queue.pop_front() else {
+            if !progress.scanning {
+                break;
+            }
+            // Copying caught up with the scan
+            match entries.recv().await {
+                Some(entry) => enqueue(
+                    &mut queue,
+                    &mut progress,
+                    &mut warnings,
+                    filters,
+                    in_job,
+                    entry?,
+                ),
+                None => progress.scanning = false,
+            }
+            continue;
+        };
+
+        let wanted: Vec<bool> = (0..dest.len())
+            .map(|index| wants_file(filters, index, &file))
+            .collect();
+        let duplicate_of = representatives.get(&file).map(|file| (*file).clone());
+        let skipped = if excluded.contains(&file) {
+            Some(SkipReason::Excluded)
+        } else if plan.skip_placeholders && stamp.placeholder {
+            Some(SkipReason::Placeholder)
+        } else {
+            None
+        };
+        if duplicate_of.is_some() || skipped.is_some() {
+            let skipped = CopiedFile::skipped(file, stamp.size, duplicate_of, skipped);
+            skip_file(
+                &tx,
+                &mut progress,
+                &mut warnings,
+                &mut files,
+                skipped,
+                &wanted,
+            );
+            continue;
+        }
+
+        tracing::debug!("Copying {}", file.display());
+        progress.current_file = Some(file.clone());
+        progress.current_file_size = stamp.size;
+        progress.current_file_bytes = 0;
+        // A stall names the file from what was last sent
+        tx.send_replace(progress.clone());
+        let source_path = source.join(&file);
+        let dest_paths: Vec<_> = dest.iter().map(|d| d.join(&file)).collect();
+        let mut kept = vec![false; dest.len()];
+        if options.skip_existing {
+            let existing_hash = HashOptions {
+                read_retries: options.read_retries,
+                ..options.verify.unwrap_or_default()
+            };
+            // Hashed once, when the first copy of the right size turns up
+            let mut source_digest = None;
+            let (mut other_size, mut other_content) = (false, false);
+            for (index, path) in dest_paths.iter().enumerate() {
+                // An archive is new with every job, so it holds nothing to keep
+                if !wanted[index] || kinds[index].is_archive() {
+                    continue;
+                }
+                let found = match tokio::fs::metadata(long_path(path)).await {
+                    Ok(metadata) => Some((path.clone(), metadata.len())),
+                    Err(_) => {
+                        let path = path.clone();
+                        tokio::task::spawn_blocking(move || {
+                            let found = find_by_nfc(&path)?;
+                            let size = std::fs::metadata(long_path(&found)).ok()?.len();
+                            Some((found, size))
+                        })
+                        .await?
+                    }
+                };
+                let Some((found, size)) = found else {
+                    continue;
+                };
+                if size != stamp.size {
+                    other_size = true;
+                    continue;
+                }
+                kept[index] = !options.verify_existing || {
+                    if source_digest.is_none() {
+                        source_digest = Some(
+                            compute_file_hash(&source_path, existing_hash, |_| true)
+                                .await
+                                .ok(),
+                        );
+                    }
+                    let existing = compute_file_hash(&found, existing_hash, |_| true)
+                        .await
+                        .ok();
+                    // A source or copy that can't be read is copied, and fails there if it must
+                    existing.is_some() && source_digest == Some(existing)
+                };
+                other_content |= !kept[index];
+            }
+            if other_size {
+                warnings.warn(&mut progress, &file, WarningKind::ReplacedOtherSize);
+            }
+            if other_content {
+                warnings.warn(&mut progress, &file, WarningKind::ReplacedOtherContent);
+            }
+        }
+        let mut written: Vec<bool> = (0..dest.len())
+            .map(|index| wanted[index] && !kept[index] && !dropped[index])
+            .collect();
+        if let Some(margin) = options.space_margin {
+            let over_margin = SpacePrompt::new(&mut space, &stop, dest, &written, &progress)
+                .keep_margin(margin, &mut budgets, stamp.size)
+                .await
+                .inspect_err(|e| report_failed_destination(&tx, &mut progress, &written, e))?;
+            if !over_margin.is_empty() {
+                drop_destinations(&tx, &mut progress, &mut written, &mut dropped, &over_margin);
+            }
+        }
+        let mut targets = in_batch(&dest_paths, &written);
+        let mut reconnect = in_batch(&reconnect_timeouts, &written);
+        // The destinations still count the file as written, but none is handed to a write
+        if options.dry_run.is_some() {
+            targets.clear();
+        }
+
+        let mut unprepared = prepare_targets(&targets, &in_batch(&kinds, &written)).await;
+        // The others can still take the file
+        if options.continue_on_error && unprepared.len() < targets.len() {
+            let unprepared: Vec<(usize, String)> = unprepared
+                .drain(..)
+                .map(|(index, e)| (index, e.to_string()))
+                .collect();
+            let left = left_behind(&written, &unprepared);
+            if !left.is_empty() {
+                fail_at_destinations(
+                    &file,
+                    dest,
+                    left,
+                    &mut written,
+                    &mut failed,
+                    &mut warnings,
+                    &mut progress,
+                );
+                targets = in_batch(&dest_paths, &written);
+                reconnect = in_batch(&reconnect_timeouts, &written);
+            }
+        }
+        if let Some((_, e)) = unprepared.into_iter().next() {
+            if !options.continue_on_error {
+                report_failed_destination(&tx, &mut progress, &written, &e);
+                return Err(e);
+            }
+            // Nothing of the file was written yet, so what the destinations hold stays
+            failed.push(abandon_file(&file, dest, &written, &[], &e).await);
+            let skipped = CopiedFile::skipped(file, stamp.size, None, Some(SkipReason::CopyFailed));
+            skip_file(
+                &tx,
+                &mut progress,
+                &mut warnings,
+                &mut files,
+                skipped,
+                &wanted,
+            );
+            continue;
+        }
+
+        let hash_options = HashOptions {
+            read_retries: options.read_retries,
+            ..options.verify.unwrap_or(HashOptions {
+                algorithm: options.salvage.unwrap_or_default(),
+                ..Default::default()
+            })
+        };
+        let algorithm =
+            (options.verify.is_some() || options.double_read || options.salvage.is_some())
+                .then_some(hash_options.algorithm);
+        let read_back = options.verify.is_some() && options.verify_method == VerifyMethod::Handle;
+        // Reconnect timeouts follow the batch, which shrinks as destinations drop out, so each
+        // copy is given its own
+        let batch_options = |written: &[bool]| BatchOptions {
+            algorithm,
+            read_back,
+            same_disk: written_to_source_disk(written),
+            continue_on_error: options.continue_on_error,
+            read_retries: options.read_retries,
+            salvage: options.salvage.is_some(),
+            stop: Some(&stop),
+            ..Default::default()
+        };
+        let archived = written
+            .iter()
+            .zip(&kinds)
+            .any(|(written, kind)| *written && kind.is_archive());
+
+        // Another link to a file this job already copied becomes a link at the destinations too,
+        // if the earlier copy went to all of them. An archive needs the data either way.
+        let identity = tokio::fs::metadata(long_path(&source_path))
+            .await
+            .ok()
+            .and_then(|metadata| link_identity(&metadata));
+        let linked_to = match identity.and_then(|identity| copied_links.get(&identity)) {
+            Some((first, first_written))
+                if !archived
+                    && written
+                        .iter()
+                        .zip(first_written)
+                        .all(|(w, first)| !w || *first) =>
+            {
+                let firsts: Vec<PathBuf> = dest
+                    .iter()
+                    .zip(&written)
+                    .filter(|(_, written)| **written)
+                    .map(|(root, _)| root.join(first))
+                    .collect();
+                link_batch(&firsts, &targets)
+                    .await
+                    .is_ok()
+                    .then(|| first.clone())
+            }
+            _ => None,
+        };
+        let mut transfer_time = None;
+        let copied = match linked_to {
+            // The scan's size stands in for reading the file
+            _ if options.dry_run == Some(DryRun::Walk) => Ok(BatchCopy::unread(stamp.size)),
+            Some(_) => linked_copy(&source_path, &targets, algorithm, read_back).await,
+            None => {
+                let targets = targets.clone();
+                let started = Instant::now();
+                let copied = read_file_copy_batch_hashed(
+                    &source_path,
+                    targets,
+                    &mut archives_in_batch(&mut archives, &written),
+                    BatchOptions {
+                        reconnect: &reconnect,
+                        ..batch_options(&written)
+                    },
+                    Some(&mut SpacePrompt::new(
+                        &mut space, &stop, dest, &written, &progress,
+                    )),
+                    &activity.in_batch(&written),
+                    Some(&chunk_progress),
+                )
+                .await;
+                transfer_time = Some(started.elapsed());
+                copied
+            }
+        };
+        let settled = settle_copy(copied, &file, &written, &targets, archived, &mut progress);
+        let mut copy = match settled.await? {
+            Settled::Copied(copy) => copy,
+            Settled::Stopped => {
+                return cancelled(archives, total_bytes, files, verified, &destination_times).await;
+            }
+            Settled::Skipped(reason, failure) => {
+                failed.extend(failure);
+                let skipped = CopiedFile::skipped(file, stamp.size, None, Some(reason));
+                skip_file(
+                    &tx,
+                    &mut progress,
+                    &mut warnings,
+                    &mut files,
+                    skipped,
+                    &wanted,
+                );
+                continue;
+            }
+        };
+        note_copied(&mut destination_times, &written, &copy.finished_at);
+        let left = left_behind(&written, &copy.failed);
+        if !copy.dropped.is_empty() {
+            drop_destinations(
+                &tx,
+                &mut progress,
+                &mut written,
+                &mut dropped,
+                &copy.dropped,
+            );
+        }
+        if !left.is_empty() {
+            fail_at_destinations(
+                &file,
+                dest,
+                left,
+                &mut written,
+                &mut failed,
+                &mut warnings,
+                &mut progress,
+            );
+        }
+        targets = in_batch(&dest_paths, &written);
+        reconnect = in_batch(&reconnect_timeouts, &written);
+        for (budget, _) in budgets
+            .iter_mut()
+            .zip(&written)
+            .filter(|(_, written)| **written)
+        {
+            *budget = budget.map(|budget| budget.saturating_sub(copy.size));
+        }
+        if let (Some(identity), None) = (identity, &linked_to) {
+            copied_links.insert(identity, (file.clone(), written.clone()));
+        }
+        let mut changed_during_copy = FileStamp::of(&source_path)
+            .await
+            .is_none_or(|now| now.changed_since(&stamp));
+        // An archive already has its entry for the file, so verification tells if it changed
+        if changed_during_copy && options.recopy_changed && !archived {
+            let restamped = FileStamp::of(&source_path).await;
+            let (reconnected, mut read_retries) = (copy.reconnected, copy.read_retries);
+            tx.send_modify(|progress| progress.current_file_bytes = 0);
+            let started = Instant::now();
+            let recopied = read_file_copy_batch_hashed(
+                &source_path,
+                targets.clone(),
+                &mut [],
+                BatchOptions {
+                    reconnect: &reconnect,
+                    ..batch_options(&written)
+                },
+                Some(&mut SpacePrompt::new(
+                    &mut space, &stop, dest, &written, &progress,
+                )),
+                &activity.in_batch(&written),
+                Some(&chunk_progress),
+            )
+            .await;
+            let settled = settle_copy(recopied, &file, &written, &targets, false, &mut progress);
+            copy = match settled.await? {
+                Settled::Copied(copy) => copy,
+                Settled::Stopped => {
+                    return cancelled(archives, total_bytes, files, verified, &destination_times)
+                        .await;
+                }
+                Settled::Skipped(reason, failure) => {
+                    failed.extend(failure);
+                    let skipped = CopiedFile::skipped(file, stamp.size, None, Some(reason));
+                    skip_file(
+                        &tx,
+                        &mut progress,
+                        &mut warnings,
+                        &mut files,
+                        skipped,
+                        &wanted,
+                    );
+                    continue;
+                }
+            };
+            note_copied(&mut destination_times, &written, &copy.finished_at);
+            let left = left_behind(&written, &copy.failed);
+            if !copy.dropped.is_empty() {
+                drop_destinations(
+                    &tx,
+                    &mut progress,
+                    &mut written,
+                    &mut dropped,
+                    &copy.dropped,
+                );
+            }
+            if !left.is_empty() {
+                fail_at_destinations(
+                    &file,
+                    dest,
+                    left,
+                    &mut written,
+                    &mut failed,
+                    &mut warnings,
+                    &mut progress,
+                );
+            }
+            transfer_time = transfer_time.map(|time| time + started.elapsed());
+            copy.reconnected |= reconnected;
+            read_retries.append(&mut copy.read_retries);
+            copy.read_retries = read_retries;
+            changed_during_copy = FileStamp::of(&source_path).await != restamped;
+        }
+        // An archive entry took the file's time when it was written
+        let written_paths: Vec<_> = dest_paths
+            .iter()
+            .zip(&written)
+            .zip(&kinds)
+            .filter(|((_, written), kind)| **written && !kind.is_archive())
+            .map(|((path, _), _)| path.clone())
+            .collect();
+        if options.dry_run.is_none() {
+            for (index, path) in dest_paths.iter().enumerate() {
+                if written[index] && !kinds[index].is_archive() {
+                    unsynced[index].push(path.clone());
+                    progress.destinations[index].pending_files += 1;
+                }
+            }
+        }
+        let mut owner_not_kept = false;
+        if options.preserve_ownership {
+            let (source_path, copies) = (source_path.clone(), written_paths.clone());
+            let failures =
+                tokio::task::spawn_blocking(move || copy_owner(&source_path, &copies)).await?;
+            ownership_failures += failures;
+            owner_not_kept = failures > 0;
+        }
+        // The copy is good either way, so a time that can't be set is only noted
+        let timestamp_not_kept = options.preserve_timestamps
+            && copy_modified_time(source_path.clone(), written_paths)
+                .await
+                .is_err();
+        let BatchCopy {
+            size,
+            source_hash,
+            read_back,
+            reconnected,
+            read_retries,
+            lost,
+            dropped: _,
+            failed: _,
+            finished_at: _,
+        } = copy;
+        let salvaged = match (lost.is_empty(), &source_hash) {
+            (false, Some(hash)) => Some(Salvaged {
+                lost,
+                hash: hash.clone(),
+            }),
+            _ => None,
+        };
+
+        // A file that changed would hash differently anyway, which says nothing about the card,
+        // and a salvaged one is known to read badly
+        let mut unreliable_read = false;
+        if let (true, false, None, Some(source_hash)) = (
+            options.double_read,
+            changed_during_copy,
+            &salvaged,
+            &source_hash,
+        ) {
+            // A second read that fails outright is as much a sign of a failing card
+            unreliable_read = match compute_file_hash(&source_path, hash_options, |_| true).await {
+                Ok(reread_hash) => reread_hash != *source_hash,
+                Err(e) => {
+                    tracing::warn!("Reading {} again failed: {e}", file.display());
+                    true
+                }
+            };
+        }
+
+        if let (Some(verify), Some(source_hash)) = (options.verify, source_hash) {
+            let mut destinations = match options.verify_method {
+                VerifyMethod::ReadBack => {
+                    let dest_hashes =
+                        join_all(dest_paths.iter().enumerate().map(|(index, path)| {
+                            let read = wanted[index] && !kinds[index].is_archive();
+                            hash_destination(path, verify, read, |_| true)
+                        }))
+                        .await;
+                    dest_paths
+                        .into_iter()
+                        .zip(dest_hashes)
+                        .map(|(path, hash)| hash.map(|hash| (path, hash)))
+                        .collect::<io::Result<Vec<_>>>()?
+                }
+                // Every destination was written from the same buffers the source hash saw, and
+                // a failed write already ended the copy
+                VerifyMethod::Stream => dest_paths
+                    .into_iter()
+                    .zip(&written)
+                    .map(|(path, wanted)| {
+                        let hash = match wanted {
+                            true => DestinationHash::Hashed(source_hash.clone()),
+                            false => DestinationHash::NotExpected,
+                        };
+                        (path, hash)
+                    })
+                    .collect(),
+                // The copy read back exactly the destinations it wrote to
+                VerifyMethod::Handle => {
+                    let mut read_back = read_back.into_iter();
+                    dest_paths
+                        .into_iter()
+                        .zip(&written)
+                        .zip(&kinds)
+                        .map(|((path, wanted), kind)| {
+                            let hash = match wanted {
+                                true if kind.is_archive() => DestinationHash::NotExpected,
+                                true => read_back.next().map_or(
+                                    DestinationHash::MissingAtDestination,
+                                    DestinationHash::Hashed,
+                                ),
+                                false => DestinationHash::NotExpected,
+                            };
+                            (path, hash)
+                        })
+                        .collect()
+                }
+            };
+            // An archive can only be read back once it is closed, at the end of the job, so its
+            // entries count as matching until then
+            for (index, (_, hash)) in destinations.iter_mut().enumerate() {
+                if wanted[index] && kinds[index].is_archive() {
+                    *hash = DestinationHash::Hashed(source_hash.clone());
+                }
+            }
+            // Kept files weren't written, so only reading them tells if they match
+            for ((path, hash), kept) in destinations.iter_mut().zip(&kept) {
+                if *kept {
+                    *hash = hash_destination(path, verify, true, |_| true).await?;
+                }
+            }
+            // A destination that was given up on, or that failed to take the file, doesn't have
+            // the file it should
+            for (index, (_, hash)) in destinations.iter_mut().enumerate() {
+                if wanted[index] && !kept[index] && !written[index] {
+                    *hash = DestinationHash::MissingAtDestination;
+                }
+            }
+            let file_report = ChecksumReportSingleFile {
+                source: (source_path, source_hash),
+                size,
+                destinations,
+                stopped_at: None,
+                lost: salvaged
+                    .as_ref()
+                    .map_or_else(Vec::new, |salvaged| salvaged.lost.clone()),
+                extra_passes: Vec::new(),
+            };
+            if file_report.consistent() {
+                progress.verified_files += 1;
+            } else {
+                progress.failed_files += 1;
+                // A salvaged file is warned about below
+                if !file_report.salvaged() {
+                    warnings.warn(&mut progress, &file, WarningKind::VerifyFailed);
+                }
+            }
+            note_verified(&mut destination_times, &wanted);
+            verified.push(file_report);
+        }
+
+        // A file kept at every destination wasn't copied
+        if written.contains(&true) {
+            total_bytes += size;
+            progress.copied_bytes += size;
+        }
+        for ReadRetry { offset, attempts } in read_retries {
+            let kind = WarningKind::ReadRetried { offset, attempts };
+            warnings.warn(&mut progress, &file, kind);
+        }
+        if let Some(salvaged) = &salvaged {
+            let kind = WarningKind::Salvaged {
+                bytes_lost: salvaged.bytes_lost(),
+            };
+            warnings.warn(&mut progress, &file, kind);
+        }
+        for (trouble, kind) in [
+            (changed_during_copy, WarningKind::ChangedDuringCopy),
+            (unreliable_read, WarningKind::UnreliableRead),
+            (reconnected, WarningKind::Reconnected),
+            (owner_not_kept, WarningKind::OwnershipNotKept),
+            (timestamp_not_kept, WarningKind::TimestampNotKept),
+        ] {
+            if trouble {
+                warnings.warn(&mut progress, &file, kind);
+            }
+        }
+        tracing::debug!("Copied {} ({size} bytes)", file.display());
+        progress.complete_in_group(&file);
+        files.push(CopiedFile {
+            path: file,
+            size,
+            changed_during_copy,
+            unreliable_read,
+            kept_existing: kept.iter().filter(|kept| **kept).count(),
+            duplicate_of: None,
+            skipped: None,
+            linked_to,
+            reconnected,
+            salvaged,
+            transfer_time,
+        });
+
+        progress.complete_file(size);
+        // Totals were taken from the scan, so the written share is counted the same way
+        progress.settle_destinations(&wanted, &written, stamp.size);
+        tx.send_replace(progress.clone());
+    }
+
+    // The scan may have ended after the last file
+    progress.mark_caught_up();
+    tx.send_replace(progress.clone());
+    for (index, archive) in archives.iter_mut().enumerate() {
+        if let Some(archive) = archive.take() {
+            archive.finish().await.map_err(|e| {
+                let e = destination_failed(index, &dest[index], e);
+                report_failed_destination(&tx, &mut progress, &vec![true; dest.len()], &e);
+                e
+            })?;
+        }
+    }
+    if let Some(verify) = options
+        .verify
+        .filter(|_| kinds.iter().any(|kind| kind.is_archive()))
+    {
+        let archive_hashes = read_archives(dest, verify.algorithm, 1, &stop).await?;
+        for file_report in &mut verified {
+            let was_consistent = file_report.consistent();
+            let file = file_report
+                .source
+                .0
+                .strip_prefix(source)
+                .unwrap_or(&file_report.source.0)
+                .to_path_buf();
+            for ((_, hash), entries) in file_report.destinations.iter_mut().zip(&archive_hashes) {
+                if let (Some(entries), false) =
+                    (entries.first(), *hash == DestinationHash::NotExpected)
+                {
+                    *hash = archive_hash(entries, &file);
+                }
+            }
+            if was_consistent && !file_report.consistent() {
+                progress.verified_files -= 1;
+                progress.failed_files += 1;
+                warnings.warn(&mut progress, &file, WarningKind::VerifyFailed);
+            }
+        }
+        tx.send_replace(progress.clone());
+    }
+    if options.preserve_timestamps {
+        let (source, dest) = (source.to_path_buf(), folders(dest));
+        let copied: Vec<_> = files.iter().map(|file| file.path.clone()).collect();
+        tokio::task::spawn_blocking(move || copy_dir_modified_times(&source, &dest, &copied))
+            .await?;
+    }
+    if options.preserve_ownership {
+        let (source, dest) = (source.to_path_buf(), folders(dest));
+        // Folders holding only skipped files may not exist at the destinations
+        let copied: Vec<_> = files
+            .iter()
+            .filter(|file| file.skipped.is_none() && file.duplicate_of.is_none())
+            .map(|file| file.path.clone())
+            .collect();
+        ownership_failures +=
+            tokio::task::spawn_blocking(move || copy_dir_owners(&source, &dest, &copied)).await?;
+    }
+    if !sync_destinations(dest, unsynced, &tx, &mut progress, &stop).await? {
+        return cancelled(archives, total_bytes, files, verified, &destination_times).await;
+    }
+    let slow = options
+        .slow_file_factor
+        .map_or_else(Vec::new, |factor| slow_files(&files, factor));
+    for slow_file in &slow {
+        let kind = WarningKind::SlowTransfer {
+            bytes_per_sec: slow_file.bytes_per_sec,
+            median_bytes_per_sec: slow_file.median_bytes_per_sec,
+        };
+        warnings.warn(&mut progress, &slow_file.path, kind);
+    }
+    let mut summary = CopySummary::new(
+        source,
+        options,
+        total_bytes,
+        files,
+        verified,
+        &destination_times,
+    );
+    summary.ownership_failures = ownership_failures;
+    summary.warnings = warnings.all;
+    if let Some(report) = &mut summary.report {
+        report.slow_files = slow.clone();
+        report.baseline = plan.baseline.clone();
+    }
+    summary.slow_files = slow;
+    summary.failed = failed;
+    summary.modified = window;
+    summary.baseline = plan.baseline.take();
+    summary.ignore = ignore;
+    if let Some(report) = &mut summary.report {
+        let whole = whole_folders(dest, filters);
+        let dest = folders(dest);
+        let copied: Vec<_> = summary.files.iter().map(|file| file.path.clone()).collect();
+        report.extra_files =
+            tokio::task::spawn_blocking(move || find_extra_files(&dest, &copied)).await??;
+        if options.verify.is_some_and(|verify| verify.check_folders) {
+            let source = source.to_path_buf();
+            report.folders =
+                Some(tokio::task::spawn_blocking(move || compare_folders(&source, &whole)).await??);
+        }
+    }
+    Ok(JobOutcome::Completed(summary))
+}
+
+/// [`copy_dirs`] for callers without a Tokio runtime, on one of its own that lives for the job.
+/// The `job` handle works as it does there. Fails rather than nest a runtime when called from
+/// inside one; use [`copy_dirs`] there.
+pub fn copy_dirs_blocking(
+    source: &Path,
+    dest: &[PathBuf],
+    filters: &[FileFilter],
+    plan: SourcePlan,
+    space: SpaceHandling,
+    options: CopyOptions,
+    job: JobHandle,
+) -> io::Result<JobOutcome<CopySummary>> {
+    job_runtime()?.block_on(copy_dirs(source, dest, filters, plan, space, options, job))
+}
+
+/// A runtime for one blocking job. Files are read and written on its blocking threads, so it is
+/// multi-threaded like the app's.
+fn job_runtime() -> io::Result<tokio::runtime::Runtime> {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(io::Error::other(
+            "A blocking job can't run inside a Tokio runtime; await the async version instead",
+        ));
+    }
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+}
+
+/// Syncs the files written to each destination, by its index in `dest`, to its drive, so that
+/// they are there once the job says it is done, not only in the OS's cache: pulling the drive
+/// before that could truncate them. Each destination counts its pending files down as they are
+/// synced. Returns `false` if `stop` was cancelled first.
+async fn sync_destinations(
+    dest: &[PathBuf],
+    unsynced: Vec<Vec<PathBuf>>,
+    tx: &watch::Sender<Progress>,
+    progress: &mut Progress,
+    stop: &CancellationToken,
+) -> io::Result<bool> {
+    progress.phase = JobPhase::Finalizing;
+    progress.current_file = None;
+    tx.send_replace(progress.clone());
+    for (index, unsynced) in unsynced.into_iter().enumerate() {
+        for path in unsynced {
+            if stop.is_cancelled() {
+                return Ok(false);
+            }
+            let synced = {
+                let path = path.clone();
+                tokio::task::spawn_blocking(move || sync_file(&path)).await?
+            };
+            if let Err(e) = synced {
+                let e = destination_failed(index, &dest[index], e);
+                let all = vec![true; dest.len()];
+                report_failed_destination(tx, progress, &all, &e);
+                return Err(e);
+            }
+            let destination = &mut progress.destinations[index];
+            destination.pending_files = destination.pending_files.saturating_sub(1);
+            tx.send_replace(progress.clone());
+        }
+        let destination = &mut progress.destinations[index];
+        destination.pending_files = 0;
+        if destination.state == DestinationState::Finalizing {
+            destination.state = DestinationState::Done;
+        }
+        tx.send_replace(progress.clone());
+    }
+    Ok(true)
+}
+
+/// Writes what the OS still holds of the file at `path` to its drive. A file that is gone has
+/// nothing left to write.
+fn sync_file(path: &Path) -> io::Result<()> {
+    // Windows only flushes a handle that may write
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(cfg!(windows))
+        .open(long_path(path));
+    match file {
+        Ok(file) => file.sync_all(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        // A read-only copy on Windows can't be opened to flush it; it was closed all the same
+        Err(e) if cfg!(windows) && e.kind() == io::ErrorKind::PermissionDenied => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// The items of the destinations a batch copy writes to, in batch order.
+fn in_batch<T: Clone>(items: &[T], written: &[bool]) -> Vec<T> {
+    items
+        .iter()
+        .zip(written)
+        .filter(|(_, written)| **written)
+        .map(|(item, _)| item.clone())
+        .collect()
+}
+
+/// The archives of the destinations a batch copy writes to, in batch order, with `None` for
+/// folders.
+fn archives_in_batch<'a>(
+    archives: &'a mut [Option<ArchiveWriter>],
+    written: &[bool],
+) -> Vec<Option<&'a mut ArchiveWriter>> {
+    archives
+        .iter_mut()
+        .zip(written)
+        .filter(|(_, written)| **written)
+        .map(|(archive, _)| archive.as_mut())
+        .collect()
+}
+
+/// How [`copy_dirs`] goes on from a file once its copy is done or has failed.
+enum Settled {
+    Copied(BatchCopy),
+    /// The job was stopped partway through the file.
+    Stopped,
+    /// The job goes on without the file, and with what it failed on, if anything.
+    Skipped(SkipReason, Option<FailedFile>),
+}
+
+/// Adds `skipped` to the job's `files` and moves the progress past it, warning about why unless
+/// it only stands in for a duplicate. `wanted` are the destinations it was meant for.
+fn skip_file(
+    tx: &watch::Sender<Progress>,
+    progress: &mut Progress,
+    warnings: &mut WarningLog,
+    files: &mut Vec<CopiedFile>,
+    skipped: CopiedFile,
+    wanted: &[bool],
+) {
+    if let Some(reason) = skipped.skipped {
+        warnings.warn(progress, &skipped.path, WarningKind::Skipped(reason));
+    }
+    progress.complete_in_group(&skipped.path);
+    progress.complete_file(skipped.size);
+    progress.settle_destinations(wanted, &[], skipped.size);
+    files.push(skipped);
+    tx.send_replace(progress.clone());
+}
+
+/// Removes what a failed batch copy of `file` left at `targets`, which could pass for a copy, and
+/// tells what failed. `written` is which of `dest` were in the batch. A batch that goes on past a
+/// failed destination only fails once none is left, so every target holds a partial copy here.
+async fn abandon_file(
+    file: &Path,
+    dest: &[PathBuf],
+    written: &[bool],
+    targets: &[PathBuf],
+    error: &io::Error,
+) -> FailedFile {
+    tracing::error!("Copying {} failed: {error}", file.display());
+    for target in targets {
+        let _ = tokio::fs::remove_file(long_path(target)).await;
+    }
+    let destination = failed_destination(error).and_then(|index| {
+        let (destination, _) = written
+            .iter()
+            .enumerate()
+            .filter(|(_, written)| **written)
+            .nth(index)?;
+        Some(dest[destination].clone())
+    });
+    FailedFile {
+        path: file.to_path_buf(),
+        destination,
+        error: error.to_string(),
+    }
+}
+
+/// The destinations a batch went on without, by their index in the job rather than in the batch,
+/// which held the destinations that are `written`.
+fn left_behind(written: &[bool], batch_failed: &[(usize, String)]) -> Vec<(usize, String)> {
+    let batch: Vec<usize> = (0..written.len()).filter(|index| written[*index]).collect();
+    batch_failed
+        .iter()
+        .filter_map(|(target, error)| Some((*batch.get(*target)?, error.clone())))
+        .collect()
+}
+
+/// Takes the destinations `file` was `left` behind at out of those it is `written` to, and lists
+/// it as failed at each. The other destinations have the file, so the job goes on.
+fn fail_at_destinations(
+    file: &Path,
+    dest: &[PathBuf],
+    left: Vec<(usize, String)>,
+    written: &mut [bool],
+    failed: &mut Vec<FailedFile>,
+    warnings: &mut WarningLog,
+    progress: &mut Progress,
+) {
+    for (index, error) in left {
+        tracing::error!(
+            "Copying {} to {} failed: {error}",
+            file.display(),
+            dest[index].display()
+        );
+        written[index] = false;
+        warnings.warn(
+            progress,
+            file,
+            WarningKind::DestinationFailed { destination: index },
+        );
+        failed.push(FailedFile {
+            path: file.to_path_buf(),
+            destination: Some(dest[index].clone()),
+            error,
+        });
+    }
+}
+
+/// Leaves the destinations a batch gave up on, by their index in the batch, out of the rest of
+/// the job, and marks them failed. `written` is updated to the destinations that got the file.
+fn drop_destinations(
+    tx: &watch::Sender<Progress>,
+    progress: &mut Progress,
+    written: &mut [bool],
+    dropped: &mut [bool],
+    batch_dropped: &[usize],
+) {
+    let batch: Vec<usize> = (0..written.len()).filter(|index| written[*index]).collect();
+    for index in batch_dropped.iter().filter_map(|target| batch.get(*target)) {
+        dropped[*index] = true;
+        written[*index] = false;
+        if let Some(destination) = progress.destinations.get_mut(*index) {
+            destination.state = DestinationState::Failed("Out of space, dropped".to_owned());
+        }
+    }
+    tx.send_replace(progress.clone());
+}
+
+/// Identity of a file that has more than one hard link, to recognize its other links. Only Unix
+/// exposes it in stable Rust.
+fn link_identity(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// Hard-links each target to the earlier copy at the same destination, replacing what is there.
+/// If a link can't be made, e.g. because the filesystem has no hard links, the links made so far
+/// are removed again, leaving the targets to a normal copy.
+async fn link_batch(firsts: &[PathBuf], targets: &[PathBuf]) -> io::Result<()> {
+    for (index, (first, target)) in firsts.iter().zip(targets).enumerate() {
+        let linked = async {
+            match tokio::fs::remove_file(long_path(target)).await {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+            tokio::fs::hard_link(long_path(first), long_path(target)).await
+        };
+        if let Err(e) = linked.await {
+            for target in &targets[..index] {
+                let _ = tokio::fs::remove_file(long_path(target)).await;
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Stands in for `read_file_copy_batch_hashed` once the targets are hard links: hashes the
+/// source and reads back the targets as asked, so verification treats them like any copy.
+async fn linked_copy(
+    source_path: &Path,
+    targets: &[PathBuf],
+    algorithm: Option<HashAlgorithm>,
+    read_back: bool,
+) -> io::Result<BatchCopy> {
+    let size = tokio::fs::metadata(long_path(source_path)).await?.len();
+    // The links were made just before
+    let finished_at = vec![Some(SystemTime::now()); targets.len()];
+    let Some(algorithm) = algorithm else {
+        return Ok(BatchCopy {
+            finished_at,
+            ..BatchCopy::unread(size)
+        });
+    };
+    let options = HashOptions {
+        algorithm,
+        ..Default::default()
+    };
+    let (source_hash, read_retries) =
+        compute_file_hash_with_retries(source_path, options, |_| true).await?;
+    let mut read_back_hashes = Vec::new();
+    if read_back {
+        for target in targets {
+            read_back_hashes.push(compute_file_hash(target, options, |_| true).await?);
+        }
+    }
+    Ok(BatchCopy {
+        size,
+        source_hash: Some(source_hash),
+        read_back: read_back_hashes,
+        reconnected: false,
+        read_retries,
+        lost: Vec::new(),
+        dropped: Vec::new(),
+        failed: Vec::new(),
+        finished_at,
+    })
+}
+
+/// Sets the modification time of a file or folder. Folders can only be opened for this with
+/// backup semantics on Windows, where a file another program holds is waited for.
+fn set_modified(path: &Path, modified: SystemTime) -> io::Result<()> {
+    retry_shared_blocking(path, || {
+        #[cfg(windows)]
+        let file = {
+            use std::os::windows::fs::OpenOptionsExt;
+            const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+            std::fs::File::options()
+                .write(true)
+                .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+                .open(long_path(path))?
+        };
+        #[cfg(not(windows))]
+        let file = std::fs::File::open(long_path(path))?;
+        file.set_modified(modified)
+    })
+}
+
+/// Gives each copy the modification time the source has now.
+async fn copy_modified_time(source: PathBuf, copies: Vec<PathBuf>) -> io::Result<()> {
+    let modified = tokio::fs::metadata(long_path(&source)).await?.modified()?;
+    tokio::task::spawn_blocking(move || {
+        copies
+            .iter()
+            .try_for_each(|copy| set_modified(copy, modified))
+    })
+    .await?
+}
+
+/// The folders, below the root, that `files` live in, deepest first.
+fn parent_dirs(files: &[PathBuf]) -> Vec<&Path> {
+    let mut dirs: Vec<&Path> = files
+        .iter()
+        .flat_map(|file| file.ancestors().skip(1))
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+    dirs
+}
+
+/// Gives `copies` the owner and group of `source`, and returns how many of them couldn't be
+/// changed. That takes privileges, and a filesystem without Unix owners (exFAT, SMB) refuses it.
+/// Other platforms have no owners to copy, so nothing is changed there.
+fn copy_owner(source: &Path, copies: &[PathBuf]) -> usize {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let Ok(metadata) = std::fs::metadata(long_path(source)) else {
+            return copies.len();
+        };
+        copies
+            .iter()
+            .filter(|copy| {
+                std::os::unix::fs::chown(
+                    long_path(copy),
+                    Some(metadata.uid()),
+                    Some(metadata.gid()),
+                )
+                .is_err()
+            })
+            .count()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = source;
+        copies.len()
+    }
+}
+
+/// Gives every folder the copied `files` live in the owner of its source folder, and returns
+/// how many folders couldn't be changed.
+fn copy_dir_owners(source: &Path, dest: &[PathBuf], files: &[PathBuf]) -> usize {
+    parent_dirs(files)
+        .into_iter()
+        .map(|dir| {
+            let copies: Vec<PathBuf> = dest.iter().map(|root| root.join(dir)).collect();
+            copy_owner(&source.join(dir), &copies)
+        })
+        .sum()
+}
+
+/// Gives every folder the copied `files` live in the modification time of its source folder.
+/// Writing a file touches its folder, so this runs once everything is copied, deepest folders
+/// first. The destination roots are left alone. Folders that can't be updated keep the time of
+/// the copy; that is cosmetic and doesn't fail the job.
+fn copy_dir_modified_times(source: &Path, dest: &[PathBuf], files: &[PathBuf]) {
+    for dir in parent_dirs(files) {
+        let Ok(modified) =
+            std::fs::metadata(long_path(&source.join(dir))).and_then(|m| m.modified())
+        else {
+            continue;
+        };
+        for root in dest {
+            let _ = set_modified(&root.join(dir), modified);
+        }
+    }
+}
+
+/// What a destination holds before a job copies into it.
+#[derive(Clone, Debug, Default)]
+pub struct ExistingContents {
+    pub files: usize,
+    pub bytes: u64,
+    /// Existing files that the job would replace.
+    pub collisions: Vec<Collision>,
+}
+
+/// A file at a destination that has the same path as a file the job copies.
+#[derive(Clone, Debug)]
+pub struct Collision {
+    /// Path at the destination.
+    pub path: PathBuf,
+    /// The source file, relative to the source.
+    pub file: PathBuf,
+    pub source: FileStamp,
+    pub existing: FileStamp,
+}
+
+impl ExistingContents {
+    pub fn is_empty(&self) -> bool {
+        self.files == 0
+    }
+}
+
+/// Looks at what is already under `dest`, and which of it copying `files` from `source` would
+/// overwrite. A destination that doesn't exist yet is empty, and so is an archive, which is
+/// written anew. This walks the destination synchronously.
+pub fn existing_contents(
+    source: &Path,
+    dest: &Path,
+    files: &[PathBuf],
+) -> io::Result<ExistingContents> {
+    if DestinationKind::of(dest).is_archive() {
+        return Ok(ExistingContents::default());
+    }
+    let mut existing = match flatten_dir_files(dest) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(ExistingContents::default()),
+        Err(e) => return Err(e),
+    };
+    existing.sort();
+    let stamp = |path: &Path| {
+        std::fs::metadata(long_path(path))
+            .map(|metadata| FileStamp::from_metadata(&metadata))
+            .unwrap_or_default()
+    };
+
+    let job: HashMap<Cow<'_, Path>, &PathBuf> =
+        files.iter().map(|file| (nfc_path(file), file)).collect();
+    let mut contents = ExistingContents {
+        files: existing.len(),
+        ..Default::default()
+    };
+    for file in existing {
+        let existing = stamp(&dest.join(&file));
+        contents.bytes += existing.size;
+        if let Some(source_file) = job.get(&nfc_path(&file)) {
+            contents.collisions.push(Collision {
+                path: dest.join(&file),
+                file: PathBuf::clone(source_file),
+                source: stamp(&source.join(source_file)),
+                existing,
+            });
+        }
+    }
+    Ok(contents)
+}
+
+/// What a job would do with each source file, worked out before it starts.
+#[derive(Clone, Debug, Default)]
+pub struct JobPreview {
+    /// Files copied to at least one destination, with their size.
+    pub copied: Vec<(PathBuf, u64)>,
+    /// Files left out by the date window or the destination filters, and those the user chose
+    /// to leave out.
+    pub filtered: Vec<(PathBuf, u64)>,
+    /// Files the baseline lists, or that every destination receiving them already holds.
+    pub present: Vec<(PathBuf, u64)>,
+    /// Files the ignore patterns name.
+    pub ignored: Vec<(PathBuf, u64)>,
+    /// Files at the destinations that the copy replaces.
+    pub overwritten: Vec<PathBuf>,
+    /// Every file on the source by type, whatever the job does with it.
+    pub file_types: FileTypeBreakdown,
+}
+
+impl JobPreview {
+    /// The bytes read from the source to make the copy.
+    pub fn copied_bytes(&self) -> u64 {
+        self.copied.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// How many extensions a [`FileTypeBreakdown`] names before lumping the rest together.
+pub const FILE_TYPE_LIMIT: usize = 8;
+
+/// Files and bytes of one type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TypeTotal {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// How a set of files splits by extension, for a glance at whether a card holds the expected mix,
+/// e.g. as many sidecar files as clips.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileTypeBreakdown {
+    /// The extensions with the most bytes, most first. Lowercase and without the dot; files
+    /// without an extension are under the empty string.
+    pub extensions: Vec<(String, TypeTotal)>,
+    /// The files of every other extension together.
+    pub other: TypeTotal,
+}
+
+impl FileTypeBreakdown {
+    /// Groups `files`, each with its size, by extension, naming at most `limit` extensions.
+    pub fn new<'a>(files: impl IntoIterator<Item = (&'a Path, u64)>, limit: usize) -> Self {
+        let mut totals: HashMap<String, TypeTotal> = HashMap::new();
+        for (path, size) in files {
+            let extension = path.extension().map_or_else(String::new, |extension| {
+                extension.to_string_lossy().to_lowercase()
+            });
+            let total = totals.entry(extension).or_default();
+            total.files += 1;
+            total.bytes += size;
+        }
+        let mut extensions: Vec<_> = totals.into_iter().collect();
+        // Ties by count, then by name, so the order doesn't change between runs
+        extensions.sort_by(|(a_name, a), (b_name, b)| {
+            (b.bytes, b.files, a_name).cmp(&(a.bytes, a.files, b_name))
+        });
+        let mut other = TypeTotal::default();
+        for (_, total) in extensions.drain(extensions.len().min(limit)..) {
+            other.files += total.files;
+            other.bytes += total.bytes;
+        }
+        FileTypeBreakdown { extensions, other }
+    }
+
+    /// The breakdown on one line, e.g. `96 × .mxf (397284474880 bytes), 4 × .bin (2048 bytes)`,
+    /// as report headers show it.
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = self
+            .extensions
+            .iter()
+            .map(|(extension, total)| {
+                let name = match extension.is_empty() {
+                    true => "no extension".to_owned(),
+                    false => format!(".{extension}"),
+                };
+                format!("{} × {name} ({} bytes)", total.files, total.bytes)
+            })
+            .collect();
+        if self.other.files > 0 {
+            parts.push(format!(
+                "{} × other ({} bytes)",
+                self.other.files, self.other.bytes
+            ));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Sorts the files of `listing` by what a job with `plan` would do with them, given what its
+/// destinations held when checked with [`existing_contents`], one entry per destination.
+/// Duplicates are only found once the copy hashes the card, so they count as copied here.
+pub fn preview_job(
+    listing: &SourceListing,
+    plan: &SourcePlan,
+    filters: &[FileFilter],
+    existing: &[ExistingContents],
+    skip_existing: bool,
+) -> JobPreview {
+    let excluded: HashSet<&PathBuf> = plan.excluded.iter().collect();
+    let offloaded: HashSet<&PathBuf> = plan
+        .baseline
+        .iter()
+        .flat_map(|baseline| &baseline.files)
+        .collect();
+    // Only a file of the source's size is kept; any other is copied again
+    let held: Vec<HashSet<&PathBuf>> = existing
+        .iter()
+        .map(|contents| {
+            contents
+                .collisions
+                .iter()
+                .filter(|c| c.existing.size == c.source.size)
+                .map(|c| &c.file)
+                .collect()
+        })
+        .collect();
+
+    let mut preview = JobPreview {
+        file_types: FileTypeBreakdown::new(
+            listing
+                .files
+                .iter()
+                .map(|(file, stamp)| (file.as_path(), stamp.size)),
+            FILE_TYPE_LIMIT,
+        ),
+        ..Default::default()
+    };
+    for (file, stamp) in &listing.files {
+        let entry = (file.clone(), stamp.size);
+        if plan.ignore.ignores(file) {
+            preview.ignored.push(entry);
+            continue;
+        }
+        if !plan.modified.contains(stamp.modified)
+            || excluded.contains(file)
+            || (plan.skip_placeholders && stamp.placeholder)
+        {
+            preview.filtered.push(entry);
+            continue;
+        }
+        if offloaded.contains(file) {
+            preview.present.push(entry);
+            continue;
+        }
+        let wanting: Vec<usize> = (0..existing.len())
+            .filter(|&index| wants_file(filters, index, file))
+            .collect();
+        if wanting.is_empty() {
+            preview.filtered.push(entry);
+        } else if skip_existing && wanting.iter().all(|&index| held[index].contains(file)) {
+            preview.present.push(entry);
+        } else {
+            preview.copied.push(entry);
+        }
+    }
+
+    let copied: HashSet<&PathBuf> = preview.copied.iter().map(|(file, _)| file).collect();
+    preview.overwritten = existing
+        .iter()
+        .flat_map(|contents| &contents.collisions)
+        .filter(|collision| {
+            copied.contains(&collision.file)
+                && (!skip_existing || collision.existing.size != collision.source.size)
+        })
+        .map(|collision| collision.path.clone())
+        .collect();
+    preview
+}
+
+/// Pairs each skipped duplicate with the file that was copied in its place.
+pub fn duplicate_pairs(files: &[CopiedFile]) -> Vec<(PathBuf, PathBuf)> {
+    files
+        .iter()
+        .filter_map(|file| {
+            let representative = file.duplicate_of.clone()?;
+            Some((file.path.clone(), representative))
+        })
+        .collect()
+}
+
+/// Pairs each file that was hard-linked at the destinations with the file it links to.
+pub fn hard_link_pairs(files: &[CopiedFile]) -> Vec<(PathBuf, PathBuf)> {
+    files
+        .iter()
+        .filter_map(|file| Some((file.path.clone(), file.linked_to.clone()?)))
+        .collect()
+}
+
+/// Files whose copy resumed after a network destination came back.
+pub fn reconnected_files(files: &[CopiedFile]) -> Vec<PathBuf> {
+    files
+        .iter()
+        .filter(|file| file.reconnected)
+        .map(|file| file.path.clone())
+        .collect()
+}
+
+/// How many times slower than the job's median a file has to be copied to be flagged, unless
+/// configured.
+pub const DEFAULT_SLOW_FILE_FACTOR: f64 = 4.0;
+
+/// Files smaller than this are left out of the transfer statistics, since opening and closing
+/// them takes longer than moving their bytes.
+const MIN_TIMED_FILE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Fewer timed files than this give no median worth comparing against.
+const MIN_TIMED_FILES: usize = 5;
+
+/// A file that was copied far slower than the rest of the job.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlowFile {
+    /// Path relative to the source.
+    pub path: PathBuf,
+    pub bytes_per_sec: u64,
+    /// Median rate of the job's timed files.
+    pub median_bytes_per_sec: u64,
+}
+
+/// Finds the transfers, each `(bytes, time)`, whose rate is more than `factor` times below the
+/// median rate of all of them. Returns the median rate in bytes per second and the indices of the
+/// slow transfers, or `None` if there are too few transfers to tell. Transfers that took no
+/// measurable time count towards the median but are never slow.
+pub fn slow_transfers(transfers: &[(u64, Duration)], factor: f64) -> Option<(f64, Vec<usize>)> {
+    if transfers.len() < MIN_TIMED_FILES {
+        return None;
+    }
+    let rates: Vec<f64> = transfers
+        .iter()
+        .map(|(bytes, time)| match time.is_zero() {
+            true => f64::INFINITY,
+            false => *bytes as f64 / time.as_secs_f64(),
+        })
+        .collect();
+    let mut sorted = rates.clone();
+    sorted.sort_by(f64::total_cmp);
+    let middle = sorted.len() / 2;
+    let median = match sorted.len() % 2 {
+        0 => (sorted[middle - 1] + sorted[middle]) / 2.0,
+        _ => sorted[middle],
+    };
+    let slow = rates
+        .iter()
+        .enumerate()
+        .filter(|(_, rate)| **rate * factor < median)
+        .map(|(index, _)| index)
+        .collect();
+    Some((median, slow))
+}
+
+/// Copied files whose rate fell more than `factor` times below the job's median.
+pub fn slow_files(files: &[CopiedFile], factor: f64) -> Vec<SlowFile> {
+    let timed: Vec<(&CopiedFile, Duration)> = files
+        .iter()
+        .filter(|file| file.size >= MIN_TIMED_FILE_SIZE)
+        .filter_map(|file| Some((file, file.transfer_time?)))
+        .collect();
+    let transfers: Vec<(u64, Duration)> = timed
+        .iter()
+        .map(|(file, time)| (file.size, *time))
+        .collect();
+    let Some((median, slow)) = slow_transfers(&transfers, factor) else {
+        return Vec::new();
+    };
+    slow.into_iter()
+        .map(|index| {
+            let (file, time) = timed[index];
+            SlowFile {
+                path: file.path.clone(),
+                bytes_per_sec: (file.size as f64 / time.as_secs_f64()) as u64,
+                median_bytes_per_sec: median as u64,
+            }
+        })
+        .collect()
+}
+
+/// Files that were salvaged, by their path relative to the source.
+pub fn salvaged_files(files: &[CopiedFile]) -> HashMap<PathBuf, Salvaged> {
+    files
+        .iter()
+        .filter_map(|file| Some((file.path.clone(), file.salvaged.clone()?)))
+        .collect()
+}
+
+/// Files that were left out of the copy, and why.
+pub fn skipped_files(files: &[CopiedFile]) -> Vec<(PathBuf, SkipReason)> {
+    files
+        .iter()
+        .filter_map(|file| Some((file.path.clone(), file.skipped?)))
+        .collect()
+}
+
+/// Finds sets of identical files among the `listing` of `source`, e.g. the same clip written to
+/// both slots of a dual-slot camera. Only files that share a size are hashed. Each set is sorted,
+/// so its first file can stand for the rest. Returns what was found so far if stopped.
+pub async fn find_duplicate_files(
+    source: &Path,
+    listing: &SourceListing,
+    options: HashOptions,
+    stop: &CancellationToken,
+) -> io::Result<Vec<Vec<PathBuf>>> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    // Hashing a placeholder would download it
+    for (file, stamp) in listing.files.iter().filter(|(_, stamp)| !stamp.placeholder) {
+        by_size.entry(stamp.size).or_default().push(file.clone());
+    }
+
+    let mut sets = Vec::new();
+    // Empty files are all alike, but not worth skipping
+    for (_, candidates) in by_size
+        .into_iter()
+        .filter(|(size, files)| *size > 0 && files.len() > 1)
+    {
+        let mut by_hash: HashMap<Digest, Vec<PathBuf>> = HashMap::new();
+        for file in candidates {
+            if stop.is_cancelled() {
+                return Ok(sets);
+            }
+            let stop_hash = stop.clone();
+            let digest = match compute_file_hash(source.join(&file), options, move |_| {
+                !stop_hash.is_cancelled()
+            })
+            .await
+            {
+                Err(_) if stop.is_cancelled() => return Ok(sets),
+                // Gone since the listing was made, so it won't be copied either
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                digest => digest?,
+            };
+            by_hash.entry(digest).or_default().push(file);
+        }
+        sets.extend(
+            by_hash
+                .into_values()
+                .filter(|set| set.len() > 1)
+                .map(|mut set| {
+                    set.sort();
+                    set
+                }),
+        );
+    }
+    sets.sort();
+    Ok(sets)
+}
+
+/// System clutter that file managers leave behind, which isn't worth reporting.
+pub fn is_junk_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    matches!(name.as_ref(), ".DS_Store" | "Thumbs.db" | "desktop.ini") || name.starts_with("._")
+}
+
+/// Lists files under the destinations that aren't among `files`, e.g. leftovers from an earlier
+/// job. Junk files are left out, and so is a destination that doesn't exist.
+pub fn find_extra_files(dest: &[PathBuf], files: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let expected: HashSet<Cow<'_, Path>> = files.iter().map(|file| nfc_path(file)).collect();
+    let mut extra = Vec::new();
+    for dest in dest {
+        let mut found = match flatten_dir_files(dest) {
+            Ok(found) => found,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        found.sort();
+        extra.extend(
+            found
+                .into_iter()
+                .filter(|file| !expected.contains(&nfc_path(file)) && !is_junk_file(file))
+                .map(|file| dest.join(file)),
+        );
+    }
+    Ok(extra)
+}
+
+/// Compares the folders under `source` with those under each destination. A destination that
+/// doesn't exist is left out.
+pub fn compare_folders(source: &Path, dest: &[PathBuf]) -> io::Result<FolderDifferences> {
+    let expected = list_folders(source)?;
+    let expected_set: HashSet<Cow<'_, Path>> =
+        expected.iter().map(|folder| nfc_path(folder)).collect();
+    let mut differences = FolderDifferences::default();
+    for dest in dest {
+        let found = match list_folders(dest) {
+            Ok(found) => found,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let found_set: HashSet<Cow<'_, Path>> =
+            found.iter().map(|folder| nfc_path(folder)).collect();
+        differences.missing.extend(
+            expected
+                .iter()
+                .filter(|folder| !found_set.contains(&nfc_path(folder)))
+                .map(|folder| dest.join(folder)),
+        );
+        differences.extra.extend(
+            found
+                .iter()
+                .filter(|folder| !expected_set.contains(&nfc_path(folder)))
+                .map(|folder| dest.join(folder)),
+        );
+    }
+    Ok(differences)
+}
+
+/// Every folder under `base_dir`, relative to it, in path order.
+fn list_folders(base_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut folders = Vec::new();
+    let mut pending = vec![base_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(long_path(&dir))? {
+            let path = dir.join(entry?.file_name());
+            if std::fs::metadata(long_path(&path)).is_ok_and(|m| m.is_dir()) {
+                folders.push(path.strip_prefix(base_dir).unwrap().to_path_buf());
+                pending.push(path);
+            }
+        }
+    }
+    folders.sort();
+    Ok(folders)
+}
+
+/// The folder destinations whose folders should match the source's: one that only takes some
+/// of the files can't be expected to.
+fn whole_folders(dest: &[PathBuf], filters: &[FileFilter]) -> Vec<PathBuf> {
+    dest.iter()
+        .enumerate()
+        .filter(|(index, root)| {
+            !DestinationKind::of(root).is_archive()
+                && filters.get(*index).is_none_or(FileFilter::is_empty)
+        })
+        .map(|(_, root)| root.clone())
+        .collect()
+}
+
+/// Lets a read-only file, e.g. a verified copy of an earlier offload that
+/// [`protect_verified`] left, be overwritten.
+async fn allow_overwrite(dest_path: &Path) -> io::Result<()> {
+    let path = long_path(dest_path);
+    match tokio::fs::metadata(&path).await {
+        Ok(metadata) if metadata.is_file() && metadata.permissions().readonly() => {
+            tokio::fs::set_permissions(&path, writable(metadata.permissions())).await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `permissions` with the owner allowed to write, and otherwise as they were.
+fn writable(mut permissions: std::fs::Permissions) -> std::fs::Permissions {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        permissions.set_mode(permissions.mode() | 0o200);
+    }
+    // Clears FILE_ATTRIBUTE_READONLY, which is all there is to it on Windows
+    #[cfg(not(unix))]
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    permissions
+}
+
+/// Clears the write bits of the file at `path`, or sets FILE_ATTRIBUTE_READONLY on Windows.
+fn set_read_only(path: &Path) -> io::Result<()> {
+    let path = long_path(path);
+    let mut permissions = std::fs::metadata(&path)?.permissions();
+    if permissions.readonly() {
+        return Ok(());
+    }
+    permissions.set_readonly(true);
+    std::fs::set_permissions(&path, permissions)
+}
+
+/// Makes way for the file at each of the batch's `targets` that isn't in an archive: creates the
+/// folders it goes in and lets a read-only file there be overwritten. Returns the destinations
+/// that couldn't be prepared, by their index in the batch, with errors tied to them.
+async fn prepare_targets(
+    targets: &[PathBuf],
+    kinds: &[DestinationKind],
+) -> Vec<(usize, io::Error)> {
+    let mut failures = Vec::new();
+    for (index, (dest_path, kind)) in targets.iter().zip(kinds).enumerate() {
+        if kind.is_archive() {
+            continue;
+        }
+        let prepared = async {
+            if let Some(parent) = dest_path.parent() {
+                tokio::fs::create_dir_all(long_path(parent)).await?;
+            }
+            ensure_not_directory(dest_path).await?;
+            // Files that are kept are never opened, so only those being overwritten get here
+            allow_overwrite(dest_path).await
+        };
+        if let Err(e) = prepared.await {
+            failures.push((index, destination_failed(index, dest_path, e)));
+        }
+    }
+    failures
+}
+
+/// `File::create` on a directory fails with an error that doesn't say what is wrong, so check
+/// for that conflict up front.
+async fn ensure_not_directory(dest_path: &Path) -> io::Result<()> {
+    match tokio::fs::metadata(long_path(dest_path)).await {
+        Ok(metadata) if metadata.is_dir() => Err(io::Error::new(
+            io::ErrorKind::IsADirectory,
+            format!(
+                "Destination has a directory where a file is expected: {}",
+                dest_path.display()
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Whether destination `index` receives `file`. Destinations without a filter get every file.
+fn wants_file(filters: &[FileFilter], index: usize, file: &Path) -> bool {
+    filters.get(index).is_none_or(|filter| filter.matches(file))
+}
+
+fn enqueue(
+    queue: &mut VecDeque<(PathBuf, FileStamp)>,
+    progress: &mut Progress,
+    warnings: &mut WarningLog,
+    filters: &[FileFilter],
+    in_job: impl Fn(&Path, &FileStamp) -> bool,
+    entry: ScanEntry,
+) {
+    let (file, stamp) = match entry {
+        ScanEntry::File(file, stamp) => (file, stamp),
+        ScanEntry::Pruned(folder) => return warnings.warn(progress, &folder, WarningKind::Pruned),
+        // The job's scan has no file limit
+        ScanEntry::Capped => return,
+    };
+    if !in_job(&file, &stamp) {
+        return;
+    }
+    for (index, destination) in progress.destinations.iter_mut().enumerate() {
+        if wants_file(filters, index, &file) {
+            destination.total_bytes += stamp.size;
+        }
+    }
+    progress.add_to_group(&file);
+    queue.push_back((file, stamp));
+    progress.total_files += 1;
+    progress.total_bytes += stamp.size;
+}
+
+/// File extensions a destination receives, for a destination that only takes part of the card,
+/// e.g. the JPEGs for a quick review drive. An empty filter lets every file through.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileFilter {
+    extensions: Vec<String>,
+}
+
+impl FileFilter {
+    /// Parses a list like `jpg, .JPEG`. Case and leading dots are ignored.
+    pub fn parse(list: &str) -> FileFilter {
+        FileFilter {
+            extensions: list
+                .split([',', ' ', ';'])
+                .map(|extension| extension.trim().trim_start_matches('.').to_lowercase())
+                .filter(|extension| !extension.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    pub fn matches(&self, file: &Path) -> bool {
+        self.is_empty()
+            || file.extension().is_some_and(|extension| {
+                let extension = extension.to_string_lossy().to_lowercase();
+                self.extensions.contains(&extension)
+            })
+    }
+}
+
+/// Folders a destination lacks or has beyond the source's, both as paths at the destination.
+/// Files aren't looked at, so an empty folder that wasn't copied shows up here.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FolderDifferences {
+    pub missing: Vec<PathBuf>,
+    pub extra: Vec<PathBuf>,
+}
+
+impl FolderDifferences {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// How the source differs from what was copied, e.g. a clip recorded after the copy.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SourceChanges {
+    /// In the source now, but not copied.
+    pub added: Vec<PathBuf>,
+    /// Copied, but gone from the source.
+    pub removed: Vec<PathBuf>,
+    /// Copied, but the source file now has a different size.
+    pub modified: Vec<PathBuf>,
+    /// The source changed while it was being copied. These are still verified.
+    pub changed_during_copy: Vec<PathBuf>,
+    /// Reading the source twice gave different data. These are still verified, but against a read
+    /// that may be corrupt.
+    pub unreliable_reads: Vec<PathBuf>,
+}
+
+impl SourceChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.changed_during_copy.is_empty()
+            && self.unreliable_reads.is_empty()
+    }
+
+    /// A warning for each file added, removed or resized on the source since the copy.
+    pub fn warnings(&self) -> Vec<JobWarning> {
+        let at = SystemTime::now();
+        [
+            (&self.added, WarningKind::AddedToSource),
+            (&self.removed, WarningKind::RemovedFromSource),
+            (&self.modified, WarningKind::ResizedOnSource),
+        ]
+        .into_iter()
+        .flat_map(|(files, kind)| {
+            files.iter().map(move |path| JobWarning {
+                at,
+                path: path.clone(),
+                kind,
+            })
+        })
+        .collect()
+    }
+}
+
+/// Compares the files a copy went through with the current source contents. Returns the copied
+/// files that are unchanged, so they can still be verified, along with the differences. Files the
+/// copy left out by date, as already offloaded or as ignored aren't counted as added. This walks
+/// the source synchronously.
+pub fn compare_with_source(
+    source: &Path,
+    summary: &CopySummary,
+) -> io::Result<(Vec<PathBuf>, SourceChanges)> {
+    let copied = &summary.files;
+    let mut current: HashMap<PathBuf, u64> = HashMap::new();
+    // Files the job left out on purpose weren't missed
+    let mut left_out: HashSet<PathBuf> = summary
+        .baseline
+        .iter()
+        .flat_map(|baseline| {
+            baseline
+                .files
+                .iter()
+                .map(|file| nfc_path(file).into_owned())
+        })
+        .collect();
+    for file in flatten_dir_files(source)? {
+        let metadata = std::fs::metadata(long_path(&source.join(&file))).ok();
+        let file = nfc_path(&file).into_owned();
+        let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+        if !summary.modified.contains(modified) || summary.ignore.ignores(&file) {
+            left_out.insert(file.clone());
+        }
+        current.insert(file, metadata.map_or(0, |m| m.len()));
+    }
+
+    let mut unchanged = Vec::with_capacity(copied.len());
+    let mut changes = SourceChanges::default();
+    for file in copied {
+        if file.changed_during_copy {
+            changes.changed_during_copy.push(file.path.clone());
+        }
+        if file.unreliable_read {
+            changes.unreliable_reads.push(file.path.clone());
+        }
+        match current.get(nfc_path(&file.path).as_ref()) {
+            // Never copied, so there is nothing to verify
+            Some(_) if file.duplicate_of.is_some() || file.skipped.is_some() => {}
+            None => changes.removed.push(file.path.clone()),
+            Some(size) if *size != file.size => changes.modified.push(file.path.clone()),
+            Some(_) => unchanged.push(file.path.clone()),
+        }
+    }
+
+    let copied: HashSet<Cow<'_, Path>> = copied.iter().map(|file| nfc_path(&file.path)).collect();
+    changes.added = current
+        .into_keys()
+        .filter(|file| !copied.contains(file.as_path()) && !left_out.contains(file))
+        .collect();
+    changes.added.sort();
+
+    Ok((unchanged, changes))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    /// Fast non-cryptographic hash; enough to catch copy errors.
+    #[default]
+    XxHash3,
+    /// Slower, but understood by standard tools such as `sha256sum`.
+    Sha256,
+    /// Cryptographic like SHA-256, and fast too, since a large file is hashed on every core at
+    /// once. Checked with `b3sum`.
+    Blake3,
+    /// Only for checking manifests from other offload tools, which commonly use it.
+    XxHash64,
+    /// Only for checking manifests from other offload tools.
+    Md5,
+    /// Only for checking manifests from other offload tools.
+    Sha1,
+}
+
+impl HashAlgorithm {
+    /// The algorithms a copy can be verified with.
+    pub const ALL: [HashAlgorithm; 3] = [
+        HashAlgorithm::XxHash3,
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Blake3,
+    ];
+
+    /// Algorithm name in BSD-style checksum lines, for algorithms the standard tools know.
+    pub fn tag_name(self) -> Option<&'static str> {
+        match self {
+            HashAlgorithm::XxHash3 | HashAlgorithm::XxHash64 => None,
+            HashAlgorithm::Sha256 => Some("SHA256"),
+            HashAlgorithm::Blake3 => Some("BLAKE3"),
+            HashAlgorithm::Md5 => Some("MD5"),
+            HashAlgorithm::Sha1 => Some("SHA1"),
+        }
+    }
+
+    /// Name of the hash element in an MHL file, for algorithms MHL has one for.
+    pub fn mhl_name(self) -> Option<&'static str> {
+        match self {
+            HashAlgorithm::XxHash3 => Some("xxh3"),
+            // Plain `xxhash64` is little-endian in MHL 1.1, unlike every other hash
+            HashAlgorithm::XxHash64 => Some("xxhash64be"),
+            HashAlgorithm::Md5 => Some("md5"),
+            HashAlgorithm::Sha1 => Some("sha1"),
+            HashAlgorithm::Sha256 => Some("sha256"),
+            HashAlgorithm::Blake3 => None,
+        }
+    }
+
+    /// Short lowercase id, as in [`Manifest::algorithm`] and the manifest digest.
+    pub fn id(self) -> &'static str {
+        match self {
+            HashAlgorithm::XxHash3 => "xxh3",
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::XxHash64 => "xxh64",
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha1",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<HashAlgorithm> {
+        [
+            HashAlgorithm::XxHash3,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Blake3,
+            HashAlgorithm::XxHash64,
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha1,
+        ]
+        .into_iter()
+        .find(|algorithm| algorithm.id() == id)
+    }
+
+    /// Whether a file can be hashed on several cores at once with the same result as on one.
+    pub fn hashes_in_parallel(self) -> bool {
+        self == HashAlgorithm::Blake3
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashAlgorithm::XxHash3 => write!(f, "xxHash3 (64-bit)"),
+            HashAlgorithm::Sha256 => write!(f, "SHA-256"),
+            HashAlgorithm::Blake3 => write!(f, "BLAKE3"),
+            HashAlgorithm::XxHash64 => write!(f, "xxHash64"),
+            HashAlgorithm::Md5 => write!(f, "MD5"),
+            HashAlgorithm::Sha1 => write!(f, "SHA-1"),
+        }
+    }
+}
+
+/// Files from this size up are hashed on a blocking thread unless configured otherwise.
+pub const DEFAULT_BLOCKING_HASH_THRESHOLD: u64 = 256 * 1024 * 1024;
+
+/// Files from this size up are hashed on several cores, where the algorithm can be, unless
+/// configured otherwise.
+pub const DEFAULT_PARALLEL_HASH_THRESHOLD: u64 = 1024 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug)]
+pub struct HashOptions {
+    pub algorithm: HashAlgorithm,
+    /// Files at least this large are hashed on a blocking thread, so a multi-GB hash can't hog an
+    /// async worker that the UI shares.
+    pub blocking_threshold: u64,
+    /// Have `hash_dirs` give up at the first file that doesn't match.
+    pub stop_on_failure: bool,
+    /// Have `hash_dirs` compare the destinations with the source instead of hashing them, so a
+    /// bad copy fails at its first differing chunk.
+    pub compare: bool,
+    /// How many more times a failed read is tried before hashing fails.
+    pub read_retries: u32,
+    /// How many times `hash_dirs` reads each destination back. The source is only read on the
+    /// first pass.
+    pub passes: u32,
+    /// Files at least this large are hashed on several cores at once, if the algorithm
+    /// [hashes in parallel](HashAlgorithm::hashes_in_parallel). `None` keeps to one core.
+    pub parallel_threshold: Option<u64>,
+    /// Also compare the folders of each destination with the source's, empty ones included.
+    pub check_folders: bool,
+}
+
+impl HashOptions {
+    /// Whether a file of `size` bytes is hashed on several cores.
+    fn in_parallel(&self, size: u64) -> bool {
+        self.algorithm.hashes_in_parallel()
+            && self
+                .parallel_threshold
+                .is_some_and(|threshold| size >= threshold)
+    }
+}
+
+impl Default for HashOptions {
+    fn default() -> Self {
+        HashOptions {
+            algorithm: HashAlgorithm::default(),
+            blocking_threshold: DEFAULT_BLOCKING_HASH_THRESHOLD,
+            stop_on_failure: false,
+            compare: false,
+            read_retries: DEFAULT_READ_RETRIES,
+            passes: 1,
+            parallel_threshold: Some(DEFAULT_PARALLEL_HASH_THRESHOLD),
+            check_folders: false,
+        }
+    }
+}
+
+/// Hash of a file, in the byte order the algorithm's reference tool prints it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Digest(pub Vec<u8>);
+
+impl fmt::LowerHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl fmt::UpperHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02X}", byte))
+    }
+}
+
+enum FileHasher {
+    XxHash3(Box<XxHash3_64>),
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+    XxHash64(XxHash64),
+    Md5(Md5),
+    Sha1(Sha1),
+}
+
+impl FileHasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::XxHash3 => FileHasher::XxHash3(Box::default()),
+            HashAlgorithm::Sha256 => FileHasher::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => FileHasher::Blake3(Box::default()),
+            HashAlgorithm::XxHash64 => FileHasher::XxHash64(XxHash64::with_seed(0)),
+            HashAlgorithm::Md5 => FileHasher::Md5(Md5::new()),
+            HashAlgorithm::Sha1 => FileHasher::Sha1(Sha1::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            FileHasher::XxHash3(hasher) => hasher.write(data),
+            FileHasher::Sha256(hasher) => hasher.update(data),
+            FileHasher::Blake3(hasher) => {
+                hasher.update(data);
+            }
+            FileHasher::XxHash64(hasher) => hasher.write(data),
+            FileHasher::Md5(hasher) => hasher.update(data),
+            FileHasher::Sha1(hasher) => hasher.update(data),
+        }
+    }
+
+    /// Like [`FileHasher::update`], but spreads the work over the rayon pool where the algorithm
+    /// allows. Blocks until it is done, so it is only for blocking threads.
+    fn update_parallel(&mut self, data: &[u8]) {
+        match self {
+            FileHasher::Blake3(hasher) => {
+                hasher.update_rayon(data);
+            }
+            hasher => hasher.update(data),
+        }
+    }
+
+    fn finish(self) -> Digest {
+        match self {
+            FileHasher::XxHash3(hasher) => Digest(hasher.finish().to_be_bytes().to_vec()),
+            FileHasher::Sha256(hasher) => Digest(hasher.finalize().to_vec()),
+            FileHasher::Blake3(hasher) => Digest(hasher.finalize().as_bytes().to_vec()),
+            FileHasher::XxHash64(hasher) => Digest(hasher.finish().to_be_bytes().to_vec()),
+            FileHasher::Md5(hasher) => Digest(hasher.finalize().to_vec()),
+            FileHasher::Sha1(hasher) => Digest(hasher.finalize().to_vec()),
+        }
+    }
+}
+
+/// Size of the buffer [`benchmark_hashes`] hashes; larger than the CPU caches, like a file is.
+const BENCHMARK_BUFFER: usize = 64 * 1024 * 1024;
+/// Times the buffer is hashed per algorithm, so short stalls average out.
+const BENCHMARK_ROUNDS: usize = 4;
+/// Most of a sample file [`benchmark_hashes`] reads.
+const BENCHMARK_SAMPLE_LIMIT: u64 = 1024 * 1024 * 1024;
+
+/// How fast an algorithm hashed data in memory.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HashSpeed {
+    pub algorithm: HashAlgorithm,
+    pub bytes_per_second: f64,
+}
+
+/// Result of [`benchmark_hashes`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HashBenchmark {
+    pub speeds: Vec<HashSpeed>,
+    /// The file read to measure a drive, and how fast it was read in bytes per second.
+    pub sample: Option<(PathBuf, f64)>,
+    pub measured_at: SystemTime,
+}
+
+impl HashBenchmark {
+    /// Whether `speed` keeps up with reading the sample; `None` without a sample.
+    pub fn keeps_up(&self, speed: &HashSpeed) -> Option<bool> {
+        self.sample
+            .as_ref()
+            .map(|(_, read_speed)| speed.bytes_per_second >= *read_speed)
+    }
+}
+
+/// Measures how fast each algorithm a copy can be verified with hashes data in memory, and how
+/// fast `sample` reads, if given. Takes a few seconds. A sample that was read recently may come
+/// from the system's cache and read faster than its drive can.
+pub fn benchmark_hashes(sample: Option<&Path>) -> io::Result<HashBenchmark> {
+    // Incompressible, in case the data path ever cares; xorshift is plenty for that
+    let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+    let buffer: Vec<u8> = (0..BENCHMARK_BUFFER / 8)
+        .flat_map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.to_le_bytes()
+        })
+        .collect();
+
+    let speeds = HashAlgorithm::ALL
+        .into_iter()
+        .map(|algorithm| {
+            let started = Instant::now();
+            let mut hasher = FileHasher::new(algorithm);
+            for _ in 0..BENCHMARK_ROUNDS {
+                hasher.update(&buffer);
+            }
+            std::hint::black_box(hasher.finish());
+            HashSpeed {
+                algorithm,
+                bytes_per_second: (BENCHMARK_BUFFER * BENCHMARK_ROUNDS) as f64
+                    / started.elapsed().as_secs_f64(),
+            }
+        })
+        .collect();
+
+    let sample = match sample {
+        Some(path) => {
+            use std::io::Read;
+
+            let mut file = std::fs::File::open(long_path(path))?.take(BENCHMARK_SAMPLE_LIMIT);
+            let mut chunk = vec![0; 1024 * 1024];
+            let started = Instant::now();
+            let mut read = 0;
+            loop {
+                match file.read(&mut chunk)? {
+                    0 => break,
+                    n => read += n,
+                }
+            }
+            let seconds = started.elapsed().as_secs_f64();
+            Some((path.to_path_buf(), read as f64 / seconds.max(f64::EPSILON)))
+        }
+        None => None,
+    };
+
+    Ok(HashBenchmark {
+        speeds,
+        sample,
+        measured_at: SystemTime::now(),
+    })
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChecksumReport {
+    pub algorithm: HashAlgorithm,
+    /// Source directory; manifest paths are written relative to it.
+    pub source_root: PathBuf,
+    pub files: Vec<ChecksumReportSingleFile>,
+    /// Source changes since the copy. These files weren't verified, but they don't count as
+    /// failures either.
+    pub source_changes: SourceChanges,
+    /// Order the files were copied in, if this verifies a copy.
+    pub copy_order: Option<CopyOrder>,
+    pub method: VerifyMethod,
+    /// Source files that weren't copied, each with the identical file that was.
+    pub duplicates: Vec<(PathBuf, PathBuf)>,
+    /// Source files left out of the copy on the user's request.
+    pub skipped: Vec<(PathBuf, SkipReason)>,
+    /// Files that were hard-linked at the destinations, each with the file it links to.
+    pub hard_links: Vec<(PathBuf, PathBuf)>,
+    /// Files whose copy was interrupted by a network destination dropping, then resumed.
+    pub reconnected: Vec<PathBuf>,
+    /// Files that were copied far slower than the others.
+    #[serde(default)]
+    pub slow_files: Vec<SlowFile>,
+    /// The earlier offload this one only added to, if it was incremental.
+    #[serde(default)]
+    pub baseline: Option<Baseline>,
+    /// Files at the destinations that weren't part of the job. They aren't failures, but a
+    /// destination with leftovers isn't a clean copy of the card.
+    pub extra_files: Vec<PathBuf>,
+    /// Verification ended at the first mismatch, so only the files up to it are in the report.
+    pub stopped_at_failure: bool,
+    /// Files that weren't checked because verification ended early.
+    pub unchecked_files: usize,
+    /// Destinations were compared with the source byte for byte instead of being hashed.
+    pub compared: bool,
+    /// Configured destinations that were turned off for the job, so they weren't copied to.
+    #[serde(default)]
+    pub disabled_destinations: Vec<PathBuf>,
+    /// When the job was done with each destination, in the order of the files' destinations.
+    /// Empty if the times weren't recorded.
+    #[serde(default)]
+    pub destination_times: Vec<DestinationTimes>,
+    /// The job the report is of, if it was given one.
+    #[serde(default)]
+    pub job_id: Option<JobId>,
+    /// The whole source volume, if it was listed for the record.
+    #[serde(default)]
+    pub volume_listing: Option<Box<VolumeListing>>,
+    /// How the destinations' folders differ from the source's, if they were compared.
+    #[serde(default)]
+    pub folders: Option<FolderDifferences>,
+    /// How many files were verified at once, and from when, if they were verified after the copy.
+    #[serde(default)]
+    pub hash_workers: Vec<HashWorkers>,
+    /// When verification ended.
+    pub finished_at: SystemTime,
+}
+
+/// How the files of one [group](file_group) of a report came out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GroupSummary {
+    pub name: String,
+    pub files: usize,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+/// Verification after a copy hashed `workers` files at once from `since` on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashWorkers {
+    pub since: SystemTime,
+    pub workers: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChecksumReportSingleFile {
+    pub source: (PathBuf, Digest),
+    /// Size of the source file.
+    pub size: u64,
+    /// One entry per destination. The hash is `None` where the destination's filter leaves the
+    /// file out, so it isn't expected there.
+    pub destinations: Vec<(PathBuf, DestinationHash)>,
+    /// Comparison stopped reading at this offset, once every destination had differed, so the
+    /// source digest only covers the bytes before it.
+    pub stopped_at: Option<u64>,
+    /// Ranges of the source that couldn't be read and were copied as zeros. The source digest is
+    /// of the salvaged content.
+    pub lost: Vec<Range<u64>>,
+    /// What each verification pass after the first found, in the order of `destinations`.
+    #[serde(default)]
+    pub extra_passes: Vec<Vec<DestinationHash>>,
+}
+
+/// What was found for a file at one destination.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DestinationHash {
+    Hashed(Digest),
+    /// The destination's filter leaves the file out, so it isn't expected there.
+    NotExpected,
+    /// The file should be at the destination but isn't.
+    MissingAtDestination,
+    /// Comparing with the source found the first difference at this byte offset. The rest of the
+    /// file wasn't read.
+    DiffersAt(u64),
+}
+
+/// How the files checked at one destination came out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DestinationSummary {
+    pub verified: usize,
+    /// Files whose content differs from the source.
+    pub failed: usize,
+    pub missing: usize,
+}
+
+impl DestinationSummary {
+    pub fn passed(&self) -> bool {
+        self.failed == 0 && self.missing == 0
+    }
+}
+
+impl ChecksumReportSingleFile {
+    /// Whether every copy matches the source. A salvaged file never does, even if the copies
+    /// hold what was salvaged, since part of the source is missing from them.
+    pub fn consistent(&self) -> bool {
+        !self.salvaged() && self.copies_match()
+    }
+
+    pub fn salvaged(&self) -> bool {
+        !self.lost.is_empty()
+    }
+
+    /// Whether `hash`, found at a destination, is an empty copy of an empty source: there, and
+    /// 0 bytes like it.
+    pub fn empty_copy(&self, hash: &DestinationHash) -> bool {
+        self.size == 0
+            && self.stopped_at.is_none()
+            && matches!(hash, DestinationHash::Hashed(digest) if *digest == self.source.1)
+    }
+
+    pub fn bytes_lost(&self) -> u64 {
+        bytes_in(&self.lost)
+    }
+
+    /// Whether every copy has the content the source digest is of on every verification pass,
+    /// which for a salvaged file is what could be salvaged.
+    pub fn copies_match(&self) -> bool {
+        self.destinations
+            .iter()
+            .map(|(_, hash)| hash)
+            .chain(self.extra_passes.iter().flatten())
+            .all(|hash| self.matches_source(hash))
+    }
+
+    /// Destinations, by index, whose copy matched the source on one verification pass but not on
+    /// another. Reading the same bytes differently points at the drive or its connection rather
+    /// than at the copy.
+    pub fn unstable_destinations(&self) -> Vec<usize> {
+        (0..self.destinations.len())
+            .filter(|&index| {
+                let first = self.matches_source(&self.destinations[index].1);
+                self.extra_passes
+                    .iter()
+                    .filter_map(|pass| pass.get(index))
+                    .any(|hash| self.matches_source(hash) != first)
+            })
+            .collect()
+    }
+
+    /// Destinations, by index, whose copy matched the source on every verification pass. None of
+    /// a salvaged file's copies are verified.
+    pub fn verified_destinations(&self) -> Vec<usize> {
+        if self.salvaged() {
+            return Vec::new();
+        }
+        (0..self.destinations.len())
+            .filter(|&index| {
+                let hash = &self.destinations[index].1;
+                matches!(hash, DestinationHash::Hashed(_))
+                    && self.matches_source(hash)
+                    && self
+                        .extra_passes
+                        .iter()
+                        .filter_map(|pass| pass.get(index))
+                        .all(|hash| self.matches_source(hash))
+            })
+            .collect()
+    }
+
+    /// Each destination the file is expected at, with whether its copy matched the source on
+    /// every verification pass.
+    pub fn destination_matches(&self) -> Vec<(PathBuf, bool)> {
+        let verified = self.verified_destinations();
+        self.destinations
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, hash))| *hash != DestinationHash::NotExpected)
+            .map(|(index, (path, _))| (path.clone(), verified.contains(&index)))
+            .collect()
+    }
+
+    fn matches_source(&self, hash: &DestinationHash) -> bool {
+        match hash {
+            DestinationHash::Hashed(hash) => *hash == self.source.1,
+            DestinationHash::NotExpected => true,
+            DestinationHash::MissingAtDestination | DestinationHash::DiffersAt(_) => false,
+        }
+    }
+}
+
+impl ChecksumReport {
+    /// Files in the job, those whose copy failed included.
+    pub fn total_files(&self) -> usize {
+        self.files.len() + self.copy_failures()
+    }
+
+    /// Files that don't match the source at every destination, or whose copy failed.
+    pub fn count_errors(&self) -> usize {
+        self.files.iter().filter(|file| !file.consistent()).count() + self.copy_failures()
+    }
+
+    /// Files whose copy failed, which weren't verified.
+    pub fn copy_failures(&self) -> usize {
+        self.skipped
+            .iter()
+            .filter(|(_, reason)| *reason == SkipReason::CopyFailed)
+            .count()
+    }
+
+    /// How the files of each [group](file_group) came out, the root's first, then by name. Files
+    /// whose copy failed count as failed.
+    pub fn groups(&self) -> Vec<GroupSummary> {
+        let mut groups: BTreeMap<String, GroupSummary> = BTreeMap::new();
+        let mut count = |file: &Path, passed: bool| {
+            let name = file_group(file);
+            let group = groups.entry(name.clone()).or_insert_with(|| GroupSummary {
+                name,
+                ..Default::default()
+            });
+            group.files += 1;
+            match passed {
+                true => group.passed += 1,
+                false => group.failed += 1,
+            }
+        };
+        for file in &self.files {
+            count(self.relative_path(file), file.consistent());
+        }
+        for (file, reason) in &self.skipped {
+            if *reason == SkipReason::CopyFailed {
+                count(file, false);
+            }
+        }
+        let mut groups: Vec<_> = groups.into_values().collect();
+        groups.sort_by_key(|group| group.name != ROOT_GROUP);
+        groups
+    }
+
+    /// Where `file` is, relative to the source.
+    pub fn relative_path<'a>(&self, file: &'a ChecksumReportSingleFile) -> &'a Path {
+        file.source
+            .0
+            .strip_prefix(&self.source_root)
+            .unwrap_or(&file.source.0)
+    }
+
+    /// How far the copies were checked: a verification while copying still leaves reading them
+    /// back from the drives.
+    pub fn verification_level(&self) -> VerificationLevel {
+        match self.method {
+            VerifyMethod::ReadBack => VerificationLevel::Archive,
+            VerifyMethod::Stream | VerifyMethod::Handle => VerificationLevel::Copy,
+        }
+    }
+
+    /// How many times the destinations were read back.
+    pub fn verification_passes(&self) -> usize {
+        1 + self.files.first().map_or(0, |file| file.extra_passes.len())
+    }
+
+    /// Files whose copy didn't read the same on every verification pass at some destination.
+    pub fn unstable_files(&self) -> impl Iterator<Item = &ChecksumReportSingleFile> {
+        self.files
+            .iter()
+            .filter(|file| !file.unstable_destinations().is_empty())
+    }
+
+    /// Verified, failed and missing counts of each destination, by destination index. Files a
+    /// destination's filter leaves out aren't counted.
+    pub fn per_destination_summary(&self) -> Vec<DestinationSummary> {
+        let mut summaries: Vec<DestinationSummary> = Vec::new();
+        for file in &self.files {
+            if summaries.len() < file.destinations.len() {
+                summaries.resize(file.destinations.len(), DestinationSummary::default());
+            }
+            let unstable = file.unstable_destinations();
+            for (index, (summary, (_, hash))) in
+                summaries.iter_mut().zip(&file.destinations).enumerate()
+            {
+                match hash {
+                    // A salvaged file is never verified, however well it was copied, and neither
+                    // is one that only matched on some passes
+                    DestinationHash::Hashed(hash)
+                        if *hash == file.source.1
+                            && !file.salvaged()
+                            && !unstable.contains(&index) =>
+                    {
+                        summary.verified += 1
+                    }
+                    DestinationHash::Hashed(_) | DestinationHash::DiffersAt(_) => {
+                        summary.failed += 1
+                    }
+                    DestinationHash::MissingAtDestination => summary.missing += 1,
+                    DestinationHash::NotExpected => {}
+                }
+            }
+        }
+        summaries
+    }
+
+    /// Adds a destination to the report with the result of each file there, in the order of
+    /// [`ChecksumReport::files`], as [`catch_up_destination`] returns them.
+    pub fn add_destination(&mut self, results: Vec<(PathBuf, DestinationHash)>) {
+        let before = self.files.first().map_or(0, |file| file.destinations.len());
+        for (file, result) in self.files.iter_mut().zip(results) {
+            file.destinations.push(result);
+        }
+        // The catch-up copy checks each file as it writes it, so both end together
+        let now = Some(SystemTime::now());
+        self.destination_times
+            .resize(before, DestinationTimes::default());
+        self.destination_times.push(DestinationTimes {
+            copied_at: now,
+            verified_at: now,
+        });
+    }
+
+    /// Files missing from each destination, by destination index.
+    pub fn missing_by_destination(&self) -> Vec<Vec<PathBuf>> {
+        let mut missing: Vec<Vec<PathBuf>> = Vec::new();
+        for file in &self.files {
+            missing.resize_with(missing.len().max(file.destinations.len()), Vec::new);
+            for (index, (path, hash)) in file.destinations.iter().enumerate() {
+                if *hash == DestinationHash::MissingAtDestination {
+                    missing[index].push(path.clone());
+                }
+            }
+        }
+        missing
+    }
+
+    /// Folds the source hashes into one digest of the whole card, so two runs can be compared at
+    /// a glance. Paths are sorted first, so the copy order doesn't matter.
+    pub fn fingerprint(&self) -> Digest {
+        let mut entries: Vec<_> = self
+            .files
+            .iter()
+            .map(|file| {
+                let relative = file
+                    .source
+                    .0
+                    .strip_prefix(&self.source_root)
+                    .unwrap_or(&file.source.0);
+                (slash_path(relative), &file.source.1)
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut hasher = FileHasher::new(self.algorithm);
+        for (path, digest) in entries {
+            // The separator keeps a path from running into the next digest
+            hasher.update(path.as_bytes());
+            hasher.update(&[0]);
+            hasher.update(&digest.0);
+        }
+        hasher.finish()
+    }
+
+    /// A SHA-256 digest of the whole job, in lowercase hex, that two parties can compare to
+    /// confirm they hold the same files. It covers every file's relative path, size and hash, so
+    /// it changes with the hash algorithm but not with the copy order or the platform:
+    ///
+    /// - Paths are relative to the source root, with `/` between components, normalized to
+    ///   Unicode NFC and encoded as UTF-8.
+    /// - Files are sorted by the bytes of their path.
+    /// - Each file is written as its path, a NUL byte, its size in decimal, a NUL byte, the
+    ///   algorithm (`xxh3` or `sha256`), `:` and the lowercase hex hash, and a final NUL byte.
+    pub fn manifest_digest(&self) -> String {
+        let algorithm = self.algorithm.id();
+        let mut entries: Vec<_> = self
+            .files
+            .iter()
+            .map(|file| {
+                let relative = file
+                    .source
+                    .0
+                    .strip_prefix(&self.source_root)
+                    .unwrap_or(&file.source.0);
+                (slash_path(&nfc_path(relative)), file)
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+
+        let mut hasher = Sha256::new();
+        for (path, file) in entries {
+            hasher.update(path.as_bytes());
+            hasher.update(format!(
+                "\0{}\0{algorithm}:{:x}\0",
+                file.size, file.source.1
+            ));
+        }
+        format!("{:x}", Digest(hasher.finalize().to_vec()))
+    }
+}
+
+/// Verifies `files` of `source` at every destination. Each file that doesn't match is added to
+/// the `job`'s warnings feed as it is found. The copies of `salvaged` files are checked against
+/// what was salvaged, since the card can't be read for them. With more than one of
+/// `options.passes`, each file's copies are read back again right after the first pass, and a copy
+/// that doesn't read the same every time is warned about as unstable. Archive destinations are
+/// read back whole, once per pass, before the first file is checked, and their files are looked
+/// up in them. As many files are verified at once as the job's `workers` says when each is
+/// started, so lowering it takes effect as the files in flight finish; the counts used are kept in
+/// the report. Cancelling its `finish` starts no more files, and ends the job once those in flight
+/// are verified.
+pub async fn hash_dirs(
+    source: &Path,
+    dest: &[PathBuf],
+    filters: &[FileFilter],
+    files: &[PathBuf],
+    salvaged: &HashMap<PathBuf, Salvaged>,
+    options: HashOptions,
+    job: JobHandle,
+) -> io::Result<JobOutcome<ChecksumReport>> {
+    let JobHandle {
+        progress: tx,
+        warnings,
+        stop,
+        finish,
+        mut workers,
+    } = job;
+    let mut warnings = WarningLog::new(warnings);
+    let into_report = |files,
+                       destination_times: &[DestinationTimes],
+                       hash_workers: &[HashWorkers]| ChecksumReport {
+        algorithm: options.algorithm,
+        source_root: source.to_path_buf(),
+        files,
+        source_changes: SourceChanges::default(),
+        copy_order: None,
+        method: VerifyMethod::ReadBack,
+        duplicates: Vec::new(),
+        skipped: Vec::new(),
+        hard_links: Vec::new(),
+        reconnected: Vec::new(),
+        slow_files: Vec::new(),
+        baseline: None,
+        extra_files: Vec::new(),
+        stopped_at_failure: false,
+        unchecked_files: 0,
+        compared: options.compare,
+        disabled_destinations: Vec::new(),
+        destination_times: destination_times.to_vec(),
+        hash_workers: hash_workers.to_vec(),
+        job_id: None,
+        volume_listing: None,
+        folders: None,
+        finished_at: SystemTime::now(),
+    };
+    // Files finish out of order, but the report lists them in the order they were given
+    let in_order = |mut checked: Vec<(usize, ChecksumReportSingleFile)>| {
+        checked.sort_by_key(|(index, _)| *index);
+        checked.into_iter().map(|(_, file)| file).collect()
+    };
+    let mut destination_times = vec![DestinationTimes::default(); dest.len()];
+    let mut hash_workers = vec![HashWorkers {
+        since: SystemTime::now(),
+        workers: (*workers.borrow_and_update()).max(1),
+    }];
+
+    // Stat everything up front so progress can follow bytes, not just files
+    let source_paths: Vec<_> = files.iter().map(|file| source.join(file)).collect();
+    let sizes = tokio::task::spawn_blocking(move || {
+        source_paths
+            .iter()
+            .map(|path| std::fs::metadata(long_path(path)).map_or(0, |metadata| metadata.len()))
+            .collect::<Vec<_>>()
+    })
+    .await?;
+
+    // Passes after the first read every copy again, which takes about as long as the first
+    let passes = options.passes.max(1);
+    let archives = match read_archives(dest, options.algorithm, passes, &stop).await {
+        Err(_) if stop.is_cancelled() => {
+            return Ok(JobOutcome::Cancelled(into_report(
+                Vec::new(),
+                &destination_times,
+                &hash_workers,
+            )));
+        }
+        archives => archives?,
+    };
+    let archives = &archives;
+    let mut progress = Progress {
+        total_files: files.len(),
+        total_bytes: sizes.iter().sum::<u64>() * u64::from(passes),
+        ..Default::default()
+    };
+    for file in files {
+        progress.add_to_group(file);
+    }
+    tx.send_replace(progress.clone());
+
+    let verify_job = VerifyJob {
+        source,
+        dest,
+        archives,
+        options,
+        stop: &stop,
+    };
+    let mut queue = files.iter().zip(sizes).enumerate();
+    let mut running = futures::stream::FuturesUnordered::new();
+    // How far into each file in flight its hashes are, by its index in `files`
+    let mut in_flight: HashMap<usize, Arc<AtomicU64>> = HashMap::new();
+    let mut checked = Vec::new();
+    let mut workers_open = true;
+    // The file that failed, once `stop_on_failure` starts no more
+    let mut failed_at = None;
+    loop {
+        let limit = hash_workers.last().map_or(1, |used| used.workers);
+        while failed_at.is_none()
+            && !finish.is_cancelled()
+            && running.len() < limit
+            && let Some((index, (file, size))) = queue.next()
+        {
+            if stop.is_cancelled() {
+                let report = in_order(checked);
+                return Ok(JobOutcome::Cancelled(into_report(
+                    report,
+                    &destination_times,
+                    &hash_workers,
+                )));
+            }
+            tracing::debug!("Verifying {}", file.display());
+            let wanted: Vec<bool> = (0..dest.len())
+                .map(|index| wants_file(filters, index, file))
+                .collect();
+            progress.current_file = Some(file.clone());
+            progress.current_file_size = size * u64::from(passes);
+            tx.send_replace(progress.clone());
+
+            // Every file in flight adds how far it is to the bar; every hash checks for a stop
+            // request
+            let done = Arc::new(AtomicU64::new(0));
+            in_flight.insert(index, done.clone());
+            let on_progress = {
+                let tx = tx.clone();
+                let stop = stop.clone();
+                move |bytes: u64| {
+                    let before = done.swap(bytes, Ordering::Relaxed);
+                    tx.send_modify(|progress| {
+                        progress.current_file_bytes =
+                            (progress.current_file_bytes + bytes).saturating_sub(before);
+                    });
+                    !stop.is_cancelled()
+                }
+            };
+            let salvaged = salvaged.get(file);
+            running.push(async move {
+                let verified =
+                    verify_file(verify_job, file, size, &wanted, salvaged, on_progress).await;
+                (index, wanted, verified)
+            });
+        }
+        if running.is_empty() {
+            break;
+        }
+
+        let finished = tokio::select! {
+            finished = running.next() => finished,
+            changed = workers.changed(), if workers_open => {
+                match changed {
+                    Ok(()) => {
+                        let workers = (*workers.borrow_and_update()).max(1);
+                        if workers != limit {
+                            tracing::info!("Verifying {workers} files at once");
+                            hash_workers.push(HashWorkers {
+                                since: SystemTime::now(),
+                                workers,
+                            });
+                        }
+                    }
+                    // Nothing can change it any more
+                    Err(_) => workers_open = false,
+                }
+                continue;
+            }
+        };
+        let Some((index, wanted, verified)) = finished else {
+            break;
+        };
+        in_flight.remove(&index);
+        let (file_report, read_retries) = match verified? {
+            Some(verified) => verified,
+            None => {
+                let report = in_order(checked);
+                return Ok(JobOutcome::Cancelled(into_report(
+                    report,
+                    &destination_times,
+                    &hash_workers,
+                )));
+            }
+        };
+
+        let file = &files[index];
+        for ReadRetry { offset, attempts } in read_retries {
+            let kind = WarningKind::ReadRetried { offset, attempts };
+            warnings.warn(&mut progress, file, kind);
+        }
+        for destination in file_report.unstable_destinations() {
+            let kind = WarningKind::UnstableMedia { destination };
+            warnings.warn(&mut progress, file, kind);
+        }
+        let consistent = file_report.consistent();
+        if consistent {
+            progress.verified_files += 1;
+        } else {
+            progress.failed_files += 1;
+            let kind = match file_report.salvaged() {
+                true => WarningKind::Salvaged {
+                    bytes_lost: file_report.bytes_lost(),
+                },
+                false => WarningKind::VerifyFailed,
+            };
+            warnings.warn(&mut progress, file, kind);
+        }
+        note_verified(&mut destination_times, &wanted);
+        progress.complete_in_group(file);
+        progress.complete_file(file_report.size * u64::from(passes));
+        progress.current_file_bytes = in_flight
+            .values()
+            .map(|done| done.load(Ordering::Relaxed))
+            .sum();
+        tx.send_replace(progress.clone());
+        checked.push((index, file_report));
+
+        // The files already in flight are still seen through
+        if !consistent && options.stop_on_failure && failed_at.is_none() {
+            failed_at = Some(index);
+        }
+    }
+
+    if finish.is_cancelled() && failed_at.is_none() && checked.len() < files.len() {
+        let report = in_order(checked);
+        return Ok(JobOutcome::Cancelled(into_report(
+            report,
+            &destination_times,
+            &hash_workers,
+        )));
+    }
+    if let Some(failed_at) = failed_at {
+        // The report ends with the failure it stopped at
+        checked.sort_by_key(|(index, _)| (*index == failed_at, *index));
+        let report: Vec<_> = checked.into_iter().map(|(_, file)| file).collect();
+        let unchecked_files = files.len() - report.len();
+        return Ok(JobOutcome::Completed(ChecksumReport {
+            stopped_at_failure: true,
+            unchecked_files,
+            ..into_report(report, &destination_times, &hash_workers)
+        }));
+    }
+    let report = in_order(checked);
+    let whole = whole_folders(dest, filters);
+    let dest = folders(dest);
+    let files = files.to_vec();
+    let extra_files =
+        tokio::task::spawn_blocking(move || find_extra_files(&dest, &files)).await??;
+    let folders = match options.check_folders {
+        true => {
+            let source = source.to_path_buf();
+            Some(tokio::task::spawn_blocking(move || compare_folders(&source, &whole)).await??)
+        }
+        false => None,
+    };
+    Ok(JobOutcome::Completed(ChecksumReport {
+        extra_files,
+        folders,
+        ..into_report(report, &destination_times, &hash_workers)
+    }))
+}
+
+/// [`hash_dirs`] for callers without a Tokio runtime, on one of its own that lives for the job.
+/// The `job` handle works as it does there. Fails rather than nest a runtime when called from
+/// inside one; use [`hash_dirs`] there.
+pub fn hash_dirs_blocking(
+    source: &Path,
+    dest: &[PathBuf],
+    filters: &[FileFilter],
+    files: &[PathBuf],
+    salvaged: &HashMap<PathBuf, Salvaged>,
+    options: HashOptions,
+    job: JobHandle,
+) -> io::Result<JobOutcome<ChecksumReport>> {
+    job_runtime()?.block_on(hash_dirs(
+        source, dest, filters, files, salvaged, options, job,
+    ))
+}
+
+/// What every file of a [`hash_dirs`] job is verified with.
+#[derive(Clone, Copy)]
+struct VerifyJob<'a> {
+    source: &'a Path,
+    dest: &'a [PathBuf],
+    /// The entries of each archive destination, one map per pass, by the destination's index in
+    /// `dest`. A folder has none.
+    archives: &'a [Vec<HashMap<PathBuf, Digest>>],
+    options: HashOptions,
+    stop: &'a CancellationToken,
+}
+
+/// Verifies one `file` of the `job`, of `size`, at the destinations that are `wanted`, with the
+/// source's read retries. `on_progress` is told how far into the file the hashes are, over every
+/// pass. Returns `None` if the job's `stop` was cancelled first.
+async fn verify_file<F>(
+    job: VerifyJob<'_>,
+    file: &Path,
+    size: u64,
+    wanted: &[bool],
+    salvaged: Option<&Salvaged>,
+    on_progress: F,
+) -> io::Result<Option<(ChecksumReportSingleFile, Vec<ReadRetry>)>>
+where
+    F: Fn(u64) -> bool + Clone + Send + 'static,
+{
+    let VerifyJob {
+        source,
+        dest,
+        archives,
+        options,
+        stop,
+    } = job;
+    let passes = options.passes.max(1);
+    let source_path = source.join(file);
+    let dest_paths: Vec<_> = dest.iter().map(|d| d.join(file)).collect();
+    let source_path_clone = source_path.clone();
+    let dest_paths_clone = dest_paths.clone();
+
+    // Only folders are read file by file
+    let read: Vec<bool> = wanted
+        .iter()
+        .zip(archives)
+        .map(|(wanted, entries)| *wanted && entries.is_empty())
+        .collect();
+    let in_archive = read != wanted;
+
+    let mut read_retries = Vec::new();
+    let mut file_report = if let Some(salvaged) = salvaged {
+        let mut destinations = Vec::with_capacity(dest_paths.len());
+        for (index, dest_path) in dest_paths.iter().enumerate() {
+            let keep_going = stop.clone();
+            let hash = hash_destination(dest_path, options, read[index], move |_| {
+                !keep_going.is_cancelled()
+            })
+            .await;
+            if stop.is_cancelled() {
+                return Ok(None);
+            }
+            destinations.push((dest_path.clone(), hash?));
+        }
+        ChecksumReportSingleFile {
+            source: (source_path, salvaged.hash.clone()),
+            size,
+            destinations,
+            stopped_at: None,
+            lost: salvaged.lost.clone(),
+            extra_passes: Vec::new(),
+        }
+    // A comparison would stop short of the end if no folder is left to compare with, and an
+    // archive entry needs the whole source hash
+    // An empty file has no content to hash, only whether each copy is there and empty too
+    } else if size == 0 && !in_archive {
+        let (source_hash, retries) =
+            compute_file_hash_with_retries(&source_path, options, on_progress.clone()).await?;
+        read_retries = retries;
+        // A source that has grown since it was listed is hashed against its copies as usual
+        let still_empty = tokio::fs::metadata(long_path(&source_path)).await?.len() == 0;
+        let mut destinations = Vec::with_capacity(dest_paths.len());
+        for (index, dest_path) in dest_paths.iter().enumerate() {
+            let hash = match still_empty {
+                true => {
+                    check_empty_destination(dest_path, options, read[index], &source_hash).await
+                }
+                false => hash_destination(dest_path, options, read[index], |_| true).await,
+            };
+            if stop.is_cancelled() {
+                return Ok(None);
+            }
+            destinations.push((dest_path.clone(), hash?));
+        }
+        ChecksumReportSingleFile {
+            source: (source_path, source_hash),
+            size,
+            destinations,
+            stopped_at: None,
+            lost: Vec::new(),
+            extra_passes: Vec::new(),
+        }
+    } else if options.compare && !in_archive {
+        let compared = compare_destinations(
+            &source_path,
+            &dest_paths,
+            wanted,
+            options.algorithm,
+            on_progress.clone(),
+        )
+        .await;
+        if stop.is_cancelled() {
+            return Ok(None);
+        }
+        // A comparison that stopped early didn't see the whole file
+        ChecksumReportSingleFile { size, ..compared? }
+    } else {
+        // Take advantage of multiple cores, just in case.
+        let source_progress = on_progress.clone();
+        let source_hash_future = spawn(async move {
+            compute_file_hash_with_retries(&source_path_clone, options, source_progress).await
+        });
+        let dest_hash_futures: Vec<_> = dest_paths_clone
+            .into_iter()
+            .enumerate()
+            .map(|(index, dest_path)| {
+                let stop = stop.clone();
+                let read = read[index];
+                spawn(async move {
+                    hash_destination(&dest_path, options, read, move |_| !stop.is_cancelled()).await
+                })
+            })
+            .collect();
+        let dest_hash_futures = join_all(dest_hash_futures);
+
+        // Execute the futures concurrently
+        let (source_hash_result, dest_hash_results) = join!(source_hash_future, dest_hash_futures);
+        if stop.is_cancelled() {
+            // The hashes may have been cut short
+            return Ok(None);
+        }
+        // Remove JoinError
+        let source_hash_result = source_hash_result?;
+        let dest_hash_results = collect_results(dest_hash_results)?;
+
+        let mut destination_hashes = Vec::new();
+        for (dest_path, dest_hash_result) in dest_paths.iter().zip(dest_hash_results) {
+            destination_hashes.push((dest_path.clone(), dest_hash_result?));
+        }
+        let (source_hash, retries) = source_hash_result?;
+        read_retries = retries;
+
+        ChecksumReportSingleFile {
+            source: (source_path, source_hash),
+            size,
+            destinations: destination_hashes,
+            stopped_at: None,
+            lost: Vec::new(),
+            extra_passes: Vec::new(),
+        }
+    };
+    let in_archives = |pass: usize, hashes: &mut [(usize, &mut DestinationHash)]| {
+        for (index, hash) in hashes {
+            if let (Some(entries), true) = (archives[*index].get(pass), wanted[*index]) {
+                **hash = archive_hash(entries, file);
+            }
+        }
+    };
+    in_archives(
+        0,
+        &mut file_report
+            .destinations
+            .iter_mut()
+            .map(|(_, hash)| hash)
+            .enumerate()
+            .collect::<Vec<_>>(),
+    );
+    for pass in 1..passes {
+        let pass_progress = {
+            let on_progress = on_progress.clone();
+            let before = size * u64::from(pass);
+            move |bytes| on_progress(before + bytes)
+        };
+        let hashes = reread_destinations(&dest_paths, &read, options, stop, pass_progress).await;
+        if stop.is_cancelled() {
+            return Ok(None);
+        }
+        let mut hashes = hashes?;
+        in_archives(
+            pass as usize,
+            &mut hashes.iter_mut().enumerate().collect::<Vec<_>>(),
+        );
+        file_report.extra_passes.push(hashes);
+    }
+    Ok(Some((file_report, read_retries)))
+}
+
+/// Copies the files of a verified job from its destination `from`, by index, to `to`, a
+/// destination added after the job, and checks each copy against the source hash in the report.
+/// The card isn't read again. Files that aren't at `from` aren't copied, and keep the result they
+/// had there. Returns the result at `to` of each file, in the order of the report. Archives can't
+/// be caught up, from or to.
+pub async fn catch_up_destination(
+    report: &ChecksumReport,
+    from: usize,
+    to: &Path,
+    options: HashOptions,
+    tx: watch::Sender<Progress>,
+    stop: CancellationToken,
+) -> io::Result<JobOutcome<Vec<(PathBuf, DestinationHash)>>> {
+    let archived = report
+        .destination_roots()
+        .get(from)
+        .is_some_and(|root| DestinationKind::of(root).is_archive());
+    if archived || DestinationKind::of(to).is_archive() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Archive destinations can't be caught up",
+        ));
+    }
+    let options = HashOptions {
+        algorithm: report.algorithm,
+        ..options
+    };
+    let copied = |hash: &DestinationHash| {
+        !matches!(
+            hash,
+            DestinationHash::NotExpected | DestinationHash::MissingAtDestination
+        )
+    };
+    let mut progress = Progress::default();
+    for file in &report.files {
+        if file
+            .destinations
+            .get(from)
+            .is_some_and(|(_, hash)| copied(hash))
+        {
+            progress.total_files += 1;
+            progress.total_bytes += file.size;
+        }
+    }
+    tx.send_replace(progress.clone());
+
+    let mut results = Vec::with_capacity(report.files.len());
+    for file in &report.files {
+        let relative = file
+            .source
+            .0
+            .strip_prefix(&report.source_root)
+            .unwrap_or(&file.source.0);
+        let target = to.join(relative);
+        let Some((origin, hash)) = file.destinations.get(from) else {
+            results.push((target, DestinationHash::NotExpected));
+            continue;
+        };
+        if !copied(hash) {
+            results.push((target, hash.clone()));
+            continue;
+        }
+        if stop.is_cancelled() {
+            return Ok(JobOutcome::Cancelled(results));
+        }
+
+        tracing::debug!("Copying {} to {}", origin.display(), target.display());
+        progress.current_file = Some(relative.to_path_buf());
+        progress.current_file_size = file.size;
+        progress.current_file_bytes = 0;
+        tx.send_replace(progress.clone());
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(long_path(parent)).await?;
+        }
+        allow_overwrite(&target).await?;
+        tokio::fs::copy(long_path(origin), long_path(&target)).await?;
+        // The copy takes the permissions of a read-only origin, which Windows won't set a time on
+        allow_overwrite(&target).await?;
+        copy_modified_time(origin.clone(), vec![target.clone()]).await?;
+
+        let on_progress = {
+            let tx = tx.clone();
+            let stop = stop.clone();
+            move |bytes| {
+                tx.send_modify(|progress| progress.current_file_bytes = bytes);
+                !stop.is_cancelled()
+            }
+        };
+        let hash = match hash_destination(&target, options, true, on_progress).await {
+            Err(_) if stop.is_cancelled() => return Ok(JobOutcome::Cancelled(results)),
+            hash => hash?,
+        };
+        if let DestinationHash::Hashed(digest) = &hash {
+            if *digest == file.source.1 {
+                progress.verified_files += 1;
+            } else {
+                progress.failed_files += 1;
+            }
+        }
+        results.push((target, hash));
+        progress.complete_file(file.size);
+        tx.send_replace(progress.clone());
+    }
+    Ok(JobOutcome::Completed(results))
+}
+
+/// Reads back every copy of a job verified while copying and checks it against the source hash
+/// in the report, which brings the job up to [`VerificationLevel::Archive`]. The card isn't read
+/// again. Copies that weren't expected stay that way, and files whose source hash is incomplete
+/// keep their result. Returns the report as a read-back verification; when cancelled, the report
+/// as it was.
+pub async fn read_back_report(
+    report: &ChecksumReport,
+    options: HashOptions,
+    tx: watch::Sender<Progress>,
+    stop: CancellationToken,
+) -> io::Result<JobOutcome<ChecksumReport>> {
+    let options = HashOptions {
+        algorithm: report.algorithm,
+        ..options
+    };
+    let roots = report.destination_roots();
+    let archives = match read_archives(&roots, report.algorithm, 1, &stop).await {
+        Err(_) if stop.is_cancelled() => return Ok(JobOutcome::Cancelled(report.clone())),
+        archives => archives?,
+    };
+    // A comparison that stopped early has no whole source hash to check against
+    let checked = |file: &ChecksumReportSingleFile| file.stopped_at.is_none();
+    let mut progress = Progress::default();
+    for file in report.files.iter().filter(|file| checked(file)) {
+        progress.total_files += 1;
+        progress.total_bytes += file.size;
+    }
+    tx.send_replace(progress.clone());
+
+    let mut read_back = report.clone();
+    let mut destination_times = report.destination_times.clone();
+    destination_times.resize(roots.len(), DestinationTimes::default());
+    for file in read_back.files.iter_mut().filter(|file| checked(file)) {
+        if stop.is_cancelled() {
+            return Ok(JobOutcome::Cancelled(report.clone()));
+        }
+        let relative = file
+            .source
+            .0
+            .strip_prefix(&report.source_root)
+            .unwrap_or(&file.source.0)
+            .to_path_buf();
+        tracing::debug!("Reading back {}", relative.display());
+        progress.current_file = Some(relative.clone());
+        progress.current_file_size = file.size;
+        progress.current_file_bytes = 0;
+        tx.send_replace(progress.clone());
+
+        let mut expected = vec![false; roots.len()];
+        for (index, (path, hash)) in file.destinations.iter_mut().enumerate() {
+            if *hash == DestinationHash::NotExpected {
+                continue;
+            }
+            expected[index] = true;
+            *hash = match archives.get(index).and_then(|passes| passes.first()) {
+                Some(entries) => archive_hash(entries, &relative),
+                None => {
+                    let on_progress = {
+                        let tx = tx.clone();
+                        let stop = stop.clone();
+                        move |bytes| {
+                            tx.send_modify(|progress| progress.current_file_bytes = bytes);
+                            !stop.is_cancelled()
+                        }
+                    };
+                    match hash_destination(path, options, true, on_progress).await {
+                        Err(_) if stop.is_cancelled() => {
+                            return Ok(JobOutcome::Cancelled(report.clone()));
+                        }
+                        hash => hash?,
+                    }
+                }
+            };
+        }
+        // Each pass was a read while copying
+        file.extra_passes.clear();
+        if file.consistent() {
+            progress.verified_files += 1;
+        } else {
+            progress.failed_files += 1;
+        }
+        note_verified(&mut destination_times, &expected);
+        progress.complete_file(file.size);
+        tx.send_replace(progress.clone());
+    }
+    read_back.method = VerifyMethod::ReadBack;
+    read_back.destination_times = destination_times;
+    read_back.finished_at = SystemTime::now();
+    Ok(JobOutcome::Completed(read_back))
+}
+
+/// How one destination holds the source when files are matched by content instead of path.
+#[derive(Clone, Debug)]
+pub struct ContentMatch {
+    pub destination: PathBuf,
+    /// Source files with the same content at the same path.
+    pub in_place: usize,
+    /// Source files whose content is at the destination under another path.
+    pub moved: Vec<PathBuf>,
+    /// Source files whose content is nowhere at the destination.
+    pub missing: Vec<PathBuf>,
+}
+
+/// Result of `reconcile_contents`.
+#[derive(Clone, Debug)]
+pub struct ContentReport {
+    pub algorithm: HashAlgorithm,
+    pub source_files: usize,
+    pub destinations: Vec<ContentMatch>,
+}
+
+/// Checks that every source file's content is somewhere at each destination, wherever it is, for
+/// a backup whose folders were reorganized. Only destination files with the size of some source
+/// file are hashed.
+pub async fn reconcile_contents(
+    source: &Path,
+    dest: &[PathBuf],
+    options: HashOptions,
+    tx: watch::Sender<Progress>,
+    stop: CancellationToken,
+) -> io::Result<JobOutcome<ContentReport>> {
+    let cancelled = || {
+        Ok(JobOutcome::Cancelled(ContentReport {
+            algorithm: options.algorithm,
+            source_files: 0,
+            destinations: Vec::new(),
+        }))
+    };
+
+    // List and stat both sides up front, so progress can follow bytes
+    let roots: Vec<PathBuf> = std::iter::once(source.to_path_buf())
+        .chain(dest.iter().cloned())
+        .collect();
+    let mut listings = tokio::task::spawn_blocking(move || {
+        roots
+            .iter()
+            .map(|root| {
+                let mut files = flatten_dir_files(root)?;
+                files.sort();
+                Ok(files
+                    .into_iter()
+                    .map(|file| {
+                        let size =
+                            std::fs::metadata(long_path(&root.join(&file))).map_or(0, |m| m.len());
+                        (file, size)
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .collect::<io::Result<Vec<_>>>()
+    })
+    .await??;
+    let source_listing = listings.remove(0);
+
+    // A destination file can only match a source file of the same size
+    let source_sizes: HashSet<u64> = source_listing.iter().map(|(_, size)| *size).collect();
+    for listing in &mut listings {
+        listing.retain(|(_, size)| source_sizes.contains(size));
+    }
+
+    let mut progress = Progress::default();
+    for (_, size) in source_listing.iter().chain(listings.iter().flatten()) {
+        progress.total_files += 1;
+        progress.total_bytes += size;
+    }
+    tx.send_replace(progress.clone());
+
+    let mut source_hashes = Vec::with_capacity(source_listing.len());
+    for (file, size) in &source_listing {
+        if stop.is_cancelled() {
+            return cancelled();
+        }
+        let stop_hash = stop.clone();
+        let digest = match compute_file_hash(source.join(file), options, move |_| {
+            !stop_hash.is_cancelled()
+        })
+        .await
+        {
+            Err(_) if stop.is_cancelled() => return cancelled(),
+            digest => digest?,
+        };
+        source_hashes.push((file, digest));
+        progress.complete_file(*size);
+        tx.send_replace(progress.clone());
+    }
+
+    let mut destinations = Vec::with_capacity(dest.len());
+    for (root, listing) in dest.iter().zip(listings) {
+        let mut found: HashMap<Digest, Vec<PathBuf>> = HashMap::new();
+        for (file, size) in listing {
+            if stop.is_cancelled() {
+                return cancelled();
+            }
+            let stop_hash = stop.clone();
+            let digest = match compute_file_hash(root.join(&file), options, move |_| {
+                !stop_hash.is_cancelled()
+            })
+            .await
+            {
+                Err(_) if stop.is_cancelled() => return cancelled(),
+                digest => digest?,
+            };
+            found
+                .entry(digest)
+                .or_default()
+                .push(nfc_path(&file).into_owned());
+            progress.complete_file(size);
+            tx.send_replace(progress.clone());
+        }
+
+        let mut content_match = ContentMatch {
+            destination: root.clone(),
+            in_place: 0,
+            moved: Vec::new(),
+            missing: Vec::new(),
+        };
+        for (file, digest) in &source_hashes {
+            match found.get(digest) {
+                Some(paths) if paths.iter().any(|path| *path == nfc_path(file)) => {
+                    content_match.in_place += 1
+                }
+                Some(_) => content_match.moved.push((*file).clone()),
+                None => content_match.missing.push((*file).clone()),
+            }
+        }
+        destinations.push(content_match);
+    }
+
+    Ok(JobOutcome::Completed(ContentReport {
+        algorithm: options.algorithm,
+        source_files: source_listing.len(),
+        destinations,
+    }))
+}
+
+/// One source file as read by both passes of [`double_read_source`], each with its hash or the
+/// error the read failed with.
+#[derive(Clone, Debug)]
+pub struct DoubleRead {
+    /// Path relative to the source.
+    pub path: PathBuf,
+    pub size: u64,
+    pub first: Result<Digest, String>,
+    pub second: Result<Digest, String>,
+}
+
+impl DoubleRead {
+    /// Both reads succeeded and gave the same data.
+    pub fn agreed(&self) -> bool {
+        matches!((&self.first, &self.second), (Ok(first), Ok(second)) if first == second)
+    }
+}
+
+/// Result of [`double_read_source`].
+#[derive(Clone, Debug)]
+pub struct ReadabilityReport {
+    pub algorithm: HashAlgorithm,
+    pub source_root: PathBuf,
+    /// Every file both passes got to, in path order.
+    pub files: Vec<DoubleRead>,
+    pub finished_at: SystemTime,
+}
+
+impl ReadabilityReport {
+    /// Files whose reads failed or didn't agree.
+    pub fn troubled(&self) -> impl Iterator<Item = &DoubleRead> {
+        self.files.iter().filter(|file| !file.agreed())
+    }
+
+    /// Exports the report in the given format, writing times as `timestamps` says, and seals it
+    /// with [`seal_report`]. Checksum lists only hold the files both reads agreed on.
+    pub fn export<P: AsRef<Path>>(
+        &self,
+        to_file: P,
+        format: ManifestFormat,
+        timestamps: &TimestampFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let to_file = to_file.as_ref();
+        match format {
+            ManifestFormat::Csv => self.export_report(to_file, timestamps)?,
+            ManifestFormat::Gnu | ManifestFormat::Bsd => {
+                self.export_checksum_list(to_file, format)?
+            }
+            ManifestFormat::Certificate => self.export_certificate(to_file, timestamps)?,
+            ManifestFormat::Json | ManifestFormat::Mhl => {
+                return Err("A readability report can't be exported as JSON or MHL".into());
+            }
+        }
+        Ok(seal_report(to_file, format)?)
+    }
+
+    fn export_report<P: AsRef<Path>>(
+        &self,
+        to_file: P,
+        timestamps: &TimestampFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut file = std::fs::File::create(to_file)?;
+        writeln!(file, "# Source: {}", nfc_path(&self.source_root).display())?;
+        writeln!(file, "# Algorithm: {}", self.algorithm)?;
+        writeln!(
+            file,
+            "# Read twice: {}",
+            timestamps.format(self.finished_at)
+        )?;
+        let mut writer = Writer::from_writer(file);
+        writer.write_record(["Agreed", "File", "Size", "First Read", "Second Read"])?;
+        let read = |result: &Result<Digest, String>| match result {
+            Ok(hash) => format!("{hash:X}"),
+            Err(error) => format!("Failed: {error}"),
+        };
+        for row in &self.files {
+            writer.write_record([
+                if row.agreed() { "Y" } else { "N" }.to_owned(),
+                nfc_path(&row.path).to_string_lossy().into_owned(),
+                row.size.to_string(),
+                read(&row.first),
+                read(&row.second),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the hashes both reads agreed on as a checksum list that `sha256sum -c` accepts when
+    /// run from the source.
+    fn export_checksum_list<P: AsRef<Path>>(
+        &self,
+        to_file: P,
+        format: ManifestFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(tag) = self.algorithm.tag_name() else {
+            return Err(format!("{} has no standard checksum list format", self.algorithm).into());
+        };
+
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(to_file)?);
+        for row in self.files.iter().filter(|row| row.agreed()) {
+            let Ok(hash) = &row.first else {
+                continue;
+            };
+            let (path, escaped) = escape_checksum_path(&slash_path(&nfc_path(&row.path)));
+            let prefix = if escaped { "\\" } else { "" };
+            if format == ManifestFormat::Bsd {
+                writeln!(writer, "{prefix}{tag} ({path}) = {hash:x}")?;
+            } else {
+                writeln!(writer, "{prefix}{hash:x}  {path}")?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes a short plain-text summary of the check, to keep with a card or reader sent for
+    /// repair.
+    fn export_certificate<P: AsRef<Path>>(
+        &self,
+        to_file: P,
+        timestamps: &TimestampFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(to_file)?);
+        let troubled: Vec<_> = self.troubled().collect();
+        writeln!(file, "LibreCard source readability check")?;
+        writeln!(file)?;
+        writeln!(
+            file,
+            "Read twice:      {}",
+            timestamps.format(self.finished_at)
+        )?;
+        writeln!(file, "Source:          {}", self.source_root.display())?;
+        let total_bytes: u64 = self.files.iter().map(|file| file.size).sum();
+        writeln!(
+            file,
+            "Files:           {} ({} bytes)",
+            self.files.len(),
+            total_bytes
+        )?;
+        writeln!(file, "Algorithm:       {}", self.algorithm)?;
+        writeln!(file)?;
+        if troubled.is_empty() {
+            writeln!(file, "PASSED: both reads of every file gave the same data.")?;
+        } else {
+            writeln!(
+                file,
+                "FAILED: {} of {} files failed to read or read differently:",
+                troubled.len(),
+                self.files.len()
+            )?;
+            for row in troubled {
+                writeln!(file, "  {}", nfc_path(&row.path).display())?;
+            }
+        }
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads and hashes every file under `source` twice, one full pass after the other, to tell a
+/// flaky card reader from a bad card without any destination involved. Reads aren't retried, so
+/// one that fails even once is reported. A source that fits in memory may be served from the
+/// system's cache on the second pass, which then only shows the first read was complete.
+pub async fn double_read_source(
+    source: &Path,
+    options: HashOptions,
+    tx: watch::Sender<Progress>,
+    stop: CancellationToken,
+) -> io::Result<JobOutcome<ReadabilityReport>> {
+    let listing = {
+        let source = source.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let mut files = flatten_dir_files(&source)?;
+            files.sort();
+            Ok::<_, io::Error>(
+                files
+                    .into_iter()
+                    .map(|file| {
+                        let size = std::fs::metadata(long_path(&source.join(&file)))
+                            .map_or(0, |m| m.len());
+                        (file, size)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .await??
+    };
+    let options = HashOptions {
+        read_retries: 0,
+        ..options
+    };
+
+    let mut progress = Progress {
+        total_files: listing.len() * 2,
+        total_bytes: listing.iter().map(|(_, size)| size).sum::<u64>() * 2,
+        ..Default::default()
+    };
+    tx.send_replace(progress.clone());
+
+    let mut passes: [Vec<Result<Digest, String>>; 2] = Default::default();
+    let mut cancelled = false;
+    'passes: for pass in &mut passes {
+        for (file, size) in &listing {
+            if stop.is_cancelled() {
+                cancelled = true;
+                break 'passes;
+            }
+            progress.current_file = Some(file.clone());
+            progress.current_file_size = *size;
+            progress.current_file_bytes = 0;
+            tx.send_replace(progress.clone());
+
+            let on_progress = {
+                let tx = tx.clone();
+                let stop = stop.clone();
+                move |bytes| {
+                    tx.send_modify(|progress| progress.current_file_bytes = bytes);
+                    !stop.is_cancelled()
+                }
+            };
+            let digest = compute_file_hash(source.join(file), options, on_progress).await;
+            // A hash cut short by the stop request isn't a failed read
+            if stop.is_cancelled() {
+                cancelled = true;
+                break 'passes;
+            }
+            if let Err(e) = &digest {
+                tracing::warn!("Failed to read {}: {e}", file.display());
+            }
+            pass.push(digest.map_err(|e| e.to_string()));
+            progress.complete_file(*size);
+            tx.send_replace(progress.clone());
+        }
+    }
+
+    let [first, second] = passes;
+    let report = ReadabilityReport {
+        algorithm: options.algorithm,
+        source_root: source.to_path_buf(),
+        files: listing
+            .into_iter()
+            .zip(first.into_iter().zip(second))
+            .map(|((path, size), (first, second))| DoubleRead {
+                path,
+                size,
+                first,
+                second,
+            })
+            .collect(),
+        finished_at: SystemTime::now(),
+    };
+    Ok(match cancelled {
+        true => JobOutcome::Cancelled(report),
+        false => JobOutcome::Completed(report),
+    })
+}
+
+/// Compares each wanted destination with the source chunk by chunk, hashing the source as it
+/// streams through. A destination is done with at its first differing byte; once every
+/// destination has differed, the rest of the source isn't read either. `on_progress` gets the
+/// bytes compared so far and stops the comparison by returning `false`.
+async fn compare_destinations<F>(
+    source_path: &Path,
+    dest_paths: &[PathBuf],
+    wanted: &[bool],
+    algorithm: HashAlgorithm,
+    mut on_progress: F,
+) -> io::Result<ChecksumReportSingleFile>
+where
+    F: FnMut(u64) -> bool,
+{
+    const CHUNK_SIZE: usize = 1024 * 1024; // 1MB
+    let mut source = File::open(long_path(source_path)).await?;
+    let mut hasher = FileHasher::new(algorithm);
+    let mut results = Vec::with_capacity(dest_paths.len());
+    // Destinations still matching so far, with their index and a buffer of their own
+    let mut matching = Vec::new();
+    for (index, (path, wanted)) in dest_paths.iter().zip(wanted).enumerate() {
+        if !*wanted {
+            results.push(Some(DestinationHash::NotExpected));
+            continue;
+        }
+        let path = match tokio::fs::metadata(long_path(path)).await {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let wanted = path.to_path_buf();
+                tokio::task::spawn_blocking(move || find_by_nfc(&wanted)).await?
+            }
+            _ => Some(path.to_path_buf()),
+        };
+        match path {
+            Some(path) => {
+                matching.push((index, open_shared(&path).await?, vec![0; CHUNK_SIZE]));
+                results.push(None);
+            }
+            None => results.push(Some(DestinationHash::MissingAtDestination)),
+        }
+    }
+
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut offset = 0;
+    let mut chunks = 0;
+    let mut differed = false;
+    let stopped_at = loop {
+        if matching.is_empty() && differed {
+            break Some(offset);
+        }
+        let bytes_read = read_full(&mut source, &mut buffer).await?;
+        let chunk = &buffer[..bytes_read];
+        hasher.update(chunk);
+
+        // At the end of the source, a destination that still has bytes is longer
+        let reads = join_all(matching.iter_mut().map(|(_, file, dest_buffer)| {
+            let len = bytes_read.max(1);
+            read_full(file, &mut dest_buffer[..len])
+        }))
+        .await;
+        let mut still_matching = Vec::with_capacity(matching.len());
+        for ((index, file, dest_buffer), read) in matching.into_iter().zip(reads) {
+            let dest_chunk = &dest_buffer[..read?];
+            let first_difference = chunk
+                .iter()
+                .zip(dest_chunk)
+                .position(|(a, b)| a != b)
+                .or_else(|| {
+                    (chunk.len() != dest_chunk.len()).then(|| chunk.len().min(dest_chunk.len()))
+                });
+            match first_difference {
+                Some(position) => {
+                    results[index] = Some(DestinationHash::DiffersAt(offset + position as u64));
+                    differed = true;
+                }
+                None => still_matching.push((index, file, dest_buffer)),
+            }
+        }
+        matching = still_matching;
+
+        if bytes_read == 0 {
+            break None;
+        }
+        offset += bytes_read as u64;
+        chunks += 1;
+        if chunks % HASH_PROGRESS_INTERVAL == 0 && !on_progress(offset) {
+            return Err(stopped_error());
+        }
+    };
+
+    let digest = hasher.finish();
+    let destinations = dest_paths
+        .iter()
+        .zip(results)
+        .map(|(path, result)| {
+            let hash = result.unwrap_or_else(|| DestinationHash::Hashed(digest.clone()));
+            (path.clone(), hash)
+        })
+        .collect();
+    Ok(ChecksumReportSingleFile {
+        source: (source_path.to_path_buf(), digest),
+        size: offset,
+        destinations,
+        stopped_at,
+        lost: Vec::new(),
+        extra_passes: Vec::new(),
+    })
+}
+
+/// Reads until `buffer` is full or the file ends, so the chunks of two files line up.
+async fn read_full(file: &mut File, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let read = file.read(&mut buffer[filled..]).await?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    Ok(filled)
+}
+
+/// Hashes a file at a destination, telling a missing file apart from one that can't be read.
+async fn hash_destination<F>(
+    path: &Path,
+    options: HashOptions,
+    wanted: bool,
+    on_progress: F,
+) -> io::Result<DestinationHash>
+where
+    F: FnMut(u64) -> bool + Send + 'static,
+{
+    if !wanted {
+        return Ok(DestinationHash::NotExpected);
+    }
+    let path = match tokio::fs::metadata(long_path(path)).await {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let wanted = path.to_path_buf();
+            match tokio::task::spawn_blocking(move || find_by_nfc(&wanted)).await? {
+                Some(found) => Cow::Owned(found),
+                None => return Ok(DestinationHash::MissingAtDestination),
+            }
+        }
+        _ => Cow::Borrowed(path),
+    };
+    match compute_file_hash(&path, options, on_progress).await {
+        Ok(digest) => Ok(DestinationHash::Hashed(digest)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(DestinationHash::MissingAtDestination),
+        Err(e) => Err(e),
+    }
+}
+
+/// Checks the copy of an empty source file at a destination by its size: an empty file there is
+/// the copy, with the source's hash. Anything else there is hashed, so it doesn't match.
+async fn check_empty_destination(
+    path: &Path,
+    options: HashOptions,
+    wanted: bool,
+    source_hash: &Digest,
+) -> io::Result<DestinationHash> {
+    if !wanted {
+        return Ok(DestinationHash::NotExpected);
+    }
+    match tokio::fs::metadata(long_path(path)).await {
+        Ok(metadata) if metadata.is_file() && metadata.len() == 0 => {
+            Ok(DestinationHash::Hashed(source_hash.clone()))
+        }
+        _ => hash_destination(path, options, true, |_| true).await,
+    }
+}
+
+/// Hashes the copies of a file once more for another verification pass, each destination on its
+/// own task. The system is first asked to drop what it has cached of them, so the pass reads the
+/// drives and not memory. `on_progress` follows the first destination the file is wanted at, and
+/// every hash stops once `stop` is cancelled.
+async fn reread_destinations<F>(
+    dest_paths: &[PathBuf],
+    wanted: &[bool],
+    options: HashOptions,
+    stop: &CancellationToken,
+    on_progress: F,
+) -> io::Result<Vec<DestinationHash>>
+where
+    F: FnMut(u64) -> bool + Send + 'static,
+{
+    let mut on_progress = Some(on_progress);
+    let hashes: Vec<_> = dest_paths
+        .iter()
+        .zip(wanted)
+        .map(|(dest_path, &wanted)| {
+            let dest_path = dest_path.clone();
+            let stop = stop.clone();
+            let mut on_progress = if wanted { on_progress.take() } else { None };
+            spawn(async move {
+                if wanted {
+                    let cached = dest_path.clone();
+                    tokio::task::spawn_blocking(move || drop_cached(&cached)).await?;
+                }
+                hash_destination(&dest_path, options, wanted, move |bytes| {
+                    let keep_going = on_progress.as_mut().is_none_or(|report| report(bytes));
+                    keep_going && !stop.is_cancelled()
+                })
+                .await
+            })
+        })
+        .collect();
+    collect_results(collect_results(join_all(hashes).await)?)
+}
+
+/// Asks the system to forget what it has cached of `path`, so the next read of it comes from the
+/// drive. Only pages already written out can be dropped, and a network share's server keeps a
+/// cache of its own, so a pass may still be served from memory.
+fn drop_cached(path: &Path) {
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+    {
+        use std::os::fd::AsRawFd;
+
+        if let Ok(file) = std::fs::File::open(long_path(path)) {
+            // Only advice; the pass reads the file either way
+            unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+    {
+        let _ = path;
+    }
+}
+
+/// Chunks hashed between two calls of the progress callback.
+const HASH_PROGRESS_INTERVAL: u64 = 16;
+
+/// Bytes read at a time from a file hashed on several cores.
+const PARALLEL_HASH_CHUNK: usize = 64 * 1024 * 1024;
+
+/// Hashes a file. `on_progress` gets the bytes hashed so far every few chunks, and hashing stops
+/// with an `Interrupted` error if it returns `false`.
+pub async fn compute_file_hash<P, F>(
+    path: P,
+    options: HashOptions,
+    on_progress: F,
+) -> io::Result<Digest>
+where
+    P: AsRef<Path>,
+    F: FnMut(u64) -> bool + Send + 'static,
+{
+    compute_file_hash_with_retries(path, options, on_progress)
+        .await
+        .map(|(digest, _)| digest)
+}
+
+/// Like [`compute_file_hash`], but also returns the reads that only succeeded when tried again,
+/// for a job to warn about.
+pub async fn compute_file_hash_with_retries<P, F>(
+    path: P,
+    options: HashOptions,
+    mut on_progress: F,
+) -> io::Result<(Digest, Vec<ReadRetry>)>
+where
+    P: AsRef<Path>,
+    F: FnMut(u64) -> bool + Send + 'static,
+{
+    let path = path.as_ref();
+    let size = tokio::fs::metadata(long_path(path)).await?.len();
+    if size >= options.blocking_threshold || options.in_parallel(size) {
+        let path = path.to_path_buf();
+        return tokio::task::spawn_blocking(move || {
+            compute_file_hash_blocking(&path, options, on_progress)
+        })
+        .await?;
+    }
+
+    let file = open_shared(path).await?;
+    let mut reader = BufReader::new(file);
+
+    // Create the hasher
+    let mut hasher = FileHasher::new(options.algorithm);
+
+    const CHUNK_SIZE: usize = 1024 * 1024; // 1MB
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut bytes_hashed = 0;
+    let mut chunks = 0;
+    let mut retries = Vec::new();
+
+    loop {
+        let (bytes_read, attempts) =
+            read_retrying(&mut reader, bytes_hashed, &mut buffer, options.read_retries).await?;
+        if attempts > 0 {
+            retries.push(ReadRetry {
+                offset: bytes_hashed,
+                attempts,
+            });
+        }
+        if bytes_read == 0 {
+            // EOF reached
+            break;
+        }
+
+        // Since memory read is far faster than disk IO, and xxHash3 has roughly the same throughput as memory read,
+        // a small file is not a long enough task to spawn_blocking. Large files took the blocking path above.
+        hasher.update(&buffer[..bytes_read]);
+
+        bytes_hashed += bytes_read as u64;
+        chunks += 1;
+        if chunks % HASH_PROGRESS_INTERVAL == 0 && !on_progress(bytes_hashed) {
+            return Err(stopped_error());
+        }
+    }
+
+    // Return the final hash
+    Ok((hasher.finish(), retries))
+}
+
+fn compute_file_hash_blocking(
+    path: &Path,
+    options: HashOptions,
+    mut on_progress: impl FnMut(u64) -> bool,
+) -> io::Result<(Digest, Vec<ReadRetry>)> {
+    let mut file = open_shared_blocking(path)?;
+    let mut hasher = FileHasher::new(options.algorithm);
+
+    const CHUNK_SIZE: usize = 1024 * 1024; // 1MB
+    // Each chunk is split over the cores, so it has to be large for that to pay off
+    let parallel = options.in_parallel(file.metadata()?.len());
+    let (chunk_size, progress_interval) = match parallel {
+        true => (PARALLEL_HASH_CHUNK, 1),
+        false => (CHUNK_SIZE, HASH_PROGRESS_INTERVAL),
+    };
+    let mut buffer = vec![0; chunk_size];
+    let mut bytes_hashed = 0;
+    let mut chunks = 0;
+    let mut retries = Vec::new();
+
+    loop {
+        let (bytes_read, attempts) =
+            read_retrying_blocking(&mut file, bytes_hashed, &mut buffer, options.read_retries)?;
+        if attempts > 0 {
+            retries.push(ReadRetry {
+                offset: bytes_hashed,
+                attempts,
+            });
+        }
+        if bytes_read == 0 {
+            break;
+        }
+        match parallel {
+            true => hasher.update_parallel(&buffer[..bytes_read]),
+            false => hasher.update(&buffer[..bytes_read]),
+        }
+
+        bytes_hashed += bytes_read as u64;
+        chunks += 1;
+        if chunks % progress_interval == 0 && !on_progress(bytes_hashed) {
+            return Err(stopped_error());
+        }
+    }
+    Ok((hasher.finish(), retries))
+}
+
+impl ChecksumReport {
+    pub fn export_report<P: AsRef<Path>>(
+        &self,
+        to_file: P,
+        timestamps: &TimestampFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut file = std::fs::File::create(to_file)?;
+        // Lets a later comparison line the files up by their path on the card
+        writeln!(file, "# Source: {}", nfc_path(&self.source_root).display())?;
+        if let Some(job_id) = self.job_id {
+            writeln!(file, "# Job: {job_id}")?;
+        }
+        writeln!(file, "# Algorithm: {}", self.algorithm)?;
+        writeln!(file, "# Verified: {}", timestamps.format(self.finished_at))?;
+        if let Some(order) = self.copy_order {
+            writeln!(file, "# Copy order: {order}")?;
+        }
+        writeln!(file, "# Verification: {}", self.method)?;
+        writeln!(file, "# Verification level: {}", self.verification_level())?;
+        let passes = self.verification_passes();
+        if passes > 1 {
+            writeln!(file, "# Verification passes: {passes}")?;
+        }
+        // Timings only compare between runs that hashed as many files at once
+        if let [first, changes @ ..] = self.hash_workers.as_slice() {
+            let mut workers = first.workers.to_string();
+            for change in changes {
+                workers.push_str(&format!(
+                    ", {} from {}",
+                    change.workers,
+                    timestamps.format(change.since)
+                ));
+            }
+            writeln!(file, "# Files verified at once: {workers}")?;
+        }
+        for group in self.groups() {
+            writeln!(
+                file,
+                "# Group {}: {} files, {} passed, {} failed",
+                group.name, group.files, group.passed, group.failed
+            )?;
+        }
+        // Checked by presence and size, as there is no content to hash
+        let empty: Vec<_> = self.files.iter().filter(|file| file.size == 0).collect();
+        if !empty.is_empty() {
+            let present = empty
+                .iter()
+                .filter(|file| {
+                    file.destinations.iter().all(|(_, hash)| {
+                        *hash == DestinationHash::NotExpected || file.empty_copy(hash)
+                    })
+                })
+                .count();
+            writeln!(
+                file,
+                "# Empty files: {}, {} present with 0 bytes at every destination",
+                empty.len(),
+                present
+            )?;
+        }
+        writeln!(
+            file,
+            "# Manifest digest (SHA-256): {}",
+            self.manifest_digest()
+        )?;
+        if self.compared {
+            writeln!(file, "# Compared byte for byte, up to the first difference")?;
+        }
+        if let (true, Some(failed)) = (self.stopped_at_failure, self.files.last()) {
+            writeln!(
+                file,
+                "# Stopped at the first failure, {}; {} files were not checked",
+                nfc_path(&failed.source.0).display(),
+                self.unchecked_files
+            )?;
+        }
+        let file_types = FileTypeBreakdown::new(
+            self.files
+                .iter()
+                .map(|file| (file.source.0.as_path(), file.size)),
+            FILE_TYPE_LIMIT,
+        );
+        writeln!(file, "# File types: {}", file_types.summary())?;
+        for (index, summary) in self.per_destination_summary().iter().enumerate() {
+            writeln!(
+                file,
+                "# Destination {}: {}, {} verified, {} failed, {} missing",
+                index + 1,
+                if summary.passed() { "passed" } else { "FAILED" },
+                summary.verified,
+                summary.failed,
+                summary.missing
+            )?;
+            let times = self
+                .destination_times
+                .get(index)
+                .copied()
+                .unwrap_or_default();
+            if let Some(copied_at) = times.copied_at {
+                writeln!(
+                    file,
+                    "# Destination {} copied: {}",
+                    index + 1,
+                    timestamps.format(copied_at)
+                )?;
+            }
+            if let Some(verified_at) = times.verified_at {
+                writeln!(
+                    file,
+                    "# Destination {} verified: {}",
+                    index + 1,
+                    timestamps.format(verified_at)
+                )?;
+            }
+        }
+        if let Some(baseline) = &self.baseline {
+            writeln!(
+                file,
+                "# Incremental offload: {} files already in {} were not copied",
+                baseline.files.len(),
+                baseline.manifest.display()
+            )?;
+        }
+        for (duplicate, representative) in &self.duplicates {
+            writeln!(
+                file,
+                "# Duplicate not copied: {} (same as {})",
+                nfc_path(duplicate).display(),
+                nfc_path(representative).display()
+            )?;
+        }
+        for (skipped, reason) in &self.skipped {
+            let reason = match reason {
+                SkipReason::Excluded => "Excluded from the copy",
+                SkipReason::PermissionDenied => "Skipped, permission denied",
+                SkipReason::Placeholder => "Skipped, cloud placeholder",
+                SkipReason::CopyFailed => "Copy failed",
+            };
+            writeln!(file, "# {reason}: {}", nfc_path(skipped).display())?;
+        }
+        for (file_path, linked_to) in &self.hard_links {
+            writeln!(
+                file,
+                "# Hard link: {} (to {})",
+                nfc_path(file_path).display(),
+                nfc_path(linked_to).display()
+            )?;
+        }
+        for reconnected in &self.reconnected {
+            writeln!(
+                file,
+                "# Resumed after the destination reconnected: {}",
+                nfc_path(reconnected).display()
+            )?;
+        }
+        for slow in &self.slow_files {
+            writeln!(
+                file,
+                "# Slow transfer: {} ({} bytes/s, job median {} bytes/s)",
+                nfc_path(&slow.path).display(),
+                slow.bytes_per_sec,
+                slow.median_bytes_per_sec
+            )?;
+        }
+        for salvaged in self.files.iter().filter(|file| file.salvaged()) {
+            let ranges: Vec<String> = salvaged
+                .lost
+                .iter()
+                .map(|range| format!("{}-{}", range.start, range.end))
+                .collect();
+            writeln!(
+                file,
+                "# Partially recovered: {} ({} bytes unreadable, at {}; copies {} the salvaged content)",
+                nfc_path(&salvaged.source.0).display(),
+                salvaged.bytes_lost(),
+                ranges.join(", "),
+                if salvaged.copies_match() {
+                    "match"
+                } else {
+                    "DON'T match"
+                }
+            )?;
+        }
+        for unstable in self.unstable_files() {
+            let destinations: Vec<String> = unstable
+                .unstable_destinations()
+                .iter()
+                .map(|index| (index + 1).to_string())
+                .collect();
+            writeln!(
+                file,
+                "# Unstable media: {} (read differently across passes at destination {})",
+                nfc_path(&unstable.source.0).display(),
+                destinations.join(", ")
+            )?;
+        }
+        let changes = &self.source_changes;
+        for (files, change) in [
+            (
+                &changes.added,
+                "Added to the source since the copy, not verified",
+            ),
+            (
+                &changes.removed,
+                "Removed from the source since the copy, not verified",
+            ),
+            (
+                &changes.modified,
+                "Resized on the source since the copy, not verified",
+            ),
+        ] {
+            for changed in files {
+                writeln!(file, "# {change}: {}", nfc_path(changed).display())?;
+            }
+        }
+        for extra in &self.extra_files {
+            writeln!(
+                file,
+                "# Extra file at destination: {}",
+                nfc_path(extra).display()
+            )?;
+        }
+        for missing in self.missing_by_destination().iter().flatten() {
+            writeln!(
+                file,
+                "# Missing at destination: {}",
+                nfc_path(missing).display()
+            )?;
+        }
+        if let Some(folders) = &self.folders {
+            for missing in &folders.missing {
+                writeln!(
+                    file,
+                    "# Folder missing at destination: {}",
+                    nfc_path(missing).display()
+                )?;
+            }
+            for extra in &folders.extra {
+                writeln!(
+                    file,
+                    "# Extra folder at destination: {}",
+                    nfc_path(extra).display()
+                )?;
+            }
+        }
+        if let Some(listing) = &self.volume_listing {
+            writeln!(
+                file,
+                "# Source volume listing: {}, {} files, {} bytes",
+                nfc_path(&listing.root).display(),
+                listing.files.len(),
+                listing.total_bytes()
+            )?;
+            for (path, size) in &listing.files {
+                writeln!(
+                    file,
+                    "# On source volume: {} ({size} bytes)",
+                    nfc_path(path).display()
+                )?;
+            }
+        }
+        let mut writer = Writer::from_writer(file);
+        let mut header: Vec<String> = vec![
+            "Consistent".to_owned(),
+            "Source".to_owned(),
+            "Source Hash".to_owned(),
+            "Size".to_owned(),
+        ];
+        let row0 = &self.files[0];
+        for i in 0..row0.destinations.len() {
+            header.push(format!("Destination File {}", i + 1));
+            header.push(format!("Destination Hash {}", i + 1));
+        }
+        for pass in 2..=passes {
+            for i in 0..row0.destinations.len() {
+                header.push(format!("Destination Hash {} (pass {pass})", i + 1));
+            }
+        }
+        writer.write_record(header)?;
+
+        let hash_cell = |hash: &DestinationHash| match hash {
+            DestinationHash::Hashed(hash) => format!("{:X}", hash),
+            DestinationHash::NotExpected => "Not expected".to_owned(),
+            DestinationHash::MissingAtDestination => "Missing".to_owned(),
+            DestinationHash::DiffersAt(offset) => format!("Differs at byte {offset}"),
+        };
+
+        for row in &self.files {
+            let mut record: Vec<String> = vec![
+                if row.consistent() {
+                    "Y".to_owned()
+                } else {
+                    "N".to_owned()
+                },
+                nfc_path(&row.source.0).to_string_lossy().into_owned(),
+                match row.stopped_at {
+                    Some(offset) => format!("Read to byte {offset}"),
+                    None => format!("{:X}", row.source.1),
+                },
+                row.size.to_string(),
+            ];
+            // An empty copy's hash says nothing the size doesn't
+            let cell = |hash: &DestinationHash| match row.empty_copy(hash) {
+                true => "Present, 0 bytes".to_owned(),
+                false => hash_cell(hash),
+            };
+            for (path, hash) in &row.destinations {
+                record.push(nfc_path(path).to_string_lossy().into_owned());
+                record.push(cell(hash));
             }
+            record.extend(row.extra_passes.iter().flatten().map(cell));
             writer.write_record(record)?;
         }
         writer.flush()?;
         Ok(())
     }
+
+    /// Exports the report in the given format, writing times as `timestamps` says, and seals it
+    /// with [`seal_report`].
+    pub fn export<P: AsRef<Path>>(
+        &self,
+        to_file: P,
+        format: ManifestFormat,
+        timestamps: &TimestampFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let to_file = to_file.as_ref();
+        match format {
+            ManifestFormat::Csv => self.export_report(to_file, timestamps)?,
+            ManifestFormat::Gnu | ManifestFormat::Bsd => {
+                self.export_checksum_list(to_file, format)?
+            }
+            ManifestFormat::Certificate => self.export_certificate(to_file, timestamps)?,
+            ManifestFormat::Json => self.export_report_json(to_file)?,
+            ManifestFormat::Mhl => self.export_mhl(to_file)?,
+        }
+        Ok(seal_report(to_file, format)?)
+    }
+
+    /// The mounted volume holding the source. The card may be ejected by now, in which case its
+    /// label is gone too.
+    fn source_volume(&self) -> Option<Volume> {
+        list_volumes()
+            .into_iter()
+            .filter(|volume| self.source_root.starts_with(&volume.mount_point))
+            .max_by_key(|volume| volume.mount_point.components().count())
+    }
+
+    /// Destination folders of the job, as found from the paths of the first file.
+    pub fn destination_roots(&self) -> Vec<PathBuf> {
+        let Some(file) = self.files.first() else {
+            return Vec::new();
+        };
+        let depth = file
+            .source
+            .0
+            .strip_prefix(&self.source_root)
+            .map_or(0, |relative| relative.components().count());
+        file.destinations
+            .iter()
+            .map(|(path, _)| path.ancestors().nth(depth).unwrap_or(path).to_path_buf())
+            .collect()
+    }
+
+    /// Writes a short plain-text attestation of the verification, for printing or handing to a
+    /// client alongside the media.
+    fn export_certificate<P: AsRef<Path>>(
+        &self,
+        to_file: P,
+        timestamps: &TimestampFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(to_file)?);
+        let passed = self.count_errors() == 0 && !self.stopped_at_failure;
+        writeln!(file, "LibreCard verification certificate")?;
+        writeln!(file)?;
+        if let Some(job_id) = self.job_id {
+            writeln!(file, "Job:             {job_id}")?;
+        }
+        writeln!(
+            file,
+            "Verified:        {}",
+            timestamps.format(self.finished_at)
+        )?;
+        writeln!(
+            file,
+            "Issued:          {}",
+            timestamps.format(SystemTime::now())
+        )?;
+        writeln!(file, "Source:          {}", self.source_root.display())?;
+        if let Some(volume) = self.source_volume() {
+            writeln!(
+                file,
+                "Source volume:   {} ({})",
+                volume.label,
+                volume.mount_point.display()
+            )?;
+        }
+        if let Some(listing) = &self.volume_listing {
+            writeln!(
+                file,
+                "Volume contents: {} files ({} bytes) on {}, listed in the report",
+                listing.files.len(),
+                listing.total_bytes(),
+                listing.root.display()
+            )?;
+        }
+        let summaries = self.per_destination_summary();
+        for (index, (root, summary)) in self.destination_roots().iter().zip(&summaries).enumerate()
+        {
+            writeln!(
+                file,
+                "Destination {}:   {} ({}: {} verified, {} failed, {} missing)",
+                index + 1,
+                root.display(),
+                if summary.passed() { "passed" } else { "FAILED" },
+                summary.verified,
+                summary.failed,
+                summary.missing
+            )?;
+            let times = self
+                .destination_times
+                .get(index)
+                .copied()
+                .unwrap_or_default();
+            if let Some(copied_at) = times.copied_at {
+                writeln!(
+                    file,
+                    "                 copied {}",
+                    timestamps.format(copied_at)
+                )?;
+            }
+            if let Some(verified_at) = times.verified_at {
+                writeln!(
+                    file,
+                    "                 verified {}",
+                    timestamps.format(verified_at)
+                )?;
+            }
+        }
+        for root in &self.disabled_destinations {
+            writeln!(
+                file,
+                "Disabled:        {} (configured, but not part of this job)",
+                root.display()
+            )?;
+        }
+        let total_bytes: u64 = self.files.iter().map(|file| file.size).sum();
+        writeln!(
+            file,
+            "Files:           {} ({} bytes)",
+            self.total_files(),
+            total_bytes
+        )?;
+        if let Some(baseline) = &self.baseline {
+            writeln!(
+                file,
+                "Incremental:     {} files already offloaded, per {}",
+                baseline.files.len(),
+                baseline.manifest.display()
+            )?;
+        }
+        let changes = &self.source_changes;
+        if !(changes.added.is_empty() && changes.removed.is_empty() && changes.modified.is_empty())
+        {
+            writeln!(
+                file,
+                "Source changed:  {} added, {} removed, {} resized since the copy, not verified",
+                changes.added.len(),
+                changes.removed.len(),
+                changes.modified.len()
+            )?;
+        }
+        if let Some(folders) = &self.folders {
+            writeln!(
+                file,
+                "Folders:         {} missing, {} extra at the destinations",
+                folders.missing.len(),
+                folders.extra.len()
+            )?;
+        }
+        let groups = self.groups();
+        if groups.len() > 1 {
+            for group in &groups {
+                writeln!(
+                    file,
+                    "Group:           {} ({} files: {} passed, {} failed)",
+                    group.name, group.files, group.passed, group.failed
+                )?;
+            }
+        }
+        writeln!(file, "Algorithm:       {}", self.algorithm)?;
+        writeln!(file, "Verification:    {}", self.method)?;
+        writeln!(
+            file,
+            "Manifest digest: {} (SHA-256)",
+            self.manifest_digest()
+        )?;
+        let salvaged: Vec<_> = self.files.iter().filter(|file| file.salvaged()).collect();
+        if !salvaged.is_empty() {
+            writeln!(
+                file,
+                "Partially recovered: {} files, {} bytes unreadable on the source",
+                salvaged.len(),
+                salvaged.iter().map(|file| file.bytes_lost()).sum::<u64>()
+            )?;
+        }
+        writeln!(file)?;
+        if passed {
+            writeln!(
+                file,
+                "PASSED: every file at every destination matches the source."
+            )?;
+        } else {
+            writeln!(
+                file,
+                "FAILED: {} of {} files don't match the source at every destination.",
+                self.count_errors(),
+                self.total_files()
+            )?;
+            if self.stopped_at_failure {
+                writeln!(
+                    file,
+                    "Verification stopped at the first failure; {} files were not checked.",
+                    self.unchecked_files
+                )?;
+            }
+        }
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Writes the source hashes as a checksum list that `sha256sum -c` accepts when run from the
+    /// source or a destination directory.
+    /// Writes the files as JSON, each with its path relative to the source and what was found at
+    /// every destination. A destination's `hash` is null where there was nothing to hash, with
+    /// `status` saying why.
+    pub fn export_report_json<P: AsRef<Path>>(&self, to_file: P) -> io::Result<()> {
+        let files: Vec<_> = self
+            .files
+            .iter()
+            .map(|row| {
+                let relative = row
+                    .source
+                    .0
+                    .strip_prefix(&self.source_root)
+                    .unwrap_or(&row.source.0);
+                let verified = row.verified_destinations();
+                let destinations: Vec<_> = row
+                    .destinations
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (path, hash))| {
+                        let (digest, status) = match hash {
+                            DestinationHash::Hashed(digest) => (
+                                Some(format!("{digest:x}")),
+                                row.empty_copy(hash).then_some("empty"),
+                            ),
+                            DestinationHash::NotExpected => (None, Some("not-expected")),
+                            DestinationHash::MissingAtDestination => (None, Some("missing")),
+                            DestinationHash::DiffersAt(_) => (None, Some("differs")),
+                        };
+                        let mut destination = serde_json::json!({
+                            "path": nfc_path(path),
+                            "hash": digest,
+                            "match": verified.contains(&index),
+                        });
+                        if let Some(status) = status {
+                            destination["status"] = status.into();
+                        }
+                        if let DestinationHash::DiffersAt(offset) = hash {
+                            destination["differs_at"] = (*offset).into();
+                        }
+                        destination
+                    })
+                    .collect();
+                serde_json::json!({
+                    "relative_path": slash_path(&nfc_path(relative)),
+                    "size": row.size,
+                    // A source that wasn't read to the end has no hash to check against
+                    "source_hash": row.stopped_at.is_none().then(|| format!("{:x}", row.source.1)),
+                    "consistent": row.consistent(),
+                    "destinations": destinations,
+                })
+            })
+            .collect();
+        let mut report = serde_json::json!({
+            "job_id": self.job_id,
+            "algorithm": self.algorithm.id(),
+            "source": nfc_path(&self.source_root),
+            "verified_at": self
+                .finished_at
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+            "verification": self.method.to_string(),
+            "verification_level": self.verification_level(),
+            "hash_workers": self
+                .hash_workers
+                .iter()
+                .map(|used| {
+                    serde_json::json!({
+                        "workers": used.workers,
+                        "since": used
+                            .since
+                            .duration_since(SystemTime::UNIX_EPOCH)
+                            .map_or(0, |since| since.as_secs()),
+                    })
+                })
+                .collect::<Vec<_>>(),
+            "source_changes": {
+                "added": self.source_changes.added.iter().map(|file| slash_path(&nfc_path(file))).collect::<Vec<_>>(),
+                "removed": self.source_changes.removed.iter().map(|file| slash_path(&nfc_path(file))).collect::<Vec<_>>(),
+                "resized": self.source_changes.modified.iter().map(|file| slash_path(&nfc_path(file))).collect::<Vec<_>>(),
+            },
+            "groups": self
+                .groups()
+                .iter()
+                .map(|group| {
+                    serde_json::json!({
+                        "name": group.name,
+                        "files": group.files,
+                        "passed": group.passed,
+                        "failed": group.failed,
+                    })
+                })
+                .collect::<Vec<_>>(),
+            "files": files,
+        });
+        if let Some(listing) = &self.volume_listing {
+            let files: Vec<_> = listing
+                .files
+                .iter()
+                .map(|(path, size)| {
+                    serde_json::json!({ "path": slash_path(&nfc_path(path)), "size": size })
+                })
+                .collect();
+            report["volume_listing"] = serde_json::json!({
+                "root": nfc_path(&listing.root),
+                "files": files,
+            });
+        }
+        if let Some(folders) = &self.folders {
+            report["folders"] = serde_json::json!({
+                "missing": folders.missing.iter().map(|folder| nfc_path(folder)).collect::<Vec<_>>(),
+                "extra": folders.extra.iter().map(|folder| nfc_path(folder)).collect::<Vec<_>>(),
+            });
+        }
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(to_file)?);
+        serde_json::to_writer_pretty(&mut writer, &report).map_err(io::Error::other)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    fn export_checksum_list<P: AsRef<Path>>(
+        &self,
+        to_file: P,
+        format: ManifestFormat,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(tag) = self.algorithm.tag_name() else {
+            return Err(format!("{} has no standard checksum list format", self.algorithm).into());
+        };
+
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(to_file)?);
+        // A source that wasn't read to the end has no hash to check against
+        for row in self.files.iter().filter(|row| row.stopped_at.is_none()) {
+            let relative = row
+                .source
+                .0
+                .strip_prefix(&self.source_root)
+                .unwrap_or(&row.source.0);
+            let (path, escaped) = escape_checksum_path(&slash_path(&nfc_path(relative)));
+            // Like coreutils, a leading backslash marks a line whose file name is escaped
+            let prefix = if escaped { "\\" } else { "" };
+            let hash = &row.source.1;
+            if format == ManifestFormat::Bsd {
+                writeln!(writer, "{prefix}{tag} ({path}) = {hash:x}")?;
+            } else {
+                writeln!(writer, "{prefix}{hash:x}  {path}")?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes an MHL 1.1 hash list, as post houses and DIT tools read next to the footage. Paths
+    /// are relative to the source, which makes them relative to each destination folder too.
+    fn export_mhl<P: AsRef<Path>>(&self, to_file: P) -> Result<(), Box<dyn Error>> {
+        let Some(name) = self.algorithm.mhl_name() else {
+            return Err(format!("{} has no MHL hash element", self.algorithm).into());
+        };
+        // MHL dates are ISO 8601 in UTC
+        let dates = TimestampFormat::new("%Y-%m-%dT%H:%M:%SZ")?;
+
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(to_file)?);
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, r#"<hashlist version="1.1">"#)?;
+        writeln!(writer, "  <creatorinfo>")?;
+        if let Some(job_id) = self.job_id {
+            writeln!(
+                writer,
+                "    <name>{}</name>",
+                escape_xml(&job_id.to_string())
+            )?;
+        }
+        writeln!(
+            writer,
+            "    <tool>LibreCard {}</tool>",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        // The report doesn't keep when the job started, and MHL 1.1 requires a start date
+        writeln!(
+            writer,
+            "    <startdate>{}</startdate>",
+            dates.format(self.finished_at)
+        )?;
+        writeln!(
+            writer,
+            "    <finishdate>{}</finishdate>",
+            dates.format(self.finished_at)
+        )?;
+        writeln!(writer, "  </creatorinfo>")?;
+        // A source that wasn't read to the end has no hash to check against
+        for row in self.files.iter().filter(|row| row.stopped_at.is_none()) {
+            let relative = row
+                .source
+                .0
+                .strip_prefix(&self.source_root)
+                .unwrap_or(&row.source.0);
+            let path = escape_xml(&slash_path(&nfc_path(relative)));
+            writeln!(writer, "  <hash>")?;
+            writeln!(writer, "    <file>{path}</file>")?;
+            writeln!(writer, "    <size>{}</size>", row.size)?;
+            writeln!(writer, "    <{name}>{:x}</{name}>", row.source.1)?;
+            writeln!(
+                writer,
+                "    <hashdate>{}</hashdate>",
+                dates.format(self.finished_at)
+            )?;
+            writeln!(writer, "  </hash>")?;
+        }
+        writeln!(writer, "</hashlist>")?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// `text` with the characters XML gives a meaning to escaped.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManifestFormat {
+    /// Native report with every destination's hash.
+    Csv,
+    /// `<hash>  <path>`, as written by `sha256sum`.
+    Gnu,
+    /// `SHA256 (<path>) = <hash>`, as written by BSD `sha256` and `sha256sum --tag`.
+    Bsd,
+    /// Plain-text summary of the verification with its result, to print for a client.
+    Certificate,
+    /// Every file with its hash at each destination, for scripts and other tools to read. Only
+    /// checksum reports are written this way.
+    Json,
+    /// MHL 1.1 hash list, as Hedge, ShotPut Pro and Silverstack write. Only checksum reports are
+    /// written this way, for algorithms MHL has a hash element for.
+    Mhl,
+}
+
+/// Starts the last line of an exported report, which holds a SHA-256 digest of every byte
+/// before it.
+const REPORT_DIGEST_PREFIX: &str = "# Report digest (SHA-256): ";
+
+/// Extension added to the name of a checksum list for the file holding its digest.
+const REPORT_DIGEST_EXTENSION: &str = "sha256";
+
+/// Makes the report just exported to `path` tamper-evident: a line with a SHA-256 digest of the
+/// exact bytes written is added to its end. A checksum list, which the standard tools would take
+/// that line for a broken entry of, gets the digest in a file next to it instead, named like it
+/// with `.sha256` added, that `sha256sum -c` checks. [`read_manifest`] checks either.
+pub fn seal_report(path: &Path, format: ManifestFormat) -> io::Result<()> {
+    let content = std::fs::read(path)?;
+    let digest = format!("{:x}", Digest(Sha256::digest(&content).to_vec()));
+    match format {
+        // A line after the closing brace or tag would make the file invalid JSON or XML
+        ManifestFormat::Gnu | ManifestFormat::Bsd | ManifestFormat::Json | ManifestFormat::Mhl => {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let (name, escaped) = escape_checksum_path(&name);
+            let prefix = if escaped { "\\" } else { "" };
+            std::fs::write(digest_file(path), format!("{prefix}{digest}  {name}\n"))
+        }
+        ManifestFormat::Csv | ManifestFormat::Certificate => {
+            let mut file = std::fs::OpenOptions::new().append(true).open(path)?;
+            writeln!(file, "{REPORT_DIGEST_PREFIX}{digest}")
+        }
+    }
+}
+
+/// The file next to a checksum list that holds its digest.
+fn digest_file(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(REPORT_DIGEST_EXTENSION);
+    path.with_file_name(name)
+}
+
+/// Whether a report holds what was exported, as told by the digest [`seal_report`] left.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReportIntegrity {
+    /// The report has no digest, e.g. because another tool wrote it.
+    #[default]
+    Unsealed,
+    Intact,
+    /// The report was changed after it was exported.
+    Altered,
+}
+
+/// Checks `content`, read from the report at `path`, against its digest. Returns what it found
+/// and the length of the content before the digest line, if it has one.
+fn check_report_digest(path: &Path, content: &[u8]) -> (ReportIntegrity, usize) {
+    let digest_of = |content: &[u8]| format!("{:x}", Digest(Sha256::digest(content).to_vec()));
+    let trimmed = content.strip_suffix(b"\n").unwrap_or(content);
+    let trimmed = trimmed.strip_suffix(b"\r").unwrap_or(trimmed);
+    let last_line = trimmed
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    if let Some(stated) = trimmed[last_line..].strip_prefix(REPORT_DIGEST_PREFIX.as_bytes()) {
+        let body = &content[..last_line];
+        let intact = digest_of(body).as_bytes() == stated.trim_ascii().to_ascii_lowercase();
+        return (integrity(intact), last_line);
+    }
+    match std::fs::read_to_string(digest_file(path)) {
+        Ok(sidecar) => {
+            let stated = sidecar
+                .trim_start_matches('\\')
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+            let intact = digest_of(content) == stated.to_ascii_lowercase();
+            (integrity(intact), content.len())
+        }
+        Err(_) => (ReportIntegrity::Unsealed, content.len()),
+    }
+}
+
+fn integrity(intact: bool) -> ReportIntegrity {
+    match intact {
+        true => ReportIntegrity::Intact,
+        false => ReportIntegrity::Altered,
+    }
+}
+
+/// Files listed in a report or checksum list, by their path relative to the card.
+#[derive(Clone, Debug, Default)]
+pub struct Manifest {
+    /// [`HashAlgorithm::id`] of the hashes, if the manifest names it or the hash length tells.
+    pub algorithm: Option<&'static str>,
+    /// Keyed by [`normalize_manifest_path`].
+    pub entries: BTreeMap<String, ManifestEntry>,
+    /// Whether the file still holds what LibreCard exported.
+    pub integrity: ReportIntegrity,
+}
+
+impl Manifest {
+    /// The entries under `folder`, a path relative to the manifest's root as
+    /// [`normalize_manifest_path`] gives it.
+    pub fn within(&self, folder: &str) -> Manifest {
+        Manifest {
+            entries: self
+                .entries
+                .iter()
+                .filter(|(path, _)| is_within(path, folder))
+                .map(|(path, entry)| (path.clone(), entry.clone()))
+                .collect(),
+            ..*self
+        }
+    }
+}
+
+/// Whether the normalized manifest `path` is in `folder` or below it.
+fn is_within(path: &str, folder: &str) -> bool {
+    folder.is_empty()
+        || path
+            .strip_prefix(folder)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Lowercase hex.
+    pub hash: String,
+    /// Only reports record sizes; checksum lists don't.
+    pub size: Option<u64>,
+}
+
+/// Reads a manifest written by [`ChecksumReport::export`] as a CSV report or a GNU or BSD
+/// checksum list, by the standard checksum tools, or by another offload tool as an MHL file.
+/// The format is told from the content. A report that was changed since it was exported is still
+/// read, and warned about.
+pub fn read_manifest(path: &Path) -> io::Result<Manifest> {
+    let mut content = std::fs::read(path)?;
+    let (integrity, len) = check_report_digest(path, &content);
+    if integrity == ReportIntegrity::Altered {
+        tracing::warn!(
+            "{} doesn't match its report digest and was changed after it was exported",
+            path.display()
+        );
+    }
+    content.truncate(len);
+    let content =
+        String::from_utf8(content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(&content);
+    let first_line = content
+        .lines()
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .unwrap_or_default();
+    let mut manifest = if first_line.starts_with('<') {
+        read_mhl(content)?
+    } else if first_line.starts_with("Consistent,") {
+        read_csv_manifest(content)?
+    } else {
+        read_checksum_list(content)?
+    };
+    // A list that doesn't name its algorithm still gives it away by the hash length
+    if manifest.algorithm.is_none() {
+        manifest.algorithm =
+            manifest
+                .entries
+                .values()
+                .next()
+                .and_then(|entry| match entry.hash.len() {
+                    16 => Some("xxh3"),
+                    32 => Some("md5"),
+                    40 => Some("sha1"),
+                    64 => Some("sha256"),
+                    _ => None,
+                });
+    }
+    manifest.integrity = integrity;
+    Ok(manifest)
+}
+
+/// The algorithm id for a name as manifests write it, e.g. `SHA256` or `xxHash3 (64-bit)`.
+fn manifest_algorithm(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase().replace('-', "");
+    if name.starts_with("sha256") {
+        Some("sha256")
+    } else if name.starts_with("blake3") {
+        Some("blake3")
+    } else if name.starts_with("xxhash3") || name.starts_with("xxh3") {
+        Some("xxh3")
+    } else if name.starts_with("xxhash64") || name.starts_with("xxh64") {
+        Some("xxh64")
+    } else if name.starts_with("sha1") {
+        Some("sha1")
+    } else if name.starts_with("md5") {
+        Some("md5")
+    } else {
+        None
+    }
+}
+
+/// Reads a report written by [`ChecksumReport::export_report`]. Its comments come before the
+/// table, and the source root among them makes the paths relative; a report without it is made
+/// relative to the folder all its files share.
+fn read_csv_manifest(content: &str) -> io::Result<Manifest> {
+    let mut source_root = None;
+    let mut algorithm = None;
+    let mut table = content;
+    while let Some(comment) = table.strip_prefix('#') {
+        let (line, rest) = comment.split_once('\n').unwrap_or((comment, ""));
+        let line = line.trim_end_matches('\r').trim_start();
+        if let Some(root) = line.strip_prefix("Source: ") {
+            source_root = Some(normalize_manifest_path(root));
+        } else if let Some(name) = line.strip_prefix("Algorithm: ") {
+            algorithm = manifest_algorithm(name);
+        }
+        table = rest;
+    }
+
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(table.as_bytes());
+    let header = reader
+        .headers()
+        .map_err(|e| invalid(e.to_string()))?
+        .clone();
+    let column = |name: &str| header.iter().position(|field| field == name);
+    let (Some(source_column), Some(hash_column)) = (column("Source"), column("Source Hash")) else {
+        return Err(invalid("Not a LibreCard report".to_owned()));
+    };
+    let size_column = column("Size");
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| invalid(e.to_string()))?;
+        let (Some(path), Some(hash)) = (record.get(source_column), record.get(hash_column)) else {
+            continue;
+        };
+        // A file compared only up to its first difference has no hash
+        if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            continue;
+        }
+        let size = size_column
+            .and_then(|column| record.get(column))
+            .and_then(|size| size.parse().ok());
+        rows.push((
+            normalize_manifest_path(path),
+            ManifestEntry {
+                hash: hash.to_lowercase(),
+                size,
+            },
+        ));
+    }
+
+    let root = source_root.unwrap_or_else(|| common_folder(rows.iter().map(|(path, _)| path)));
+    let entries = rows
+        .into_iter()
+        .map(|(path, entry)| {
+            let relative = match root.is_empty() {
+                true => path.as_str(),
+                false => path
+                    .strip_prefix(root.as_str())
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .unwrap_or(&path),
+            };
+            (relative.to_owned(), entry)
+        })
+        .collect();
+    Ok(Manifest {
+        algorithm,
+        entries,
+        ..Default::default()
+    })
+}
+
+/// Reads an MHL file: MHL 1.1 as Hedge, ShotPut Pro and Silverstack leave next to the footage,
+/// or an ASC MHL generation from an `ascmhl` folder. Either may give a file several hashes; the
+/// manifest takes the fastest algorithm that every file has a hash in.
+fn read_mhl(content: &str) -> io::Result<Manifest> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let document = roxmltree::Document::parse(content).map_err(|e| invalid(e.to_string()))?;
+    if !document.root_element().has_tag_name("hashlist") {
+        return Err(invalid("Not an MHL file".to_owned()));
+    }
+
+    let mut files = Vec::new();
+    let mut unsupported = BTreeSet::new();
+    // MHL 1.1 has its `<hash>` entries right under the root, ASC MHL under `<hashes>`. ASC MHL's
+    // `<directoryhash>` entries are skipped, as folders are checked through their files.
+    for entry in document
+        .descendants()
+        .filter(|node| node.has_tag_name("hash"))
+    {
+        let mut path = None;
+        let mut size = None;
+        let mut hashes = Vec::new();
+        for element in entry.children().filter(|node| node.is_element()) {
+            let value = element.text().unwrap_or_default().trim();
+            match element.tag_name().name() {
+                // MHL 1.1
+                "file" => path = Some(value),
+                "size" => size = value.parse().ok(),
+                // ASC MHL
+                "path" => {
+                    path = Some(value);
+                    size = element.attribute("size").and_then(|size| size.parse().ok());
+                }
+                "creationdate" | "lastmodificationdate" | "hashdate" => {}
+                // A hash that failed verification in an ASC MHL generation isn't the file's
+                _ if element.attribute("action") == Some("failed") => {}
+                name => match mhl_hash(name, value) {
+                    Some(hash) => hashes.push(hash),
+                    None => {
+                        unsupported.insert(name.to_owned());
+                    }
+                },
+            }
+        }
+        let Some(path) = path.filter(|path| !path.is_empty()) else {
+            return Err(invalid("An MHL entry has no file path".to_owned()));
+        };
+        files.push((normalize_manifest_path(path), size, hashes));
+    }
+
+    let Some(algorithm) = [
+        HashAlgorithm::XxHash3,
+        HashAlgorithm::XxHash64,
+        HashAlgorithm::Md5,
+        HashAlgorithm::Sha1,
+        HashAlgorithm::Sha256,
+    ]
+    .into_iter()
+    .find(|algorithm| {
+        files
+            .iter()
+            .all(|(_, _, hashes)| hashes.iter().any(|(hashed, _)| hashed == algorithm))
+    }) else {
+        let message = match unsupported.is_empty() {
+            true => "The MHL file has no hash algorithm that covers every file".to_owned(),
+            false => format!(
+                "Unsupported hash algorithm in the MHL file: {}",
+                unsupported.into_iter().collect::<Vec<_>>().join(", ")
+            ),
+        };
+        return Err(io::Error::new(io::ErrorKind::Unsupported, message));
+    };
+
+    let entries = files
+        .into_iter()
+        .filter_map(|(path, size, hashes)| {
+            let (_, hash) = hashes
+                .into_iter()
+                .find(|(hashed, _)| *hashed == algorithm)?;
+            Some((path, ManifestEntry { hash, size }))
+        })
+        .collect();
+    Ok(Manifest {
+        algorithm: Some(algorithm.id()),
+        entries,
+        ..Default::default()
+    })
+}
+
+/// The algorithm and lowercase, big-endian hex hash of an MHL hash element, if the algorithm is
+/// one LibreCard can compute.
+fn mhl_hash(name: &str, value: &str) -> Option<(HashAlgorithm, String)> {
+    let algorithm = match name {
+        // MHL 1.1 writes `xxhash64` with its bytes in little-endian order
+        "xxhash64" => {
+            let bytes = u64::from_str_radix(value, 16).ok()?.swap_bytes();
+            return Some((HashAlgorithm::XxHash64, format!("{bytes:016x}")));
+        }
+        "xxhash64be" | "xxh64" => HashAlgorithm::XxHash64,
+        "xxh3" => HashAlgorithm::XxHash3,
+        "md5" => HashAlgorithm::Md5,
+        "sha1" => HashAlgorithm::Sha1,
+        "sha256" => HashAlgorithm::Sha256,
+        _ => return None,
+    };
+    value
+        .chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| (algorithm, value.to_lowercase()))
+}
+
+/// Name of the marker a verified offload leaves in each of its destination folders.
+pub const OFFLOAD_MARKER: &str = ".librecard.json";
+
+/// How many folders deep below a destination [`find_offload_markers`] looks, which reaches the
+/// session folders under a sub-root.
+const MARKER_SEARCH_DEPTH: usize = 3;
+
+/// What a destination folder holds a verified offload of, so the drive can be recognized when it
+/// is plugged in again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OffloadMarker {
+    /// The same in every destination of the job.
+    pub job_id: String,
+    pub source: PathBuf,
+    pub source_volume: Option<Volume>,
+    /// See [`ChecksumReport::manifest_digest`].
+    pub manifest_digest: String,
+    pub algorithm: HashAlgorithm,
+    pub files: usize,
+    pub bytes: u64,
+    pub verified_at: SystemTime,
+    /// `None` in markers from before the level was recorded.
+    #[serde(default)]
+    pub verification_level: Option<VerificationLevel>,
+}
+
+impl OffloadMarker {
+    pub fn new(report: &ChecksumReport) -> OffloadMarker {
+        let manifest_digest = report.manifest_digest();
+        let seconds = report
+            .finished_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        OffloadMarker {
+            // Reports from before jobs had ids get one from their time and content
+            job_id: report.job_id.map_or_else(
+                || format!("{seconds}-{}", &manifest_digest[..12]),
+                |job_id| job_id.to_string(),
+            ),
+            source: report.source_root.clone(),
+            source_volume: report.source_volume(),
+            manifest_digest,
+            algorithm: report.algorithm,
+            files: report.total_files(),
+            bytes: report.files.iter().map(|file| file.size).sum(),
+            verified_at: report.finished_at,
+            verification_level: Some(report.verification_level()),
+        }
+    }
+}
+
+/// Leaves an [`OffloadMarker`] of `report` in each of its destination folders, replacing the one
+/// an earlier offload to the same folder left. Archives get none.
+pub fn write_offload_markers(report: &ChecksumReport) -> io::Result<()> {
+    let content =
+        serde_json::to_vec_pretty(&OffloadMarker::new(report)).map_err(io::Error::other)?;
+    for root in folders(&report.destination_roots()) {
+        std::fs::write(long_path(&root.join(OFFLOAD_MARKER)), &content)?;
+    }
+    Ok(())
+}
+
+/// Makes every copy `report` verified read-only, so a master isn't edited in place by mistake.
+/// Copies that failed or weren't verified are left as they are. An archive is made read-only
+/// as a whole, once every file in it is verified. Returns the copies that couldn't be made
+/// read-only, with why.
+pub fn protect_verified(report: &ChecksumReport) -> Vec<(PathBuf, io::Error)> {
+    let roots = report.destination_roots();
+    let mut archives: Vec<Option<bool>> = roots
+        .iter()
+        .map(|root| DestinationKind::of(root).is_archive().then_some(true))
+        .collect();
+    let mut failures = Vec::new();
+    for file in &report.files {
+        let verified = file.verified_destinations();
+        for (index, (path, hash)) in file.destinations.iter().enumerate() {
+            if *hash == DestinationHash::NotExpected {
+                continue;
+            }
+            let verified = verified.contains(&index);
+            match archives.get_mut(index) {
+                Some(Some(archive_verified)) => *archive_verified &= verified,
+                _ if verified => {
+                    if let Err(e) = set_read_only(path) {
+                        failures.push((path.clone(), e));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    for (root, archive) in roots.iter().zip(archives) {
+        if archive == Some(true)
+            && let Err(e) = set_read_only(root)
+        {
+            failures.push((root.clone(), e));
+        }
+    }
+    failures
+}
+
+/// The offload markers in `dest` and the folders below it, each with the folder it is in.
+/// Markers that can't be read are left out.
+pub fn find_offload_markers(dest: &Path) -> Vec<(PathBuf, OffloadMarker)> {
+    fn search(dir: &Path, depth: usize, found: &mut Vec<(PathBuf, OffloadMarker)>) {
+        let marker = std::fs::read(long_path(&dir.join(OFFLOAD_MARKER)))
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok());
+        if let Some(marker) = marker {
+            found.push((dir.to_path_buf(), marker));
+        }
+        if depth == 0 {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(long_path(dir)) else {
+            return;
+        };
+        let mut folders: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .map(|entry| dir.join(entry.file_name()))
+            .collect();
+        folders.sort();
+        for folder in folders {
+            search(&folder, depth - 1, found);
+        }
+    }
+
+    let mut found = Vec::new();
+    search(dest, MARKER_SEARCH_DEPTH, &mut found);
+    found
+}
+
+/// The folder the paths in the manifest at `path` are relative to: the one it's in, or for an
+/// ASC MHL generation, the one holding its `ascmhl` folder.
+pub fn manifest_root(path: &Path) -> PathBuf {
+    let folder = path.parent().unwrap_or(Path::new(""));
+    match folder.file_name() {
+        Some(name) if name.eq_ignore_ascii_case("ascmhl") => {
+            folder.parent().unwrap_or(Path::new("")).to_path_buf()
+        }
+        _ => folder.to_path_buf(),
+    }
+}
+
+/// The deepest folder holding every path, without a trailing `/`.
+fn common_folder<'a>(mut paths: impl Iterator<Item = &'a String>) -> String {
+    let Some(first) = paths.next() else {
+        return String::new();
+    };
+    let mut common: Vec<&str> = first.split('/').collect();
+    // The first path's own file name isn't a folder
+    common.pop();
+    for path in paths {
+        let folders: Vec<&str> = path.split('/').collect();
+        let shared = common
+            .iter()
+            .zip(&folders[..folders.len() - 1])
+            .take_while(|(a, b)| a == b)
+            .count();
+        common.truncate(shared);
+    }
+    common.join("/")
+}
+
+/// Reads a GNU (`<hash>  <path>`) or BSD (`SHA256 (<path>) = <hash>`) checksum list.
+fn read_checksum_list(content: &str) -> io::Result<Manifest> {
+    let mut manifest = Manifest::default();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // Like coreutils, a leading backslash marks a line whose file name is escaped
+        let (line, escaped) = match line.strip_prefix('\\') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let parsed = match line.rsplit_once(") = ") {
+            Some((tagged, hash)) => tagged.split_once(" (").map(|(tag, path)| {
+                manifest.algorithm = manifest.algorithm.or_else(|| manifest_algorithm(tag));
+                (hash, path)
+            }),
+            None => line.split_once(' ').and_then(|(hash, path)| {
+                // A `*` marks a file read in binary mode, which is the same on every platform
+                Some((hash, path.strip_prefix([' ', '*'])?))
+            }),
+        };
+        let Some((hash, path)) =
+            parsed.filter(|(hash, _)| hash.chars().all(|c| c.is_ascii_hexdigit()))
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Line {} is not a checksum: {line}", number + 1),
+            ));
+        };
+        let path = match escaped {
+            true => unescape_checksum_path(path),
+            false => path.to_owned(),
+        };
+        manifest.entries.insert(
+            normalize_manifest_path(&path),
+            ManifestEntry {
+                hash: hash.to_lowercase(),
+                size: None,
+            },
+        );
+    }
+    Ok(manifest)
+}
+
+/// Reverses [`escape_checksum_path`].
+fn unescape_checksum_path(path: &str) -> String {
+    let mut unescaped = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// A path as manifests from any platform are compared by: Unicode NFC, `/` between components
+/// (a `\` from Windows counts as one too), and no empty or `.` components.
+pub fn normalize_manifest_path(path: &str) -> String {
+    let path: String = path.nfc().collect();
+    path.split(['/', '\\'])
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// How two manifests of the same files differ, by normalized relative path.
+#[derive(Clone, Debug, Default)]
+pub struct ManifestDiff {
+    /// Files both list with the same hash and, where both know it, size.
+    pub matching: usize,
+    pub only_in_first: Vec<String>,
+    pub only_in_second: Vec<String>,
+    /// Files listed with different hashes. Empty if the manifests use different algorithms.
+    pub hash_mismatches: Vec<String>,
+    /// Files listed with different sizes, with the first and the second size.
+    pub size_mismatches: Vec<(String, u64, u64)>,
+    /// The manifests were hashed with different algorithms, so only paths and sizes compare.
+    pub algorithms_differ: bool,
+    /// The first or the second manifest was changed after it was exported.
+    pub altered: [bool; 2],
+    /// Only the files under this folder, relative to the manifest's root, were compared.
+    pub subfolder: Option<String>,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_in_first.is_empty()
+            && self.only_in_second.is_empty()
+            && self.hash_mismatches.is_empty()
+            && self.size_mismatches.is_empty()
+            && !self.algorithms_differ
+    }
+
+    /// Writes one CSV row per difference.
+    pub fn export<P: AsRef<Path>>(&self, to_file: P) -> Result<(), Box<dyn Error>> {
+        let mut file = std::fs::File::create(to_file)?;
+        if let Some(folder) = &self.subfolder {
+            writeln!(file, "# Partial verification of {folder}")?;
+        }
+        let mut writer = Writer::from_writer(file);
+        writer.write_record(["Path", "Difference", "First", "Second"])?;
+        for path in &self.only_in_first {
+            writer.write_record([path.as_str(), "Only in first", "", ""])?;
+        }
+        for path in &self.only_in_second {
+            writer.write_record([path.as_str(), "Only in second", "", ""])?;
+        }
+        for path in &self.hash_mismatches {
+            writer.write_record([path.as_str(), "Hash differs", "", ""])?;
+        }
+        for (path, first, second) in &self.size_mismatches {
+            writer.write_record([
+                path.as_str(),
+                "Size differs",
+                &first.to_string(),
+                &second.to_string(),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Lines two manifests up by path and lists what differs.
+pub fn diff_manifests(first: &Manifest, second: &Manifest) -> ManifestDiff {
+    let algorithms_differ = matches!(
+        (first.algorithm, second.algorithm),
+        (Some(a), Some(b)) if a != b
+    );
+    let mut diff = ManifestDiff {
+        algorithms_differ,
+        altered: [first, second].map(|manifest| manifest.integrity == ReportIntegrity::Altered),
+        ..Default::default()
+    };
+    for (path, entry) in &first.entries {
+        let Some(other) = second.entries.get(path) else {
+            diff.only_in_first.push(path.clone());
+            continue;
+        };
+        let mut differs = false;
+        if !algorithms_differ && entry.hash != other.hash {
+            diff.hash_mismatches.push(path.clone());
+            differs = true;
+        }
+        if let (Some(size), Some(other_size)) = (entry.size, other.size)
+            && size != other_size
+        {
+            diff.size_mismatches.push((path.clone(), size, other_size));
+            differs = true;
+        }
+        if !differs {
+            diff.matching += 1;
+        }
+    }
+    diff.only_in_second = second
+        .entries
+        .keys()
+        .filter(|path| !first.entries.contains_key(*path))
+        .cloned()
+        .collect();
+    diff
+}
+
+/// Checks the folder a manifest describes against it, hashing its files with the manifest's
+/// algorithm; the manifest is the first side of the diff. Files the manifest doesn't list are
+/// only listed, not read. The manifest itself, MHL files from other copies, and ASC MHL's
+/// `ascmhl` folder are left out. With a `subfolder`, relative to the manifest's root, only the
+/// files under it are checked, on both sides.
+pub async fn verify_manifest_folder(
+    manifest_path: &Path,
+    manifest: &Manifest,
+    subfolder: Option<&str>,
+    options: HashOptions,
+    tx: watch::Sender<Progress>,
+    stop: CancellationToken,
+) -> io::Result<JobOutcome<ManifestDiff>> {
+    let Some(algorithm) = manifest.algorithm.and_then(HashAlgorithm::from_id) else {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "The manifest doesn't tell which hash algorithm it uses",
+        ));
+    };
+    let options = HashOptions {
+        algorithm,
+        ..options
+    };
+    let root = manifest_root(manifest_path);
+    let subfolder = subfolder
+        .map(normalize_manifest_path)
+        .filter(|folder| !folder.is_empty());
+    let within;
+    let manifest = match &subfolder {
+        Some(folder) => {
+            within = manifest.within(folder);
+            &within
+        }
+        None => manifest,
+    };
+
+    let listing = {
+        let (root, subfolder) = (root.clone(), subfolder.clone());
+        tokio::task::spawn_blocking(move || {
+            // Only the subfolder is walked, which is all there is to check
+            let mut files = match subfolder {
+                Some(folder) => match flatten_dir_files(&root.join(&folder)) {
+                    Ok(files) => files
+                        .into_iter()
+                        .map(|file| Path::new(&folder).join(file))
+                        .collect(),
+                    // Every file the manifest lists there is missing
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+                    Err(e) => return Err(e),
+                },
+                None => flatten_dir_files(&root)?,
+            };
+            files.sort();
+            Ok::<_, io::Error>(
+                files
+                    .into_iter()
+                    .map(|file| {
+                        let size =
+                            std::fs::metadata(long_path(&root.join(&file))).map_or(0, |m| m.len());
+                        (file, size)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .await??
+    };
+    let manifest_name = manifest_path.file_name();
+    let listing: Vec<(PathBuf, String, u64)> = listing
+        .into_iter()
+        .filter(|(file, _)| {
+            let is_mhl = file
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("mhl"));
+            let is_manifest =
+                file.parent() == Some(Path::new("")) && file.file_name() == manifest_name;
+            !is_mhl && !is_manifest
+        })
+        .map(|(file, size)| {
+            let relative = normalize_manifest_path(&file.to_string_lossy());
+            (file, relative, size)
+        })
+        .filter(|(_, relative, _)| {
+            relative
+                .split('/')
+                .next()
+                .is_none_or(|first| !first.eq_ignore_ascii_case("ascmhl"))
+        })
+        .collect();
+
+    let mut progress = Progress::default();
+    for (_, relative, size) in &listing {
+        if manifest.entries.contains_key(relative) {
+            progress.total_files += 1;
+            progress.total_bytes += size;
+        }
+    }
+    tx.send_replace(progress.clone());
+
+    let mut found = Manifest {
+        algorithm: manifest.algorithm,
+        ..Default::default()
+    };
+    for (file, relative, size) in listing {
+        let hash = if manifest.entries.contains_key(&relative) {
+            if stop.is_cancelled() {
+                return Ok(JobOutcome::Cancelled(ManifestDiff::default()));
+            }
+            let stop_hash = stop.clone();
+            let digest = match compute_file_hash(root.join(&file), options, move |_| {
+                !stop_hash.is_cancelled()
+            })
+            .await
+            {
+                Err(_) if stop.is_cancelled() => {
+                    return Ok(JobOutcome::Cancelled(ManifestDiff::default()));
+                }
+                digest => digest?,
+            };
+            progress.complete_file(size);
+            tx.send_replace(progress.clone());
+            format!("{digest:x}")
+        } else {
+            String::new()
+        };
+        found.entries.insert(
+            relative,
+            ManifestEntry {
+                hash,
+                size: Some(size),
+            },
+        );
+    }
+    Ok(JobOutcome::Completed(ManifestDiff {
+        subfolder,
+        ..diff_manifests(manifest, &found)
+    }))
+}
+
+/// The UTC calendar date of `time` as (year, month, day), and the seconds into that day.
+pub fn utc_date(time: SystemTime) -> ((i64, i64, i64), u64) {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's `civil_from_days`
+    let days = days as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    ((year, month, day), seconds)
+}
+
+/// Days since 1970-01-01 of a civil date, after Howard Hinnant's `days_from_civil`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Offset of the local time zone from UTC at `time`, in seconds. Only Unix-like systems tell;
+/// elsewhere local time is taken to be UTC.
+pub fn local_utc_offset(time: SystemTime) -> i64 {
+    #[cfg(unix)]
+    {
+        let seconds = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs()) as libc::time_t;
+        // SAFETY: `tm` is plain data, and localtime_r only writes to it
+        let mut tm = unsafe { std::mem::zeroed::<libc::tm>() };
+        if unsafe { libc::localtime_r(&seconds, &mut tm) }.is_null() {
+            return 0;
+        }
+        tm.tm_gmtoff as i64
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = time;
+        0
+    }
+}
+
+/// Parses a local date and time like `2024-04-12 18:00`, or a date alone for its midnight, into
+/// the point in time it names. Seconds are optional, and a `T` may stand between date and time.
+pub fn parse_local_time(text: &str) -> Result<SystemTime, String> {
+    let text = text.trim();
+    let invalid = || format!("Not a date and time like 2024-04-12 18:00: {text}");
+    let (date, time) = text
+        .split_once([' ', 'T'])
+        .map_or((text, ""), |(date, time)| (date, time.trim()));
+    let numbers = |part: &str, separator| {
+        part.split(separator)
+            .map(|number| number.parse::<i64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let [year, month, day] = numbers(date, '-')?[..] else {
+        return Err(invalid());
+    };
+    let (hour, minute, second) = match time {
+        "" => (0, 0, 0),
+        time => match numbers(time, ':')?[..] {
+            [hour, minute] => (hour, minute, 0),
+            [hour, minute, second] => (hour, minute, second),
+            _ => return Err(invalid()),
+        },
+    };
+    if !(1970..=9999).contains(&year)
+        || !(0..24).contains(&hour)
+        || !(0..60).contains(&minute)
+        || !(0..60).contains(&second)
+    {
+        return Err(invalid());
+    }
+    // A day past the end of its month would land in the next one
+    let days = days_from_civil(year, month, day);
+    let midnight = SystemTime::UNIX_EPOCH + Duration::from_secs(days.max(0) as u64 * 86400);
+    if !(1..=12).contains(&month) || utc_date(midnight).0 != (year, month, day) {
+        return Err(invalid());
+    }
+
+    let local = days * 86400 + hour * 3600 + minute * 60 + second;
+    let at = |seconds: i64| SystemTime::UNIX_EPOCH + Duration::from_secs(seconds.max(0) as u64);
+    // The offset depends on the date, and taking it a second time settles a time close to a
+    // change of offset
+    let guess = local - local_utc_offset(at(local));
+    Ok(at(local - local_utc_offset(at(guess))))
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// How reports and generated file names write a point in time, as a strftime-style pattern. Times
+/// are always UTC, and reports aren't localized, so month names are English.
+///
+/// Supported fields: `%Y` year, `%y` two-digit year, `%m` month, `%b` and `%B` abbreviated and full
+/// month name, `%d` day, `%H` hour, `%I` hour on a 12-hour clock, `%p` `AM` or `PM`, `%M` minute,
+/// `%S` second, `%F` for `%Y-%m-%d`, `%T` for `%H:%M:%S`, `%Z` for `UTC`, and `%%` for `%`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimestampFormat {
+    pattern: String,
+}
+
+impl TimestampFormat {
+    /// `2024-04-12 14:03:09 UTC`
+    pub const ISO_8601: &str = "%Y-%m-%d %H:%M:%S %Z";
+    /// `04/12/2024 02:03:09 PM UTC`
+    pub const US: &str = "%m/%d/%Y %I:%M:%S %p %Z";
+    /// `12.04.2024 14:03:09 UTC`
+    pub const EUROPEAN: &str = "%d.%m.%Y %H:%M:%S %Z";
+
+    /// Checks `pattern`, returning why it can't be used if it can't.
+    pub fn new(pattern: &str) -> Result<TimestampFormat, String> {
+        let format = TimestampFormat {
+            pattern: pattern.to_owned(),
+        };
+        format.try_format(SystemTime::UNIX_EPOCH)?;
+        Ok(format)
+    }
+
+    pub fn format(&self, time: SystemTime) -> String {
+        self.try_format(time).unwrap_or_default()
+    }
+
+    /// The timestamp with the characters file systems don't allow in names replaced by `-`.
+    pub fn format_for_file_name(&self, time: SystemTime) -> String {
+        self.format(time)
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+                c => c,
+            })
+            .collect()
+    }
+
+    fn try_format(&self, time: SystemTime) -> Result<String, String> {
+        let ((year, month, day), seconds) = utc_date(time);
+        let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        let month_name = MONTH_NAMES[(month - 1) as usize];
+
+        let mut formatted = String::with_capacity(self.pattern.len() * 2);
+        let mut chars = self.pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                formatted.push(c);
+                continue;
+            }
+            let field = match chars.next() {
+                Some('Y') => format!("{year:04}"),
+                Some('y') => format!("{:02}", year.rem_euclid(100)),
+                Some('m') => format!("{month:02}"),
+                Some('b') => month_name[..3].to_owned(),
+                Some('B') => month_name.to_owned(),
+                Some('d') => format!("{day:02}"),
+                Some('H') => format!("{hour:02}"),
+                Some('I') => format!("{:02}", (hour + 11) % 12 + 1),
+                Some('p') => if hour < 12 { "AM" } else { "PM" }.to_owned(),
+                Some('M') => format!("{minute:02}"),
+                Some('S') => format!("{second:02}"),
+                Some('F') => format!("{year:04}-{month:02}-{day:02}"),
+                Some('T') => format!("{hour:02}:{minute:02}:{second:02}"),
+                Some('Z') => "UTC".to_owned(),
+                Some('%') => "%".to_owned(),
+                Some(other) => return Err(format!("Unknown field %{other}")),
+                None => return Err("The format ends with a lone %".to_owned()),
+            };
+            formatted.push_str(&field);
+        }
+        Ok(formatted)
+    }
+}
+
+impl Default for TimestampFormat {
+    fn default() -> Self {
+        TimestampFormat {
+            pattern: TimestampFormat::ISO_8601.to_owned(),
+        }
+    }
+}
+
+/// Joins the components with `/`, which checksum tools expect on every platform.
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Escapes a file name the way coreutils does, returning whether anything was escaped.
+fn escape_checksum_path(path: &str) -> (String, bool) {
+    if !path.contains(['\\', '\n', '\r']) {
+        return (path.to_owned(), false);
+    }
+    let escaped = path
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    (escaped, true)
 }
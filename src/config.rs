@@ -0,0 +1,430 @@
+use crate::paths;
+use crate::status_server::DEFAULT_STATUS_PORT;
+use librecard_core::backend::{
+    CopyOptions, CopyOrder, DEFAULT_BLOCKING_HASH_THRESHOLD, DEFAULT_PARALLEL_HASH_THRESHOLD,
+    DEFAULT_QUIESCENCE_INTERVAL, DEFAULT_READ_RETRIES, DEFAULT_RECONNECT_TIMEOUT,
+    DEFAULT_SCAN_FILE_LIMIT, DEFAULT_SLOW_FILE_FACTOR, DEFAULT_STALL_INTERVAL, HashAlgorithm,
+    HashBenchmark, HashOptions, SYSTEM_FILE_PATTERNS, ScanLimits, SpaceMargin, TimestampFormat,
+    VerifyMethod,
+};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// What to do with a file that already exists at a destination.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverwritePolicy {
+    #[default]
+    Overwrite,
+    Skip,
+    /// Like `Skip`, but a file is only kept once its hash matches the source's.
+    SkipVerified,
+    /// List the files before copying and let the user choose. Unattended jobs skip them.
+    Ask,
+}
+
+impl OverwritePolicy {
+    pub const ALL: [OverwritePolicy; 4] = [
+        OverwritePolicy::Overwrite,
+        OverwritePolicy::Skip,
+        OverwritePolicy::SkipVerified,
+        OverwritePolicy::Ask,
+    ];
+}
+
+/// How reports and exported file names write times.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimestampStyle {
+    #[default]
+    Iso8601,
+    Us,
+    European,
+    /// The pattern in [`Config::custom_timestamp_format`].
+    Custom,
+}
+
+impl TimestampStyle {
+    pub const ALL: [TimestampStyle; 4] = [
+        TimestampStyle::Iso8601,
+        TimestampStyle::Us,
+        TimestampStyle::European,
+        TimestampStyle::Custom,
+    ];
+}
+
+/// How much room the views take.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LayoutDensity {
+    /// Compact while the window is short, e.g. on a small laptop screen.
+    #[default]
+    Auto,
+    Regular,
+    /// Smaller text and spacing, with secondary information collapsed.
+    Compact,
+}
+
+impl LayoutDensity {
+    pub const ALL: [LayoutDensity; 3] = [
+        LayoutDensity::Auto,
+        LayoutDensity::Regular,
+        LayoutDensity::Compact,
+    ];
+}
+
+/// Persistent application settings, stored as TOML in the user's config directory.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// UI locale, or `None` to follow the system language.
+    pub language: Option<String>,
+    /// Use the colorblind-safe palette for status indicators.
+    pub high_contrast: bool,
+    pub layout_density: LayoutDensity,
+    /// Look for a newer release at startup; `None` until the user has been asked.
+    pub check_for_updates: Option<bool>,
+    /// Serve the job status as JSON on localhost, e.g. for a status display on the cart.
+    pub serve_status: bool,
+    /// Port the job status is served on; `None` uses the built-in port.
+    pub status_port: Option<u16>,
+    /// Checksum used for verification.
+    pub hash_algorithm: HashAlgorithm,
+    /// Last run of the hash benchmark in settings, kept to show it again.
+    pub hash_benchmark: Option<HashBenchmark>,
+    /// Files at least this many MiB are hashed on a blocking thread; `None` uses the built-in
+    /// threshold.
+    pub blocking_hash_threshold_mib: Option<u64>,
+    /// Hash large files on every core at once with an algorithm that gives the same digest that
+    /// way, i.e. BLAKE3.
+    pub parallel_hashing: bool,
+    /// Files at least this many MiB are hashed on every core with `parallel_hashing`; `None`
+    /// uses the built-in threshold.
+    pub parallel_hash_threshold_mib: Option<u64>,
+    /// Seconds to wait for a network destination that dropped during a write; `None` uses the
+    /// built-in timeout, and 0 fails at once.
+    pub network_reconnect_secs: Option<u64>,
+    /// Seconds a copy may move no data before it is reported as stalled; `None` uses the built-in
+    /// interval, and 0 turns the check off.
+    pub stall_warning_secs: Option<u64>,
+    /// Seconds between the two looks at the source before a copy that tell whether files are
+    /// still being written; `None` uses the built-in interval, and 0 turns the check off.
+    pub quiescence_secs: Option<u64>,
+    /// Folder levels below a source that jobs go into; deeper folders are left out with a
+    /// warning. `None` goes all the way down.
+    pub max_scan_depth: Option<usize>,
+    /// Files the listing of a newly picked source stops at until the user confirms the source;
+    /// `None` uses the built-in limit, and 0 turns it off.
+    pub scan_file_limit: Option<usize>,
+    /// Times a failed read is tried again before the file fails; `None` uses the built-in count.
+    pub read_retries: Option<u32>,
+    /// Flag files copied this many times slower than the job's median; `None` uses the built-in
+    /// factor, and 0 turns the check off.
+    pub slow_file_factor: Option<f64>,
+    /// Free space to leave on every destination, e.g. `{ percent = 5.0 }` to never fill a drive
+    /// beyond 95% or `{ gib = 50 }` to always leave 50 GiB.
+    pub free_space_margin: Option<SpaceMargin>,
+    pub card_watch: CardWatchConfig,
+    /// Source of the last job the user started, filled in again at the next start.
+    pub last_source: Option<PathBuf>,
+    /// Destinations of the last job the user started, filled in again at the next start.
+    pub last_destinations: Vec<PathBuf>,
+    /// Copy each card into its own numbered session folder on the destinations.
+    pub separate_sessions: bool,
+    /// Copy a file again if it changed while being copied, e.g. because the camera still writes.
+    pub recopy_changed_files: bool,
+    /// Go on copying past a file that fails, or a destination that fails to take it, and list
+    /// them at the end, instead of ending the job at the first failure.
+    pub continue_on_error: bool,
+    /// Keep the modification times of copied files and folders.
+    pub preserve_timestamps: bool,
+    /// Keep the owner and group of copied files, which needs privileges, e.g. for ingest running
+    /// as root.
+    pub preserve_ownership: bool,
+    pub copy_order: CopyOrder,
+    pub overwrite_policy: OverwritePolicy,
+    /// Ignore patterns applied to every source, in the syntax of its own
+    /// [`IGNORE_FILE`](librecard_core::backend::IGNORE_FILE), whose patterns win over these.
+    pub ignore_file: Option<PathBuf>,
+    /// Leave out [`SYSTEM_FILE_PATTERNS`], i.e. system clutter and hidden files.
+    pub ignore_system_files: bool,
+    /// More ignore patterns for every source, separated by commas, e.g. `*.tmp, CACHE/`. The
+    /// ignore file's patterns win over these.
+    pub ignore_patterns: String,
+    /// Make every verified copy read-only once the job is verified, so masters aren't edited in
+    /// place by mistake.
+    pub read_only_verified: bool,
+    /// Read every source file twice to catch unstable reads from a failing card.
+    pub double_read_sources: bool,
+    /// Copy what can still be read of a file the card fails to read, instead of failing it. The
+    /// unreadable parts are filled with zeros and the file is reported as partially recovered.
+    pub salvage_unreadable: bool,
+    /// Before copying, check that every file on the card can be opened and read.
+    pub check_source_readable: bool,
+    /// List every file on the source's volume, not only those copied, for the report.
+    pub record_volume_listing: bool,
+    /// Hash the card before copying and copy identical files only once.
+    pub skip_duplicates: bool,
+    /// End verification at the first file that doesn't match.
+    pub stop_on_first_failure: bool,
+    /// Verify by comparing the copies with the card byte for byte, which gives up on a file at its
+    /// first difference, instead of hashing them.
+    pub compare_bytes: bool,
+    /// Check that each destination has the source's folders, empty ones included, and no others.
+    pub check_folders: bool,
+    /// Times verification after copying reads each destination back, up to
+    /// [`MAX_VERIFICATION_PASSES`]; `None` reads them once.
+    pub verification_passes: Option<u32>,
+    /// Files verification after copying hashes at once, up to [`MAX_VERIFY_WORKERS`]; `None`
+    /// hashes one at a time.
+    pub verify_workers: Option<usize>,
+    /// A dry run reads every source file, as a copy would, rather than only walking the job.
+    pub dry_run_reads_source: bool,
+    /// Verify each file right after copying it instead of in a separate pass.
+    pub verify_while_copying: bool,
+    /// How verification while copying checks the destinations.
+    pub verify_method: VerifyMethod,
+    pub timestamp_style: TimestampStyle,
+    /// strftime-style pattern for [`TimestampStyle::Custom`], e.g. `%d/%m/%Y %H:%M`.
+    pub custom_timestamp_format: String,
+    /// Window geometry from the last session.
+    pub window: Option<WindowGeometry>,
+    /// Settings from the defaults file and environment, before the user's own changes. Only the
+    /// changes are saved, so a new default still reaches settings the user never touched.
+    #[serde(skip)]
+    seeded: toml::Table,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+}
+
+/// Profile for the kiosk-style "watch for card" mode.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardWatchConfig {
+    pub enabled: bool,
+    /// Label of the volume that triggers a job when it is mounted.
+    pub volume_label: String,
+    /// Serial number or UUID of that volume, e.g. `1234-ABCD`, for telling apart cards a camera
+    /// labels alike. Either may be left empty to match any.
+    pub volume_serial: String,
+    pub destinations: Vec<PathBuf>,
+}
+
+/// Prefix of environment variables that override a default, e.g. `LIBRECARD_HASH_ALGORITHM`.
+const ENV_PREFIX: &str = "LIBRECARD_";
+
+/// Most verification passes the settings offer.
+pub const MAX_VERIFICATION_PASSES: u32 = 3;
+
+/// Most files verification hashes at once.
+pub const MAX_VERIFY_WORKERS: usize = 16;
+
+impl Config {
+    fn path() -> Option<PathBuf> {
+        paths::config_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    /// Defaults shipped by an administrator: the file named by `LIBRECARD_DEFAULTS`, or
+    /// `defaults.toml` next to the executable.
+    fn defaults_path() -> Option<PathBuf> {
+        std::env::var_os("LIBRECARD_DEFAULTS")
+            .map(PathBuf::from)
+            .or_else(|| Some(paths::executable_dir()?.join("defaults.toml")))
+    }
+
+    /// Loads the settings. Built-in defaults are overridden by the defaults file, then by
+    /// environment variables, then by the user's config file. A missing file is skipped, and a
+    /// value that doesn't parse keeps the one below it.
+    pub fn load() -> Config {
+        let mut table = toml::Table::try_from(Config::default()).unwrap_or_default();
+        if let Some(defaults) = Self::defaults_path().and_then(|path| read_table(&path)) {
+            merge_valid(&mut table, defaults);
+        }
+        merge_valid(&mut table, env_table());
+        let seeded = table.clone();
+
+        if let Some(user) = Self::path().and_then(|path| read_table(&path)) {
+            merge_valid(&mut table, user);
+        }
+        Config {
+            seeded,
+            ..toml::Value::Table(table).try_into().unwrap_or_default()
+        }
+    }
+
+    pub fn copy_options(&self) -> CopyOptions {
+        CopyOptions {
+            order: self.copy_order,
+            verify: self.verify_while_copying.then(|| self.hash_options()),
+            verify_method: self.verify_method,
+            recopy_changed: self.recopy_changed_files,
+            skip_existing: self.overwrite_policy != OverwritePolicy::Overwrite,
+            verify_existing: self.overwrite_policy == OverwritePolicy::SkipVerified,
+            double_read: self.double_read_sources,
+            preserve_timestamps: self.preserve_timestamps,
+            preserve_ownership: self.preserve_ownership,
+            reconnect_timeout: match self.network_reconnect_secs {
+                None => Some(DEFAULT_RECONNECT_TIMEOUT),
+                Some(0) => None,
+                Some(secs) => Some(Duration::from_secs(secs)),
+            },
+            read_retries: self.read_retries.unwrap_or(DEFAULT_READ_RETRIES),
+            salvage: self.salvage_unreadable.then_some(self.hash_algorithm),
+            slow_file_factor: match self.slow_file_factor {
+                None => Some(DEFAULT_SLOW_FILE_FACTOR),
+                Some(factor) if factor <= 0.0 => None,
+                factor => factor,
+            },
+            space_margin: self.free_space_margin,
+            dry_run: None,
+            stall_after: match self.stall_warning_secs {
+                None => Some(DEFAULT_STALL_INTERVAL),
+                Some(0) => None,
+                Some(secs) => Some(Duration::from_secs(secs)),
+            },
+            continue_on_error: self.continue_on_error,
+        }
+    }
+
+    /// Limits for listing a newly picked source.
+    pub fn scan_limits(&self) -> ScanLimits {
+        ScanLimits {
+            max_depth: self.max_scan_depth,
+            max_files: match self.scan_file_limit {
+                None => Some(DEFAULT_SCAN_FILE_LIMIT),
+                Some(0) => None,
+                limit => limit,
+            },
+        }
+    }
+
+    /// How long the source is watched for files still being written before a copy, if at all.
+    pub fn quiescence_interval(&self) -> Option<Duration> {
+        match self.quiescence_secs {
+            None => Some(DEFAULT_QUIESCENCE_INTERVAL),
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    pub fn hash_options(&self) -> HashOptions {
+        HashOptions {
+            algorithm: self.hash_algorithm,
+            blocking_threshold: self
+                .blocking_hash_threshold_mib
+                .map_or(DEFAULT_BLOCKING_HASH_THRESHOLD, |mib| mib * 1024 * 1024),
+            stop_on_failure: self.stop_on_first_failure,
+            compare: self.compare_bytes,
+            check_folders: self.check_folders,
+            read_retries: self.read_retries.unwrap_or(DEFAULT_READ_RETRIES),
+            passes: self.verification_passes(),
+            parallel_threshold: self.parallel_hashing.then(|| {
+                self.parallel_hash_threshold_mib
+                    .map_or(DEFAULT_PARALLEL_HASH_THRESHOLD, |mib| mib * 1024 * 1024)
+            }),
+        }
+    }
+
+    /// Ignore patterns for every source, before those of the ignore files.
+    pub fn ignore_patterns(&self) -> Vec<String> {
+        let system = match self.ignore_system_files {
+            true => &SYSTEM_FILE_PATTERNS[..],
+            false => &[],
+        };
+        system
+            .iter()
+            .map(|pattern| (*pattern).to_owned())
+            .chain(
+                self.ignore_patterns
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(str::to_owned),
+            )
+            .collect()
+    }
+
+    pub fn verification_passes(&self) -> u32 {
+        self.verification_passes
+            .unwrap_or(1)
+            .clamp(1, MAX_VERIFICATION_PASSES)
+    }
+
+    pub fn verify_workers(&self) -> usize {
+        self.verify_workers
+            .unwrap_or(1)
+            .clamp(1, MAX_VERIFY_WORKERS)
+    }
+
+    pub fn status_port(&self) -> u16 {
+        self.status_port.unwrap_or(DEFAULT_STATUS_PORT)
+    }
+
+    /// The timestamp format the settings pick, or why the custom pattern can't be used, in which
+    /// case ISO 8601 stands in for it.
+    pub fn timestamp_format(&self) -> Result<TimestampFormat, String> {
+        let pattern = match self.timestamp_style {
+            TimestampStyle::Iso8601 => TimestampFormat::ISO_8601,
+            TimestampStyle::Us => TimestampFormat::US,
+            TimestampStyle::European => TimestampFormat::EUROPEAN,
+            TimestampStyle::Custom => &self.custom_timestamp_format,
+        };
+        TimestampFormat::new(pattern)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No config directory"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut table = toml::Table::try_from(self).map_err(io::Error::other)?;
+        table.retain(|key, value| self.seeded.get(key) != Some(value));
+        let content = toml::to_string_pretty(&table).map_err(io::Error::other)?;
+        std::fs::write(path, content)
+    }
+}
+
+fn read_table(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+/// Overrides from `LIBRECARD_*` variables, e.g. `LIBRECARD_HIGH_CONTRAST=true` sets
+/// `high_contrast`. Values are read as TOML, or as a plain string if they aren't valid TOML.
+fn env_table() -> toml::Table {
+    std::env::vars()
+        .filter_map(|(name, value)| {
+            let key = name.strip_prefix(ENV_PREFIX)?.to_lowercase();
+            let value = format!("value = {value}")
+                .parse::<toml::Table>()
+                .ok()
+                .and_then(|mut table| table.remove("value"))
+                .unwrap_or(toml::Value::String(value));
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Copies each key of `layer` into `table`, unless it would leave the table an invalid config.
+fn merge_valid(table: &mut toml::Table, layer: toml::Table) {
+    for (key, value) in layer {
+        let previous = table.insert(key.clone(), value);
+        if toml::Value::Table(table.clone())
+            .try_into::<Config>()
+            .is_err()
+        {
+            match previous {
+                Some(previous) => table.insert(key, previous),
+                None => table.remove(&key),
+            };
+        }
+    }
+}
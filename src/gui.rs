@@ -1,43 +1,307 @@
-﻿use crate::backend::{ChecksumReport, Progress, copy_dirs, flatten_dir_files, hash_dirs};
-use human_bytes::human_bytes;
-use iced::widget::{button, column, container, progress_bar, row, text, text_input};
-use iced::{Color, Element, Length, Subscription, Task, time};
+use crate::config::{
+    Config, LayoutDensity, MAX_VERIFICATION_PASSES, MAX_VERIFY_WORKERS, OverwritePolicy,
+    TimestampStyle,
+};
+use crate::gui::console::{LogEntry, console_events, push_entry};
+use crate::gui::crash::CrashReport;
+use crate::gui::focus::{button, checkbox};
+use crate::gui::geometry::update_geometry;
+use crate::gui::job_history::{JobHistory, JobRecord, RecordedDestination, RecordedOutcome};
+use crate::gui::last_job::LastJob;
+use crate::gui::metrics::{COMPACT_BELOW_HEIGHT, Metrics};
+use crate::gui::path_text::path_text;
+use crate::gui::status::{Status, status_text};
+use crate::i18n::{
+    LANGUAGES, apply_language, format_bytes, format_clock, format_count, format_duration,
+    format_recent_time, format_time, format_utc_offset,
+};
+use crate::paths::expand_typed;
+use crate::status_server::{
+    DEFAULT_STATUS_PORT, DestinationStage, DestinationStatus, JobStage, JobStatus, ReportOutcome,
+    ReportSummary, StatusServer,
+};
+use crate::update::{Release, newer_release};
+use futures::Stream;
+use iced::widget::{
+    column, container, focus_next, focus_previous, horizontal_space, pick_list, progress_bar, row,
+    scrollable, text, text_input, tooltip,
+};
+use iced::{Element, Length, Subscription, Task, event, keyboard, time, window};
+use librecard_core::backend::{
+    Baseline, ChecksumReport, Collision, ContentReport, CopyOrder, CopySummary, DestinationHash,
+    DestinationKind, DestinationState, Digest, DryRun, ExistingContents, FileFilter, FileStamp,
+    FullDestination, HashAlgorithm, HashBenchmark, IgnoreRules, JobEnd, JobHandle, JobId,
+    JobOutcome, JobPhase, JobPreview, JobWarning, Manifest, ManifestDiff, ManifestFormat,
+    ModifiedWindow, OffloadMarker, PermissionChoice, PermissionHandling, Preflight, Progress,
+    ROOT_GROUP, Reachability, ReadabilityReport, ReportIntegrity, ScanLimits, SkipReason,
+    SourceChanges, SourceListing, SourcePlan, SpaceChoice, SpaceForecast, SpaceHandling,
+    TimestampFormat, TypeTotal, UnreadableFile, VerificationLevel, VerifyMethod, Volume,
+    WarningKind, benchmark_hashes, bytes_in_place, catch_up_destination, check_reachable,
+    check_source_readable, compare_with_source, compute_file_hash, copy_dirs,
+    destinations_on_source_volume, diff_manifests, double_read_source, duplicate_pairs,
+    existing_contents, file_group, files_in_manifest, find_duplicate_destinations,
+    find_duplicate_files, find_growing_files, find_nested_destinations, find_offload_markers,
+    flatten_dir_files_within, forecast_space, hard_link_pairs, hash_dirs, join_subroot,
+    list_dir_files, list_source_volume, list_volumes, local_utc_offset, manifest_root,
+    next_session_folder, parse_local_time, preview_job, probe_writable, protect_verified,
+    read_back_report, read_manifest, reconcile_contents, reconnected_files, salvaged_files,
+    scan_source, skipped_files, verify_manifest_folder, write_offload_markers,
+};
 use rfd::FileDialog;
-use std::path::PathBuf;
-use std::time::Duration;
-use tokio::sync::watch;
+use rust_i18n::t;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, Level};
+
+mod console;
+mod crash;
+mod focus;
+mod geometry;
+mod job_history;
+mod last_job;
+mod metrics;
+mod path_text;
+mod status;
+
+pub use console::init as init_console;
+pub use crash::install as install_panic_hook;
+
+pub use geometry::window_settings;
+
+/// Past this many destinations, each chunk read from the card waits on that many writes, so the
+/// slowest drive paces the rest and the disks contend. More still works, but with a warning.
+const RECOMMENDED_MAX_DESTINATIONS: usize = 8;
+
+/// Widget ID of the destination list, to scroll it to a row that was just added.
+const DESTINATION_LIST_ID: &str = "destination-list";
 
 #[derive(Debug, Default)]
 enum LibreCardAppStage {
     #[default]
     Input,
 
+    Settings,
+
     Copying {
         progress: Progress,
         rx: watch::Receiver<Progress>,
+        stop: CancellationToken,
+        /// Ends the job once the file being copied is complete, for closing the app.
+        finish: CancellationToken,
+        started_at: Instant,
+        /// The source file the job can't read and is waiting on a choice for.
+        asking: watch::Receiver<Option<PathBuf>>,
+        choices: mpsc::UnboundedSender<PermissionChoice>,
+        /// The destination that ran out of space, waiting on a choice like `asking`.
+        space_asking: watch::Receiver<Option<FullDestination>>,
+        space_choices: mpsc::UnboundedSender<SpaceChoice>,
+        /// Files that had trouble so far. Every warning comes with a progress update, which
+        /// redraws the view.
+        warnings: watch::Receiver<Vec<JobWarning>>,
+        rates: DestinationRates,
     },
 
     CopyComplete {
-        total_bytes_copied: u64,
+        /// Also lists the files found while copying, so verification doesn't have to scan the
+        /// source again.
+        summary: CopySummary,
     },
 
     Checksumming {
         progress: Progress,
         rx: watch::Receiver<Progress>,
+        stop: CancellationToken,
+        started_at: Instant,
+        /// Files that didn't match so far, latest last.
+        warnings: watch::Receiver<Vec<JobWarning>>,
+        /// How many files are verified at once, for a job that can change it as it runs.
+        workers: Option<watch::Sender<usize>>,
     },
 
     ChecksumComplete {
         report: ChecksumReport,
+        /// Computed once, as they sort every path.
+        fingerprint: Digest,
+        manifest_digest: String,
+    },
+
+    /// Result of matching the destinations to the source by content only.
+    ReconcileComplete {
+        report: ContentReport,
+    },
+
+    /// Result of reading the source twice, with no destination involved.
+    ReadabilityComplete {
+        report: ReadabilityReport,
+    },
+
+    /// Differences between two manifests, e.g. our report and the one an archive sent back.
+    ReportDiff {
+        first: PathBuf,
+        second: PathBuf,
+        diff: ManifestDiff,
+        /// `second` is the folder the manifest describes, hashed to check it against the manifest.
+        folder: bool,
+    },
+
+    /// Hashing a single file, to compare with a hash from elsewhere.
+    HashFile {
+        path: PathBuf,
+        algorithm: HashAlgorithm,
+        progress: Progress,
+        rx: watch::Receiver<Progress>,
+        stop: CancellationToken,
+        /// Set once the file is hashed, or couldn't be.
+        digest: Option<Result<Digest, String>>,
+        /// The hash to compare with, as pasted.
+        expected: String,
     },
+
+    /// A job was stopped on purpose; not an error.
+    Cancelled {
+        completed: usize,
+        total: usize,
+    },
+
+    /// The app panicked during a job the last time it ran.
+    Recovery(Box<CrashReport>),
 }
 
 #[derive(Debug, Default)]
 pub struct LibreCardApp {
     stage: LibreCardAppStage,
     source_directory: Option<PathBuf>,
-    destination_directories: Vec<Option<PathBuf>>,
+    /// Files found under the source when it was selected, or why they couldn't be listed. Saves
+    /// scanning the card again before a job.
+    source_listing: Option<(PathBuf, Result<SourceListing, String>)>,
+    /// Stops the running scan of the source, if there is one.
+    source_scan: Option<CancellationToken>,
+    /// Source the user confirmed is meant although listing it went past the file limit.
+    uncapped_source: Option<PathBuf>,
+    destinations: Vec<Destination>,
+    /// Local date and time a source file must be modified at or after to be copied, as typed.
+    modified_after: String,
+    /// Local date and time a source file must be modified before to be copied, as typed.
+    modified_before: String,
+    /// Manifest of an earlier offload of the card; files it lists aren't copied again.
+    baseline: Option<(PathBuf, Manifest)>,
+    /// Match files against the baseline by hash as well, not only by path and size.
+    baseline_hashes: bool,
+    /// Result of the last reachability check of each destination path that was entered.
+    destination_reachability: HashMap<PathBuf, Reachability>,
+    /// Offloads found in the selected destinations, each with the folder it is in.
+    destination_markers: HashMap<PathBuf, Vec<(PathBuf, OffloadMarker)>>,
     error_message: Option<String>,
+    /// The hash benchmark is running; its result goes to the config.
+    benchmark_running: bool,
+    warning_message: Option<String>,
     total_bytes_copied: Option<u64>,
+    config: Config,
+    /// Set when the current job was started by the card watcher, so verification follows the copy.
+    auto_verify: bool,
+    /// Copies only simulate the job, writing nothing to the destinations.
+    dry_run: bool,
+    /// Mount point of the watched card that already triggered a job, to avoid re-triggering
+    /// until the card is removed.
+    watched_volume: Option<PathBuf>,
+    /// The window was asked to close while a job is running and the user hasn't decided yet.
+    close_prompt: bool,
+    /// The running job lists its warnings rather than only the latest.
+    show_warnings: bool,
+    /// Destinations that already hold files, waiting for the user to confirm the copy.
+    nonempty_prompt: Option<Vec<(PathBuf, ExistingContents)>>,
+    dont_ask_nonempty: bool,
+    /// Destinations the user chose not to be asked about again.
+    nonempty_confirmed_for: Option<Vec<PathBuf>>,
+    /// Files the job would overwrite, waiting for the user to choose.
+    overwrite_prompt: Option<Vec<Collision>>,
+    /// Destination rows without a folder, by row number, waiting for the user to copy without
+    /// them.
+    empty_rows_prompt: Option<Vec<usize>>,
+    /// The user chose to copy without the rows that have no folder, for the rest of the session.
+    skip_empty_rows: bool,
+    /// What the destinations held when the current job checked them, one entry per destination.
+    job_contents: Vec<ExistingContents>,
+    /// What the job would do with each source file, waiting for the user to start it.
+    preview_prompt: Option<JobPreview>,
+    /// Group of files listed on the preview.
+    expanded_preview: Option<PreviewList>,
+    /// How the current job fits on its destinations, once checked before copying.
+    job_space_forecast: Option<Vec<SpaceForecast>>,
+    /// The current job's destinations took a test write before copying.
+    job_writable: bool,
+    /// The job would go into the free space margin of a destination, waiting for the user to
+    /// decide.
+    shortfall_prompt: bool,
+    /// The readability check of the source is running before a copy.
+    preflight_running: bool,
+    /// Source files that couldn't be read, waiting for the user to exclude them or give up.
+    unreadable_prompt: Option<Vec<UnreadableFile>>,
+    /// Source files the current job leaves out.
+    job_excluded: Vec<PathBuf>,
+    /// Cloud placeholders on the source, waiting for the user to choose whether to download them.
+    placeholder_prompt: Option<Vec<PathBuf>>,
+    /// Source files still being written, waiting for the user to wait for them, leave them out or
+    /// copy them anyway.
+    growing_prompt: Option<Vec<PathBuf>>,
+    /// The user's answer to the placeholder prompt for the current job: leave them out, or
+    /// download this many.
+    job_skip_placeholders: bool,
+    job_downloaded_placeholders: usize,
+    /// Modification times the current job copies.
+    job_window: ModifiedWindow,
+    /// Patterns of source files the current job leaves out.
+    job_ignore: IgnoreRules,
+    /// The earlier offload the current job adds to, with the files it leaves out.
+    job_baseline: Option<Baseline>,
+    /// Source file the running copy isn't allowed to read, waiting for the user to choose.
+    permission_prompt: Option<PathBuf>,
+    /// Destination the running copy filled up, waiting for the user to choose.
+    space_prompt: Option<FullDestination>,
+    /// The user's answer to the overwrite prompt for the current job.
+    job_skip_existing: Option<bool>,
+    /// Quit as soon as the running job has stopped after its current file.
+    quit_after_job: bool,
+    /// Incremented for every job, so each job gets its own progress subscription.
+    job_serial: u64,
+    /// Destinations of the last copy, including its session folder, for verification.
+    job_destinations: Vec<PathBuf>,
+    job_filters: Vec<FileFilter>,
+    /// Rows of the last copy that were disabled, for its report.
+    job_disabled: Vec<PathBuf>,
+    /// The last job started, for its report, its history entry and the log.
+    job_id: Option<JobId>,
+    /// Outcome of the last finished job, for the status bar.
+    last_result: Option<(Status, String)>,
+    /// Destination whose missing files are listed on the verification result.
+    expanded_missing: Option<usize>,
+    /// Top-level folder whose files are listed on the verification result.
+    expanded_group: Option<String>,
+    /// The last verification that completed, possibly in an earlier run of the app.
+    last_job: Option<LastJob>,
+    /// A newer release found by the update check, until the user dismisses it.
+    available_update: Option<Release>,
+    /// Serves the job status on localhost while the setting is on.
+    status_server: Option<StatusServer>,
+    /// Port field of the settings, as typed.
+    status_port_input: String,
+    /// Recently finished jobs, to run again on another card.
+    job_history: JobHistory,
+    /// The current job's copy is the newest entry of the history, for its verification to update.
+    job_recorded: bool,
+    /// The latest tracing events, oldest first.
+    console: VecDeque<LogEntry>,
+    console_open: bool,
+    /// The information the compact layout collapses is expanded.
+    show_details: bool,
+    /// Least severe events shown on the console.
+    console_level: ConsoleLevelChoice,
 }
 
 #[derive(Debug, Clone)]
@@ -46,488 +310,7691 @@ pub enum LibreCardMessage {
     OpenSourceDirectoryDialog,
     OpenDestinationDirectoryDialog(usize),
     AddDestinationDirectory,
+    AddArchiveDestination,
     RemoveDestinationDirectory(usize),
+    DestinationLabelChanged(usize, String),
+    DestinationSubrootChanged(usize, String),
+    DestinationFilterChanged(usize, String),
+    ToggleDestinationEnabled(usize, bool),
+    ModifiedAfterChanged(String),
+    ModifiedBeforeChanged(String),
+    ChooseBaseline,
+    BaselineOpened(Result<Option<(PathBuf, Manifest)>, String>),
+    ClearBaseline,
+    ToggleBaselineHashes(bool),
+    DestinationPathChanged(usize, String),
+    DestinationChecked(PathBuf, Reachability),
+    /// Offloads a destination already holds, each with the folder it is in.
+    DestinationMarkersFound(PathBuf, Vec<(PathBuf, OffloadMarker)>),
+
+    // Settings messages
+    OpenSettings,
+    CloseSettings,
+    LanguageSelected(LanguageChoice),
+    ToggleHighContrast(bool),
+    LayoutDensitySelected(LayoutDensityChoice),
+    /// Shows or hides the information the compact layout collapses.
+    ToggleDetails,
+    /// Answer to the one-time question whether to check for updates, or the setting changed.
+    ToggleUpdateCheck(bool),
+    UpdateChecked(Option<Release>),
+    OpenReleasePage,
+    DismissUpdate,
+    ToggleServeStatus(bool),
+    StatusPortChanged(String),
+    /// The status server on this port ended, or failed to start.
+    StatusServerStopped(u16, Result<(), String>),
+    ToggleRecopyChanged(bool),
+    ToggleDoubleRead(bool),
+    ToggleSalvage(bool),
+    ToggleCheckSourceReadable(bool),
+    ToggleRecordVolumeListing(bool),
+    SourceChecked(Result<JobOutcome<Preflight>, String>),
+    ResolveUnreadable(bool),
+    ResolvePlaceholders(PlaceholderChoice),
+    ResolveGrowing(GrowingChoice),
+    PermissionDenied(Option<PathBuf>),
+    ResolvePermission(PermissionChoice),
+    DestinationFull(Option<FullDestination>),
+    ResolveSpace(SpaceChoice),
+    CheckDestinations,
+    /// Each destination of the job with the probe file left there, or why it can't be written.
+    WritabilityProbed(Vec<(PathBuf, Result<Option<PathBuf>, String>)>),
+    SpaceForecasted(Vec<SpaceForecast>),
+    ResolveShortfall(bool),
+    SourceScanned(PathBuf, Result<JobOutcome<SourceListing>, String>),
+    /// List the source past the file limit, which the user confirmed is the right one.
+    ScanWholeSource,
+    TogglePreserveTimestamps(bool),
+    TogglePreserveOwnership(bool),
+    ToggleReadOnlyVerified(bool),
+    ToggleParallelHashing(bool),
+    ToggleSkipDuplicates(bool),
+    ToggleStopOnFirstFailure(bool),
+    ToggleCompareBytes(bool),
+    ToggleCheckFolders(bool),
+    ToggleIgnoreSystemFiles(bool),
+    IgnorePatternsChanged(String),
+    ToggleDryRun(bool),
+    ToggleDryRunReadsSource(bool),
+    ToggleWarnings,
+    ToggleVerifyWhileCopying(bool),
+    CopyOrderSelected(CopyOrderChoice),
+    VerifyMethodSelected(VerifyMethodChoice),
+    VerificationPassesSelected(u32),
+    VerifyWorkersSelected(usize),
+    /// Verify this many files at once from now on, in the running verification.
+    HashWorkersChanged(usize),
+    HashAlgorithmSelected(HashAlgorithm),
+    /// Benchmarks the hash algorithms, asking for a sample file to read first if `sample` is set.
+    BenchmarkHashes {
+        sample: bool,
+    },
+    HashesBenchmarked(Result<HashBenchmark, String>),
+
+    // Card watch messages
+    ToggleSeparateSessions(bool),
+    ToggleContinueOnError(bool),
+    ToggleCardWatch(bool),
+    CardWatchLabelChanged(String),
+    SaveCardWatchLabel,
+    CardWatchSerialChanged(String),
+    SaveCardWatchDestinations,
+    PollVolumes,
+    VolumesPolled(Vec<Volume>),
 
     // Action messages
     StartCopy,
+    /// The job's destinations that are on the source's drive, looked up once the job starts.
+    SameDiskChecked(Vec<PathBuf>),
     StartChecksum,
-    ExportChecksum,
+    ExportChecksum(ManifestFormat),
 
     // Progress updates
+    ProgressUpdated(Progress),
     Tick,
-    CopyCompleted(Result<u64, String>),
-    ChecksumCompleted(Result<ChecksumReport, String>),
+    CopyCompleted(JobEnd<CopySummary>),
+    ChecksumCompleted(JobEnd<ChecksumReport>),
+    /// Verifies the destinations against the source as they are, without copying first.
+    StartVerifyOnly,
+    StartReconcile,
+    ReconcileCompleted(Result<JobOutcome<ContentReport>, String>),
+    /// Reads the source twice and compares the passes, to tell a flaky reader from a bad card.
+    StartReadabilityCheck,
+    ReadabilityChecked(Result<JobOutcome<ReadabilityReport>, String>),
+    ExportReadability(ManifestFormat),
+    /// Reads back the copies of a job verified while copying.
+    UpgradeVerification,
+    /// The report before the read-back, and the report after it.
+    VerificationUpgraded(
+        Box<ChecksumReport>,
+        Result<JobOutcome<ChecksumReport>, String>,
+    ),
+    /// Copies the verified job to a drive that wasn't one of its destinations.
+    CatchUpDestination,
+    /// The report before the catch-up, the new destination, and its results.
+    CatchUpCompleted(
+        ChecksumReport,
+        PathBuf,
+        Result<JobOutcome<Vec<(PathBuf, DestinationHash)>>, String>,
+    ),
+    CompareReports,
+    /// `None` if a file dialog was dismissed.
+    ReportsCompared(Result<Option<(PathBuf, PathBuf, ManifestDiff)>, String>),
+    /// Whether to check only a folder the manifest covers
+    VerifyManifest(bool),
+    /// `None` if the file dialog was dismissed.
+    ManifestOpened(Result<Option<(PathBuf, Manifest, Option<String>)>, String>),
+    ManifestVerified(PathBuf, Result<JobOutcome<ManifestDiff>, String>),
+    ChooseFileToHash,
+    /// A file or folder was dropped onto the window.
+    FileDropped(PathBuf),
+    FileHashed(PathBuf, Result<Digest, String>),
+    CancelFileHash,
+    /// Stops the running copy or verification.
+    CancelOperation,
+    ExpectedHashChanged(String),
+    CopyFileHash,
+    CopyJobId(JobId),
+    ExportReportDiff,
+    ExportFailedFiles,
     ExportCompleted(Result<(), String>),
+    ViewLastJob,
+    ExportLastJob,
+    /// Fills in the destinations of the job at this index of the history, then asks for a source.
+    RerunJob(usize),
+    /// Destination folders of the job being run again that don't exist anymore.
+    RerunDestinationsChecked(Vec<PathBuf>),
+    LastJobSaved(Result<(), String>),
+    OffloadMarkersWritten(Result<(), String>),
+    /// Copies that couldn't be made read-only, with why.
+    VerifiedProtected(Vec<(PathBuf, String)>),
 
     // Error handling
     DismissError,
-}
-
-impl LibreCardApp {
-    pub fn update(&mut self, message: LibreCardMessage) -> Task<LibreCardMessage> {
-        match message {
-            LibreCardMessage::Tick => {
-                // Poll progress channel on timer tick
-                match &mut self.stage {
-                    LibreCardAppStage::Copying { progress, rx } => {
-                        if let Ok(true) = rx.has_changed() {
-                            *progress = *rx.borrow();
-                        }
-                    }
-                    LibreCardAppStage::Checksumming { progress, rx } => {
-                        if let Ok(true) = rx.has_changed() {
-                            *progress = *rx.borrow();
-                        }
-                    }
-                    _ => {}
-                }
-                Task::none()
-            }
+    DismissWarning,
+    DestinationsChecked(Result<Vec<(PathBuf, ExistingContents)>, String>),
+    ToggleDontAskNonEmpty(bool),
+    ConfirmNonEmpty,
+    CancelNonEmpty,
+    /// Answer to the overwrite prompt: skip existing files, overwrite them, or `None` to cancel.
+    ResolveOverwrite(Option<bool>),
+    /// Copy without the destination rows that have no folder, or don't copy.
+    ResolveEmptyRows(bool),
+    PreviewJob,
+    TogglePreviewList(PreviewList),
+    CancelPreview,
+    OverwritePolicySelected(OverwritePolicyChoice),
+    TimestampStyleSelected(TimestampStyleChoice),
+    CustomTimestampFormatChanged(String),
+    /// Starts the copy once the destinations are settled.
+    BeginCopy,
+    ToggleMissingList(usize),
+    ToggleGroup(String),
+    CopyCrashDetails,
+    DismissCrashReport,
+    BackToInput,
 
-            LibreCardMessage::CopyCompleted(result) => {
-                match result {
-                    Ok(bytes) => {
-                        self.stage = LibreCardAppStage::CopyComplete {
-                            total_bytes_copied: bytes,
-                        };
-                        self.total_bytes_copied = Some(bytes);
-                    }
-                    Err(error) => {
-                        self.stage = LibreCardAppStage::Input;
-                        self.error_message = Some(error);
-                    }
-                }
-                Task::none()
-            }
+    // Window management
+    WindowChanged(window::Event),
+    /// Tab moves the keyboard focus forwards, Shift+Tab backwards.
+    MoveFocus {
+        backwards: bool,
+    },
+    WindowCloseRequested,
+    FinishAndQuit,
+    KeepRunning,
 
-            LibreCardMessage::OpenSourceDirectoryDialog => {
-                let dir = FileDialog::new().pick_folder();
-                self.source_directory = dir;
-                Task::none()
-            }
+    // Log console
+    LogRecorded(LogEntry),
+    ToggleConsole,
+    ConsoleLevelSelected(ConsoleLevelChoice),
+    /// Copies the events shown on the console to the clipboard.
+    CopyConsole,
+}
 
-            LibreCardMessage::OpenDestinationDirectoryDialog(index) => {
-                if index < self.destination_directories.len() {
-                    self.destination_directories[index] = FileDialog::new().pick_folder();
-                }
-                Task::none()
-            }
+/// Entry of the language picker; `None` follows the system language.
+/// Answer to the prompt about cloud placeholders on the source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaceholderChoice {
+    Skip,
+    Download,
+    Abort,
+}
 
-            LibreCardMessage::AddDestinationDirectory => {
-                self.destination_directories.push(None);
-                Task::none()
-            }
+/// Answer to the prompt about source files that are still being written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrowingChoice {
+    Recheck,
+    Exclude,
+    Proceed,
+    Abort,
+}
 
-            LibreCardMessage::RemoveDestinationDirectory(index) => {
-                if self.destination_directories.len() > 1 {
-                    self.destination_directories.remove(index);
-                }
-                Task::none()
-            }
+/// A group of files on the preview shown before a copy starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreviewList {
+    Copied,
+    Filtered,
+    Present,
+    Ignored,
+    Overwritten,
+}
 
-            LibreCardMessage::StartCopy => {
-                // Validate input
-                if self.source_directory.is_none() {
-                    self.error_message = Some("Source directory not selected.".to_string());
-                    return Task::none();
-                }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LanguageChoice(Option<&'static str>);
 
-                let valid_destinations: Vec<PathBuf> = self
-                    .destination_directories
+impl LanguageChoice {
+    fn all() -> Vec<LanguageChoice> {
+        std::iter::once(LanguageChoice(None))
+            .chain(
+                LANGUAGES
                     .iter()
-                    .filter_map(|opt| opt.clone())
-                    .collect();
-
-                if valid_destinations.is_empty() {
-                    self.error_message =
-                        Some("No valid destination directories selected.".to_string());
-                    return Task::none();
-                }
+                    .map(|(locale, _)| LanguageChoice(Some(locale))),
+            )
+            .collect()
+    }
 
-                // Start copy operation
-                let source = self.source_directory.clone().unwrap();
-                let destinations = valid_destinations;
+    fn from_config(language: Option<&str>) -> LanguageChoice {
+        LanguageChoice(
+            LANGUAGES
+                .iter()
+                .map(|(locale, _)| *locale)
+                .find(|locale| Some(*locale) == language),
+        )
+    }
+}
 
-                let (tx, rx) = watch::channel(Progress::default());
+impl fmt::Display for LanguageChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            None => write!(f, "{}", t!("settings.language.system")),
+            Some(locale) => {
+                let name = LANGUAGES
+                    .iter()
+                    .find(|(l, _)| *l == locale)
+                    .map_or(locale, |(_, name)| name);
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
 
-                self.stage = LibreCardAppStage::Copying {
-                    progress: Progress::default(),
-                    rx,
-                };
+/// Entry of the copy order picker, labelled in the UI language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CopyOrderChoice(CopyOrder);
 
-                // Task to perform the copy operation
-                Task::perform(
-                    async move {
-                        match copy_dirs(&source, &destinations, tx).await {
-                            Ok(bytes) => LibreCardMessage::CopyCompleted(Ok(bytes)),
-                            Err(e) => LibreCardMessage::CopyCompleted(Err(e.to_string())),
-                        }
-                    },
-                    |msg| msg,
-                )
-            }
+impl fmt::Display for CopyOrderChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.0 {
+            CopyOrder::Path => t!("settings.copy_order.path"),
+            CopyOrder::LargestFirst => t!("settings.copy_order.largest_first"),
+            CopyOrder::SmallestFirst => t!("settings.copy_order.smallest_first"),
+        };
+        write!(f, "{}", label)
+    }
+}
 
-            LibreCardMessage::StartChecksum => {
-                let source = self.source_directory.clone().unwrap();
-                let destinations: Vec<PathBuf> = self
-                    .destination_directories
-                    .iter()
-                    .filter_map(|opt| opt.clone())
-                    .collect();
+/// Entry of the overwrite policy picker, labelled in the UI language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverwritePolicyChoice(OverwritePolicy);
 
-                // Get list of files to checksum
-                match flatten_dir_files(&source) {
-                    Ok(files) => {
-                        let (tx, rx) = watch::channel(Progress::default());
+/// Entry of the layout density picker, labelled in the UI language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LayoutDensityChoice(LayoutDensity);
 
-                        self.stage = LibreCardAppStage::Checksumming {
-                            progress: Progress::default(),
-                            rx,
-                        };
+impl fmt::Display for LayoutDensityChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.0 {
+            LayoutDensity::Auto => t!("settings.layout.auto"),
+            LayoutDensity::Regular => t!("settings.layout.regular"),
+            LayoutDensity::Compact => t!("settings.layout.compact"),
+        };
+        write!(f, "{}", label)
+    }
+}
 
-                        // Task to perform the checksum operation
-                        Task::perform(
-                            async move {
-                                match hash_dirs(&source, &destinations, &files, tx).await {
-                                    Ok(report) => LibreCardMessage::ChecksumCompleted(Ok(report)),
-                                    Err(e) => {
-                                        LibreCardMessage::ChecksumCompleted(Err(e.to_string()))
-                                    }
-                                }
-                            },
-                            |msg| msg,
-                        )
-                    }
-                    Err(e) => {
-                        self.error_message = Some(format!("Failed to list files: {}", e));
-                        Task::none()
-                    }
-                }
-            }
+impl fmt::Display for OverwritePolicyChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.0 {
+            OverwritePolicy::Overwrite => t!("settings.overwrite.overwrite"),
+            OverwritePolicy::Skip => t!("settings.overwrite.skip"),
+            OverwritePolicy::SkipVerified => t!("settings.overwrite.skip_verified"),
+            OverwritePolicy::Ask => t!("settings.overwrite.ask"),
+        };
+        write!(f, "{}", label)
+    }
+}
 
-            LibreCardMessage::ChecksumCompleted(result) => {
-                match result {
-                    Ok(report) => {
-                        self.stage = LibreCardAppStage::ChecksumComplete { report };
-                    }
-                    Err(error) => {
-                        self.stage = LibreCardAppStage::Input;
-                        self.error_message = Some(error);
-                    }
-                }
-                Task::none()
-            }
+/// Entry of the timestamp format picker, labelled in the UI language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimestampStyleChoice(TimestampStyle);
 
-            LibreCardMessage::ExportChecksum => {
-                if let LibreCardAppStage::ChecksumComplete { ref report } = self.stage {
-                    let report_clone = report.clone();
+impl fmt::Display for TimestampStyleChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.0 {
+            TimestampStyle::Iso8601 => t!("settings.timestamp_format.iso_8601"),
+            TimestampStyle::Us => t!("settings.timestamp_format.us"),
+            TimestampStyle::European => t!("settings.timestamp_format.european"),
+            TimestampStyle::Custom => t!("settings.timestamp_format.custom"),
+        };
+        write!(f, "{}", label)
+    }
+}
 
-                    Task::perform(
-                        async move {
-                            if let Some(path) = FileDialog::new()
-                                .add_filter("CSV", &["csv"])
-                                .set_file_name("checksum_report.csv")
-                                .save_file()
-                            {
-                                match report_clone.export_report(path) {
-                                    Ok(()) => LibreCardMessage::ExportCompleted(Ok(())),
-                                    Err(err) => {
-                                        LibreCardMessage::ExportCompleted(Err(err.to_string()))
-                                    }
-                                }
-                            } else {
-                                LibreCardMessage::ExportCompleted(Ok(()))
-                            }
-                        },
-                        |msg| msg,
-                    )
-                } else {
-                    Task::none()
-                }
-            }
+/// Entry of the console's level filter, labelled in the UI language. Events below the chosen
+/// level are hidden.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConsoleLevelChoice(Level);
 
-            LibreCardMessage::ExportCompleted(result) => {
-                if let Err(error) = result {
-                    self.error_message = Some(format!("Failed to export report: {}", error));
-                }
-                Task::none()
-            }
+impl ConsoleLevelChoice {
+    const ALL: [ConsoleLevelChoice; 4] = [
+        ConsoleLevelChoice(Level::ERROR),
+        ConsoleLevelChoice(Level::WARN),
+        ConsoleLevelChoice(Level::INFO),
+        ConsoleLevelChoice(Level::DEBUG),
+    ];
+}
 
-            LibreCardMessage::DismissError => {
-                self.error_message = None;
-                Task::none()
-            }
-        }
+impl Default for ConsoleLevelChoice {
+    fn default() -> Self {
+        ConsoleLevelChoice(Level::DEBUG)
     }
+}
 
-    pub fn view(&self) -> Element<LibreCardMessage> {
-        let content = match &self.stage {
-            LibreCardAppStage::Input => self.view_input_stage(),
-            LibreCardAppStage::Copying { progress, .. } => self.view_copy_stage(progress),
-            LibreCardAppStage::CopyComplete { total_bytes_copied } => {
-                self.view_copy_complete_stage(*total_bytes_copied)
-            }
-            LibreCardAppStage::Checksumming { progress, .. } => self.view_checksum_stage(progress),
-            LibreCardAppStage::ChecksumComplete { report } => {
-                self.view_checksum_complete_stage(report)
-            }
+impl fmt::Display for ConsoleLevelChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.0 {
+            Level::ERROR => t!("console.level.error"),
+            Level::WARN => t!("console.level.warn"),
+            Level::INFO => t!("console.level.info"),
+            _ => t!("console.level.debug"),
         };
+        write!(f, "{}", label)
+    }
+}
 
-        let content: Element<LibreCardMessage> = if let Some(error) = &self.error_message {
-            column![
-                content,
-                container(
-                    column![
-                        text(error).color(Color::from_rgb(0.9, 0.0, 0.0)),
-                        button(text("Dismiss")).on_press(LibreCardMessage::DismissError),
-                    ]
-                    .spacing(10)
-                )
-                .width(Length::Fill)
-                .padding(20)
-            ]
-            .spacing(20)
-            .into()
-        } else {
-            content
-        };
+/// Entry of the verification method picker, labelled in the UI language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyMethodChoice(VerifyMethod);
 
-        container(content)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .padding(20)
-            .into()
+impl fmt::Display for VerifyMethodChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.0 {
+            VerifyMethod::ReadBack => t!("settings.verify_method.read_back"),
+            VerifyMethod::Stream => t!("settings.verify_method.stream"),
+            VerifyMethod::Handle => t!("settings.verify_method.handle"),
+        };
+        write!(f, "{}", label)
     }
+}
 
-    pub fn subscription(&self) -> Subscription<LibreCardMessage> {
-        match &self.stage {
-            LibreCardAppStage::Copying { .. } | LibreCardAppStage::Checksumming { .. } => {
-                time::every(Duration::from_millis(200)).map(|_| LibreCardMessage::Tick)
-            }
-            _ => Subscription::none(),
-        }
+/// The destination a catch-up copy reads from: the first that passed verification. `None` if
+/// none did, or verification didn't check every file.
+fn catch_up_origin(report: &ChecksumReport) -> Option<usize> {
+    if report.stopped_at_failure {
+        return None;
     }
+    report
+        .per_destination_summary()
+        .iter()
+        .position(|summary| summary.passed() && summary.verified > 0)
 }
 
-impl LibreCardApp {
-    fn view_input_stage(&self) -> Element<LibreCardMessage> {
-        let title = text("Choose Source & Destination")
-            .size(28)
-            .width(Length::Fill)
-            .align_x(iced::alignment::Horizontal::Center);
+/// Status bar summary of a finished verification.
+fn verify_result(report: &ChecksumReport) -> (Status, String) {
+    let failed = report.count_errors();
+    let status = if failed == 0 {
+        Status::Success
+    } else {
+        Status::Failure
+    };
+    let message = t!(
+        "status_bar.verified",
+        count = format_count(report.total_files()),
+        failed = format_count(failed)
+    );
+    (status, message.into_owned())
+}
 
-        // Source directory
-        let source_path = self
-            .source_directory
-            .as_ref()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|| "No directory selected".to_string());
+/// The cached listing of `source`, or a fresh scan of it no more than `max_depth` folders deep. A
+/// scan cut short by `stop` returns what it found, leaving the job to notice the cancellation.
+async fn listing_or_scan(
+    source: &Path,
+    listing: Option<SourceListing>,
+    max_depth: Option<usize>,
+    stop: &CancellationToken,
+) -> io::Result<SourceListing> {
+    let limits = ScanLimits {
+        max_depth,
+        max_files: None,
+    };
+    match listing {
+        Some(listing) => Ok(listing),
+        None => match scan_source(source, limits, stop).await? {
+            JobOutcome::Completed(listing) | JobOutcome::Cancelled(listing) => Ok(listing),
+        },
+    }
+}
 
-        let source_row = row![
-            text("Source Directory:").width(Length::FillPortion(1)),
-            text_input("", &source_path)
-                .padding(10)
-                .width(Length::FillPortion(3)),
-            button("Browse").on_press(LibreCardMessage::OpenSourceDirectoryDialog),
-        ]
-        .spacing(10)
-        .align_y(iced::alignment::Alignment::Center);
+/// Write rate of each destination over the last few progress updates.
+#[derive(Debug, Default)]
+struct DestinationRates {
+    sampled: Option<(Instant, Vec<u64>)>,
+    per_second: Vec<f64>,
+}
 
-        // Destination directories
-        let mut destination_rows = Vec::new();
-        for (idx, dest_opt) in self.destination_directories.iter().enumerate() {
-            let dest_path = dest_opt
-                .as_ref()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| "No directory selected".to_string());
+impl DestinationRates {
+    /// Rates are measured over at least this long, as progress comes per file.
+    const MIN_INTERVAL: Duration = Duration::from_secs(1);
 
-            let mut row_elements = vec![
-                text(format!("Destination {}:", idx + 1))
-                    .width(Length::FillPortion(1))
-                    .into(),
-                text_input("", &dest_path)
-                    .padding(10)
-                    .width(Length::FillPortion(3))
-                    .into(),
-                button("Browse")
-                    .on_press(LibreCardMessage::OpenDestinationDirectoryDialog(idx))
-                    .into(),
-            ];
+    /// Folds the bytes written since the last sample into the rates. The previous rate keeps half
+    /// its weight, so one large or tiny file doesn't make the figure jump.
+    fn sample(&mut self, progress: &Progress) {
+        let now = Instant::now();
+        let written: Vec<u64> = progress
+            .destinations
+            .iter()
+            .map(|destination| destination.written_bytes)
+            .collect();
+        let Some((at, before)) = &self.sampled else {
+            self.per_second = vec![0.0; written.len()];
+            self.sampled = Some((now, written));
+            return;
+        };
+        let elapsed = now.duration_since(*at);
+        if elapsed < Self::MIN_INTERVAL {
+            return;
+        }
+        self.per_second.resize(written.len(), 0.0);
+        for ((rate, now), before) in self.per_second.iter_mut().zip(&written).zip(before) {
+            let recent = now.saturating_sub(*before) as f64 / elapsed.as_secs_f64();
+            *rate = (*rate + recent) / 2.0;
+        }
+        self.sampled = Some((now, written));
+    }
 
-            // Add remove button if more than one destination exists
-            if self.destination_directories.len() > 1 {
-                row_elements.push(
-                    button("Remove")
-                        .on_press(LibreCardMessage::RemoveDestinationDirectory(idx))
-                        .into(),
-                );
-            }
+    fn per_second(&self, index: usize) -> f64 {
+        self.per_second.get(index).copied().unwrap_or_default()
+    }
+}
 
-            destination_rows.push(
-                row(row_elements)
-                    .spacing(10)
-                    .align_y(iced::alignment::Alignment::Center),
-            );
+/// One line describing a job warning, for the warning feed.
+fn warning_text(warning: &JobWarning) -> String {
+    let path = warning.path.display();
+    match warning.kind {
+        WarningKind::Skipped(SkipReason::Excluded) => t!("warning.excluded", path = path),
+        WarningKind::Skipped(SkipReason::PermissionDenied) => {
+            t!("warning.permission_denied", path = path)
         }
+        WarningKind::Skipped(SkipReason::Placeholder) => t!("warning.placeholder", path = path),
+        WarningKind::Skipped(SkipReason::CopyFailed) => t!("warning.copy_failed", path = path),
+        WarningKind::Pruned => t!("warning.pruned", path = path),
+        WarningKind::AddedToSource => t!("warning.added_to_source", path = path),
+        WarningKind::RemovedFromSource => t!("warning.removed_from_source", path = path),
+        WarningKind::ResizedOnSource => t!("warning.resized_on_source", path = path),
+        WarningKind::ReplacedOtherSize => t!("warning.replaced_other_size", path = path),
+        WarningKind::ReplacedOtherContent => t!("warning.replaced_other_content", path = path),
+        WarningKind::ChangedDuringCopy => t!("warning.changed", path = path),
+        WarningKind::UnreliableRead => t!("warning.unreliable", path = path),
+        WarningKind::Reconnected => t!("warning.reconnected", path = path),
+        WarningKind::ReadRetried { offset, attempts } => t!(
+            "warning.read_retried",
+            path = path,
+            offset = format_count(offset as usize),
+            attempts = attempts
+        ),
+        WarningKind::Salvaged { bytes_lost } => t!(
+            "warning.salvaged",
+            path = path,
+            lost = format_bytes(bytes_lost)
+        ),
+        WarningKind::OwnershipNotKept => t!("warning.ownership", path = path),
+        WarningKind::TimestampNotKept => t!("warning.timestamp", path = path),
+        WarningKind::VerifyFailed => t!("warning.verify_failed", path = path),
+        WarningKind::SlowTransfer {
+            bytes_per_sec,
+            median_bytes_per_sec,
+        } => t!(
+            "warning.slow_transfer",
+            path = path,
+            rate = format_bytes(bytes_per_sec),
+            median = format_bytes(median_bytes_per_sec)
+        ),
+        WarningKind::UnstableMedia { destination } => t!(
+            "warning.unstable_media",
+            path = path,
+            destination = destination + 1
+        ),
+        WarningKind::DestinationFailed { destination } => t!(
+            "warning.destination_failed",
+            path = path,
+            destination = destination + 1
+        ),
+    }
+    .into_owned()
+}
 
-        // Add destination button
-        let add_button =
-            button("Add Destination Directory").on_press(LibreCardMessage::AddDestinationDirectory);
+/// A feed line: the warning with the time it happened.
+fn timed_warning_text(warning: &JobWarning) -> String {
+    format!("{}  {}", format_clock(warning.at), warning_text(warning))
+}
 
-        // Start copy button - only enabled if we have valid source and at least one destination
-        let is_valid_input = self.source_directory.is_some()
-            && self.destination_directories.iter().any(|d| d.is_some());
+/// Checks in the background whether a destination path can be reached.
+fn check_destination(path: PathBuf) -> Task<LibreCardMessage> {
+    Task::batch([
+        find_markers(path.clone()),
+        Task::perform(
+            async move {
+                let reachability = check_reachable(&path).await;
+                (path, reachability)
+            },
+            |(path, reachability)| LibreCardMessage::DestinationChecked(path, reachability),
+        ),
+    ])
+}
 
-        let start_button = button(text("Start Copy").size(20))
-            .width(Length::Fill)
-            .padding(15);
+/// Looks for the markers earlier offloads left in a destination.
+fn find_markers(path: PathBuf) -> Task<LibreCardMessage> {
+    Task::perform(
+        async move {
+            let search = path.clone();
+            let markers = tokio::task::spawn_blocking(move || find_offload_markers(&search))
+                .await
+                .unwrap_or_default();
+            (path, markers)
+        },
+        |(path, markers)| LibreCardMessage::DestinationMarkersFound(path, markers),
+    )
+}
 
-        let start_button = if is_valid_input {
-            start_button.on_press(LibreCardMessage::StartCopy)
-        } else {
-            start_button
-        };
+/// A destination of a job and the files it receives.
+type JobDestination = (PathBuf, FileFilter);
 
-        // Assemble everything
-        let mut content = column![title, source_row].spacing(20);
+/// A destination row of the input stage.
+#[derive(Clone, Debug)]
+struct Destination {
+    path: Option<PathBuf>,
+    /// The path as typed, which the field keeps showing while `path` holds what it expands to.
+    typed: Option<String>,
+    /// Name to tell the row apart by, e.g. `Shuttle A`; the row number is shown without one.
+    label: String,
+    /// Folder below `path` that mirrors the source root.
+    subroot: String,
+    /// File types to copy, e.g. `jpg`; empty copies everything.
+    filter: String,
+    /// A disabled row is kept, but jobs leave it out.
+    enabled: bool,
+}
 
-        for row in destination_rows {
-            content = content.push(row);
+impl Default for Destination {
+    fn default() -> Self {
+        Destination {
+            path: None,
+            typed: None,
+            label: String::new(),
+            subroot: String::new(),
+            filter: String::new(),
+            enabled: true,
         }
+    }
+}
 
-        content = content
+impl Destination {
+    /// Where a job would copy to, if the row is used for jobs.
+    fn job_path(&self) -> Option<PathBuf> {
+        let path = self.path.as_ref().filter(|_| self.enabled)?;
+        Some(join_subroot(path, &self.subroot))
+    }
+}
+
+impl LibreCardApp {
+    pub fn new(config: Config) -> Self {
+        apply_language(config.language.as_deref());
+        let stage = match CrashReport::load() {
+            Some(report) => LibreCardAppStage::Recovery(Box::new(report)),
+            None => LibreCardAppStage::Input,
+        };
+        // Folders of drives that have since been unplugged or moved are left out
+        let source_directory = config.last_source.clone().filter(|source| source.is_dir());
+        let destinations = config
+            .last_destinations
+            .iter()
+            .filter(|path| match DestinationKind::of(path) {
+                DestinationKind::Folder => path.is_dir(),
+                _ => path.parent().is_some_and(Path::is_dir),
+            })
+            .map(|path| Destination {
+                path: Some(path.clone()),
+                ..Default::default()
+            })
+            .collect();
+        LibreCardApp {
+            stage,
+            status_port_input: config.status_port().to_string(),
+            source_directory,
+            destinations,
+            config,
+            last_job: LastJob::load(),
+            job_history: JobHistory::load(),
+            ..Default::default()
+        }
+    }
+
+    /// Scans the source and checks the destinations filled in from the last job, as picking them
+    /// would.
+    pub fn check_restored_directories(&mut self) -> Task<LibreCardMessage> {
+        let checks: Vec<_> = self
+            .destinations
+            .iter()
+            .filter_map(|destination| destination.path.clone())
+            .map(check_destination)
+            .collect();
+        Task::batch([self.scan_source(), Task::batch(checks)])
+    }
+
+    /// Shows a completed verification and keeps its report for the next start.
+    fn finish_verification(&mut self, mut report: ChecksumReport) -> Task<LibreCardMessage> {
+        report.disabled_destinations = self.job_disabled.clone();
+        report.job_id = self.job_id;
+        self.record_job(RecordedOutcome::Verified {
+            files: report.total_files(),
+            failed: report.count_errors(),
+            level: Some(report.verification_level()),
+        });
+        self.show_verification(report)
+    }
+
+    /// The badge for how far a job's copies were checked.
+    fn view_verification_level(&self, level: VerificationLevel) -> iced::widget::Text<'_> {
+        let (status, key) = match level {
+            VerificationLevel::Copy => (Status::Warning, "verification_level.copy"),
+            VerificationLevel::Archive => (Status::Success, "verification_level.archive"),
+        };
+        status_text(status, t!(key), self.config.high_contrast)
+    }
+
+    /// Shows a verification report, keeps it for the next start and marks the destinations if it
+    /// passed. Verified copies are made read-only if the settings ask for it.
+    fn show_verification(&mut self, report: ChecksumReport) -> Task<LibreCardMessage> {
+        self.last_result = Some(verify_result(&report));
+        self.expanded_missing = None;
+        self.expanded_group = None;
+        let last_job = LastJob {
+            destinations: self.job_destinations.clone(),
+            report: report.clone(),
+        };
+        self.last_job = Some(last_job.clone());
+        // Only an offload that checked out is worth recognizing later
+        let passed =
+            (report.count_errors() == 0 && !report.stopped_at_failure).then(|| report.clone());
+        self.stage = LibreCardAppStage::ChecksumComplete {
+            fingerprint: report.fingerprint(),
+            manifest_digest: report.manifest_digest(),
+            report,
+        };
+        let save = Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || last_job.save())
+                    .await
+                    .unwrap_or_else(|e| Err(e.into()))
+                    .map_err(|e| e.to_string())
+            },
+            LibreCardMessage::LastJobSaved,
+        );
+        let protect = match &self.stage {
+            LibreCardAppStage::ChecksumComplete { report, .. }
+                if self.config.read_only_verified =>
+            {
+                let report = report.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || protect_verified(&report))
+                            .await
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|(path, e)| (path, e.to_string()))
+                            .collect()
+                    },
+                    LibreCardMessage::VerifiedProtected,
+                )
+            }
+            _ => Task::none(),
+        };
+        let Some(report) = passed else {
+            return Task::batch([save, protect]);
+        };
+        let mark = Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || write_offload_markers(&report))
+                    .await
+                    .unwrap_or_else(|e| Err(e.into()))
+                    .map_err(|e| e.to_string())
+            },
+            LibreCardMessage::OffloadMarkersWritten,
+        );
+        Task::batch([save, protect, mark])
+    }
+
+    /// Adds the current job to the history, or updates its outcome once it is verified.
+    fn record_job(&mut self, outcome: RecordedOutcome) {
+        let Some(source) = self.source_directory.clone() else {
+            return;
+        };
+        match self.job_history.jobs.first_mut() {
+            Some(latest) if self.job_recorded => {
+                latest.outcome = outcome;
+                latest.finished_at = SystemTime::now();
+            }
+            _ => {
+                let destinations = self
+                    .destinations
+                    .iter()
+                    .filter_map(|destination| {
+                        Some(RecordedDestination {
+                            directory: destination.path.clone()?,
+                            label: destination.label.clone(),
+                            subroot: destination.subroot.clone(),
+                            filter: destination.filter.clone(),
+                            enabled: destination.enabled,
+                        })
+                    })
+                    .collect();
+                self.job_history.record(JobRecord {
+                    job_id: self.job_id,
+                    source,
+                    destinations,
+                    separate_sessions: self.config.separate_sessions,
+                    outcome,
+                    finished_at: SystemTime::now(),
+                });
+                self.job_recorded = true;
+            }
+        }
+        if let Err(e) = self.job_history.save() {
+            self.warning_message = Some(t!("history.save_error", error = e).into_owned());
+        }
+    }
+
+    /// Looks for a newer release in the background, if the user agreed to it.
+    pub fn check_for_update(&self) -> Task<LibreCardMessage> {
+        if self.config.check_for_updates == Some(true) {
+            Task::perform(newer_release(), LibreCardMessage::UpdateChecked)
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Starts the status server if the settings ask for one, stopping the one running before.
+    pub fn restart_status_server(&mut self) -> Task<LibreCardMessage> {
+        self.status_server = None;
+        if !self.config.serve_status {
+            return Task::none();
+        }
+        let port = self.config.status_port();
+        let (server, serving) = StatusServer::new(port);
+        server.publish(self.job_status());
+        self.status_server = Some(server);
+        Task::perform(
+            async move { serving.await.map_err(|e| e.to_string()) },
+            move |result| LibreCardMessage::StatusServerStopped(port, result),
+        )
+    }
+
+    /// What the status server reports: the running job, read from the same progress the views
+    /// show, and how the last one ended.
+    fn job_status(&self) -> JobStatus {
+        let last_report = self
+            .last_result
+            .as_ref()
+            .map(|(status, message)| ReportSummary {
+                outcome: match status {
+                    Status::Success => ReportOutcome::Success,
+                    Status::Warning => ReportOutcome::Warning,
+                    Status::Failure => ReportOutcome::Failure,
+                },
+                message: message.clone(),
+            });
+        let (stage, progress, started_at, rates) = match &self.stage {
+            LibreCardAppStage::Input
+            | LibreCardAppStage::Settings
+            | LibreCardAppStage::Recovery(_) => (JobStage::Idle, None, None, None),
+            LibreCardAppStage::Copying {
+                progress,
+                started_at,
+                rates,
+                ..
+            } => {
+                let stage = match progress.phase {
+                    JobPhase::Scanning => JobStage::Scanning,
+                    JobPhase::Running => JobStage::Copying,
+                    JobPhase::Finalizing => JobStage::Finalizing,
+                };
+                (stage, Some(progress), Some(*started_at), Some(rates))
+            }
+            LibreCardAppStage::Checksumming {
+                progress,
+                started_at,
+                ..
+            } => (JobStage::Verifying, Some(progress), Some(*started_at), None),
+            LibreCardAppStage::HashFile { progress, .. } => {
+                (JobStage::Hashing, Some(progress), None, None)
+            }
+            _ => (JobStage::Finished, None, None, None),
+        };
+        let Some(progress) = progress else {
+            return JobStatus {
+                stage,
+                job_id: self.job_id.filter(|_| stage != JobStage::Idle),
+                last_report,
+                ..Default::default()
+            };
+        };
+
+        let destinations = progress
+            .destinations
+            .iter()
+            .enumerate()
+            .map(|(index, destination)| {
+                let (state, error) = match &destination.state {
+                    DestinationState::Writing => (DestinationStage::Writing, None),
+                    DestinationState::Finalizing => (DestinationStage::Finalizing, None),
+                    DestinationState::Done => (DestinationStage::Done, None),
+                    DestinationState::Failed(error) => {
+                        (DestinationStage::Failed, Some(error.clone()))
+                    }
+                };
+                DestinationStatus {
+                    path: self.job_destinations.get(index).cloned(),
+                    state,
+                    error,
+                    bytes_written: destination.written_bytes,
+                    bytes_total: destination.total_bytes,
+                    files_pending: destination.pending_files,
+                    bytes_per_second: rates.map_or(0.0, |rates| rates.per_second(index)),
+                }
+            })
+            .collect();
+        let bytes_per_second = started_at.map_or(0.0, |started_at| {
+            progress.done_bytes() as f64 / started_at.elapsed().as_secs_f64().max(1.0)
+        });
+        JobStatus {
+            stage,
+            job_id: self.job_id,
+            dry_run: self.dry_run && matches!(stage, JobStage::Scanning | JobStage::Copying),
+            files_done: progress.completed_files,
+            files_total: progress.total_files,
+            bytes_done: progress.done_bytes(),
+            bytes_total: progress.total_bytes,
+            bytes_per_second,
+            current_file: progress.current_file.clone(),
+            destinations,
+            last_report,
+        }
+    }
+
+    /// Lists the newly selected source in the background, abandoning the scan of the previous one.
+    fn scan_source(&mut self) -> Task<LibreCardMessage> {
+        if let Some(stop) = self.source_scan.take() {
+            stop.cancel();
+        }
+        self.source_listing = None;
+        let Some(source) = self.source_directory.clone() else {
+            return Task::none();
+        };
+
+        let mut limits = self.config.scan_limits();
+        if self.uncapped_source.as_ref() == Some(&source) {
+            limits.max_files = None;
+        }
+        let stop = CancellationToken::new();
+        self.source_scan = Some(stop.clone());
+        Task::perform(
+            async move {
+                let result = scan_source(&source, limits, &stop)
+                    .await
+                    .map_err(|e| t!("src_folder.scan_error", error = e).into_owned());
+                (source, result)
+            },
+            |(source, result)| LibreCardMessage::SourceScanned(source, result),
+        )
+    }
+
+    /// Hashes one file with the selected algorithm, on its own stage.
+    fn hash_file(&mut self, path: PathBuf) -> Task<LibreCardMessage> {
+        let options = self.config.hash_options();
+        let (tx, rx) = watch::channel(Progress::default());
+        let stop = CancellationToken::new();
+        self.job_serial += 1;
+        self.stage = LibreCardAppStage::HashFile {
+            path: path.clone(),
+            algorithm: options.algorithm,
+            progress: Progress::default(),
+            rx,
+            stop: stop.clone(),
+            digest: None,
+            expected: String::new(),
+        };
+        Task::perform(
+            async move {
+                let size = tokio::fs::metadata(&path).await.map_or(0, |m| m.len());
+                tx.send_modify(|progress| {
+                    progress.total_files = 1;
+                    progress.total_bytes = size;
+                    progress.current_file_size = size;
+                });
+                let on_progress = move |bytes| {
+                    tx.send_modify(|progress| progress.current_file_bytes = bytes);
+                    !stop.is_cancelled()
+                };
+                let result = compute_file_hash(&path, options, on_progress)
+                    .await
+                    .map_err(|e| t!("hash_file.error", error = e).into_owned());
+                LibreCardMessage::FileHashed(path, result)
+            },
+            |msg| msg,
+        )
+    }
+
+    /// The listing of the current source, if it has been scanned.
+    fn cached_listing(&self) -> Option<SourceListing> {
+        match &self.source_listing {
+            Some((source, Ok(listing))) if Some(source) == self.source_directory.as_ref() => {
+                Some(listing.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Selected destinations with their sub-roots applied; rows without a directory or that are
+    /// disabled are skipped.
+    fn resolved_destinations(&self) -> Vec<PathBuf> {
+        self.destinations
+            .iter()
+            .filter_map(Destination::job_path)
+            .collect()
+    }
+
+    /// What to call the running job's destination at `index`: the label of its row, or else its
+    /// path.
+    fn job_destination_name(&self, index: usize) -> String {
+        let Some(path) = self.job_destinations.get(index) else {
+            return t!("dst_folder", index = index + 1).into_owned();
+        };
+        // A session folder may have been added below the row's path
+        self.destinations
+            .iter()
+            .filter(|destination| !destination.label.is_empty())
+            .find(|destination| {
+                destination
+                    .job_path()
+                    .is_some_and(|root| path.starts_with(root))
+            })
+            .map_or_else(
+                || path.display().to_string(),
+                |destination| destination.label.clone(),
+            )
+    }
+
+    /// Disabled rows that have a directory, with their sub-roots applied.
+    fn disabled_destinations(&self) -> Vec<PathBuf> {
+        self.destinations
+            .iter()
+            .filter(|destination| !destination.enabled)
+            .filter_map(|destination| {
+                let path = destination.path.as_ref()?;
+                Some(join_subroot(path, &destination.subroot))
+            })
+            .collect()
+    }
+
+    /// Row numbers of the enabled destination rows that have no folder.
+    fn empty_destination_rows(&self) -> Vec<usize> {
+        self.destinations
+            .iter()
+            .enumerate()
+            .filter(|(_, destination)| destination.enabled && destination.path.is_none())
+            .map(|(idx, _)| idx + 1)
+            .collect()
+    }
+
+    /// Destinations for a job with their filters, without rows that resolve to the same location
+    /// as an earlier row. Also returns the skipped rows as `(earlier, duplicate)` row numbers.
+    fn unique_destinations(&self) -> (Vec<JobDestination>, Vec<(usize, usize)>) {
+        let (rows, paths): (Vec<usize>, Vec<PathBuf>) = self
+            .destinations
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, destination)| Some((idx + 1, destination.job_path()?)))
+            .unzip();
+
+        let duplicates = find_duplicate_destinations(&paths);
+        let unique = paths
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !duplicates.iter().any(|(_, duplicate)| duplicate == i))
+            .map(|(i, path)| {
+                let filter = self
+                    .destinations
+                    .get(rows[i] - 1)
+                    .map_or_else(FileFilter::default, |destination| {
+                        FileFilter::parse(&destination.filter)
+                    });
+                (path, filter)
+            })
+            .collect();
+        let duplicate_rows = duplicates
+            .into_iter()
+            .map(|(earlier, duplicate)| (rows[earlier], rows[duplicate]))
+            .collect();
+        (unique, duplicate_rows)
+    }
+
+    fn save_config(&mut self) {
+        if let Err(e) = self.config.save() {
+            self.error_message = Some(t!("settings.save_error", error = e).into_owned());
+        }
+    }
+
+    pub fn update(&mut self, message: LibreCardMessage) -> Task<LibreCardMessage> {
+        let task = self.handle_message(message);
+        if let Some(server) = &self.status_server {
+            server.publish(self.job_status());
+        }
+        // What to recover should the app panic before the next message
+        crash::track(
+            matches!(
+                self.stage,
+                LibreCardAppStage::Copying { .. } | LibreCardAppStage::Checksumming { .. }
+            )
+            .then(|| (self.source_directory.clone(), self.job_status())),
+        );
+        task
+    }
+
+    fn handle_message(&mut self, message: LibreCardMessage) -> Task<LibreCardMessage> {
+        match message {
+            LibreCardMessage::ProgressUpdated(update) => {
+                match &mut self.stage {
+                    LibreCardAppStage::Copying {
+                        progress, rates, ..
+                    } => {
+                        rates.sample(&update);
+                        *progress = update;
+                    }
+                    LibreCardAppStage::Checksumming { progress, .. }
+                    | LibreCardAppStage::HashFile { progress, .. } => *progress = update,
+                    _ => {}
+                }
+                Task::none()
+            }
+
+            // Only redraws the elapsed time
+            LibreCardMessage::Tick => Task::none(),
+
+            LibreCardMessage::CopyCompleted(result) => {
+                self.permission_prompt = None;
+                self.space_prompt = None;
+                if self.quit_after_job {
+                    return self.quit();
+                }
+                match result {
+                    // Straight back to the job, to fix what was wrong with it and start again
+                    JobEnd::Cancelled(summary) => {
+                        let total = self.current_progress().total_files;
+                        self.warning_message = Some(
+                            t!(
+                                "copying.cancelled",
+                                completed = format_count(summary.files.len()),
+                                total = format_count(total)
+                            )
+                            .into_owned(),
+                        );
+                        self.record_job(RecordedOutcome::Cancelled {
+                            completed: summary.files.len(),
+                            total,
+                            verifying: false,
+                        });
+                        self.last_result =
+                            Some((Status::Warning, t!("status_bar.cancelled").into_owned()));
+                        self.auto_verify = false;
+                        return self.update(LibreCardMessage::BackToInput);
+                    }
+                    // Nothing to verify or to run again on the next card
+                    JobEnd::Completed(summary) | JobEnd::CompletedWithWarnings(summary)
+                        if summary.dry_run =>
+                    {
+                        self.last_result = Some((
+                            Status::Success,
+                            t!(
+                                "status_bar.dry_run",
+                                count = format_count(summary.files.len())
+                            )
+                            .into_owned(),
+                        ));
+                        self.auto_verify = false;
+                        self.stage = LibreCardAppStage::CopyComplete { summary };
+                    }
+                    JobEnd::Completed(mut summary) | JobEnd::CompletedWithWarnings(mut summary) => {
+                        self.total_bytes_copied = Some(summary.total_bytes);
+                        self.last_result = Some(match summary.failed_files() {
+                            0 => (
+                                Status::Success,
+                                t!(
+                                    "status_bar.copied",
+                                    count = format_count(summary.files.len())
+                                )
+                                .into_owned(),
+                            ),
+                            failed => (
+                                Status::Failure,
+                                t!(
+                                    "status_bar.copied_failed",
+                                    count = format_count(summary.files.len() - failed),
+                                    failed = format_count(failed)
+                                )
+                                .into_owned(),
+                            ),
+                        });
+                        // The listing was asked for, so its absence is worth pointing out
+                        if self.config.record_volume_listing && summary.volume_listing.is_none() {
+                            self.warning_message = Some(t!("volume_listing.failed").into_owned());
+                        }
+                        if let Some(mut report) = summary.report.take() {
+                            // Every file was verified as it landed
+                            self.auto_verify = false;
+                            report.volume_listing = summary.volume_listing;
+                            return self.finish_verification(report);
+                        }
+                        self.record_job(RecordedOutcome::Copied {
+                            files: summary.files.len(),
+                        });
+                        self.stage = LibreCardAppStage::CopyComplete { summary };
+                        if self.auto_verify {
+                            return Task::done(LibreCardMessage::StartChecksum);
+                        }
+                    }
+                    JobEnd::Failed(error) => {
+                        self.stage = LibreCardAppStage::Input;
+                        tracing::error!("Job failed: {error}");
+                        self.error_message = Some(error);
+                        self.last_result =
+                            Some((Status::Failure, t!("status_bar.failed").into_owned()));
+                        self.auto_verify = false;
+                    }
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::OpenSourceDirectoryDialog => {
+                let dir = FileDialog::new().pick_folder();
+                self.source_directory = dir;
+                self.scan_source()
+            }
+
+            LibreCardMessage::SourceScanned(source, result) => {
+                // A scan of an earlier selection that finished before it saw the cancellation
+                if self.source_directory.as_ref() != Some(&source) {
+                    return Task::none();
+                }
+                self.source_scan = None;
+                match result {
+                    Ok(JobOutcome::Completed(listing)) => {
+                        self.source_listing = Some((source, Ok(listing)));
+                    }
+                    Ok(JobOutcome::Cancelled(_)) => {}
+                    Err(error) => self.source_listing = Some((source, Err(error))),
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ScanWholeSource => {
+                self.uncapped_source = self.source_directory.clone();
+                self.scan_source()
+            }
+
+            LibreCardMessage::OpenDestinationDirectoryDialog(index) => {
+                let Some(destination) = self.destinations.get_mut(index) else {
+                    return Task::none();
+                };
+                destination.path = FileDialog::new().pick_folder();
+                destination.typed = None;
+                match &destination.path {
+                    Some(path) => check_destination(path.clone()),
+                    None => Task::none(),
+                }
+            }
+
+            LibreCardMessage::DestinationPathChanged(index, path) => {
+                let Some(destination) = self.destinations.get_mut(index) else {
+                    return Task::none();
+                };
+                // Typed paths may be shares, e.g. `\\nas\archive`, with no drive letter mapped
+                destination.path = (!path.trim().is_empty()).then(|| expand_typed(&path));
+                destination.typed = Some(path);
+                match &destination.path {
+                    Some(path) => check_destination(path.clone()),
+                    None => Task::none(),
+                }
+            }
+
+            LibreCardMessage::DestinationChecked(path, reachability) => {
+                self.destination_reachability.insert(path, reachability);
+                Task::none()
+            }
+
+            LibreCardMessage::DestinationMarkersFound(path, markers) => {
+                self.destination_markers.insert(path, markers);
+                Task::none()
+            }
+
+            LibreCardMessage::AddArchiveDestination => {
+                let Some(path) = FileDialog::new()
+                    .add_filter("ZIP", &["zip"])
+                    .add_filter("TAR", &["tar"])
+                    .save_file()
+                else {
+                    return Task::none();
+                };
+                self.destinations.push(Destination {
+                    path: Some(path.clone()),
+                    ..Default::default()
+                });
+                Task::batch([
+                    check_destination(path),
+                    scrollable::snap_to(
+                        scrollable::Id::new(DESTINATION_LIST_ID),
+                        scrollable::RelativeOffset::END,
+                    ),
+                ])
+            }
+
+            LibreCardMessage::AddDestinationDirectory => {
+                self.destinations.push(Destination::default());
+                // Straight to typing the path of the new row
+                let index = self.destinations.len() - 1;
+                Task::batch([
+                    text_input::focus(destination_input_id(index)),
+                    scrollable::snap_to(
+                        scrollable::Id::new(DESTINATION_LIST_ID),
+                        scrollable::RelativeOffset::END,
+                    ),
+                ])
+            }
+
+            LibreCardMessage::RemoveDestinationDirectory(index) => {
+                if self.destinations.len() > 1 && index < self.destinations.len() {
+                    self.destinations.remove(index);
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::OpenSettings => {
+                self.stage = LibreCardAppStage::Settings;
+                Task::none()
+            }
+
+            LibreCardMessage::CloseSettings => {
+                self.stage = LibreCardAppStage::Input;
+                Task::none()
+            }
+
+            LibreCardMessage::LanguageSelected(choice) => {
+                self.config.language = choice.0.map(str::to_owned);
+                apply_language(choice.0);
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::CopyOrderSelected(choice) => {
+                self.config.copy_order = choice.0;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleVerifyWhileCopying(enabled) => {
+                self.config.verify_while_copying = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::VerifyMethodSelected(choice) => {
+                self.config.verify_method = choice.0;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::VerificationPassesSelected(passes) => {
+                self.config.verification_passes = Some(passes);
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::VerifyWorkersSelected(workers) => {
+                self.config.verify_workers = Some(workers);
+                self.save_config();
+                Task::none()
+            }
+
+            // Only for this job; the settings still start the next one
+            LibreCardMessage::HashWorkersChanged(workers) => {
+                if let LibreCardAppStage::Checksumming {
+                    workers: Some(sender),
+                    ..
+                } = &self.stage
+                {
+                    sender.send_replace(workers.clamp(1, MAX_VERIFY_WORKERS));
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleRecopyChanged(enabled) => {
+                self.config.recopy_changed_files = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleDoubleRead(enabled) => {
+                self.config.double_read_sources = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleSalvage(enabled) => {
+                self.config.salvage_unreadable = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleCheckSourceReadable(enabled) => {
+                self.config.check_source_readable = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleRecordVolumeListing(enabled) => {
+                self.config.record_volume_listing = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::TogglePreserveTimestamps(enabled) => {
+                self.config.preserve_timestamps = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleReadOnlyVerified(enabled) => {
+                self.config.read_only_verified = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleParallelHashing(enabled) => {
+                self.config.parallel_hashing = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::TogglePreserveOwnership(enabled) => {
+                self.config.preserve_ownership = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleSkipDuplicates(enabled) => {
+                self.config.skip_duplicates = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleWarnings => {
+                self.show_warnings = !self.show_warnings;
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleCompareBytes(enabled) => {
+                self.config.compare_bytes = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleCheckFolders(enabled) => {
+                self.config.check_folders = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleIgnoreSystemFiles(enabled) => {
+                self.config.ignore_system_files = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::IgnorePatternsChanged(patterns) => {
+                self.config.ignore_patterns = patterns;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleDryRun(enabled) => {
+                self.dry_run = enabled;
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleDryRunReadsSource(enabled) => {
+                self.config.dry_run_reads_source = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleStopOnFirstFailure(enabled) => {
+                self.config.stop_on_first_failure = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleHighContrast(enabled) => {
+                self.config.high_contrast = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::LayoutDensitySelected(choice) => {
+                self.config.layout_density = choice.0;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleDetails => {
+                self.show_details = !self.show_details;
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleUpdateCheck(enabled) => {
+                let asked = self.config.check_for_updates.is_some();
+                self.config.check_for_updates = Some(enabled);
+                self.save_config();
+                // Answering the question at startup checks right away
+                if asked {
+                    Task::none()
+                } else {
+                    self.check_for_update()
+                }
+            }
+
+            LibreCardMessage::ToggleServeStatus(enabled) => {
+                self.config.serve_status = enabled;
+                self.save_config();
+                self.restart_status_server()
+            }
+
+            // Only taken while the server is off, so half-typed ports are never bound
+            LibreCardMessage::StatusPortChanged(input) => {
+                if let Ok(port) = input.trim().parse::<u16>()
+                    && port != 0
+                {
+                    self.config.status_port = Some(port);
+                    self.save_config();
+                }
+                self.status_port_input = input;
+                Task::none()
+            }
+
+            LibreCardMessage::StatusServerStopped(port, result) => {
+                if let Err(error) = result {
+                    if self
+                        .status_server
+                        .as_ref()
+                        .is_some_and(|server| server.port() == port)
+                    {
+                        self.status_server = None;
+                    }
+                    self.error_message = Some(
+                        t!("settings.serve_status.error", port = port, error = error).into_owned(),
+                    );
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::UpdateChecked(release) => {
+                self.available_update = release;
+                Task::none()
+            }
+
+            LibreCardMessage::OpenReleasePage => {
+                if let Some(release) = &self.available_update
+                    && let Err(e) = open::that_detached(&release.url)
+                {
+                    self.error_message =
+                        Some(t!("update.open_error", url = release.url, error = e).into_owned());
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::DismissUpdate => {
+                self.available_update = None;
+                Task::none()
+            }
+
+            LibreCardMessage::HashAlgorithmSelected(algorithm) => {
+                self.config.hash_algorithm = algorithm;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::BenchmarkHashes { sample } => {
+                let sample = match sample {
+                    true => match FileDialog::new().pick_file() {
+                        Some(path) => Some(path),
+                        None => return Task::none(),
+                    },
+                    false => None,
+                };
+                self.benchmark_running = true;
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || benchmark_hashes(sample.as_deref()))
+                            .await
+                            .unwrap_or_else(|e| Err(e.into()))
+                            .map_err(|e| t!("settings.benchmark.error", error = e).into_owned())
+                    },
+                    LibreCardMessage::HashesBenchmarked,
+                )
+            }
+
+            LibreCardMessage::HashesBenchmarked(result) => {
+                self.benchmark_running = false;
+                match result {
+                    Ok(benchmark) => {
+                        self.config.hash_benchmark = Some(benchmark);
+                        self.save_config();
+                    }
+                    Err(error) => self.error_message = Some(error),
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::DestinationLabelChanged(index, label) => {
+                if let Some(destination) = self.destinations.get_mut(index) {
+                    destination.label = label;
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::DestinationSubrootChanged(index, subroot) => {
+                if let Some(destination) = self.destinations.get_mut(index) {
+                    destination.subroot = subroot;
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::DestinationFilterChanged(index, filter) => {
+                if let Some(destination) = self.destinations.get_mut(index) {
+                    destination.filter = filter;
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleDestinationEnabled(index, enabled) => {
+                if let Some(destination) = self.destinations.get_mut(index) {
+                    destination.enabled = enabled;
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ModifiedAfterChanged(after) => {
+                self.modified_after = after;
+                Task::none()
+            }
+
+            LibreCardMessage::ModifiedBeforeChanged(before) => {
+                self.modified_before = before;
+                Task::none()
+            }
+
+            LibreCardMessage::ChooseBaseline => {
+                let title = t!("baseline.pick").into_owned();
+                Task::perform(
+                    async move {
+                        let Some(path) = FileDialog::new().set_title(title).pick_file() else {
+                            return Ok(None);
+                        };
+                        let read = {
+                            let path = path.clone();
+                            tokio::task::spawn_blocking(move || read_manifest(&path))
+                        };
+                        match read.await.unwrap_or_else(|e| Err(e.into())) {
+                            Ok(manifest) => Ok(Some((path, manifest))),
+                            Err(e) => Err(t!("compare.read_error", error = e).into_owned()),
+                        }
+                    },
+                    LibreCardMessage::BaselineOpened,
+                )
+            }
+
+            LibreCardMessage::BaselineOpened(result) => {
+                match result {
+                    Ok(Some(baseline)) => {
+                        if baseline.1.integrity == ReportIntegrity::Altered {
+                            self.warning_message = Some(
+                                t!("baseline.altered", path = baseline.0.display()).into_owned(),
+                            );
+                        }
+                        self.baseline = Some(baseline);
+                    }
+                    Ok(None) => {}
+                    Err(error) => self.error_message = Some(error),
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ClearBaseline => {
+                self.baseline = None;
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleBaselineHashes(enabled) => {
+                self.baseline_hashes = enabled;
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleSeparateSessions(enabled) => {
+                self.config.separate_sessions = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleContinueOnError(enabled) => {
+                self.config.continue_on_error = enabled;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleCardWatch(enabled) => {
+                self.config.card_watch.enabled = enabled;
+                self.watched_volume = None;
+                self.save_config();
+                Task::none()
+            }
+
+            // Saved once the label is entered rather than on every keystroke; quitting saves too
+            LibreCardMessage::CardWatchLabelChanged(label) => {
+                self.config.card_watch.volume_label = label;
+                Task::none()
+            }
+
+            LibreCardMessage::CardWatchSerialChanged(serial) => {
+                self.config.card_watch.volume_serial = serial;
+                Task::none()
+            }
+
+            // Saves the serial as well
+            LibreCardMessage::SaveCardWatchLabel => {
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::SaveCardWatchDestinations => {
+                self.config.card_watch.destinations = self.resolved_destinations();
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::PollVolumes => Task::perform(
+                async {
+                    tokio::task::spawn_blocking(list_volumes)
+                        .await
+                        .unwrap_or_default()
+                },
+                LibreCardMessage::VolumesPolled,
+            ),
+
+            LibreCardMessage::VolumesPolled(volumes) => {
+                // A job may have started while the poll was in flight
+                if !self.is_idle() || !self.config.card_watch.enabled {
+                    return Task::none();
+                }
+
+                let watch = &self.config.card_watch;
+                let card = volumes
+                    .into_iter()
+                    .find(|v| v.matches(&watch.volume_label, &watch.volume_serial));
+
+                match card {
+                    None => {
+                        // Card removed, so the next insertion triggers a new job
+                        self.watched_volume = None;
+                        Task::none()
+                    }
+                    Some(card) if self.watched_volume.as_ref() == Some(&card.mount_point) => {
+                        Task::none()
+                    }
+                    Some(card) => {
+                        self.watched_volume = Some(card.mount_point.clone());
+                        self.source_directory = Some(card.mount_point);
+                        self.destinations = self
+                            .config
+                            .card_watch
+                            .destinations
+                            .iter()
+                            .map(|path| Destination {
+                                path: Some(path.clone()),
+                                ..Default::default()
+                            })
+                            .collect();
+                        // The watcher backs up the whole card, whatever was typed for the last job
+                        self.modified_after.clear();
+                        self.modified_before.clear();
+                        self.baseline = None;
+                        self.error_message = None;
+                        self.auto_verify = true;
+                        let scan = self.scan_source();
+                        Task::batch([scan, self.update(LibreCardMessage::StartCopy)])
+                    }
+                }
+            }
+
+            LibreCardMessage::StartCopy => {
+                // Validate input
+                if self.source_directory.is_none() {
+                    self.error_message = Some(t!("src_folder.not_selected").into_owned());
+                    self.auto_verify = false;
+                    return Task::none();
+                }
+
+                // A listing cut short would leave files out of the job unseen
+                if matches!(&self.source_listing, Some((_, Ok(listing))) if listing.capped) {
+                    self.error_message = Some(t!("src_folder.capped_start").into_owned());
+                    self.auto_verify = false;
+                    return Task::none();
+                }
+
+                let (valid_destinations, duplicate_rows) = self.unique_destinations();
+
+                if valid_destinations.is_empty() {
+                    self.error_message = Some(t!("dst_folder.not_selected").into_owned());
+                    self.auto_verify = false;
+                    return Task::none();
+                }
+
+                // A row added but never given a folder would quietly mean one copy fewer
+                let empty_rows = self.empty_destination_rows();
+                if !empty_rows.is_empty() && !self.skip_empty_rows && !self.auto_verify {
+                    self.empty_rows_prompt = Some(empty_rows);
+                    return Task::none();
+                }
+
+                let paths: Vec<_> = valid_destinations.iter().map(|(d, _)| d.clone()).collect();
+                let nested =
+                    find_nested_destinations(self.source_directory.as_deref().unwrap(), &paths);
+                if !nested.is_empty() {
+                    self.error_message = Some(
+                        nested
+                            .iter()
+                            .map(|&i| t!("dst_folder.inside_source", path = paths[i].display()))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    );
+                    self.auto_verify = false;
+                    return Task::none();
+                }
+
+                // Writing the same bytes twice to one drive would look like a second backup, and
+                // so would a copy on the card's own drive. Telling the drives apart can take a
+                // while for a network share, so the latter is added once it is known.
+                let warnings: Vec<_> = duplicate_rows
+                    .iter()
+                    .map(|(earlier, duplicate)| {
+                        t!("dst_folder.duplicate", first = earlier, second = duplicate)
+                    })
+                    .chain(
+                        empty_rows
+                            .iter()
+                            .map(|row| t!("dst_folder.empty_row.skipped", index = row)),
+                    )
+                    .collect();
+                self.warning_message = (!warnings.is_empty()).then(|| warnings.join("\n"));
+                let same_disk = {
+                    let source = self.source_directory.clone().unwrap();
+                    Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || {
+                                destinations_on_source_volume(&source, &paths)
+                                    .into_iter()
+                                    .map(|i| paths[i].clone())
+                                    .collect()
+                            })
+                            .await
+                            .unwrap_or_default()
+                        },
+                        LibreCardMessage::SameDiskChecked,
+                    )
+                };
+
+                let window = match self.modified_window() {
+                    Ok(window) => window,
+                    Err(error) => {
+                        self.error_message = Some(error);
+                        self.auto_verify = false;
+                        return Task::none();
+                    }
+                };
+
+                // The ignore file is a few lines, quick to read even off a card
+                let ignore = match IgnoreRules::load(
+                    self.source_directory.as_deref().unwrap(),
+                    self.config.ignore_file.as_deref(),
+                    &self.config.ignore_patterns(),
+                ) {
+                    Ok(ignore) => ignore,
+                    Err(e) => {
+                        self.error_message = Some(t!("ignore.error", error = e).into_owned());
+                        self.auto_verify = false;
+                        return Task::none();
+                    }
+                };
+
+                let (mut destinations, filters): (Vec<_>, Vec<_>) =
+                    valid_destinations.into_iter().unzip();
+                // The card watcher has folders of its own, which the user didn't pick
+                if !self.auto_verify {
+                    self.config.last_source = self.source_directory.clone();
+                    self.config.last_destinations = self
+                        .destinations
+                        .iter()
+                        .filter(|destination| destination.enabled)
+                        .filter_map(|destination| destination.path.clone())
+                        .collect();
+                    self.save_config();
+                }
+                if self.config.separate_sessions {
+                    let session = next_session_folder(&destinations);
+                    destinations = destinations
+                        .iter()
+                        .map(|d| match DestinationKind::of(d) {
+                            DestinationKind::Folder => d.join(&session),
+                            // Each job already writes an archive of its own
+                            _ => d.clone(),
+                        })
+                        .collect();
+                }
+                self.job_destinations = destinations;
+                self.job_filters = filters;
+                self.job_disabled = self.disabled_destinations();
+                self.job_id = Some(JobId::generate());
+
+                self.job_skip_existing = None;
+                self.job_contents = Vec::new();
+                self.preview_prompt = None;
+                self.job_recorded = false;
+                self.job_excluded = Vec::new();
+                self.job_skip_placeholders = false;
+                self.job_downloaded_placeholders = 0;
+                self.job_window = window;
+                self.job_ignore = ignore;
+                self.job_baseline = None;
+                self.job_space_forecast = None;
+                // A dry run writes nothing, so it needn't be able to
+                self.job_writable = self.dry_run;
+
+                let source = self.source_directory.clone().unwrap();
+                let listing = self.cached_listing();
+                let max_depth = self.config.max_scan_depth;
+                let check_readable = self.config.check_source_readable;
+                let quiescence = self.config.quiescence_interval();
+                let baseline = self.baseline.clone();
+                let compare_hashes = self.baseline_hashes;
+                self.preflight_running = true;
+                let preflight = Task::perform(
+                    async move {
+                        let stop = CancellationToken::new();
+                        let listing = listing_or_scan(&source, listing, max_depth, &stop).await?;
+                        let in_baseline = match &baseline {
+                            Some((_, manifest)) => {
+                                match files_in_manifest(
+                                    &source,
+                                    &listing,
+                                    manifest,
+                                    compare_hashes,
+                                    &stop,
+                                )
+                                .await
+                                {
+                                    JobOutcome::Completed(files) | JobOutcome::Cancelled(files) => {
+                                        files
+                                    }
+                                }
+                            }
+                            None => Vec::new(),
+                        };
+                        let mut outcome = match check_readable {
+                            true => check_source_readable(&source, listing, &stop).await,
+                            // The listing alone shows the cloud placeholders
+                            false => JobOutcome::Completed(Preflight {
+                                listing,
+                                ..Default::default()
+                            }),
+                        };
+                        if let JobOutcome::Completed(preflight) = &mut outcome {
+                            preflight.in_baseline = in_baseline;
+                            if let Some(interval) = quiescence {
+                                preflight.growing = match find_growing_files(
+                                    &source,
+                                    &mut preflight.listing,
+                                    interval,
+                                    &stop,
+                                )
+                                .await
+                                {
+                                    JobOutcome::Completed(files) | JobOutcome::Cancelled(files) => {
+                                        files
+                                    }
+                                };
+                            }
+                        }
+                        Ok(outcome)
+                    },
+                    |result: io::Result<_>| {
+                        LibreCardMessage::SourceChecked(
+                            result.map_err(|e| t!("src_folder.scan_error", error = e).into_owned()),
+                        )
+                    },
+                );
+                Task::batch([same_disk, preflight])
+            }
+
+            LibreCardMessage::SameDiskChecked(paths) => {
+                let same_disk = paths
+                    .iter()
+                    .map(|path| t!("dst_folder.same_disk", path = path.display()).into_owned());
+                let warnings: Vec<String> = self
+                    .warning_message
+                    .take()
+                    .into_iter()
+                    .chain(same_disk)
+                    .collect();
+                self.warning_message = (!warnings.is_empty()).then(|| warnings.join("\n"));
+                Task::none()
+            }
+
+            LibreCardMessage::SourceChecked(result) => {
+                self.preflight_running = false;
+                match result {
+                    Ok(JobOutcome::Completed(mut preflight)) => {
+                        self.job_baseline = self.baseline.as_ref().map(|(manifest, _)| Baseline {
+                            manifest: manifest.clone(),
+                            files: std::mem::take(&mut preflight.in_baseline),
+                        });
+                        let source = self.source_directory.clone().unwrap();
+                        self.source_listing = Some((source, Ok(preflight.listing)));
+                        // Only the files the job copies are worth asking about
+                        let in_job = self.job_listing();
+                        let placeholders: Vec<PathBuf> = in_job
+                            .files
+                            .iter()
+                            .filter(|(_, stamp)| stamp.placeholder)
+                            .map(|(file, _)| file.clone())
+                            .collect();
+                        let in_job: HashSet<&PathBuf> =
+                            in_job.files.iter().map(|(file, _)| file).collect();
+                        preflight
+                            .unreadable
+                            .retain(|file| in_job.contains(&file.path));
+                        preflight.growing.retain(|file| in_job.contains(file));
+                        if placeholders.is_empty()
+                            && preflight.unreadable.is_empty()
+                            && preflight.growing.is_empty()
+                        {
+                            return Task::done(LibreCardMessage::CheckDestinations);
+                        }
+                        // A card that is still recording settles once the camera stops, and a
+                        // torn copy isn't worth making unattended
+                        if self.auto_verify && !preflight.growing.is_empty() {
+                            self.warning_message = Some(
+                                t!(
+                                    "growing.waiting",
+                                    count = format_count(preflight.growing.len())
+                                )
+                                .into_owned(),
+                            );
+                            let scan = self.scan_source();
+                            return Task::batch([scan, Task::done(LibreCardMessage::StartCopy)]);
+                        }
+                        // Nobody is there to ask, and the rest of the card still needs a backup.
+                        // Downloading unasked could take hours.
+                        if self.auto_verify {
+                            self.job_excluded =
+                                preflight.unreadable.into_iter().map(|f| f.path).collect();
+                            self.job_skip_placeholders = true;
+                            return Task::done(LibreCardMessage::CheckDestinations);
+                        }
+                        // The placeholders are asked about first, then the unreadable files, then
+                        // the ones still being written
+                        if !placeholders.is_empty() {
+                            self.placeholder_prompt = Some(placeholders);
+                        }
+                        if !preflight.unreadable.is_empty() {
+                            self.unreadable_prompt = Some(preflight.unreadable);
+                        }
+                        if !preflight.growing.is_empty() {
+                            self.growing_prompt = Some(preflight.growing);
+                        }
+                        Task::none()
+                    }
+                    Ok(JobOutcome::Cancelled(_)) => Task::none(),
+                    Err(error) => {
+                        self.error_message = Some(error);
+                        self.auto_verify = false;
+                        Task::none()
+                    }
+                }
+            }
+
+            LibreCardMessage::ResolveUnreadable(exclude) => {
+                let unreadable = self.unreadable_prompt.take().unwrap_or_default();
+                if !exclude {
+                    self.growing_prompt = None;
+                    return Task::none();
+                }
+                self.job_excluded = unreadable.into_iter().map(|file| file.path).collect();
+                if self.growing_prompt.is_some() {
+                    return Task::none();
+                }
+                Task::done(LibreCardMessage::CheckDestinations)
+            }
+
+            LibreCardMessage::ResolvePlaceholders(choice) => {
+                let placeholders = self.placeholder_prompt.take().unwrap_or_default();
+                match choice {
+                    PlaceholderChoice::Skip => self.job_skip_placeholders = true,
+                    PlaceholderChoice::Download => {
+                        self.job_downloaded_placeholders = placeholders.len();
+                    }
+                    PlaceholderChoice::Abort => {
+                        self.unreadable_prompt = None;
+                        self.growing_prompt = None;
+                        return Task::none();
+                    }
+                }
+                if self.unreadable_prompt.is_some() || self.growing_prompt.is_some() {
+                    return Task::none();
+                }
+                Task::done(LibreCardMessage::CheckDestinations)
+            }
+
+            LibreCardMessage::ResolveGrowing(choice) => {
+                let growing = self.growing_prompt.take().unwrap_or_default();
+                match choice {
+                    GrowingChoice::Recheck => {
+                        // A fresh listing also picks up the files recorded since
+                        let scan = self.scan_source();
+                        Task::batch([scan, Task::done(LibreCardMessage::StartCopy)])
+                    }
+                    GrowingChoice::Exclude => {
+                        self.job_excluded.extend(growing);
+                        Task::done(LibreCardMessage::CheckDestinations)
+                    }
+                    GrowingChoice::Proceed => Task::done(LibreCardMessage::CheckDestinations),
+                    GrowingChoice::Abort => Task::none(),
+                }
+            }
+
+            LibreCardMessage::PermissionDenied(file) => {
+                self.permission_prompt = file;
+                Task::none()
+            }
+
+            LibreCardMessage::ResolvePermission(choice) => {
+                self.permission_prompt = None;
+                if let LibreCardAppStage::Copying { choices, .. } = &self.stage {
+                    // The job is gone if it was stopped in the meantime
+                    let _ = choices.send(choice);
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::DestinationFull(full) => {
+                self.space_prompt = full;
+                Task::none()
+            }
+
+            LibreCardMessage::ResolveSpace(choice) => {
+                self.space_prompt = None;
+                if let LibreCardAppStage::Copying { space_choices, .. } = &self.stage {
+                    let _ = space_choices.send(choice);
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::CheckDestinations => {
+                if !self.job_writable {
+                    return self.probe_destinations();
+                }
+                if self.job_space_forecast.is_none() {
+                    return self.forecast_space();
+                }
+                // The watcher neither asks about the destinations nor shows a preview
+                if self.auto_verify {
+                    return Task::done(LibreCardMessage::BeginCopy);
+                }
+
+                let source = self.source_directory.clone().unwrap();
+                let destinations = self.job_destinations.clone();
+                let filters = self.job_filters.clone();
+                let max_depth = self.config.max_scan_depth;
+                // Files the job leaves out can't collide with anything
+                let listing = self.cached_listing().map(|_| self.job_listing());
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let files = match listing {
+                                Some(listing) => listing.paths(),
+                                None => flatten_dir_files_within(&source, max_depth)?.0,
+                            };
+                            destinations
+                                .iter()
+                                .zip(&filters)
+                                .map(|(dest, filter)| {
+                                    let files: Vec<_> = files
+                                        .iter()
+                                        .filter(|file| filter.matches(file))
+                                        .cloned()
+                                        .collect();
+                                    existing_contents(&source, dest, &files)
+                                        .map(|contents| (dest.clone(), contents))
+                                })
+                                .collect::<io::Result<Vec<_>>>()
+                        })
+                        .await
+                        .unwrap_or_else(|e| Err(e.into()))
+                        .map_err(|e| t!("dst_folder.check_error", error = e).into_owned())
+                    },
+                    LibreCardMessage::DestinationsChecked,
+                )
+            }
+
+            LibreCardMessage::WritabilityProbed(probed) => {
+                let mut failures = Vec::new();
+                let mut left = Vec::new();
+                for (index, (_, result)) in probed.into_iter().enumerate() {
+                    match result {
+                        Ok(probe) => left.extend(probe),
+                        Err(reason) => failures.push(
+                            t!(
+                                "dst_folder.not_writable",
+                                name = self.job_destination_name(index),
+                                reason = reason
+                            )
+                            .into_owned(),
+                        ),
+                    }
+                }
+                if !left.is_empty() {
+                    let left = left.iter().map(|probe| {
+                        t!("dst_folder.probe_left", path = probe.display()).into_owned()
+                    });
+                    self.warning_message = Some(
+                        self.warning_message
+                            .take()
+                            .into_iter()
+                            .chain(left)
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    );
+                }
+                if !failures.is_empty() {
+                    self.error_message = Some(failures.join("\n"));
+                    self.auto_verify = false;
+                    return Task::none();
+                }
+                self.job_writable = true;
+                Task::done(LibreCardMessage::CheckDestinations)
+            }
+
+            LibreCardMessage::SpaceForecasted(forecast) => {
+                let fits = forecast.iter().all(SpaceForecast::fits);
+                // Some network mounts don't report their size; the copy can still go ahead
+                let unknown: Vec<_> = forecast
+                    .iter()
+                    .filter(|forecast| forecast.space.is_none())
+                    .map(|forecast| {
+                        t!("space.unknown", path = forecast.destination.display()).into_owned()
+                    })
+                    .collect();
+                if !unknown.is_empty() {
+                    self.warning_message = Some(
+                        self.warning_message
+                            .take()
+                            .into_iter()
+                            .chain(unknown)
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    );
+                }
+                self.job_space_forecast = Some(forecast);
+                if fits {
+                    return Task::done(LibreCardMessage::CheckDestinations);
+                }
+                // An unattended job stops rather than break the margin nobody can confirm
+                if self.auto_verify {
+                    self.error_message = Some(t!("space.shortfall").into_owned());
+                    self.auto_verify = false;
+                    return Task::none();
+                }
+                self.shortfall_prompt = true;
+                Task::none()
+            }
+
+            LibreCardMessage::ResolveShortfall(copy) => {
+                self.shortfall_prompt = false;
+                match copy {
+                    true => Task::done(LibreCardMessage::CheckDestinations),
+                    false => Task::none(),
+                }
+            }
+
+            LibreCardMessage::DestinationsChecked(result) => match result {
+                Ok(contents) => {
+                    self.job_contents = contents.iter().map(|(_, c)| c.clone()).collect();
+                    let collisions: Vec<_> = contents
+                        .iter()
+                        .flat_map(|(_, contents)| contents.collisions.iter().cloned())
+                        .collect();
+                    if self.ask_overwrite() && !collisions.is_empty() {
+                        self.overwrite_prompt = Some(collisions);
+                        return Task::none();
+                    }
+
+                    let nonempty: Vec<_> = contents
+                        .into_iter()
+                        .filter(|(_, contents)| !contents.is_empty())
+                        .collect();
+                    if nonempty.is_empty() || !self.confirm_nonempty() {
+                        return Task::done(LibreCardMessage::PreviewJob);
+                    }
+                    self.nonempty_prompt = Some(nonempty);
+                    Task::none()
+                }
+                Err(error) => {
+                    self.error_message = Some(error);
+                    Task::none()
+                }
+            },
+
+            LibreCardMessage::ToggleDontAskNonEmpty(enabled) => {
+                self.dont_ask_nonempty = enabled;
+                Task::none()
+            }
+
+            LibreCardMessage::ConfirmNonEmpty => {
+                self.nonempty_prompt = None;
+                if self.dont_ask_nonempty {
+                    self.nonempty_confirmed_for = Some(self.job_destinations.clone());
+                }
+                Task::done(LibreCardMessage::PreviewJob)
+            }
+
+            LibreCardMessage::CancelNonEmpty => {
+                self.nonempty_prompt = None;
+                Task::none()
+            }
+
+            LibreCardMessage::ResolveEmptyRows(proceed) => {
+                self.empty_rows_prompt = None;
+                if !proceed {
+                    return Task::none();
+                }
+                self.skip_empty_rows = true;
+                self.update(LibreCardMessage::StartCopy)
+            }
+
+            LibreCardMessage::ResolveOverwrite(choice) => {
+                self.overwrite_prompt = None;
+                match choice {
+                    Some(skip) => {
+                        self.job_skip_existing = Some(skip);
+                        Task::done(LibreCardMessage::PreviewJob)
+                    }
+                    None => Task::none(),
+                }
+            }
+
+            LibreCardMessage::PreviewJob => {
+                let Some(listing) = self.cached_listing() else {
+                    return Task::done(LibreCardMessage::BeginCopy);
+                };
+                let plan = SourcePlan {
+                    excluded: self.job_excluded.clone(),
+                    skip_placeholders: self.job_skip_placeholders,
+                    modified: self.job_window,
+                    baseline: self.job_baseline.clone(),
+                    ignore: self.job_ignore.clone(),
+                    max_depth: self.config.max_scan_depth,
+                    ..Default::default()
+                };
+                let skip_existing = self
+                    .job_skip_existing
+                    .unwrap_or(self.config.overwrite_policy != OverwritePolicy::Overwrite);
+                self.preview_prompt = Some(preview_job(
+                    &listing,
+                    &plan,
+                    &self.job_filters,
+                    &self.job_contents,
+                    skip_existing,
+                ));
+                self.expanded_preview = None;
+                Task::none()
+            }
+
+            LibreCardMessage::TogglePreviewList(list) => {
+                self.expanded_preview = match self.expanded_preview {
+                    Some(expanded) if expanded == list => None,
+                    _ => Some(list),
+                };
+                Task::none()
+            }
+
+            LibreCardMessage::CancelPreview => {
+                self.preview_prompt = None;
+                Task::none()
+            }
+
+            LibreCardMessage::OverwritePolicySelected(choice) => {
+                self.config.overwrite_policy = choice.0;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::TimestampStyleSelected(choice) => {
+                self.config.timestamp_style = choice.0;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::CustomTimestampFormatChanged(pattern) => {
+                self.config.custom_timestamp_format = pattern;
+                self.save_config();
+                Task::none()
+            }
+
+            LibreCardMessage::BeginCopy => {
+                self.preview_prompt = None;
+                let source = self.source_directory.clone().unwrap();
+                let destinations = self.job_destinations.clone();
+                let filters = self.job_filters.clone();
+                let mut copy_options = self.config.copy_options();
+                if let Some(skip) = self.job_skip_existing {
+                    copy_options.skip_existing = skip;
+                }
+                copy_options.dry_run =
+                    self.dry_run
+                        .then_some(match self.config.dry_run_reads_source {
+                            true => DryRun::ReadSource,
+                            false => DryRun::Walk,
+                        });
+                let skip_duplicates = self.config.skip_duplicates;
+                let hash_options = self.config.hash_options();
+                let listing = self.cached_listing();
+                let excluded = self.job_excluded.clone();
+                let skip_placeholders = self.job_skip_placeholders;
+                let window = self.job_window;
+                let baseline = self.job_baseline.clone();
+                let ignore = self.job_ignore.clone();
+                let max_depth = self.config.max_scan_depth;
+                let record_volume = self.config.record_volume_listing;
+                let job_id = *self.job_id.get_or_insert_with(JobId::generate);
+
+                let (tx, rx) = watch::channel(Progress::default());
+                let (warnings_tx, warnings) = watch::channel(Vec::new());
+                let stop = CancellationToken::new();
+                let (asking_tx, asking) = watch::channel(None);
+                let (choices, choices_rx) = mpsc::unbounded_channel();
+                // Nobody is there to ask when the watcher started the job
+                let permission_denied = match self.auto_verify {
+                    true => PermissionHandling::SkipAll,
+                    false => PermissionHandling::Ask {
+                        asking: asking_tx,
+                        choices: choices_rx,
+                    },
+                };
+                let (space_asking_tx, space_asking) = watch::channel(None);
+                let (space_choices, space_choices_rx) = mpsc::unbounded_channel();
+                let space = match self.auto_verify {
+                    true => SpaceHandling::Fail,
+                    false => SpaceHandling::Ask {
+                        asking: space_asking_tx,
+                        choices: space_choices_rx,
+                    },
+                };
+
+                self.job_serial += 1;
+                self.permission_prompt = None;
+                self.space_prompt = None;
+                self.show_warnings = false;
+                let finish = CancellationToken::new();
+                self.stage = LibreCardAppStage::Copying {
+                    progress: Progress::default(),
+                    rx,
+                    stop: stop.clone(),
+                    finish: finish.clone(),
+                    started_at: Instant::now(),
+                    asking,
+                    choices,
+                    space_asking,
+                    space_choices,
+                    warnings,
+                    rates: DestinationRates::default(),
+                };
+
+                // Task to perform the copy operation
+                Task::perform(
+                    crash::catch_panic(
+                        async move {
+                            // Stopping here leaves the copy to notice and stop before it starts
+                            let volume_listing = match record_volume {
+                                true => match list_source_volume(&source, &tx, &stop).await {
+                                    Ok(JobOutcome::Completed(listing)) => Some(Box::new(listing)),
+                                    Ok(JobOutcome::Cancelled(_)) => None,
+                                    Err(e) => {
+                                        tracing::warn!("Couldn't list the source volume: {e}");
+                                        None
+                                    }
+                                },
+                                false => None,
+                            };
+                            let duplicates = if skip_duplicates {
+                                let found =
+                                    match listing_or_scan(&source, listing, max_depth, &stop).await
+                                    {
+                                        Ok(listing) => {
+                                            find_duplicate_files(
+                                                &source,
+                                                &listing,
+                                                hash_options,
+                                                &stop,
+                                            )
+                                            .await
+                                        }
+                                        Err(e) => Err(e),
+                                    };
+                                match found {
+                                    Ok(duplicates) => duplicates,
+                                    Err(e) => {
+                                        return LibreCardMessage::CopyCompleted(JobEnd::Failed(
+                                            t!("copying.error", error = e).into_owned(),
+                                        ));
+                                    }
+                                }
+                            } else {
+                                Vec::new()
+                            };
+                            let plan = SourcePlan {
+                                duplicates,
+                                excluded,
+                                permission_denied,
+                                skip_placeholders,
+                                modified: window,
+                                baseline,
+                                ignore,
+                                max_depth,
+                            };
+                            let result = copy_dirs(
+                                &source,
+                                &destinations,
+                                &filters,
+                                plan,
+                                space,
+                                copy_options,
+                                JobHandle {
+                                    stop,
+                                    finish,
+                                    ..JobHandle::new(tx, warnings_tx)
+                                },
+                            )
+                            .await
+                            .map(|outcome| match outcome {
+                                JobOutcome::Completed(mut summary) => {
+                                    summary.volume_listing = volume_listing;
+                                    JobOutcome::Completed(summary)
+                                }
+                                cancelled => cancelled,
+                            });
+                            LibreCardMessage::CopyCompleted(JobEnd::new(
+                                result,
+                                |summary| !summary.failed.is_empty(),
+                                |e| t!("copying.error", error = e).into_owned(),
+                            ))
+                        }
+                        .instrument(tracing::info_span!("job", job = %job_id)),
+                        |error| {
+                            LibreCardMessage::CopyCompleted(JobEnd::Failed(
+                                t!("job.panicked", error = error).into_owned(),
+                            ))
+                        },
+                    ),
+                    |msg| msg,
+                )
+            }
+
+            LibreCardMessage::StartVerifyOnly => {
+                if self.source_directory.is_none() {
+                    self.error_message = Some(t!("src_folder.not_selected").into_owned());
+                    return Task::none();
+                }
+                let (destinations, _) = self.unique_destinations();
+                if destinations.is_empty() {
+                    self.error_message = Some(t!("dst_folder.not_selected").into_owned());
+                    return Task::none();
+                }
+                let (destinations, filters) = destinations.into_iter().unzip();
+                self.job_destinations = destinations;
+                self.job_filters = filters;
+                self.job_disabled = self.disabled_destinations();
+                self.job_id = Some(JobId::generate());
+                self.job_recorded = false;
+                // With no copy to go by, the verification lists the source itself
+                self.update(LibreCardMessage::StartChecksum)
+            }
+
+            LibreCardMessage::StartReconcile => {
+                let Some(source) = self.source_directory.clone() else {
+                    self.error_message = Some(t!("src_folder.not_selected").into_owned());
+                    return Task::none();
+                };
+                let (destinations, _) = self.unique_destinations();
+                if destinations.is_empty() {
+                    self.error_message = Some(t!("dst_folder.not_selected").into_owned());
+                    return Task::none();
+                }
+                let destinations: Vec<PathBuf> =
+                    destinations.into_iter().map(|(path, _)| path).collect();
+                self.job_destinations = destinations.clone();
+                let hash_options = self.config.hash_options();
+
+                let (tx, rx) = watch::channel(Progress::default());
+                let stop = CancellationToken::new();
+
+                // Runs like a verification, so it shares that stage, but has no warnings to feed
+                self.job_serial += 1;
+                self.show_warnings = false;
+                self.stage = LibreCardAppStage::Checksumming {
+                    progress: Progress::default(),
+                    rx,
+                    stop: stop.clone(),
+                    started_at: Instant::now(),
+                    warnings: watch::channel(Vec::new()).1,
+                    workers: None,
+                };
+
+                Task::perform(
+                    async move {
+                        reconcile_contents(&source, &destinations, hash_options, tx, stop)
+                            .await
+                            .map_err(|e| t!("checksum.error", error = e).into_owned())
+                    },
+                    LibreCardMessage::ReconcileCompleted,
+                )
+            }
+
+            LibreCardMessage::ReconcileCompleted(result) => {
+                if self.quit_after_job {
+                    return self.quit();
+                }
+                match result {
+                    Ok(JobOutcome::Completed(report)) => {
+                        let missing = report
+                            .destinations
+                            .iter()
+                            .map(|destination| destination.missing.len())
+                            .sum::<usize>();
+                        let status = if missing == 0 {
+                            Status::Success
+                        } else {
+                            Status::Failure
+                        };
+                        self.last_result = Some((
+                            status,
+                            t!("status_bar.reconciled", missing = format_count(missing))
+                                .into_owned(),
+                        ));
+                        self.stage = LibreCardAppStage::ReconcileComplete { report };
+                    }
+                    Ok(JobOutcome::Cancelled(_)) => {
+                        let progress = self.current_progress();
+                        self.stage = LibreCardAppStage::Cancelled {
+                            completed: progress.completed_files,
+                            total: progress.total_files,
+                        };
+                        self.last_result =
+                            Some((Status::Warning, t!("status_bar.cancelled").into_owned()));
+                    }
+                    Err(error) => {
+                        self.stage = LibreCardAppStage::Input;
+                        tracing::error!("Job failed: {error}");
+                        self.error_message = Some(error);
+                        self.last_result =
+                            Some((Status::Failure, t!("status_bar.failed").into_owned()));
+                    }
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::StartReadabilityCheck => {
+                let Some(source) = self.source_directory.clone() else {
+                    self.error_message = Some(t!("src_folder.not_selected").into_owned());
+                    return Task::none();
+                };
+                let hash_options = self.config.hash_options();
+
+                let (tx, rx) = watch::channel(Progress::default());
+                let stop = CancellationToken::new();
+
+                // Runs like a verification, so it shares that stage; failed reads are in the report
+                self.job_serial += 1;
+                self.show_warnings = false;
+                self.stage = LibreCardAppStage::Checksumming {
+                    progress: Progress::default(),
+                    rx,
+                    stop: stop.clone(),
+                    started_at: Instant::now(),
+                    warnings: watch::channel(Vec::new()).1,
+                    workers: None,
+                };
+
+                Task::perform(
+                    async move {
+                        double_read_source(&source, hash_options, tx, stop)
+                            .await
+                            .map_err(|e| t!("checksum.error", error = e).into_owned())
+                    },
+                    LibreCardMessage::ReadabilityChecked,
+                )
+            }
+
+            LibreCardMessage::ReadabilityChecked(result) => {
+                if self.quit_after_job {
+                    return self.quit();
+                }
+                match result {
+                    Ok(JobOutcome::Completed(report)) => {
+                        let troubled = report.troubled().count();
+                        let status = match troubled {
+                            0 => Status::Success,
+                            _ => Status::Failure,
+                        };
+                        self.last_result = Some((
+                            status,
+                            t!("status_bar.read_twice", troubled = format_count(troubled))
+                                .into_owned(),
+                        ));
+                        self.stage = LibreCardAppStage::ReadabilityComplete { report };
+                    }
+                    Ok(JobOutcome::Cancelled(_)) => {
+                        let progress = self.current_progress();
+                        self.stage = LibreCardAppStage::Cancelled {
+                            completed: progress.completed_files,
+                            total: progress.total_files,
+                        };
+                        self.last_result =
+                            Some((Status::Warning, t!("status_bar.cancelled").into_owned()));
+                    }
+                    Err(error) => {
+                        self.stage = LibreCardAppStage::Input;
+                        tracing::error!("Job failed: {error}");
+                        self.error_message = Some(error);
+                        self.last_result =
+                            Some((Status::Failure, t!("status_bar.failed").into_owned()));
+                    }
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ExportReadability(format) => {
+                let LibreCardAppStage::ReadabilityComplete { ref report } = self.stage else {
+                    return Task::none();
+                };
+                let report = report.clone();
+                let timestamps = self.timestamp_format();
+                let tag = report.algorithm.tag_name().unwrap_or_default();
+                let stamp = timestamps.format_for_file_name(report.finished_at);
+                let dialog = match format {
+                    ManifestFormat::Csv => FileDialog::new()
+                        .add_filter("CSV", &["csv"])
+                        .set_file_name(format!("readability_report {stamp}.csv")),
+                    ManifestFormat::Gnu => FileDialog::new().set_file_name(format!("{tag}SUMS")),
+                    ManifestFormat::Bsd => {
+                        FileDialog::new().set_file_name(format!("CHECKSUM.{tag}"))
+                    }
+                    ManifestFormat::Certificate => FileDialog::new()
+                        .add_filter(t!("checksum.export.text_file"), &["txt"])
+                        .set_file_name(format!("readability_check {stamp}.txt")),
+                    // Only checksum reports are written as JSON
+                    ManifestFormat::Json | ManifestFormat::Mhl => return Task::none(),
+                };
+                Task::perform(
+                    async move {
+                        match dialog.save_file() {
+                            Some(path) => report
+                                .export(path, format, &timestamps)
+                                .map_err(|e| e.to_string()),
+                            None => Ok(()),
+                        }
+                    },
+                    LibreCardMessage::ExportCompleted,
+                )
+            }
+
+            LibreCardMessage::UpgradeVerification => {
+                let LibreCardAppStage::ChecksumComplete { report, .. } = &self.stage else {
+                    return Task::none();
+                };
+                let report = report.clone();
+                let hash_options = self.config.hash_options();
+
+                let (tx, rx) = watch::channel(Progress::default());
+                let stop = CancellationToken::new();
+
+                self.job_serial += 1;
+                self.show_warnings = false;
+                self.stage = LibreCardAppStage::Checksumming {
+                    progress: Progress::default(),
+                    rx,
+                    stop: stop.clone(),
+                    started_at: Instant::now(),
+                    warnings: watch::channel(Vec::new()).1,
+                    workers: None,
+                };
+
+                Task::perform(
+                    async move {
+                        let outcome = read_back_report(&report, hash_options, tx, stop)
+                            .await
+                            .map_err(|e| t!("checksum.error", error = e).into_owned());
+                        LibreCardMessage::VerificationUpgraded(Box::new(report), outcome)
+                    },
+                    |msg| msg,
+                )
+            }
+
+            LibreCardMessage::VerificationUpgraded(report, outcome) => {
+                if self.quit_after_job {
+                    return self.quit();
+                }
+                match outcome {
+                    Ok(JobOutcome::Completed(upgraded)) => {
+                        // The job may be an earlier one, opened as the last job
+                        let recorded = self
+                            .job_history
+                            .jobs
+                            .iter_mut()
+                            .find(|job| job.job_id.is_some() && job.job_id == upgraded.job_id);
+                        if let Some(job) = recorded {
+                            job.outcome = RecordedOutcome::Verified {
+                                files: upgraded.total_files(),
+                                failed: upgraded.count_errors(),
+                                level: Some(upgraded.verification_level()),
+                            };
+                            job.finished_at = SystemTime::now();
+                            if let Err(e) = self.job_history.save() {
+                                self.warning_message =
+                                    Some(t!("history.save_error", error = e).into_owned());
+                            }
+                        }
+                        return self.show_verification(upgraded);
+                    }
+                    // The report stays as it was
+                    Ok(JobOutcome::Cancelled(_)) => {
+                        self.last_result =
+                            Some((Status::Warning, t!("status_bar.cancelled").into_owned()));
+                    }
+                    Err(error) => {
+                        tracing::error!("Job failed: {error}");
+                        self.error_message = Some(error);
+                        self.last_result =
+                            Some((Status::Failure, t!("status_bar.failed").into_owned()));
+                    }
+                }
+                self.stage = LibreCardAppStage::ChecksumComplete {
+                    fingerprint: report.fingerprint(),
+                    manifest_digest: report.manifest_digest(),
+                    report: *report,
+                };
+                Task::none()
+            }
+
+            LibreCardMessage::CatchUpDestination => {
+                let LibreCardAppStage::ChecksumComplete { report, .. } = &self.stage else {
+                    return Task::none();
+                };
+                let Some(from) = catch_up_origin(report) else {
+                    return Task::none();
+                };
+                let Some(to) = FileDialog::new().pick_folder() else {
+                    return Task::none();
+                };
+                if report.destination_roots().contains(&to) {
+                    self.error_message = Some(t!("catch_up.already_destination").into_owned());
+                    return Task::none();
+                }
+                let report = report.clone();
+                let hash_options = self.config.hash_options();
+
+                let (tx, rx) = watch::channel(Progress::default());
+                let stop = CancellationToken::new();
+
+                // Copies and verifies one file at a time, so the verification stage fits it
+                self.job_serial += 1;
+                self.show_warnings = false;
+                self.stage = LibreCardAppStage::Checksumming {
+                    progress: Progress::default(),
+                    rx,
+                    stop: stop.clone(),
+                    started_at: Instant::now(),
+                    warnings: watch::channel(Vec::new()).1,
+                    workers: None,
+                };
+
+                Task::perform(
+                    async move {
+                        let outcome =
+                            catch_up_destination(&report, from, &to, hash_options, tx, stop)
+                                .await
+                                .map_err(|e| t!("catch_up.error", error = e).into_owned());
+                        LibreCardMessage::CatchUpCompleted(report, to, outcome)
+                    },
+                    |msg| msg,
+                )
+            }
+
+            LibreCardMessage::CatchUpCompleted(mut report, to, outcome) => {
+                if self.quit_after_job {
+                    return self.quit();
+                }
+                match outcome {
+                    Ok(JobOutcome::Completed(results)) => {
+                        report.add_destination(results);
+                        self.job_destinations.push(to);
+                        return self.show_verification(report);
+                    }
+                    // The report stays as it was; a partly copied drive isn't a destination
+                    Ok(JobOutcome::Cancelled(_)) => {
+                        self.last_result =
+                            Some((Status::Warning, t!("status_bar.cancelled").into_owned()));
+                    }
+                    Err(error) => {
+                        tracing::error!("Job failed: {error}");
+                        self.error_message = Some(error);
+                        self.last_result =
+                            Some((Status::Failure, t!("status_bar.failed").into_owned()));
+                    }
+                }
+                self.stage = LibreCardAppStage::ChecksumComplete {
+                    fingerprint: report.fingerprint(),
+                    manifest_digest: report.manifest_digest(),
+                    report,
+                };
+                Task::none()
+            }
+
+            LibreCardMessage::StartChecksum => {
+                let source = self.source_directory.clone().unwrap();
+                let destinations = self.job_destinations.clone();
+                let filters = self.job_filters.clone();
+                let hash_options = self.config.hash_options();
+
+                // The copy pass has usually collected the list of files already
+                let copied = match &self.stage {
+                    LibreCardAppStage::CopyComplete { summary } => Some(summary.clone()),
+                    _ => None,
+                };
+                let copy_order = copied.as_ref().map(|summary| summary.order);
+                let duplicates = copied
+                    .as_ref()
+                    .map_or_else(Vec::new, |summary| duplicate_pairs(&summary.files));
+                let skipped = copied
+                    .as_ref()
+                    .map_or_else(Vec::new, |summary| skipped_files(&summary.files));
+                let hard_links = copied
+                    .as_ref()
+                    .map_or_else(Vec::new, |summary| hard_link_pairs(&summary.files));
+                let reconnected = copied
+                    .as_ref()
+                    .map_or_else(Vec::new, |summary| reconnected_files(&summary.files));
+                let salvaged = copied
+                    .as_ref()
+                    .map_or_else(HashMap::new, |summary| salvaged_files(&summary.files));
+                let slow_files = copied
+                    .as_ref()
+                    .map_or_else(Vec::new, |summary| summary.slow_files.clone());
+                let baseline = copied.as_ref().and_then(|summary| summary.baseline.clone());
+                let copy_times = copied
+                    .as_ref()
+                    .map_or_else(Vec::new, |summary| summary.destination_times.clone());
+                let volume_listing = copied
+                    .as_ref()
+                    .and_then(|summary| summary.volume_listing.clone());
+                let ignore_file = self.config.ignore_file.clone();
+                let ignore_patterns = self.config.ignore_patterns();
+                let job_id = *self.job_id.get_or_insert_with(JobId::generate);
+
+                let (tx, rx) = watch::channel(Progress::default());
+                let (warnings_tx, warnings) = watch::channel(Vec::new());
+                let (workers, workers_rx) = watch::channel(self.config.verify_workers());
+                let stop = CancellationToken::new();
+
+                self.job_serial += 1;
+                self.show_warnings = false;
+                self.stage = LibreCardAppStage::Checksumming {
+                    progress: Progress::default(),
+                    rx,
+                    stop: stop.clone(),
+                    started_at: Instant::now(),
+                    warnings,
+                    workers: Some(workers),
+                };
+
+                // Task to perform the checksum operation
+                Task::perform(
+                    crash::catch_panic(
+                        async move {
+                            let listed = match copied {
+                                // Verify exactly what was copied, minus what changed on the card since
+                                Some(copied) => {
+                                    let source = source.clone();
+                                    tokio::task::spawn_blocking(move || {
+                                        compare_with_source(&source, &copied)
+                                    })
+                                    .await
+                                    .unwrap_or_else(|e| Err(e.into()))
+                                }
+                                None => match list_dir_files(&source, &tx, &stop).await {
+                                    // On cancellation hash_dirs stops before the first file. A copy
+                                    // would have left out the ignored files, so they aren't expected.
+                                    Ok(
+                                        JobOutcome::Completed(files) | JobOutcome::Cancelled(files),
+                                    ) => IgnoreRules::load(
+                                        &source,
+                                        ignore_file.as_deref(),
+                                        &ignore_patterns,
+                                    )
+                                    .map(|ignore| {
+                                        let files = files
+                                            .into_iter()
+                                            .filter(|file| !ignore.ignores(file))
+                                            .collect();
+                                        (files, SourceChanges::default())
+                                    }),
+                                    Err(e) => Err(e),
+                                },
+                            };
+                            let (files, source_changes) = match listed {
+                                Ok(listed) => listed,
+                                Err(e) => {
+                                    return LibreCardMessage::ChecksumCompleted(JobEnd::Failed(
+                                        t!("checksum.list_error", error = e).into_owned(),
+                                    ));
+                                }
+                            };
+                            // Only the copied files are verified, so the changes show while they are
+                            warnings_tx.send_modify(|feed| feed.extend(source_changes.warnings()));
+
+                            let result = hash_dirs(
+                                &source,
+                                &destinations,
+                                &filters,
+                                &files,
+                                &salvaged,
+                                hash_options,
+                                JobHandle {
+                                    stop,
+                                    workers: workers_rx,
+                                    ..JobHandle::new(tx, warnings_tx)
+                                },
+                            )
+                            .await
+                            .map(|outcome| match outcome {
+                                JobOutcome::Completed(mut report) => {
+                                    // Copied files that changed on the card since aren't leftovers
+                                    report.extra_files.retain(|extra| {
+                                        !destinations.iter().any(|dest| {
+                                            extra.strip_prefix(dest).is_ok_and(|file| {
+                                                source_changes
+                                                    .removed
+                                                    .iter()
+                                                    .chain(&source_changes.modified)
+                                                    .any(|changed| changed == file)
+                                            })
+                                        })
+                                    });
+                                    report.source_changes = source_changes;
+                                    report.copy_order = copy_order;
+                                    report.duplicates = duplicates;
+                                    report.skipped = skipped;
+                                    report.hard_links = hard_links;
+                                    report.reconnected = reconnected;
+                                    report.slow_files = slow_files;
+                                    report.baseline = baseline;
+                                    report.volume_listing = volume_listing;
+                                    for (times, copied) in
+                                        report.destination_times.iter_mut().zip(&copy_times)
+                                    {
+                                        times.copied_at = copied.copied_at;
+                                    }
+                                    JobOutcome::Completed(report)
+                                }
+                                cancelled => cancelled,
+                            });
+                            LibreCardMessage::ChecksumCompleted(JobEnd::new(
+                                result,
+                                |report| report.count_errors() > 0 || report.stopped_at_failure,
+                                |e| t!("checksum.error", error = e).into_owned(),
+                            ))
+                        }
+                        .instrument(tracing::info_span!("job", job = %job_id)),
+                        |error| {
+                            LibreCardMessage::ChecksumCompleted(JobEnd::Failed(
+                                t!("job.panicked", error = error).into_owned(),
+                            ))
+                        },
+                    ),
+                    |msg| msg,
+                )
+            }
+
+            LibreCardMessage::ChecksumCompleted(result) => {
+                if self.quit_after_job {
+                    return self.quit();
+                }
+                self.auto_verify = false;
+                match result {
+                    JobEnd::Completed(report) | JobEnd::CompletedWithWarnings(report) => {
+                        return self.finish_verification(report);
+                    }
+                    JobEnd::Cancelled(report) => {
+                        let total = self.current_progress().total_files;
+                        self.warning_message = Some(
+                            t!(
+                                "checksum.cancelled",
+                                completed = format_count(report.total_files()),
+                                total = format_count(total)
+                            )
+                            .into_owned(),
+                        );
+                        self.record_job(RecordedOutcome::Cancelled {
+                            completed: report.total_files(),
+                            total,
+                            verifying: true,
+                        });
+                        self.last_result =
+                            Some((Status::Warning, t!("status_bar.cancelled").into_owned()));
+                        return self.update(LibreCardMessage::BackToInput);
+                    }
+                    JobEnd::Failed(error) => {
+                        self.stage = LibreCardAppStage::Input;
+                        tracing::error!("Job failed: {error}");
+                        self.error_message = Some(error);
+                        self.last_result =
+                            Some((Status::Failure, t!("status_bar.failed").into_owned()));
+                    }
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ExportChecksum(format) => {
+                let timestamps = self.timestamp_format();
+                if let LibreCardAppStage::ChecksumComplete { ref report, .. } = self.stage {
+                    let report_clone = report.clone();
+                    let tag = report.algorithm.tag_name().unwrap_or_default();
+                    let stamp = timestamps.format_for_file_name(report.finished_at);
+                    let dialog = match format {
+                        ManifestFormat::Csv => FileDialog::new()
+                            .add_filter("CSV", &["csv"])
+                            .set_file_name(format!("checksum_report {stamp}.csv")),
+                        ManifestFormat::Gnu => {
+                            FileDialog::new().set_file_name(format!("{tag}SUMS"))
+                        }
+                        ManifestFormat::Bsd => {
+                            FileDialog::new().set_file_name(format!("CHECKSUM.{tag}"))
+                        }
+                        ManifestFormat::Certificate => FileDialog::new()
+                            .add_filter(t!("checksum.export.text_file"), &["txt"])
+                            .set_file_name(format!("verification_certificate {stamp}.txt")),
+                        ManifestFormat::Json => FileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .set_file_name(format!("checksum_report {stamp}.json")),
+                        ManifestFormat::Mhl => FileDialog::new()
+                            .add_filter("MHL", &["mhl"])
+                            .set_file_name(format!("checksum_report {stamp}.mhl")),
+                    };
+
+                    Task::perform(
+                        async move {
+                            if let Some(path) = dialog.save_file() {
+                                match report_clone.export(path, format, &timestamps) {
+                                    Ok(()) => LibreCardMessage::ExportCompleted(Ok(())),
+                                    Err(err) => {
+                                        LibreCardMessage::ExportCompleted(Err(err.to_string()))
+                                    }
+                                }
+                            } else {
+                                LibreCardMessage::ExportCompleted(Ok(()))
+                            }
+                        },
+                        |msg| msg,
+                    )
+                } else {
+                    Task::none()
+                }
+            }
+
+            LibreCardMessage::CompareReports => {
+                let first_title = t!("compare.pick_first").into_owned();
+                let second_title = t!("compare.pick_second").into_owned();
+                Task::perform(
+                    async move {
+                        let Some(first) = FileDialog::new().set_title(first_title).pick_file()
+                        else {
+                            return Ok(None);
+                        };
+                        let Some(second) = FileDialog::new().set_title(second_title).pick_file()
+                        else {
+                            return Ok(None);
+                        };
+                        let read = {
+                            let (first, second) = (first.clone(), second.clone());
+                            tokio::task::spawn_blocking(move || {
+                                Ok::<_, io::Error>((
+                                    read_manifest(&first)?,
+                                    read_manifest(&second)?,
+                                ))
+                            })
+                        };
+                        match read.await.unwrap_or_else(|e| Err(e.into())) {
+                            Ok((a, b)) => Ok(Some((first, second, diff_manifests(&a, &b)))),
+                            Err(e) => Err(t!("compare.read_error", error = e).into_owned()),
+                        }
+                    },
+                    LibreCardMessage::ReportsCompared,
+                )
+            }
+
+            LibreCardMessage::ReportsCompared(result) => {
+                match result {
+                    Ok(Some((first, second, diff))) => {
+                        self.stage = LibreCardAppStage::ReportDiff {
+                            first,
+                            second,
+                            diff,
+                            folder: false,
+                        };
+                    }
+                    Ok(None) => {}
+                    Err(error) => self.error_message = Some(error),
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ChooseFileToHash => match FileDialog::new().pick_file() {
+                Some(path) => self.hash_file(path),
+                None => Task::none(),
+            },
+
+            LibreCardMessage::FileDropped(path) => {
+                // Folders are for jobs, and a drop elsewhere would replace what is on screen
+                if matches!(self.stage, LibreCardAppStage::Input) && path.is_file() {
+                    self.hash_file(path)
+                } else {
+                    Task::none()
+                }
+            }
+
+            LibreCardMessage::FileHashed(hashed, result) => {
+                // A file cancelled and picked again may finish after the new one started
+                if let LibreCardAppStage::HashFile { path, digest, .. } = &mut self.stage
+                    && *path == hashed
+                {
+                    *digest = Some(result);
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::CancelFileHash => {
+                if let LibreCardAppStage::HashFile { stop, .. } = &self.stage {
+                    stop.cancel();
+                }
+                self.stage = LibreCardAppStage::Input;
+                Task::none()
+            }
+
+            LibreCardMessage::CancelOperation => {
+                if let LibreCardAppStage::Copying { stop, .. }
+                | LibreCardAppStage::Checksumming { stop, .. } = &self.stage
+                {
+                    stop.cancel();
+                }
+                // The job ends instead of waiting for an answer
+                self.space_prompt = None;
+                self.permission_prompt = None;
+                Task::none()
+            }
+
+            LibreCardMessage::ExpectedHashChanged(value) => {
+                if let LibreCardAppStage::HashFile { expected, .. } = &mut self.stage {
+                    *expected = value;
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::CopyFileHash => match &self.stage {
+                LibreCardAppStage::HashFile {
+                    digest: Some(Ok(digest)),
+                    ..
+                } => iced::clipboard::write(format!("{digest:x}")),
+                _ => Task::none(),
+            },
+
+            LibreCardMessage::CopyJobId(job_id) => iced::clipboard::write(job_id.to_string()),
+
+            LibreCardMessage::VerifyManifest(partial) => {
+                let title = t!("verify_manifest.pick").into_owned();
+                let subfolder_title = t!("verify_manifest.pick_subfolder").into_owned();
+                Task::perform(
+                    async move {
+                        let Some(path) = FileDialog::new().set_title(title).pick_file() else {
+                            return Ok(None);
+                        };
+                        let read = {
+                            let path = path.clone();
+                            tokio::task::spawn_blocking(move || read_manifest(&path))
+                        };
+                        let manifest = match read.await.unwrap_or_else(|e| Err(e.into())) {
+                            Ok(manifest) => manifest,
+                            Err(e) => {
+                                return Err(t!("compare.read_error", error = e).into_owned());
+                            }
+                        };
+                        if !partial {
+                            return Ok(Some((path, manifest, None)));
+                        }
+
+                        let root = manifest_root(&path);
+                        let Some(folder) = FileDialog::new()
+                            .set_title(subfolder_title)
+                            .set_directory(&root)
+                            .pick_folder()
+                        else {
+                            return Ok(None);
+                        };
+                        match folder.strip_prefix(&root) {
+                            Ok(relative) => Ok(Some((
+                                path,
+                                manifest,
+                                Some(relative.to_string_lossy().into_owned()),
+                            ))),
+                            Err(_) => Err(t!(
+                                "verify_manifest.outside",
+                                folder = folder.display(),
+                                root = root.display()
+                            )
+                            .into_owned()),
+                        }
+                    },
+                    LibreCardMessage::ManifestOpened,
+                )
+            }
+
+            LibreCardMessage::ManifestOpened(result) => {
+                let (path, manifest, subfolder) = match result {
+                    Ok(Some(opened)) => opened,
+                    Ok(None) => return Task::none(),
+                    Err(error) => {
+                        self.error_message = Some(error);
+                        return Task::none();
+                    }
+                };
+                self.job_destinations = vec![manifest_root(&path)];
+                self.job_disabled = Vec::new();
+                let hash_options = self.config.hash_options();
+
+                let (tx, rx) = watch::channel(Progress::default());
+                let stop = CancellationToken::new();
+
+                // Hashes like a verification, so it shares that stage
+                self.job_serial += 1;
+                self.show_warnings = false;
+                self.stage = LibreCardAppStage::Checksumming {
+                    progress: Progress::default(),
+                    rx,
+                    stop: stop.clone(),
+                    started_at: Instant::now(),
+                    warnings: watch::channel(Vec::new()).1,
+                    workers: None,
+                };
+
+                Task::perform(
+                    async move {
+                        let result = verify_manifest_folder(
+                            &path,
+                            &manifest,
+                            subfolder.as_deref(),
+                            hash_options,
+                            tx,
+                            stop,
+                        )
+                        .await
+                        .map_err(|e| t!("checksum.error", error = e).into_owned());
+                        LibreCardMessage::ManifestVerified(path, result)
+                    },
+                    |msg| msg,
+                )
+            }
+
+            LibreCardMessage::ManifestVerified(path, result) => {
+                if self.quit_after_job {
+                    return self.quit();
+                }
+                match result {
+                    Ok(JobOutcome::Completed(diff)) => {
+                        let status = if diff.is_empty() {
+                            Status::Success
+                        } else {
+                            Status::Failure
+                        };
+                        self.last_result = Some((
+                            status,
+                            t!("status_bar.stage.manifest_verified").into_owned(),
+                        ));
+                        self.stage = LibreCardAppStage::ReportDiff {
+                            second: manifest_root(&path),
+                            first: path,
+                            diff,
+                            folder: true,
+                        };
+                    }
+                    Ok(JobOutcome::Cancelled(_)) => {
+                        let progress = self.current_progress();
+                        self.stage = LibreCardAppStage::Cancelled {
+                            completed: progress.completed_files,
+                            total: progress.total_files,
+                        };
+                        self.last_result =
+                            Some((Status::Warning, t!("status_bar.cancelled").into_owned()));
+                    }
+                    Err(error) => {
+                        self.stage = LibreCardAppStage::Input;
+                        tracing::error!("Job failed: {error}");
+                        self.error_message = Some(error);
+                        self.last_result =
+                            Some((Status::Failure, t!("status_bar.failed").into_owned()));
+                    }
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ExportFailedFiles => {
+                let LibreCardAppStage::CopyComplete { ref summary } = self.stage else {
+                    return Task::none();
+                };
+                let summary = summary.clone();
+                let stamp = self
+                    .timestamp_format()
+                    .format_for_file_name(SystemTime::now());
+                let dialog = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name(format!("failed_files {stamp}.csv"));
+                Task::perform(
+                    async move {
+                        match dialog.save_file() {
+                            Some(path) => summary.export_failed(path).map_err(|e| e.to_string()),
+                            None => Ok(()),
+                        }
+                    },
+                    LibreCardMessage::ExportCompleted,
+                )
+            }
+
+            LibreCardMessage::ExportReportDiff => {
+                let LibreCardAppStage::ReportDiff { ref diff, .. } = self.stage else {
+                    return Task::none();
+                };
+                let diff = diff.clone();
+                let stamp = self
+                    .timestamp_format()
+                    .format_for_file_name(SystemTime::now());
+                let dialog = FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name(format!("report_differences {stamp}.csv"));
+                Task::perform(
+                    async move {
+                        match dialog.save_file() {
+                            Some(path) => diff.export(path).map_err(|e| e.to_string()),
+                            None => Ok(()),
+                        }
+                    },
+                    LibreCardMessage::ExportCompleted,
+                )
+            }
+
+            LibreCardMessage::ExportCompleted(result) => {
+                if let Err(error) = result {
+                    self.error_message =
+                        Some(t!("checksum.export.error", error = error).into_owned());
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::DismissError => {
+                self.error_message = None;
+                Task::none()
+            }
+
+            LibreCardMessage::BackToInput => {
+                self.stage = LibreCardAppStage::Input;
+                // The job may have left markers in them
+                Task::batch(
+                    self.destinations
+                        .iter()
+                        .filter_map(|destination| destination.path.clone())
+                        .map(find_markers),
+                )
+            }
+
+            LibreCardMessage::ViewLastJob => {
+                if let Some(last_job) = self.last_job.clone() {
+                    self.job_destinations = last_job.destinations;
+                    self.expanded_missing = None;
+                    self.expanded_group = None;
+                    self.stage = LibreCardAppStage::ChecksumComplete {
+                        fingerprint: last_job.report.fingerprint(),
+                        manifest_digest: last_job.report.manifest_digest(),
+                        report: last_job.report,
+                    };
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::ExportLastJob => {
+                let view = self.update(LibreCardMessage::ViewLastJob);
+                let export = self.update(LibreCardMessage::ExportChecksum(ManifestFormat::Csv));
+                Task::batch([view, export])
+            }
+
+            LibreCardMessage::RerunJob(index) => {
+                let Some(job) = self.job_history.jobs.get(index).cloned() else {
+                    return Task::none();
+                };
+                self.destinations = job
+                    .destinations
+                    .iter()
+                    .map(|destination| Destination {
+                        path: Some(destination.directory.clone()),
+                        typed: None,
+                        label: destination.label.clone(),
+                        subroot: destination.subroot.clone(),
+                        filter: destination.filter.clone(),
+                        enabled: destination.enabled,
+                    })
+                    .collect();
+                if self.config.separate_sessions != job.separate_sessions {
+                    self.config.separate_sessions = job.separate_sessions;
+                    self.save_config();
+                }
+
+                // The card is a new one, so only the source is asked for
+                self.source_directory = None;
+                let directories: Vec<PathBuf> = job
+                    .destinations
+                    .into_iter()
+                    .map(|destination| destination.directory)
+                    .collect();
+                let mut tasks: Vec<_> =
+                    directories.iter().cloned().map(check_destination).collect();
+                // A drive that isn't plugged in leaves a folder that is merely missing, which the
+                // reachability check accepts because the copy would create it
+                tasks.push(Task::perform(
+                    async move {
+                        let mut missing = Vec::new();
+                        for directory in directories {
+                            if !tokio::fs::try_exists(&directory).await.unwrap_or(false) {
+                                missing.push(directory);
+                            }
+                        }
+                        missing
+                    },
+                    LibreCardMessage::RerunDestinationsChecked,
+                ));
+                tasks.push(self.scan_source());
+                tasks.push(Task::done(LibreCardMessage::OpenSourceDirectoryDialog));
+                Task::batch(tasks)
+            }
+
+            LibreCardMessage::RerunDestinationsChecked(missing) => {
+                if !missing.is_empty() {
+                    self.warning_message = Some(
+                        t!(
+                            "history.missing_destinations",
+                            paths = missing
+                                .iter()
+                                .map(|path| path.display().to_string())
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        )
+                        .into_owned(),
+                    );
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::LastJobSaved(result) => {
+                if let Err(e) = result {
+                    self.warning_message = Some(t!("last_job.save_error", error = e).into_owned());
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::VerifiedProtected(failures) => {
+                for (path, error) in &failures {
+                    tracing::warn!("Couldn't make {} read-only: {error}", path.display());
+                }
+                if let Some((path, error)) = failures.first() {
+                    self.warning_message = Some(
+                        t!(
+                            "read_only.failed",
+                            count = format_count(failures.len()),
+                            path = path.display(),
+                            error = error
+                        )
+                        .into_owned(),
+                    );
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::OffloadMarkersWritten(result) => {
+                if let Err(e) = result {
+                    self.warning_message = Some(t!("marker.write_error", error = e).into_owned());
+                }
+                Task::none()
+            }
+
+            LibreCardMessage::DismissWarning => {
+                self.warning_message = None;
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleMissingList(index) => {
+                self.expanded_missing = match self.expanded_missing {
+                    Some(expanded) if expanded == index => None,
+                    _ => Some(index),
+                };
+                Task::none()
+            }
+
+            LibreCardMessage::CopyCrashDetails => match &self.stage {
+                LibreCardAppStage::Recovery(report) => iced::clipboard::write(report.details()),
+                _ => Task::none(),
+            },
+
+            LibreCardMessage::DismissCrashReport => {
+                CrashReport::clear();
+                self.stage = LibreCardAppStage::Input;
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleGroup(name) => {
+                self.expanded_group = match &self.expanded_group {
+                    Some(expanded) if *expanded == name => None,
+                    _ => Some(name),
+                };
+                Task::none()
+            }
+
+            LibreCardMessage::WindowChanged(event) => {
+                update_geometry(&mut self.config.window, &event);
+                Task::none()
+            }
+
+            LibreCardMessage::MoveFocus { backwards } => match backwards {
+                true => focus_previous(),
+                false => focus_next(),
+            },
+
+            LibreCardMessage::WindowCloseRequested => {
+                if self.is_job_running() {
+                    // Closing now would truncate the file being written
+                    self.close_prompt = true;
+                    Task::none()
+                } else {
+                    self.quit()
+                }
+            }
+
+            LibreCardMessage::FinishAndQuit => {
+                self.close_prompt = false;
+                self.quit_after_job = true;
+                match &self.stage {
+                    // The file being copied is completed first
+                    LibreCardAppStage::Copying { finish, .. } => {
+                        finish.cancel();
+                        Task::none()
+                    }
+                    // A verification leaves nothing half-done
+                    LibreCardAppStage::Checksumming { stop, .. } => {
+                        stop.cancel();
+                        Task::none()
+                    }
+                    // The job finished while the prompt was shown
+                    _ => self.quit(),
+                }
+            }
+
+            LibreCardMessage::KeepRunning => {
+                self.close_prompt = false;
+                Task::none()
+            }
+
+            LibreCardMessage::LogRecorded(entry) => {
+                push_entry(&mut self.console, entry);
+                Task::none()
+            }
+
+            LibreCardMessage::ToggleConsole => {
+                self.console_open = !self.console_open;
+                Task::none()
+            }
+
+            LibreCardMessage::ConsoleLevelSelected(choice) => {
+                self.console_level = choice;
+                Task::none()
+            }
+
+            LibreCardMessage::CopyConsole => {
+                let lines: Vec<String> = self
+                    .visible_console()
+                    .map(|entry| entry.to_string())
+                    .collect();
+                iced::clipboard::write(lines.join("\n"))
+            }
+        }
+    }
+
+    /// Sizes for the views: compact if chosen in the settings, or by default while the window is
+    /// too short for the regular layout.
+    fn metrics(&self) -> Metrics {
+        let compact = match self.config.layout_density {
+            LayoutDensity::Auto => self
+                .config
+                .window
+                .is_some_and(|window| window.height < COMPACT_BELOW_HEIGHT),
+            LayoutDensity::Regular => false,
+            LayoutDensity::Compact => true,
+        };
+        match compact {
+            true => Metrics::COMPACT,
+            false => Metrics::REGULAR,
+        }
+    }
+
+    /// Whether to show the information the compact layout collapses.
+    fn details_shown(&self) -> bool {
+        !self.metrics().collapse_details || self.show_details
+    }
+
+    /// Button expanding the collapsed information, only in the compact layout.
+    fn view_details_toggle(&self) -> Option<Element<'_, LibreCardMessage>> {
+        let metrics = self.metrics();
+        metrics.collapse_details.then(|| {
+            button(
+                text(if self.show_details {
+                    t!("details.hide")
+                } else {
+                    t!("details.show")
+                })
+                .size(metrics.small),
+            )
+            .style(iced::widget::button::text)
+            .on_press(LibreCardMessage::ToggleDetails)
+            .into()
+        })
+    }
+
+    /// Events on the console at or above the chosen level.
+    fn visible_console(&self) -> impl Iterator<Item = &LogEntry> {
+        self.console
+            .iter()
+            .filter(|entry| entry.level <= self.console_level.0)
+    }
+
+    fn quit(&mut self) -> Task<LibreCardMessage> {
+        // Nowhere left to show a save error
+        let _ = self.config.save();
+        iced::exit()
+    }
+
+    pub fn view(&self) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let content = match &self.stage {
+            LibreCardAppStage::Input => self.view_input_stage(),
+            LibreCardAppStage::Settings => self.view_settings_stage(),
+            LibreCardAppStage::Copying {
+                progress,
+                started_at,
+                warnings,
+                rates,
+                ..
+            } => self.view_copy_stage(progress, *started_at, &warnings.borrow(), rates),
+            LibreCardAppStage::CopyComplete { summary } => self.view_copy_complete_stage(summary),
+            LibreCardAppStage::Checksumming {
+                progress,
+                started_at,
+                warnings,
+                workers,
+                ..
+            } => self.view_checksum_stage(
+                progress,
+                *started_at,
+                &warnings.borrow(),
+                workers.as_ref().map(|workers| *workers.borrow()),
+            ),
+            LibreCardAppStage::ChecksumComplete {
+                report,
+                fingerprint,
+                manifest_digest,
+            } => self.view_checksum_complete_stage(report, fingerprint, manifest_digest),
+            LibreCardAppStage::ReconcileComplete { report } => {
+                self.view_reconcile_complete_stage(report)
+            }
+            LibreCardAppStage::ReadabilityComplete { report } => {
+                self.view_readability_complete_stage(report)
+            }
+            LibreCardAppStage::ReportDiff {
+                first,
+                second,
+                diff,
+                folder,
+            } => self.view_report_diff_stage(first, second, diff, *folder),
+            LibreCardAppStage::HashFile {
+                path,
+                algorithm,
+                progress,
+                digest,
+                expected,
+                ..
+            } => self.view_hash_file_stage(path, *algorithm, progress, digest.as_ref(), expected),
+            LibreCardAppStage::Cancelled { completed, total } => {
+                self.view_cancelled_stage(*completed, *total)
+            }
+            LibreCardAppStage::Recovery(report) => self.view_recovery_stage(report),
+        };
+
+        let content: Element<LibreCardMessage> = if let Some(file) = &self.permission_prompt {
+            column![content, self.view_permission_prompt(file)]
+                .spacing(metrics.spacing)
+                .into()
+        } else {
+            content
+        };
+        let content: Element<LibreCardMessage> = if let Some(full) = &self.space_prompt {
+            column![content, self.view_space_prompt(full)]
+                .spacing(metrics.spacing)
+                .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> =
+            if let Some(placeholders) = &self.placeholder_prompt {
+                column![content, self.view_placeholder_prompt(placeholders)]
+                    .spacing(metrics.spacing)
+                    .into()
+            } else if let Some(unreadable) = &self.unreadable_prompt {
+                column![content, self.view_unreadable_prompt(unreadable)]
+                    .spacing(metrics.spacing)
+                    .into()
+            } else if let Some(growing) = &self.growing_prompt {
+                column![content, self.view_growing_prompt(growing)]
+                    .spacing(metrics.spacing)
+                    .into()
+            } else {
+                content
+            };
+
+        let content: Element<LibreCardMessage> = if let Some(collisions) = &self.overwrite_prompt {
+            column![content, self.view_overwrite_prompt(collisions)]
+                .spacing(metrics.spacing)
+                .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> = if let Some(rows) = &self.empty_rows_prompt {
+            column![content, self.view_empty_rows_prompt(rows)]
+                .spacing(metrics.spacing)
+                .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> = if self.shortfall_prompt {
+            column![content, self.view_shortfall_prompt()]
+                .spacing(metrics.spacing)
+                .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> = if let Some(nonempty) = &self.nonempty_prompt {
+            column![content, self.view_nonempty_prompt(nonempty)]
+                .spacing(metrics.spacing)
+                .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> = if let Some(preview) = &self.preview_prompt {
+            column![content, self.view_preview_prompt(preview)]
+                .spacing(metrics.spacing)
+                .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> = if self.close_prompt {
+            column![content, self.view_close_prompt()]
+                .spacing(metrics.spacing)
+                .into()
+        } else if self.quit_after_job {
+            column![
+                content,
+                status_text(
+                    Status::Warning,
+                    t!("close.finishing"),
+                    self.config.high_contrast
+                )
+            ]
+            .spacing(metrics.spacing)
+            .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> = if let Some(release) = &self.available_update {
+            column![
+                content,
+                container(
+                    row![
+                        text(t!("update.available", version = &release.version))
+                            .width(Length::Fill),
+                        button(text(t!("update.open"))).on_press(LibreCardMessage::OpenReleasePage),
+                        button(text(t!("dismiss"))).on_press(LibreCardMessage::DismissUpdate),
+                    ]
+                    .spacing(metrics.tight_spacing)
+                    .align_y(iced::alignment::Alignment::Center)
+                )
+                .width(Length::Fill)
+                .padding(metrics.padding)
+            ]
+            .spacing(metrics.spacing)
+            .into()
+        } else if self.config.check_for_updates.is_none() {
+            column![
+                content,
+                container(
+                    row![
+                        text(t!("update.ask")).width(Length::Fill),
+                        button(text(t!("update.ask.yes")))
+                            .on_press(LibreCardMessage::ToggleUpdateCheck(true)),
+                        button(text(t!("update.ask.no")))
+                            .on_press(LibreCardMessage::ToggleUpdateCheck(false)),
+                    ]
+                    .spacing(metrics.tight_spacing)
+                    .align_y(iced::alignment::Alignment::Center)
+                )
+                .width(Length::Fill)
+                .padding(metrics.padding)
+            ]
+            .spacing(metrics.spacing)
+            .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> = if let Some(warning) = &self.warning_message {
+            column![
+                content,
+                container(
+                    column![
+                        status_text(Status::Warning, warning, self.config.high_contrast),
+                        button(text(t!("dismiss"))).on_press(LibreCardMessage::DismissWarning),
+                    ]
+                    .spacing(metrics.tight_spacing)
+                )
+                .width(Length::Fill)
+                .padding(metrics.padding)
+            ]
+            .spacing(metrics.spacing)
+            .into()
+        } else {
+            content
+        };
+
+        let content: Element<LibreCardMessage> = if let Some(error) = &self.error_message {
+            column![
+                content,
+                container(
+                    column![
+                        status_text(Status::Failure, error, self.config.high_contrast),
+                        button(text(t!("dismiss"))).on_press(LibreCardMessage::DismissError),
+                    ]
+                    .spacing(metrics.tight_spacing)
+                )
+                .width(Length::Fill)
+                .padding(metrics.padding)
+            ]
+            .spacing(metrics.spacing)
+            .into()
+        } else {
+            content
+        };
+
+        let mut layout = column![container(content).width(Length::Fill).height(Length::Fill)];
+        if self.console_open {
+            layout = layout.push(self.view_console());
+        }
+        layout
+            .push(self.view_status_bar())
+            .spacing(metrics.tight_spacing)
+            .padding(metrics.padding)
+            .into()
+    }
+
+    /// The latest tracing events, for troubleshooting without finding a log file.
+    fn view_console(&self) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let lines = column(self.visible_console().map(|entry| {
+            let line = match entry.level {
+                Level::ERROR => status_text(Status::Failure, entry, self.config.high_contrast),
+                Level::WARN => status_text(Status::Warning, entry, self.config.high_contrast),
+                _ => text(entry.to_string()),
+            };
+            line.size(metrics.small).into()
+        }));
+
+        column![
+            row![
+                text(t!("console.title")).width(Length::Fill),
+                pick_list(
+                    ConsoleLevelChoice::ALL,
+                    Some(self.console_level),
+                    LibreCardMessage::ConsoleLevelSelected,
+                ),
+                button(text(t!("console.copy"))).on_press(LibreCardMessage::CopyConsole),
+            ]
+            .spacing(metrics.tight_spacing)
+            .align_y(iced::alignment::Alignment::Center),
+            // Newest events at the bottom, like a terminal
+            scrollable(lines)
+                .width(Length::Fill)
+                .height(Length::Fixed(metrics.list_height))
+                .anchor_bottom(),
+        ]
+        .spacing(metrics.tight_spacing)
+        .into()
+    }
+
+    /// One line of context that stays at the bottom of every stage.
+    fn view_status_bar(&self) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let stage = match self.stage {
+            LibreCardAppStage::Input => t!("status_bar.stage.input"),
+            LibreCardAppStage::Settings => t!("status_bar.stage.settings"),
+            LibreCardAppStage::Copying { .. } => t!("status_bar.stage.copying"),
+            LibreCardAppStage::CopyComplete { .. } => t!("status_bar.stage.copy_complete"),
+            LibreCardAppStage::Checksumming { .. } => t!("status_bar.stage.checksumming"),
+            LibreCardAppStage::ChecksumComplete { .. } => {
+                t!("status_bar.stage.checksum_complete")
+            }
+            LibreCardAppStage::ReconcileComplete { .. } => t!("status_bar.stage.reconciled"),
+            LibreCardAppStage::ReadabilityComplete { .. } => t!("status_bar.stage.read_twice"),
+            LibreCardAppStage::ReportDiff { folder: false, .. } => {
+                t!("status_bar.stage.reports_compared")
+            }
+            LibreCardAppStage::ReportDiff { folder: true, .. } => {
+                t!("status_bar.stage.manifest_verified")
+            }
+            LibreCardAppStage::HashFile { .. } => t!("status_bar.stage.hash_file"),
+            LibreCardAppStage::Cancelled { .. } => t!("status_bar.stage.cancelled"),
+            LibreCardAppStage::Recovery(_) => t!("status_bar.stage.recovery"),
+        };
+
+        // A running job has settled its destinations; otherwise count the selected rows
+        let destinations = if self.is_idle() || matches!(self.stage, LibreCardAppStage::Settings) {
+            self.resolved_destinations().len()
+        } else {
+            self.job_destinations.len()
+        };
+
+        let mut bar = row![
+            text(stage).size(metrics.small),
+            text(t!(
+                "status_bar.destinations",
+                count = format_count(destinations)
+            ))
+            .size(metrics.small),
+            text(self.config.hash_algorithm.to_string()).size(metrics.small),
+        ]
+        .spacing(metrics.spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        if let Some((status, message)) = &self.last_result {
+            bar = bar.push(
+                status_text(
+                    *status,
+                    t!("status_bar.last_job", result = message),
+                    self.config.high_contrast,
+                )
+                .size(metrics.small),
+            );
+        }
+
+        // Pushed to the corner of the window
+        bar.push(horizontal_space())
+            .push(
+                button(
+                    text(if self.console_open {
+                        t!("console.hide")
+                    } else {
+                        t!("console.show")
+                    })
+                    .size(metrics.small),
+                )
+                .padding([2, 8])
+                .on_press(LibreCardMessage::ToggleConsole),
+            )
+            .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<LibreCardMessage> {
+        let window_events = event::listen_with(|event, _, _| match event {
+            iced::Event::Window(
+                event @ (window::Event::Opened { .. }
+                | window::Event::Resized(_)
+                | window::Event::Moved(_)),
+            ) => Some(LibreCardMessage::WindowChanged(event)),
+            iced::Event::Window(window::Event::FileDropped(path)) => {
+                Some(LibreCardMessage::FileDropped(path))
+            }
+            _ => None,
+        });
+        let close_requests =
+            window::close_requests().map(|_| LibreCardMessage::WindowCloseRequested);
+
+        let stage_events = match &self.stage {
+            LibreCardAppStage::Copying {
+                rx,
+                asking,
+                space_asking,
+                ..
+            } => Subscription::batch([
+                Subscription::run_with_id(
+                    ("progress", self.job_serial),
+                    progress_updates(rx.clone()),
+                ),
+                Subscription::run_with_id(
+                    ("permission", self.job_serial),
+                    permission_prompts(asking.clone()),
+                ),
+                Subscription::run_with_id(
+                    ("space", self.job_serial),
+                    space_prompts(space_asking.clone()),
+                ),
+                time::every(Duration::from_secs(1)).map(|_| LibreCardMessage::Tick),
+            ]),
+            LibreCardAppStage::Checksumming { rx, .. } => Subscription::batch([
+                Subscription::run_with_id(
+                    ("progress", self.job_serial),
+                    progress_updates(rx.clone()),
+                ),
+                time::every(Duration::from_secs(1)).map(|_| LibreCardMessage::Tick),
+            ]),
+            LibreCardAppStage::HashFile {
+                rx, digest: None, ..
+            } => Subscription::run_with_id(
+                ("progress", self.job_serial),
+                progress_updates(rx.clone()),
+            ),
+            _ if self.is_idle() && self.config.card_watch.enabled => {
+                time::every(Duration::from_secs(2)).map(|_| LibreCardMessage::PollVolumes)
+            }
+            _ => Subscription::none(),
+        };
+
+        let log_events = Subscription::run(console_events).map(LibreCardMessage::LogRecorded);
+
+        // Text fields let Tab through, so it reaches here wherever the focus is
+        let focus_keys = keyboard::on_key_press(|key, modifiers| match key {
+            keyboard::Key::Named(keyboard::key::Named::Tab) => Some(LibreCardMessage::MoveFocus {
+                backwards: modifiers.shift(),
+            }),
+            _ => None,
+        });
+
+        Subscription::batch([
+            window_events,
+            close_requests,
+            stage_events,
+            log_events,
+            focus_keys,
+        ])
+    }
+
+    fn is_job_running(&self) -> bool {
+        matches!(
+            self.stage,
+            LibreCardAppStage::Copying { .. } | LibreCardAppStage::Checksumming { .. }
+        )
+    }
+
+    /// Whether the job should list the files it would overwrite first. The watcher copies
+    /// unattended, so it can't ask.
+    fn ask_overwrite(&self) -> bool {
+        self.config.overwrite_policy == OverwritePolicy::Ask && !self.auto_verify
+    }
+
+    /// Whether to confirm copying into destinations that already hold files. The watcher copies
+    /// into the same destinations every time.
+    fn confirm_nonempty(&self) -> bool {
+        !self.auto_verify && self.nonempty_confirmed_for.as_ref() != Some(&self.job_destinations)
+    }
+
+    /// The files of the source the current job copies: those in its date window that the
+    /// baseline doesn't already hold.
+    fn job_listing(&self) -> SourceListing {
+        let mut listing = self
+            .cached_listing()
+            .unwrap_or_default()
+            .within(self.job_window);
+        listing
+            .files
+            .retain(|(file, _)| !self.job_ignore.ignores(file));
+        if let Some(baseline) = &self.job_baseline {
+            let offloaded: HashSet<&PathBuf> = baseline.files.iter().collect();
+            listing.files.retain(|(file, _)| !offloaded.contains(file));
+        }
+        listing
+    }
+
+    /// The window of modification times typed in for the source, or why it can't be used.
+    fn modified_window(&self) -> Result<ModifiedWindow, String> {
+        let parse = |typed: &str| match typed.trim() {
+            "" => Ok(None),
+            typed => parse_local_time(typed)
+                .map(Some)
+                .map_err(|error| t!("src_folder.date_invalid", error = error).into_owned()),
+        };
+        Ok(ModifiedWindow {
+            after: parse(&self.modified_after)?,
+            before: parse(&self.modified_before)?,
+        })
+    }
+
+    /// Works out how the files the job copies fit on each destination, leaving the free space
+    /// margin.
+    /// Tries a small write at each destination of the job, so one that can't be written to stops
+    /// the job before it copies anything.
+    fn probe_destinations(&self) -> Task<LibreCardMessage> {
+        let destinations = self.job_destinations.clone();
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    destinations
+                        .into_iter()
+                        .map(|destination| {
+                            let result = probe_writable(&destination).map_err(|e| match e.kind() {
+                                io::ErrorKind::ReadOnlyFilesystem => {
+                                    t!("dst_folder.read_only").into_owned()
+                                }
+                                io::ErrorKind::PermissionDenied => {
+                                    t!("dst_folder.write_denied").into_owned()
+                                }
+                                _ => e.to_string(),
+                            });
+                            (destination, result)
+                        })
+                        .collect()
+                })
+                .await
+                .unwrap_or_default()
+            },
+            LibreCardMessage::WritabilityProbed,
+        )
+    }
+
+    fn forecast_space(&self) -> Task<LibreCardMessage> {
+        let listing = self.job_listing();
+        let written: Vec<(PathBuf, Vec<(PathBuf, u64)>)> = self
+            .job_destinations
+            .iter()
+            .zip(&self.job_filters)
+            .map(|(destination, filter)| {
+                let files = listing
+                    .files
+                    .iter()
+                    .filter(|(file, stamp)| {
+                        filter.matches(file)
+                            && !self.job_excluded.contains(file)
+                            && !(self.job_skip_placeholders && stamp.placeholder)
+                    })
+                    .map(|(file, stamp)| (file.clone(), stamp.size))
+                    .collect();
+                (destination.clone(), files)
+            })
+            .collect();
+        let margin = self.config.free_space_margin;
+        let skip_existing = self.config.copy_options().skip_existing;
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    // Files the copy keeps need no room
+                    let required: Vec<(PathBuf, u64)> = written
+                        .into_iter()
+                        .map(|(destination, files)| {
+                            let bytes: u64 = files.iter().map(|(_, size)| size).sum();
+                            let in_place = bytes_in_place(&destination, &files, skip_existing);
+                            (destination, bytes - in_place)
+                        })
+                        .collect();
+                    forecast_space(&required, margin)
+                })
+                .await
+                .unwrap_or_default()
+            },
+            LibreCardMessage::SpaceForecasted,
+        )
+    }
+
+    /// Whether no job is running, i.e. the card watcher may start one.
+    fn is_idle(&self) -> bool {
+        matches!(
+            self.stage,
+            LibreCardAppStage::Input
+                | LibreCardAppStage::ChecksumComplete { .. }
+                | LibreCardAppStage::ReconcileComplete { .. }
+                | LibreCardAppStage::ReadabilityComplete { .. }
+                | LibreCardAppStage::ReportDiff { .. }
+                | LibreCardAppStage::Cancelled { .. }
+        )
+    }
+
+    /// Last progress reported by the running job.
+    fn current_progress(&self) -> Progress {
+        match &self.stage {
+            LibreCardAppStage::Copying { progress, .. }
+            | LibreCardAppStage::Checksumming { progress, .. } => progress.clone(),
+            _ => Progress::default(),
+        }
+    }
+}
+
+/// Widget ID of the path field of the destination row at `index`.
+fn destination_input_id(index: usize) -> text_input::Id {
+    text_input::Id::new(format!("destination-{index}"))
+}
+
+/// Emits a message whenever the job publishes new progress. Updates arriving in quick succession
+/// are coalesced, so fast transfers don't flood the UI with redraws.
+fn progress_updates(rx: watch::Receiver<Progress>) -> impl Stream<Item = LibreCardMessage> {
+    const MIN_INTERVAL: Duration = Duration::from_millis(50);
+
+    futures::stream::unfold(rx, |mut rx| async move {
+        rx.changed().await.ok()?;
+        let progress = rx.borrow_and_update().clone();
+        tokio::time::sleep(MIN_INTERVAL).await;
+        Some((LibreCardMessage::ProgressUpdated(progress), rx))
+    })
+}
+
+/// Emits a message whenever the job starts or stops waiting on a file it isn't allowed to read.
+fn permission_prompts(
+    rx: watch::Receiver<Option<PathBuf>>,
+) -> impl Stream<Item = LibreCardMessage> {
+    futures::stream::unfold(rx, |mut rx| async move {
+        rx.changed().await.ok()?;
+        let file = rx.borrow_and_update().clone();
+        Some((LibreCardMessage::PermissionDenied(file), rx))
+    })
+}
+
+/// Emits a message whenever the job starts or stops waiting on a destination that is full.
+fn space_prompts(
+    rx: watch::Receiver<Option<FullDestination>>,
+) -> impl Stream<Item = LibreCardMessage> {
+    futures::stream::unfold(rx, |mut rx| async move {
+        rx.changed().await.ok()?;
+        let full = rx.borrow_and_update().clone();
+        Some((LibreCardMessage::DestinationFull(full), rx))
+    })
+}
+
+impl LibreCardApp {
+    fn view_input_stage(&self) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("input.title"))
+            .size(metrics.title)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        // Source directory
+        let source_path = match &self.source_directory {
+            Some(path) => path_text(path, metrics.emphasis),
+            None => text(t!("folder_not_selected")).into(),
+        };
+
+        let source_row = row![
+            text(t!("src_folder")).width(Length::FillPortion(1)),
+            container(source_path)
+                .padding(metrics.field_padding)
+                .width(Length::FillPortion(3)),
+            button(text(t!("browse_folder"))).on_press(LibreCardMessage::OpenSourceDirectoryDialog),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        let window = self.modified_window();
+        let date_row = row![
+            text(t!("src_folder.modified")).width(Length::FillPortion(1)),
+            text_input(&t!("src_folder.modified_after"), &self.modified_after)
+                .on_input(LibreCardMessage::ModifiedAfterChanged)
+                .padding(metrics.field_padding)
+                .width(Length::FillPortion(2)),
+            text_input(&t!("src_folder.modified_before"), &self.modified_before)
+                .on_input(LibreCardMessage::ModifiedBeforeChanged)
+                .padding(metrics.field_padding)
+                .width(Length::FillPortion(2)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+        // The bounds are typed in local time, so show what they mean in the UTC of the reports
+        let date_hint: Option<Element<'_, LibreCardMessage>> = match &window {
+            Err(error) => {
+                Some(status_text(Status::Warning, error, self.config.high_contrast).into())
+            }
+            Ok(window) if window.is_unbounded() => None,
+            Ok(window) => {
+                let offset = format_utc_offset(local_utc_offset(SystemTime::now()));
+                let bound = |time: Option<SystemTime>| {
+                    time.map_or_else(|| t!("src_folder.unbounded").into_owned(), format_time)
+                };
+                Some(
+                    text(t!(
+                        "src_folder.window",
+                        offset = offset,
+                        after = bound(window.after),
+                        before = bound(window.before)
+                    ))
+                    .size(metrics.small)
+                    .into(),
+                )
+            }
+        };
+
+        let baseline_label: Element<'_, LibreCardMessage> = match &self.baseline {
+            Some((path, manifest)) => text(t!(
+                "baseline.chosen",
+                path = path.display(),
+                count = format_count(manifest.entries.len())
+            ))
+            .into(),
+            None => text(t!("baseline.none")).into(),
+        };
+        let mut baseline_row = row![
+            text(t!("baseline")).width(Length::FillPortion(1)),
+            container(baseline_label).width(Length::FillPortion(3)),
+            button(text(t!("baseline.choose"))).on_press(LibreCardMessage::ChooseBaseline),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+        if self.baseline.is_some() {
+            baseline_row = baseline_row
+                .push(
+                    checkbox(t!("baseline.compare_hashes"), self.baseline_hashes)
+                        .on_toggle(LibreCardMessage::ToggleBaselineHashes),
+                )
+                .push(button(text(t!("baseline.clear"))).on_press(LibreCardMessage::ClearBaseline));
+        }
+
+        // Totals to check the right card was picked
+        let source_summary: Option<Element<'_, LibreCardMessage>> =
+            match (&self.source_scan, &self.source_listing) {
+                (Some(_), _) => Some(text(t!("src_folder.counting")).into()),
+                (None, Some((_, Ok(listing)))) if listing.capped => Some(
+                    column![
+                        status_text(
+                            Status::Warning,
+                            t!(
+                                "src_folder.capped",
+                                count = format_count(listing.files.len())
+                            ),
+                            self.config.high_contrast,
+                        ),
+                        button(text(t!("src_folder.scan_all")))
+                            .on_press(LibreCardMessage::ScanWholeSource),
+                    ]
+                    .spacing(metrics.tight_spacing)
+                    .into(),
+                ),
+                (None, Some((_, Ok(listing)))) => Some(
+                    column![match &window {
+                        Ok(window) if !window.is_unbounded() => {
+                            let in_window = listing.within(*window);
+                            text(t!(
+                                "src_folder.summary_window",
+                                count = format_count(in_window.files.len()),
+                                size = format_bytes(in_window.total_bytes()),
+                                total = format_count(listing.files.len())
+                            ))
+                        }
+                        _ => text(t!(
+                            "src_folder.summary",
+                            count = format_count(listing.files.len()),
+                            size = format_bytes(listing.total_bytes())
+                        )),
+                    }]
+                    // Folders below the depth limit would otherwise vanish from the job unseen
+                    .push_maybe((!listing.pruned.is_empty()).then(|| {
+                        status_text(
+                            Status::Warning,
+                            t!(
+                                "src_folder.pruned",
+                                count = format_count(listing.pruned.len())
+                            ),
+                            self.config.high_contrast,
+                        )
+                    }))
+                    .spacing(metrics.tight_spacing)
+                    .into(),
+                ),
+                (None, Some((_, Err(error)))) => {
+                    Some(status_text(Status::Warning, error, self.config.high_contrast).into())
+                }
+                (None, None) => None,
+            };
+
+        // Destination directories
+        let mut destination_rows = Vec::new();
+        for (idx, destination) in self.destinations.iter().enumerate() {
+            let dest_opt = &destination.path;
+            let dest_path = dest_opt
+                .as_ref()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let typed = destination.typed.as_ref().unwrap_or(&dest_path);
+
+            let path_input = text_input(&t!("folder_not_selected"), typed)
+                .id(destination_input_id(idx))
+                .on_input(move |s| LibreCardMessage::DestinationPathChanged(idx, s))
+                .padding(metrics.field_padding);
+            // The field scrolls to the cursor, so a long path is only readable in full on hover
+            let path_input: Element<'_, LibreCardMessage> = match dest_opt {
+                Some(_) => tooltip(
+                    path_input,
+                    container(text(dest_path.clone()))
+                        .padding(5)
+                        .style(container::rounded_box),
+                    tooltip::Position::Bottom,
+                )
+                .into(),
+                None => path_input.into(),
+            };
+
+            let mut row_elements = vec![
+                tooltip(
+                    checkbox("", destination.enabled).on_toggle(move |enabled| {
+                        LibreCardMessage::ToggleDestinationEnabled(idx, enabled)
+                    }),
+                    container(text(t!("dst_folder.enabled")))
+                        .padding(5)
+                        .style(container::rounded_box),
+                    tooltip::Position::Bottom,
+                )
+                .into(),
+                text_input(&t!("dst_folder.label", index = idx + 1), &destination.label)
+                    .on_input(move |s| LibreCardMessage::DestinationLabelChanged(idx, s))
+                    .padding(metrics.field_padding)
+                    .width(Length::FillPortion(1))
+                    .into(),
+                container(path_input).width(Length::FillPortion(3)).into(),
+                text_input(&t!("dst_folder.subroot"), &destination.subroot)
+                    .on_input(move |s| LibreCardMessage::DestinationSubrootChanged(idx, s))
+                    .padding(metrics.field_padding)
+                    .width(Length::FillPortion(1))
+                    .into(),
+                text_input(&t!("dst_folder.filter"), &destination.filter)
+                    .on_input(move |s| LibreCardMessage::DestinationFilterChanged(idx, s))
+                    .padding(metrics.field_padding)
+                    .width(Length::FillPortion(1))
+                    .into(),
+                button(text(t!("browse_folder")))
+                    .on_press(LibreCardMessage::OpenDestinationDirectoryDialog(idx))
+                    .into(),
+            ];
+
+            // Add remove button if more than one destination exists
+            if self.destinations.len() > 1 {
+                row_elements.push(
+                    button(text(t!("dst_folder.delete")))
+                        .on_press(LibreCardMessage::RemoveDestinationDirectory(idx))
+                        .into(),
+                );
+            }
+
+            let destination_row = row(row_elements)
+                .spacing(metrics.tight_spacing)
+                .align_y(iced::alignment::Alignment::Center);
+            // A disabled row is often a drive that isn't plugged in today
+            let problem = dest_opt
+                .as_ref()
+                .filter(|_| destination.enabled)
+                .and_then(|path| self.destination_reachability.get(path))
+                .and_then(|reachability| match reachability {
+                    Reachability::Reachable => None,
+                    Reachability::Unreachable => Some(t!("dst_folder.unreachable")),
+                    Reachability::AccessDenied => Some(t!("dst_folder.access_denied")),
+                })
+                .or_else(|| {
+                    (destination.enabled && dest_opt.is_none() && self.empty_rows_prompt.is_some())
+                        .then(|| t!("dst_folder.empty_row"))
+                });
+            let markers = dest_opt
+                .as_ref()
+                .and_then(|path| self.destination_markers.get(path))
+                .filter(|markers| !markers.is_empty())
+                .map(|markers| self.view_offload_markers(markers));
+            let archive = dest_opt
+                .as_ref()
+                .filter(|path| DestinationKind::of(path).is_archive())
+                .map(|_| text(t!("dst_folder.archive")));
+            // Shows what a path with `~` or a variable in it stands for
+            let expanded = dest_opt
+                .as_ref()
+                .filter(|_| *typed != dest_path)
+                .map(|_| text(t!("dst_folder.expanded", path = dest_path)));
+            destination_rows.push(match (problem, markers, archive, expanded) {
+                (None, None, None, None) => Element::from(destination_row),
+                (problem, markers, archive, expanded) => column![destination_row]
+                    .push_maybe(expanded)
+                    .push_maybe(archive)
+                    .push_maybe(problem.map(|problem| {
+                        status_text(Status::Failure, problem, self.config.high_contrast)
+                    }))
+                    .push_maybe(markers)
+                    .spacing(5)
+                    .into(),
+            });
+        }
+
+        // Add destination button
+        let add_button = row![
+            button(text(t!("dst_folder.add"))).on_press(LibreCardMessage::AddDestinationDirectory),
+            button(text(t!("dst_folder.add_archive")))
+                .on_press(LibreCardMessage::AddArchiveDestination),
+        ]
+        .spacing(metrics.tight_spacing);
+
+        // Card watch: auto-start a job when the configured volume is mounted
+        let watch = &self.config.card_watch;
+        let watch_row = row![
+            checkbox(t!("card_watch"), watch.enabled).on_toggle(LibreCardMessage::ToggleCardWatch),
+            text_input(&t!("card_watch.label"), &watch.volume_label)
+                .on_input(LibreCardMessage::CardWatchLabelChanged)
+                .on_submit(LibreCardMessage::SaveCardWatchLabel)
+                .padding(metrics.field_padding)
+                .width(Length::FillPortion(2)),
+            text_input(&t!("card_watch.serial"), &watch.volume_serial)
+                .on_input(LibreCardMessage::CardWatchSerialChanged)
+                .on_submit(LibreCardMessage::SaveCardWatchLabel)
+                .padding(metrics.field_padding)
+                .width(Length::FillPortion(1)),
+            text(t!(
+                "card_watch.destinations",
+                count = format_count(watch.destinations.len())
+            )),
+            button(text(t!("card_watch.use_current")))
+                .on_press(LibreCardMessage::SaveCardWatchDestinations),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        // Start copy button - only enabled if we have valid source and at least one destination
+        let is_valid_input = self.source_directory.is_some()
+            && self
+                .destinations
+                .iter()
+                .any(|destination| destination.job_path().is_some());
+
+        let start_label = match self.dry_run {
+            true => t!("dry_run.start"),
+            false => t!("copying.start"),
+        };
+        let start_button = button(text(start_label).size(metrics.heading))
+            .width(Length::Fill)
+            .padding(metrics.button_padding);
+        let dry_run_checkbox =
+            checkbox(t!("dry_run"), self.dry_run).on_toggle(LibreCardMessage::ToggleDryRun);
+
+        let start_button = if is_valid_input && !self.preflight_running {
+            start_button.on_press(LibreCardMessage::StartCopy)
+        } else {
+            start_button
+        };
+
+        // For a backup made earlier, e.g. to check it is still intact
+        let verify_only_button = button(text(t!("verify_only.start")))
+            .on_press_maybe(is_valid_input.then_some(LibreCardMessage::StartVerifyOnly));
+
+        // For a backup whose folders were reorganized since
+        let reconcile_button = button(text(t!("reconcile.start")))
+            .on_press_maybe(is_valid_input.then_some(LibreCardMessage::StartReconcile));
+
+        // Needs only the card, to tell a flaky reader from a failing card
+        let readability_button = button(text(t!("readability.start"))).on_press_maybe(
+            self.source_directory
+                .is_some()
+                .then_some(LibreCardMessage::StartReadabilityCheck),
+        );
+
+        // Assemble everything
+        let destination_list = container(
+            scrollable(
+                column(destination_rows)
+                    .spacing(metrics.spacing)
+                    .padding(iced::Padding::ZERO.right(15)),
+            )
+            .id(scrollable::Id::new(DESTINATION_LIST_ID))
+            .width(Length::Fill),
+        )
+        .max_height(metrics.destination_list_height);
+        let last_job = self.last_job.as_ref().map(|last_job| {
+            let source = last_job.report.source_root.file_name().map_or_else(
+                || last_job.report.source_root.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            container(
+                row![
+                    text(t!(
+                        "last_job.summary",
+                        source = source,
+                        count = format_count(last_job.destinations.len()),
+                        when = format_recent_time(last_job.report.finished_at)
+                    ))
+                    .width(Length::Fill),
+                    button(text(t!("last_job.view"))).on_press(LibreCardMessage::ViewLastJob),
+                    button(text(t!("checksum.export"))).on_press(LibreCardMessage::ExportLastJob),
+                ]
+                .spacing(metrics.tight_spacing)
+                .align_y(iced::alignment::Alignment::Center),
+            )
+            .padding(metrics.field_padding)
+            .style(container::rounded_box)
+        });
+
+        // Earlier jobs, to copy the next card to the same places
+        let history = (!self.job_history.jobs.is_empty()).then(|| {
+            let jobs = self
+                .job_history
+                .jobs
+                .iter()
+                .enumerate()
+                .map(|(index, job)| {
+                    let name = |path: &Path| {
+                        path.file_name().map_or_else(
+                            || path.display().to_string(),
+                            |name| name.to_string_lossy().into_owned(),
+                        )
+                    };
+                    let destinations: Vec<String> = job
+                        .destinations
+                        .iter()
+                        .map(|destination| name(&destination.directory))
+                        .collect();
+                    let (status, outcome) = match job.outcome {
+                        RecordedOutcome::Copied { files } => (
+                            Status::Warning,
+                            t!("history.copied", count = format_count(files)),
+                        ),
+                        RecordedOutcome::Verified { files, failed, .. } => (
+                            if failed == 0 {
+                                Status::Success
+                            } else {
+                                Status::Failure
+                            },
+                            t!(
+                                "history.verified",
+                                count = format_count(files),
+                                failed = format_count(failed)
+                            ),
+                        ),
+                        RecordedOutcome::Cancelled {
+                            completed,
+                            total,
+                            verifying,
+                        } => (
+                            Status::Warning,
+                            t!(
+                                if verifying {
+                                    "history.cancelled_verifying"
+                                } else {
+                                    "history.cancelled"
+                                },
+                                completed = format_count(completed),
+                                total = format_count(total)
+                            ),
+                        ),
+                    };
+                    row![
+                        status_text(
+                            status,
+                            t!(
+                                "history.job",
+                                source = name(&job.source),
+                                destinations = destinations.join(", "),
+                                outcome = outcome,
+                                when = format_recent_time(job.finished_at)
+                            ),
+                            self.config.high_contrast,
+                        )
+                        .width(Length::Fill),
+                    ]
+                    .push_maybe(match job.outcome {
+                        RecordedOutcome::Verified {
+                            level: Some(level), ..
+                        } => Some(self.view_verification_level(level).size(metrics.small)),
+                        _ => None,
+                    })
+                    .push(
+                        button(text(t!("history.run_again")))
+                            .on_press(LibreCardMessage::RerunJob(index)),
+                    )
+                    .spacing(metrics.tight_spacing)
+                    .align_y(iced::alignment::Alignment::Center)
+                    .into()
+                });
+            column![text(t!("history.title")).size(metrics.emphasis)]
+                .extend(jobs)
+                .spacing(5)
+        });
+
+        let details_shown = self.details_shown();
+        let mut content = column![title]
+            .push_maybe(last_job.filter(|_| details_shown))
+            .push_maybe(history.filter(|_| details_shown))
+            .push_maybe(
+                (self.last_job.is_some() || !self.job_history.jobs.is_empty())
+                    .then(|| self.view_details_toggle())
+                    .flatten(),
+            )
+            .push(source_row)
+            .push(date_row)
+            .push_maybe(date_hint)
+            .push(baseline_row)
+            .push_maybe(source_summary)
+            .push(destination_list)
+            .spacing(metrics.spacing);
+
+        let destination_count = self.resolved_destinations().len();
+        if destination_count > RECOMMENDED_MAX_DESTINATIONS {
+            content = content.push(status_text(
+                Status::Warning,
+                t!(
+                    "dst_folder.too_many",
+                    count = format_count(destination_count),
+                    max = RECOMMENDED_MAX_DESTINATIONS
+                ),
+                self.config.high_contrast,
+            ));
+        }
+
+        let settings_button = button(text(t!("settings"))).on_press(LibreCardMessage::OpenSettings);
+
+        let sessions_checkbox = checkbox(t!("separate_sessions"), self.config.separate_sessions)
+            .on_toggle(LibreCardMessage::ToggleSeparateSessions);
+        let continue_checkbox = checkbox(t!("continue_on_error"), self.config.continue_on_error)
+            .on_toggle(LibreCardMessage::ToggleContinueOnError);
+
+        content = content
             .push(add_button)
+            .push(sessions_checkbox)
+            .push(continue_checkbox)
+            .push(watch_row);
+
+        // The watcher silently does nothing without a label or serial, and destinations
+        let unnamed = watch.volume_label.trim().is_empty() && watch.volume_serial.trim().is_empty();
+        if watch.enabled && (unnamed || watch.destinations.is_empty()) {
+            content = content.push(status_text(
+                Status::Warning,
+                t!("card_watch.incomplete"),
+                self.config.high_contrast,
+            ));
+        }
+
+        if self.preflight_running && self.config.check_source_readable {
+            content = content.push(text(t!("src_folder.checking")));
+        }
+        if self.preflight_running && self.config.quiescence_interval().is_some() {
+            content = content.push(text(t!("src_folder.settling")));
+        }
+
+        let compare_button =
+            button(text(t!("compare.start"))).on_press(LibreCardMessage::CompareReports);
+        let verify_manifest_button = button(text(t!("verify_manifest.start")))
+            .on_press(LibreCardMessage::VerifyManifest(false));
+        let verify_subfolder_button = button(text(t!("verify_manifest.start_subfolder")))
+            .on_press(LibreCardMessage::VerifyManifest(true));
+        let hash_file_button =
+            button(text(t!("hash_file.start"))).on_press(LibreCardMessage::ChooseFileToHash);
+
+        content = content
+            .push(dry_run_checkbox)
             .push(start_button)
-            .spacing(20)
-            .padding(20)
+            .push(
+                row![
+                    verify_only_button,
+                    reconcile_button,
+                    readability_button,
+                    compare_button,
+                    verify_manifest_button,
+                    verify_subfolder_button,
+                    hash_file_button
+                ]
+                .spacing(metrics.tight_spacing),
+            )
+            .push(settings_button)
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
             .width(Length::Fill);
 
-        container(content).into()
+        container(content).into()
+    }
+
+    /// The offloads a destination already holds. One that looks like the selected card may be
+    /// about to be offloaded twice.
+    fn view_offload_markers<'a>(
+        &'a self,
+        markers: &'a [(PathBuf, OffloadMarker)],
+    ) -> Element<'a, LibreCardMessage> {
+        // A drive used for many cards would push the rest of the input stage away
+        const SHOWN_MARKERS: usize = 3;
+
+        let metrics = self.metrics();
+        let listing = match &self.source_listing {
+            Some((_, Ok(listing))) => Some(listing),
+            _ => None,
+        };
+        let lines = markers.iter().take(SHOWN_MARKERS).map(|(folder, marker)| {
+            let source = match &marker.source_volume {
+                Some(volume) if !volume.label.is_empty() => volume.label.clone(),
+                _ => marker.source.display().to_string(),
+            };
+            let message = t!(
+                "marker.found",
+                source = source,
+                count = format_count(marker.files),
+                size = format_bytes(marker.bytes),
+                when = format_time(marker.verified_at),
+                folder = folder.display()
+            );
+            let message = match marker.verification_level {
+                Some(VerificationLevel::Copy) => t!("marker.copy_verified", found = message),
+                _ => message,
+            };
+            // Same totals as the card is the best guess without hashing it
+            let same_card = match listing {
+                Some(listing) => {
+                    listing.files.len() == marker.files && listing.total_bytes() == marker.bytes
+                }
+                None => self.source_directory.as_ref() == Some(&marker.source),
+            };
+            match same_card {
+                true => status_text(
+                    Status::Warning,
+                    t!("marker.same_card", found = message),
+                    self.config.high_contrast,
+                )
+                .size(metrics.small)
+                .into(),
+                false => text(message).size(metrics.small).into(),
+            }
+        });
+        let mut list = column(lines).spacing(4);
+        if markers.len() > SHOWN_MARKERS {
+            list = list.push(
+                text(t!(
+                    "preview.more",
+                    count = format_count(markers.len() - SHOWN_MARKERS)
+                ))
+                .size(metrics.small),
+            );
+        }
+        list.into()
+    }
+
+    fn view_close_prompt(&self) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let message = match self.stage {
+            LibreCardAppStage::Checksumming { .. } => t!("close.prompt.checksum"),
+            _ => t!("close.prompt.copy"),
+        };
+
+        container(
+            column![
+                status_text(Status::Warning, message, self.config.high_contrast),
+                row![
+                    button(text(t!("close.finish_and_quit")))
+                        .on_press(LibreCardMessage::FinishAndQuit),
+                    button(text(t!("close.keep_running"))).on_press(LibreCardMessage::KeepRunning),
+                ]
+                .spacing(metrics.tight_spacing),
+            ]
+            .spacing(metrics.tight_spacing),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_space_prompt<'a>(&'a self, full: &'a FullDestination) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let available = match full.available {
+            Some(bytes) => format_bytes(bytes),
+            None => t!("space.available_unknown").into_owned(),
+        };
+        let message = match full.reserved {
+            0 => t!(
+                "space.prompt",
+                path = full.path.display(),
+                needed = format_bytes(full.needed),
+                available = available
+            ),
+            reserved => t!(
+                "space.prompt_margin",
+                path = full.path.display(),
+                needed = format_bytes(full.needed),
+                available = available,
+                reserved = format_bytes(reserved)
+            ),
+        };
+        container(
+            column![
+                status_text(Status::Failure, message, self.config.high_contrast),
+                row![
+                    button(text(t!("space.retry")))
+                        .on_press(LibreCardMessage::ResolveSpace(SpaceChoice::Retry)),
+                    button(text(t!("space.drop")))
+                        .on_press(LibreCardMessage::ResolveSpace(SpaceChoice::Drop)),
+                    button(text(t!("permission.abort")))
+                        .on_press(LibreCardMessage::ResolveSpace(SpaceChoice::Abort)),
+                ]
+                .spacing(metrics.tight_spacing),
+            ]
+            .spacing(metrics.tight_spacing),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_permission_prompt<'a>(&'a self, file: &'a Path) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        container(
+            column![
+                status_text(
+                    Status::Failure,
+                    t!("permission.prompt", path = file.display()),
+                    self.config.high_contrast,
+                ),
+                row![
+                    button(text(t!("permission.skip")))
+                        .on_press(LibreCardMessage::ResolvePermission(PermissionChoice::Skip)),
+                    button(text(t!("permission.skip_all"))).on_press(
+                        LibreCardMessage::ResolvePermission(PermissionChoice::SkipAll)
+                    ),
+                    button(text(t!("permission.abort")))
+                        .on_press(LibreCardMessage::ResolvePermission(PermissionChoice::Abort)),
+                ]
+                .spacing(metrics.tight_spacing),
+            ]
+            .spacing(metrics.tight_spacing),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_placeholder_prompt<'a>(
+        &'a self,
+        placeholders: &'a [PathBuf],
+    ) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let list = column(
+            placeholders
+                .iter()
+                .map(|file| path_text(file, metrics.small)),
+        )
+        .spacing(4);
+
+        container(
+            column![
+                status_text(
+                    Status::Warning,
+                    t!(
+                        "placeholder.prompt",
+                        count = format_count(placeholders.len())
+                    ),
+                    self.config.high_contrast,
+                ),
+                scrollable(list).height(Length::Fixed(metrics.list_height)),
+                row![
+                    button(text(t!("placeholder.skip"))).on_press(
+                        LibreCardMessage::ResolvePlaceholders(PlaceholderChoice::Skip)
+                    ),
+                    button(text(t!("placeholder.download"))).on_press(
+                        LibreCardMessage::ResolvePlaceholders(PlaceholderChoice::Download)
+                    ),
+                    button(text(t!("permission.abort"))).on_press(
+                        LibreCardMessage::ResolvePlaceholders(PlaceholderChoice::Abort)
+                    ),
+                ]
+                .spacing(metrics.tight_spacing),
+            ]
+            .spacing(metrics.tight_spacing),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_unreadable_prompt<'a>(
+        &'a self,
+        unreadable: &'a [UnreadableFile],
+    ) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let list = column(unreadable.iter().map(|file| {
+            text(t!(
+                "unreadable.file",
+                path = file.path.display(),
+                error = file.error
+            ))
+            .size(metrics.small)
+            .into()
+        }))
+        .spacing(4);
+
+        container(
+            column![
+                status_text(
+                    Status::Failure,
+                    t!("unreadable.prompt", count = format_count(unreadable.len())),
+                    self.config.high_contrast,
+                ),
+                scrollable(list).height(Length::Fixed(metrics.list_height)),
+                row![
+                    button(text(t!("unreadable.exclude")))
+                        .on_press(LibreCardMessage::ResolveUnreadable(true)),
+                    button(text(t!("dst_folder.dont_copy")))
+                        .on_press(LibreCardMessage::ResolveUnreadable(false)),
+                ]
+                .spacing(metrics.tight_spacing),
+            ]
+            .spacing(metrics.tight_spacing),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_growing_prompt<'a>(&'a self, growing: &'a [PathBuf]) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let list = column(growing.iter().map(|file| path_text(file, metrics.small))).spacing(4);
+
+        container(
+            column![
+                status_text(
+                    Status::Warning,
+                    t!("growing.prompt", count = format_count(growing.len())),
+                    self.config.high_contrast,
+                ),
+                scrollable(list).height(Length::Fixed(metrics.list_height)),
+                row![
+                    button(text(t!("growing.recheck")))
+                        .on_press(LibreCardMessage::ResolveGrowing(GrowingChoice::Recheck)),
+                    button(text(t!("growing.exclude")))
+                        .on_press(LibreCardMessage::ResolveGrowing(GrowingChoice::Exclude)),
+                    button(text(t!("growing.proceed")))
+                        .on_press(LibreCardMessage::ResolveGrowing(GrowingChoice::Proceed)),
+                    button(text(t!("permission.abort")))
+                        .on_press(LibreCardMessage::ResolveGrowing(GrowingChoice::Abort)),
+                ]
+                .spacing(metrics.tight_spacing),
+            ]
+            .spacing(metrics.tight_spacing),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_overwrite_prompt<'a>(
+        &'a self,
+        collisions: &'a [Collision],
+    ) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let stamp = |stamp: &FileStamp| {
+            let modified = stamp.modified.map_or_else(|| "?".to_owned(), format_time);
+            format!("{}, {}", format_bytes(stamp.size), modified)
+        };
+        let list = column(collisions.iter().map(|collision| {
+            text(t!(
+                "overwrite.file",
+                path = collision.path.display(),
+                source = stamp(&collision.source),
+                existing = stamp(&collision.existing)
+            ))
+            .size(metrics.small)
+            .into()
+        }))
+        .spacing(4);
+
+        container(
+            column![
+                status_text(
+                    Status::Warning,
+                    t!("overwrite.prompt", count = format_count(collisions.len())),
+                    self.config.high_contrast,
+                ),
+                scrollable(list).height(Length::Fixed(metrics.list_height)),
+                row![
+                    button(text(t!("overwrite.all")))
+                        .on_press(LibreCardMessage::ResolveOverwrite(Some(false))),
+                    button(text(t!("overwrite.skip_all")))
+                        .on_press(LibreCardMessage::ResolveOverwrite(Some(true))),
+                    button(text(t!("dst_folder.dont_copy")))
+                        .on_press(LibreCardMessage::ResolveOverwrite(None)),
+                ]
+                .spacing(metrics.tight_spacing),
+            ]
+            .spacing(metrics.tight_spacing),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_empty_rows_prompt(&self, rows: &[usize]) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let rows: Vec<String> = rows.iter().map(usize::to_string).collect();
+        let remaining = self.unique_destinations().0.len();
+
+        container(
+            column![
+                status_text(
+                    Status::Warning,
+                    t!(
+                        "dst_folder.empty_row.prompt",
+                        rows = rows.join(", "),
+                        count = format_count(remaining)
+                    ),
+                    self.config.high_contrast,
+                ),
+                row![
+                    button(text(t!("dst_folder.empty_row.proceed")))
+                        .on_press(LibreCardMessage::ResolveEmptyRows(true)),
+                    button(text(t!("dst_folder.dont_copy")))
+                        .on_press(LibreCardMessage::ResolveEmptyRows(false)),
+                ]
+                .spacing(metrics.tight_spacing),
+            ]
+            .spacing(metrics.tight_spacing),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_shortfall_prompt(&self) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let mut prompt = column![text(t!("space.shortfall"))].spacing(metrics.tight_spacing);
+        for forecast in self.job_space_forecast.iter().flatten() {
+            let Some(allowed) = forecast.allowed() else {
+                continue;
+            };
+            let status = match forecast.fits() {
+                true => Status::Success,
+                false => Status::Failure,
+            };
+            prompt = prompt.push(status_text(
+                status,
+                t!(
+                    "space.forecast",
+                    path = forecast.destination.display(),
+                    required = format_bytes(forecast.required),
+                    allowed = format_bytes(allowed),
+                    reserved = format_bytes(forecast.reserved)
+                ),
+                self.config.high_contrast,
+            ));
+        }
+        container(
+            prompt.push(
+                row![
+                    button(text(t!("dst_folder.copy_anyway")))
+                        .on_press(LibreCardMessage::ResolveShortfall(true)),
+                    button(text(t!("dst_folder.dont_copy")))
+                        .on_press(LibreCardMessage::ResolveShortfall(false)),
+                ]
+                .spacing(metrics.tight_spacing),
+            ),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_nonempty_prompt<'a>(
+        &'a self,
+        nonempty: &'a [(PathBuf, ExistingContents)],
+    ) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let mut prompt = column![].spacing(metrics.tight_spacing);
+        for (dest, contents) in nonempty {
+            prompt = prompt.push(status_text(
+                Status::Warning,
+                t!(
+                    "dst_folder.not_empty",
+                    path = dest.display(),
+                    count = format_count(contents.files),
+                    size = format_bytes(contents.bytes),
+                    overwritten = format_count(contents.collisions.len())
+                ),
+                self.config.high_contrast,
+            ));
+        }
+
+        container(
+            prompt
+                .push(
+                    checkbox(t!("dst_folder.dont_ask_again"), self.dont_ask_nonempty)
+                        .on_toggle(LibreCardMessage::ToggleDontAskNonEmpty),
+                )
+                .push(
+                    row![
+                        button(text(t!("dst_folder.copy_anyway")))
+                            .on_press(LibreCardMessage::ConfirmNonEmpty),
+                        button(text(t!("dst_folder.dont_copy")))
+                            .on_press(LibreCardMessage::CancelNonEmpty),
+                    ]
+                    .spacing(metrics.tight_spacing),
+                ),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    fn view_preview_prompt<'a>(&'a self, preview: &'a JobPreview) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        // Listing a whole card would make the overlay crawl
+        const LIST_LIMIT: usize = 500;
+
+        let size =
+            |files: &[(PathBuf, u64)]| format_bytes(files.iter().map(|(_, size)| size).sum());
+        let groups = [
+            (
+                PreviewList::Copied,
+                None,
+                t!(
+                    "preview.copied",
+                    count = format_count(preview.copied.len()),
+                    size = format_bytes(preview.copied_bytes())
+                ),
+                preview.copied.len(),
+            ),
+            (
+                PreviewList::Filtered,
+                None,
+                t!(
+                    "preview.filtered",
+                    count = format_count(preview.filtered.len()),
+                    size = size(&preview.filtered)
+                ),
+                preview.filtered.len(),
+            ),
+            (
+                PreviewList::Present,
+                None,
+                t!(
+                    "preview.present",
+                    count = format_count(preview.present.len()),
+                    size = size(&preview.present)
+                ),
+                preview.present.len(),
+            ),
+            (
+                PreviewList::Ignored,
+                None,
+                t!(
+                    "preview.ignored",
+                    count = format_count(preview.ignored.len()),
+                    size = size(&preview.ignored)
+                ),
+                preview.ignored.len(),
+            ),
+            (
+                PreviewList::Overwritten,
+                Some(Status::Warning),
+                t!(
+                    "preview.overwritten",
+                    count = format_count(preview.overwritten.len())
+                ),
+                preview.overwritten.len(),
+            ),
+        ];
+
+        let mut prompt = column![text(t!("preview.title"))].spacing(metrics.tight_spacing);
+        for (list, status, summary, count) in groups {
+            let summary: Element<'_, LibreCardMessage> = match status {
+                Some(status) if count > 0 => {
+                    status_text(status, summary, self.config.high_contrast).into()
+                }
+                _ => text(summary).into(),
+            };
+            let expanded = self.expanded_preview == Some(list);
+            let mut line = row![container(summary).width(Length::Fill)]
+                .spacing(metrics.tight_spacing)
+                .align_y(iced::alignment::Alignment::Center);
+            if count > 0 {
+                line = line.push(
+                    button(text(if expanded {
+                        t!("checksum.missing.hide")
+                    } else {
+                        t!("checksum.missing.show")
+                    }))
+                    .on_press(LibreCardMessage::TogglePreviewList(list)),
+                );
+            }
+            prompt = prompt.push(line);
+            if !expanded {
+                continue;
+            }
+            let paths: Vec<&Path> = match list {
+                PreviewList::Copied => preview.copied.iter().map(|(f, _)| f.as_path()).collect(),
+                PreviewList::Filtered => {
+                    preview.filtered.iter().map(|(f, _)| f.as_path()).collect()
+                }
+                PreviewList::Present => preview.present.iter().map(|(f, _)| f.as_path()).collect(),
+                PreviewList::Ignored => preview.ignored.iter().map(|(f, _)| f.as_path()).collect(),
+                PreviewList::Overwritten => {
+                    preview.overwritten.iter().map(PathBuf::as_path).collect()
+                }
+            };
+            let mut files = column(
+                paths
+                    .iter()
+                    .take(LIST_LIMIT)
+                    .map(|path| path_text(path, metrics.small)),
+            );
+            if paths.len() > LIST_LIMIT {
+                files = files.push(
+                    text(t!(
+                        "preview.more",
+                        count = format_count(paths.len() - LIST_LIMIT)
+                    ))
+                    .size(metrics.small),
+                );
+            }
+            prompt =
+                prompt.push(scrollable(files).height(Length::Fixed(metrics.short_list_height)));
+        }
+        // What the card holds, to catch a wrong card or a missing set of sidecar files
+        let file_types = &preview.file_types;
+        if !file_types.extensions.is_empty() {
+            let type_row = |name: Cow<'static, str>, total: TypeTotal| {
+                row![
+                    text(name).size(metrics.small).width(Length::FillPortion(2)),
+                    text(format_count(total.files))
+                        .size(metrics.small)
+                        .width(Length::FillPortion(1))
+                        .align_x(iced::alignment::Horizontal::Right),
+                    text(format_bytes(total.bytes))
+                        .size(metrics.small)
+                        .width(Length::FillPortion(1))
+                        .align_x(iced::alignment::Horizontal::Right),
+                ]
+                .spacing(metrics.tight_spacing)
+            };
+            let mut table = column![text(t!("preview.file_types")).size(metrics.small)];
+            for (extension, total) in &file_types.extensions {
+                let name = match extension.is_empty() {
+                    true => t!("preview.file_types.none"),
+                    false => Cow::Owned(format!(".{extension}")),
+                };
+                table = table.push(type_row(name, *total));
+            }
+            if file_types.other.files > 0 {
+                table = table.push(type_row(t!("preview.file_types.other"), file_types.other));
+            }
+            prompt = prompt.push(table);
+        }
+        if self.config.skip_duplicates {
+            prompt = prompt.push(text(t!("preview.duplicates")).size(metrics.small));
+        }
+
+        container(
+            prompt.push(
+                row![
+                    button(text(t!(
+                        "preview.start",
+                        count = format_count(preview.copied.len()),
+                        size = format_bytes(preview.copied_bytes())
+                    )))
+                    .on_press(LibreCardMessage::BeginCopy),
+                    button(text(t!("dst_folder.dont_copy")))
+                        .on_press(LibreCardMessage::CancelPreview),
+                ]
+                .spacing(metrics.tight_spacing),
+            ),
+        )
+        .width(Length::Fill)
+        .padding(metrics.padding)
+        .into()
+    }
+
+    /// The timestamp format from the settings. A custom pattern that can't be used falls back to
+    /// ISO 8601 with a warning.
+    fn timestamp_format(&mut self) -> TimestampFormat {
+        self.config.timestamp_format().unwrap_or_else(|error| {
+            self.warning_message =
+                Some(t!("settings.timestamp_format.invalid", error = error).into_owned());
+            TimestampFormat::default()
+        })
+    }
+
+    fn view_settings_stage(&self) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("settings"))
+            .size(metrics.title)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let language_row = row![
+            text(t!("settings.language")).width(Length::FillPortion(1)),
+            pick_list(
+                LanguageChoice::all(),
+                Some(LanguageChoice::from_config(self.config.language.as_deref())),
+                LibreCardMessage::LanguageSelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        let hash_algorithm_row = row![
+            text(t!("settings.hash_algorithm")).width(Length::FillPortion(1)),
+            pick_list(
+                HashAlgorithm::ALL,
+                Some(self.config.hash_algorithm),
+                LibreCardMessage::HashAlgorithmSelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        let benchmark_buttons = row![
+            button(text(t!("settings.benchmark"))).on_press_maybe(
+                (!self.benchmark_running)
+                    .then_some(LibreCardMessage::BenchmarkHashes { sample: false })
+            ),
+            button(text(t!("settings.benchmark.sample"))).on_press_maybe(
+                (!self.benchmark_running)
+                    .then_some(LibreCardMessage::BenchmarkHashes { sample: true })
+            ),
+        ]
+        .spacing(metrics.tight_spacing);
+        let mut benchmark_settings = column![benchmark_buttons].spacing(metrics.tight_spacing);
+        if self.benchmark_running {
+            benchmark_settings = benchmark_settings.push(text(t!("settings.benchmark.running")));
+        } else if let Some(benchmark) = &self.config.hash_benchmark {
+            benchmark_settings = benchmark_settings.push(
+                text(t!(
+                    "settings.benchmark.measured_at",
+                    time = format_time(benchmark.measured_at)
+                ))
+                .size(metrics.small),
+            );
+            if let Some((sample, read_speed)) = &benchmark.sample {
+                benchmark_settings = benchmark_settings.push(text(t!(
+                    "settings.benchmark.read",
+                    speed = format_bytes(*read_speed as u64),
+                    file = sample.display()
+                )));
+            }
+            for speed in &benchmark.speeds {
+                let line = t!(
+                    "settings.benchmark.speed",
+                    algorithm = speed.algorithm,
+                    speed = format_bytes(speed.bytes_per_second as u64)
+                );
+                benchmark_settings = benchmark_settings.push(match benchmark.keeps_up(speed) {
+                    Some(true) => status_text(
+                        Status::Success,
+                        t!("settings.benchmark.keeps_up", line = line),
+                        self.config.high_contrast,
+                    ),
+                    Some(false) => status_text(
+                        Status::Warning,
+                        t!("settings.benchmark.too_slow", line = line),
+                        self.config.high_contrast,
+                    ),
+                    None => text(line),
+                });
+            }
+        }
+
+        let high_contrast_checkbox =
+            checkbox(t!("settings.high_contrast"), self.config.high_contrast)
+                .on_toggle(LibreCardMessage::ToggleHighContrast);
+
+        let update_check_checkbox = checkbox(
+            t!("settings.check_for_updates"),
+            self.config.check_for_updates == Some(true),
+        )
+        .on_toggle(LibreCardMessage::ToggleUpdateCheck);
+
+        let serve_status_row = row![
+            checkbox(t!("settings.serve_status"), self.config.serve_status)
+                .on_toggle(LibreCardMessage::ToggleServeStatus),
+            text_input(&DEFAULT_STATUS_PORT.to_string(), &self.status_port_input)
+                .on_input_maybe(
+                    (!self.config.serve_status).then_some(LibreCardMessage::StatusPortChanged)
+                )
+                .width(Length::Fixed(80.0)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+        let mut serve_status_settings = column![serve_status_row].spacing(metrics.tight_spacing);
+        if self.status_server.is_some() {
+            serve_status_settings = serve_status_settings.push(
+                text(t!(
+                    "settings.serve_status.address",
+                    address = format!("http://localhost:{}/status", self.config.status_port())
+                ))
+                .size(metrics.small),
+            );
+        } else if self.status_port_input.trim().parse::<u16>().ok()
+            != Some(self.config.status_port())
+        {
+            serve_status_settings = serve_status_settings.push(
+                status_text(
+                    Status::Warning,
+                    t!("settings.serve_status.invalid_port"),
+                    self.config.high_contrast,
+                )
+                .size(metrics.small),
+            );
+        }
+
+        let copy_order_row = row![
+            text(t!("settings.copy_order")).width(Length::FillPortion(1)),
+            pick_list(
+                CopyOrder::ALL.map(CopyOrderChoice),
+                Some(CopyOrderChoice(self.config.copy_order)),
+                LibreCardMessage::CopyOrderSelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        let verify_checkbox = checkbox(
+            t!("settings.verify_while_copying"),
+            self.config.verify_while_copying,
+        )
+        .on_toggle(LibreCardMessage::ToggleVerifyWhileCopying);
+
+        let layout_row = row![
+            text(t!("settings.layout")).width(Length::FillPortion(1)),
+            pick_list(
+                LayoutDensity::ALL.map(LayoutDensityChoice),
+                Some(LayoutDensityChoice(self.config.layout_density)),
+                LibreCardMessage::LayoutDensitySelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        let overwrite_row = row![
+            text(t!("settings.overwrite")).width(Length::FillPortion(1)),
+            pick_list(
+                OverwritePolicy::ALL.map(OverwritePolicyChoice),
+                Some(OverwritePolicyChoice(self.config.overwrite_policy)),
+                LibreCardMessage::OverwritePolicySelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        // Only verification while copying has a choice of method
+        let verify_method_row = row![
+            text(t!("settings.verify_method")).width(Length::FillPortion(1)),
+            pick_list(
+                VerifyMethod::ALL.map(VerifyMethodChoice),
+                Some(VerifyMethodChoice(self.config.verify_method)),
+                LibreCardMessage::VerifyMethodSelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        // Verifying while copying reads each copy back once, so only the separate pass repeats
+        let passes_row = row![
+            text(t!("settings.verification_passes")).width(Length::FillPortion(1)),
+            pick_list(
+                (1..=MAX_VERIFICATION_PASSES).collect::<Vec<_>>(),
+                Some(self.config.verification_passes()),
+                LibreCardMessage::VerificationPassesSelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        let workers_row = row![
+            text(t!("settings.verify_workers")).width(Length::FillPortion(1)),
+            pick_list(
+                (1..=MAX_VERIFY_WORKERS).collect::<Vec<_>>(),
+                Some(self.config.verify_workers()),
+                LibreCardMessage::VerifyWorkersSelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+
+        let timestamp_row = row![
+            text(t!("settings.timestamp_format")).width(Length::FillPortion(1)),
+            pick_list(
+                TimestampStyle::ALL.map(TimestampStyleChoice),
+                Some(TimestampStyleChoice(self.config.timestamp_style)),
+                LibreCardMessage::TimestampStyleSelected,
+            )
+            .width(Length::FillPortion(3)),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
+        let mut timestamp_settings = column![timestamp_row].spacing(metrics.tight_spacing);
+        if self.config.timestamp_style == TimestampStyle::Custom {
+            timestamp_settings = timestamp_settings.push(
+                text_input(
+                    TimestampFormat::ISO_8601,
+                    &self.config.custom_timestamp_format,
+                )
+                .on_input(LibreCardMessage::CustomTimestampFormatChanged),
+            );
+        }
+        timestamp_settings = timestamp_settings.push(match self.config.timestamp_format() {
+            Ok(format) => text(t!(
+                "settings.timestamp_format.example",
+                example = format.format(SystemTime::now())
+            ))
+            .size(metrics.small),
+            Err(error) => status_text(
+                Status::Warning,
+                t!("settings.timestamp_format.invalid", error = error),
+                self.config.high_contrast,
+            )
+            .size(metrics.small),
+        });
+
+        let recopy_checkbox = checkbox(
+            t!("settings.recopy_changed"),
+            self.config.recopy_changed_files,
+        )
+        .on_toggle(LibreCardMessage::ToggleRecopyChanged);
+
+        let timestamps_checkbox = checkbox(
+            t!("settings.preserve_timestamps"),
+            self.config.preserve_timestamps,
+        )
+        .on_toggle(LibreCardMessage::TogglePreserveTimestamps);
+
+        let ownership_checkbox = checkbox(
+            t!("settings.preserve_ownership"),
+            self.config.preserve_ownership,
+        )
+        .on_toggle(LibreCardMessage::TogglePreserveOwnership);
+
+        let read_only_checkbox = checkbox(
+            t!("settings.read_only_verified"),
+            self.config.read_only_verified,
+        )
+        .on_toggle(LibreCardMessage::ToggleReadOnlyVerified);
+
+        let readable_checkbox = checkbox(
+            t!("settings.check_source_readable"),
+            self.config.check_source_readable,
+        )
+        .on_toggle(LibreCardMessage::ToggleCheckSourceReadable);
+
+        let volume_listing_checkbox = checkbox(
+            t!("settings.record_volume_listing"),
+            self.config.record_volume_listing,
+        )
+        .on_toggle(LibreCardMessage::ToggleRecordVolumeListing);
+
+        let double_read_checkbox =
+            checkbox(t!("settings.double_read"), self.config.double_read_sources)
+                .on_toggle(LibreCardMessage::ToggleDoubleRead);
+
+        let salvage_checkbox = checkbox(t!("settings.salvage"), self.config.salvage_unreadable)
+            .on_toggle(LibreCardMessage::ToggleSalvage);
+
+        let skip_duplicates_checkbox =
+            checkbox(t!("settings.skip_duplicates"), self.config.skip_duplicates)
+                .on_toggle(LibreCardMessage::ToggleSkipDuplicates);
+
+        let stop_on_failure_checkbox = checkbox(
+            t!("settings.stop_on_first_failure"),
+            self.config.stop_on_first_failure,
+        )
+        .on_toggle(LibreCardMessage::ToggleStopOnFirstFailure);
+
+        let compare_checkbox = checkbox(t!("settings.compare_bytes"), self.config.compare_bytes)
+            .on_toggle(LibreCardMessage::ToggleCompareBytes);
+
+        let check_folders_checkbox =
+            checkbox(t!("settings.check_folders"), self.config.check_folders)
+                .on_toggle(LibreCardMessage::ToggleCheckFolders);
+
+        let ignore_settings = column![
+            checkbox(
+                t!("settings.ignore_system_files"),
+                self.config.ignore_system_files
+            )
+            .on_toggle(LibreCardMessage::ToggleIgnoreSystemFiles),
+            text_input(
+                &t!("settings.ignore_patterns"),
+                &self.config.ignore_patterns
+            )
+            .on_input(LibreCardMessage::IgnorePatternsChanged),
+        ]
+        .spacing(metrics.tight_spacing);
+
+        let dry_run_reads_checkbox = checkbox(
+            t!("settings.dry_run_reads_source"),
+            self.config.dry_run_reads_source,
+        )
+        .on_toggle(LibreCardMessage::ToggleDryRunReadsSource);
+
+        let parallel_hashing_checkbox = checkbox(
+            t!("settings.parallel_hashing"),
+            self.config.parallel_hashing,
+        )
+        .on_toggle(LibreCardMessage::ToggleParallelHashing);
+
+        let back_button = button(text(t!("back"))).on_press(LibreCardMessage::CloseSettings);
+
+        column![
+            title,
+            language_row,
+            layout_row,
+            hash_algorithm_row,
+            parallel_hashing_checkbox,
+            benchmark_settings,
+            copy_order_row,
+            overwrite_row,
+            verify_checkbox,
+            verify_method_row,
+            passes_row,
+            workers_row,
+            timestamp_settings,
+            recopy_checkbox,
+            timestamps_checkbox,
+            ownership_checkbox,
+            read_only_checkbox,
+            readable_checkbox,
+            volume_listing_checkbox,
+            double_read_checkbox,
+            salvage_checkbox,
+            skip_duplicates_checkbox,
+            stop_on_failure_checkbox,
+            compare_checkbox,
+            check_folders_checkbox,
+            ignore_settings,
+            dry_run_reads_checkbox,
+            high_contrast_checkbox,
+            update_check_checkbox,
+            serve_status_settings,
+            back_button
+        ]
+        .spacing(metrics.spacing)
+        .padding(metrics.padding)
+        .width(Length::Fill)
+        .into()
+    }
+
+    fn view_copy_stage(
+        &self,
+        progress: &Progress,
+        started_at: Instant,
+        warnings: &[JobWarning],
+        rates: &DestinationRates,
+    ) -> Element<'_, LibreCardMessage> {
+        if progress.phase == JobPhase::Scanning {
+            return self.view_scan_stage(progress, started_at);
+        }
+
+        let metrics = self.metrics();
+        let title = text(match self.dry_run {
+            true => t!("dry_run.title"),
+            false => t!("copying.title"),
+        })
+        .size(metrics.title)
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let high_contrast = self.config.high_contrast;
+        let troubled = progress.warnings > 0 || progress.stall.is_some();
+        let progress_bar = progress_bar(0.0..=1.0, progress.byte_fraction())
+            .width(Length::Fill)
+            .height(metrics.progress_bar_height)
+            .style(move |theme| {
+                let style = iced::widget::progress_bar::primary(theme);
+                match troubled {
+                    true => iced::widget::progress_bar::Style {
+                        bar: Status::Failure.color(high_contrast).into(),
+                        ..style
+                    },
+                    false => style,
+                }
+            });
+
+        let progress_text = text(t!(
+            if progress.scanning {
+                "copying.scanning"
+            } else {
+                "copying"
+            },
+            copied = format_count(progress.completed_files),
+            total = format_count(progress.total_files)
+        ))
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let bytes_text = text(t!(
+            "progress.bytes",
+            completed = format_bytes(progress.done_bytes()),
+            total = format_bytes(progress.total_bytes)
+        ))
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let copied_text = text(t!(
+            "copying.copied",
+            size = format_bytes(progress.copied_so_far())
+        ))
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let elapsed_text = text(t!("elapsed", time = format_duration(started_at.elapsed())))
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        // Shown right under the bar, since the bar itself just stops
+        let stall = progress.stall.as_ref().map(|stall| {
+            let file = progress
+                .current_file
+                .as_ref()
+                .map(|file| file.display().to_string())
+                .unwrap_or_default();
+            let culprit = match stall.destinations.as_slice() {
+                [] => t!("copying.stalled.source"),
+                destinations => t!(
+                    "copying.stalled.destinations",
+                    destinations = destinations
+                        .iter()
+                        .map(|index| self.job_destination_name(*index))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            };
+            status_text(
+                Status::Failure,
+                t!(
+                    "copying.stalled",
+                    seconds = format_count(stall.idle.as_secs() as usize),
+                    file = file,
+                    culprit = culprit
+                ),
+                high_contrast,
+            )
+            .size(metrics.heading)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center)
+        });
+
+        // The bar is full by now, but the drives aren't safe to pull yet
+        let finalizing = (progress.phase == JobPhase::Finalizing).then(|| {
+            let lines = progress
+                .destinations
+                .iter()
+                .enumerate()
+                .filter(|(_, destination)| destination.pending_files > 0)
+                .map(|(index, destination)| {
+                    status_text(
+                        Status::Warning,
+                        t!(
+                            "copying.finalizing",
+                            destination = self.job_destination_name(index),
+                            count = format_count(destination.pending_files)
+                        ),
+                        high_contrast,
+                    )
+                    .size(metrics.heading)
+                    .width(Length::Fill)
+                    .align_x(iced::alignment::Horizontal::Center)
+                    .into()
+                });
+            column(lines).spacing(metrics.tight_spacing)
+        });
+
+        let mut content = column![title, progress_bar]
+            .push_maybe(stall)
+            .push_maybe(finalizing)
+            .push_maybe(
+                self.details_shown()
+                    .then(|| self.view_destination_progress(progress, rates)),
+            )
+            .push(progress_text)
+            .push(bytes_text)
+            .push(copied_text)
+            .push_maybe(self.view_group_progress(progress))
+            .push_maybe(self.view_rate(progress, started_at))
+            .push_maybe(self.view_details_toggle());
+
+        if let Some(counts) = self.view_verification_counts(progress) {
+            content = content.push(counts);
+        }
+
+        if let Some(feed) = self.view_warning_feed(progress, warnings) {
+            content = content.push(feed);
+        }
+
+        content
+            .push(elapsed_text)
+            .push(self.view_cancel_button())
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// A row per destination with what it has written and how fast, a check once it has every
+    /// file, or the error it failed with.
+    /// The job's average rate so far and, once the source is fully scanned, the time left at it.
+    fn view_rate(
+        &self,
+        progress: &Progress,
+        started_at: Instant,
+    ) -> Option<Element<'_, LibreCardMessage>> {
+        let elapsed = started_at.elapsed().as_secs_f64();
+        let done = progress.done_bytes();
+        // The first second is mostly opening files, which says little about the rate
+        if elapsed < 1.0 || done == 0 {
+            return None;
+        }
+        let per_second = done as f64 / elapsed;
+        let rate = format_bytes(per_second as u64);
+        let message = match progress.scanning {
+            true => t!("progress.rate", rate = rate),
+            false => {
+                let left = progress.total_bytes.saturating_sub(done) as f64 / per_second;
+                t!(
+                    "progress.rate_left",
+                    rate = rate,
+                    left = format_duration(Duration::from_secs_f64(left))
+                )
+            }
+        };
+        Some(
+            text(message)
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center)
+                .into(),
+        )
+    }
+
+    /// How far each top-level folder of the source has come, when there is more than one.
+    fn view_group_progress(&self, progress: &Progress) -> Option<Element<'_, LibreCardMessage>> {
+        let metrics = self.metrics();
+        (progress.groups.len() > 1 && self.details_shown()).then(|| {
+            let mut groups: Vec<_> = progress.groups.iter().collect();
+            groups.sort_by_key(|(name, _)| name.as_str() != ROOT_GROUP);
+            let groups = groups
+                .into_iter()
+                .map(|(name, group)| {
+                    t!(
+                        "progress.group",
+                        group = name,
+                        completed = format_count(group.completed_files),
+                        total = format_count(group.total_files)
+                    )
+                })
+                .collect::<Vec<_>>();
+            text(groups.join("  ·  "))
+                .size(metrics.detail)
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center)
+                .into()
+        })
+    }
+
+    fn view_destination_progress<'a>(
+        &'a self,
+        progress: &Progress,
+        rates: &DestinationRates,
+    ) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let high_contrast = self.config.high_contrast;
+        let rows = progress
+            .destinations
+            .iter()
+            .enumerate()
+            .map(|(index, destination)| {
+                let label: Element<'a, LibreCardMessage> = match self.job_destinations.get(index) {
+                    Some(path) => path_text(path, metrics.detail),
+                    None => text(t!("dst_folder", index = index + 1))
+                        .size(metrics.detail)
+                        .into(),
+                };
+                let written = text(t!(
+                    "progress.bytes",
+                    completed = format_bytes(destination.written_bytes),
+                    total = format_bytes(destination.total_bytes)
+                ))
+                .size(metrics.detail);
+                let state: Element<'a, LibreCardMessage> = match &destination.state {
+                    DestinationState::Writing => text(t!(
+                        "copying.destination.rate",
+                        rate = format_bytes(rates.per_second(index) as u64)
+                    ))
+                    .size(metrics.detail)
+                    .into(),
+                    DestinationState::Finalizing => status_text(
+                        Status::Warning,
+                        t!(
+                            "copying.destination.finalizing",
+                            count = format_count(destination.pending_files)
+                        ),
+                        high_contrast,
+                    )
+                    .size(metrics.detail)
+                    .into(),
+                    DestinationState::Done => status_text(
+                        Status::Success,
+                        t!("copying.destination.done"),
+                        high_contrast,
+                    )
+                    .size(metrics.detail)
+                    .into(),
+                    DestinationState::Failed(error) => {
+                        status_text(Status::Failure, error, high_contrast)
+                            .size(metrics.detail)
+                            .into()
+                    }
+                };
+                let mut row = row![label, written, state]
+                    .spacing(15)
+                    .align_y(iced::alignment::Alignment::Center);
+                let forecast = self.job_space_forecast.as_ref().and_then(|f| f.get(index));
+                if let Some(forecast) = forecast
+                    && let Some(left) = forecast.left_after()
+                {
+                    let (status, message) = match left {
+                        Ok(left) if forecast.fits() => (
+                            Status::Success,
+                            t!("space.left_after", left = format_bytes(left)),
+                        ),
+                        Ok(left) => (
+                            Status::Warning,
+                            t!(
+                                "space.left_after_margin",
+                                left = format_bytes(left),
+                                reserved = format_bytes(forecast.reserved)
+                            ),
+                        ),
+                        Err(missing) => (
+                            Status::Failure,
+                            t!("space.missing", missing = format_bytes(missing)),
+                        ),
+                    };
+                    row =
+                        row.push(status_text(status, message, high_contrast).size(metrics.detail));
+                }
+                row.into()
+            });
+        column(rows).spacing(5).into()
+    }
+
+    /// The running job's warnings: a line with the count and the latest one, which expands into
+    /// the feed of recent warnings, newest first.
+    fn view_warning_feed<'a>(
+        &self,
+        progress: &Progress,
+        warnings: &[JobWarning],
+    ) -> Option<Element<'a, LibreCardMessage>> {
+        let metrics = self.metrics();
+        let latest = warnings.last()?;
+        let summary = status_text(
+            Status::Warning,
+            t!(
+                "warnings.summary",
+                count = format_count(progress.warnings.max(warnings.len())),
+                latest = timed_warning_text(latest)
+            ),
+            self.config.high_contrast,
+        );
+        let mut feed = column![
+            button(summary)
+                .style(iced::widget::button::text)
+                .width(Length::Fill)
+                .on_press(LibreCardMessage::ToggleWarnings)
+        ]
+        .spacing(metrics.tight_spacing);
+        if self.show_warnings {
+            let list = column(
+                warnings
+                    .iter()
+                    .rev()
+                    .map(|warning| text(timed_warning_text(warning)).size(metrics.small).into()),
+            )
+            .spacing(4);
+            feed = feed.push(scrollable(list).height(Length::Fixed(metrics.list_height)));
+            if progress.warnings > warnings.len() {
+                feed = feed.push(
+                    text(t!(
+                        "warnings.older_hidden",
+                        count = format_count(progress.warnings - warnings.len())
+                    ))
+                    .size(metrics.small),
+                );
+            }
+        }
+        Some(feed.into())
+    }
+
+    /// Running verified/failed counts, once anything has been verified.
+    fn view_verification_counts(
+        &self,
+        progress: &Progress,
+    ) -> Option<Element<'_, LibreCardMessage>> {
+        if progress.verified_files + progress.failed_files == 0 {
+            return None;
+        }
+        let status = if progress.failed_files == 0 {
+            Status::Success
+        } else {
+            Status::Failure
+        };
+        Some(
+            status_text(
+                status,
+                t!(
+                    "copying.verified",
+                    verified = format_count(progress.verified_files),
+                    failed = format_count(progress.failed_files)
+                ),
+                self.config.high_contrast,
+            )
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center)
+            .into(),
+        )
+    }
+
+    /// Stops the running job. What it has written so far stays; a file it was partway through is
+    /// removed.
+    fn view_cancel_button(&self) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        button(text(t!("job.cancel")))
+            .on_press(LibreCardMessage::CancelOperation)
+            .padding(metrics.button_padding)
+            .into()
+    }
+
+    fn view_scan_stage(
+        &self,
+        progress: &Progress,
+        started_at: Instant,
+    ) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("scanning.title"))
+            .size(metrics.title)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let found_text = text(t!(
+            "scanning",
+            files = format_count(progress.total_files),
+            size = format_bytes(progress.total_bytes)
+        ))
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let elapsed_text = text(t!("elapsed", time = format_duration(started_at.elapsed())))
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        column![title, found_text, elapsed_text, self.view_cancel_button()]
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn view_copy_complete_stage<'a>(
+        &'a self,
+        summary: &'a CopySummary,
+    ) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let (total_bytes_copied, files) = (summary.total_bytes, &summary.files);
+        let title = text(match summary.dry_run {
+            true => t!("dry_run.finished.title"),
+            false => t!("copying.finished.title"),
+        })
+        .size(metrics.title)
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let bytes_text = text(t!(
+            match summary.dry_run {
+                true => "dry_run.finished",
+                false => "copying.finished",
+            },
+            size = format_bytes(total_bytes_copied)
+        ))
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        // A dry run wrote nothing, so there is nothing to verify
+        let next_button = match summary.dry_run {
+            true => button(text(t!("back")).size(metrics.heading))
+                .on_press(LibreCardMessage::BackToInput),
+            false => button(text(t!("checksum.start")).size(metrics.heading))
+                .on_press(LibreCardMessage::StartChecksum),
+        }
+        .width(Length::Fill)
+        .padding(metrics.button_padding);
+
+        let mut content =
+            column![title].push_maybe(self.job_id.map(|job_id| self.view_job_id(job_id)));
+        if summary.dry_run {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!("dry_run.watermark"),
+                    self.config.high_contrast,
+                )
+                .size(metrics.emphasis)
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+        content = content.push(bytes_text);
+
+        // The card isn't fully backed up, which matters more than anything below
+        if !summary.failed.is_empty() {
+            let list = column(summary.failed.iter().map(|failure| {
+                let path = failure.path.display();
+                text(match &failure.destination {
+                    Some(destination) => t!(
+                        "copying.failed_file.destination",
+                        path = path,
+                        destination = destination.display(),
+                        error = failure.error
+                    ),
+                    None => t!("copying.failed_file", path = path, error = failure.error),
+                })
+                .size(metrics.small)
+                .into()
+            }))
+            .spacing(4);
+            content = content
+                .push(
+                    status_text(
+                        Status::Failure,
+                        t!(
+                            "copying.failed",
+                            failed = format_count(summary.failed_files()),
+                            total = format_count(files.len())
+                        ),
+                        self.config.high_contrast,
+                    )
+                    .width(Length::Fill)
+                    .align_x(iced::alignment::Horizontal::Center),
+                )
+                .push(scrollable(list).height(Length::Fixed(metrics.list_height)))
+                .push(
+                    button(text(t!("copying.failed.export")))
+                        .on_press(LibreCardMessage::ExportFailedFiles),
+                );
+        }
+
+        let changed = files.iter().filter(|file| file.changed_during_copy).count();
+        if changed > 0 {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!("copying.changed_during_copy", count = format_count(changed)),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        // Lines that need no action, collapsed in the compact layout
+        let mut details = Vec::new();
+
+        let kept: usize = files.iter().map(|file| file.kept_existing).sum();
+        if kept > 0 {
+            details.push(t!("copying.kept_existing", count = format_count(kept)));
+        }
+
+        let duplicates = files
+            .iter()
+            .filter(|file| file.duplicate_of.is_some())
+            .count();
+        if duplicates > 0 {
+            details.push(t!(
+                "copying.duplicates_skipped",
+                count = format_count(duplicates)
+            ));
+        }
+
+        let excluded = files
+            .iter()
+            .filter(|file| file.skipped == Some(SkipReason::Excluded))
+            .count();
+        if excluded > 0 {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!("copying.excluded", count = format_count(excluded)),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        if let Some(baseline) = &summary.baseline {
+            details.push(t!(
+                "copying.baseline",
+                count = format_count(baseline.files.len()),
+                manifest = baseline.manifest.display()
+            ));
+        }
+
+        let denied = files
+            .iter()
+            .filter(|file| file.skipped == Some(SkipReason::PermissionDenied))
+            .count();
+        if denied > 0 {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!("copying.permission_denied", count = format_count(denied)),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        let salvaged = files.iter().filter(|file| file.salvaged.is_some()).count();
+        if salvaged > 0 {
+            content = content.push(
+                status_text(
+                    Status::Failure,
+                    t!("copying.salvaged", count = format_count(salvaged)),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        let reconnected = files.iter().filter(|file| file.reconnected).count();
+        if reconnected > 0 {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!("copying.reconnected", count = format_count(reconnected)),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        let placeholders = files
+            .iter()
+            .filter(|file| file.skipped == Some(SkipReason::Placeholder))
+            .count();
+        if placeholders > 0 {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!(
+                        "copying.placeholders_skipped",
+                        count = format_count(placeholders)
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+        if self.job_downloaded_placeholders > 0 {
+            details.push(t!(
+                "copying.placeholders_downloaded",
+                count = format_count(self.job_downloaded_placeholders)
+            ));
+        }
+        if !details.is_empty() {
+            if self.details_shown() {
+                content = content.extend(details.into_iter().map(|line| {
+                    text(line)
+                        .width(Length::Fill)
+                        .align_x(iced::alignment::Horizontal::Center)
+                        .into()
+                }));
+            }
+            content = content.push_maybe(self.view_details_toggle());
+        }
+
+        if summary.ownership_failures > 0 {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!(
+                        "copying.ownership_failures",
+                        count = format_count(summary.ownership_failures)
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        let unreliable = files.iter().filter(|file| file.unreliable_read).count();
+        if unreliable > 0 {
+            content = content.push(
+                status_text(
+                    Status::Failure,
+                    t!("copying.unreliable_read", count = format_count(unreliable)),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        if !summary.warnings.is_empty() {
+            let list = column(
+                summary
+                    .warnings
+                    .iter()
+                    .map(|warning| text(timed_warning_text(warning)).size(metrics.small).into()),
+            )
+            .spacing(4);
+            content = content
+                .push(text(t!(
+                    "warnings.all",
+                    count = format_count(summary.warnings.len())
+                )))
+                .push(scrollable(list).height(Length::Fixed(metrics.list_height)));
+        }
+
+        content
+            .push(next_button)
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn view_checksum_stage(
+        &self,
+        progress: &Progress,
+        started_at: Instant,
+        warnings: &[JobWarning],
+        workers: Option<usize>,
+    ) -> Element<'_, LibreCardMessage> {
+        if progress.phase == JobPhase::Scanning {
+            return self.view_scan_stage(progress, started_at);
+        }
+
+        let metrics = self.metrics();
+        let title = text(t!("checksum.title"))
+            .size(metrics.title)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let progress_bar = progress_bar(0.0..=1.0, progress.byte_fraction())
+            .width(Length::Fill)
+            .height(metrics.progress_bar_height);
+
+        let progress_text = text(t!(
+            "checksum",
+            completed = format_count(progress.completed_files),
+            total = format_count(progress.total_files)
+        ))
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let bytes_text = text(t!(
+            "progress.bytes",
+            completed = format_bytes(progress.done_bytes()),
+            total = format_bytes(progress.total_bytes)
+        ))
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let elapsed_text = text(t!("elapsed", time = format_duration(started_at.elapsed())))
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let mut content = column![title, progress_bar, progress_text, bytes_text]
+            .push_maybe(self.view_group_progress(progress))
+            .push_maybe(self.view_rate(progress, started_at));
+
+        // Only reported for files large enough to take a while, and only one file at a time
+        if progress.current_file_bytes > 0
+            && self.details_shown()
+            && workers.is_none_or(|workers| workers == 1)
+        {
+            content = content.push(
+                text(t!(
+                    "checksum.current_file",
+                    completed = format_bytes(progress.current_file_bytes),
+                    total = format_bytes(progress.current_file_size)
+                ))
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        if let Some(counts) = self.view_verification_counts(progress) {
+            content = content.push(counts);
+        }
+
+        if let Some(feed) = self.view_warning_feed(progress, warnings) {
+            content = content.push(feed);
+        }
+
+        // Files in flight finish first, so fewer only take over as they do
+        let workers = workers.map(|workers| {
+            let step = |label, to: usize| {
+                button(text(label).size(metrics.small))
+                    .on_press_maybe(
+                        (1..=MAX_VERIFY_WORKERS)
+                            .contains(&to)
+                            .then_some(LibreCardMessage::HashWorkersChanged(to)),
+                    )
+                    .padding(metrics.button_padding)
+            };
+            container(
+                row![
+                    text(t!("checksum.workers", count = workers)).size(metrics.small),
+                    step("−", workers.saturating_sub(1)),
+                    step("+", workers + 1),
+                ]
+                .spacing(metrics.tight_spacing)
+                .align_y(iced::alignment::Vertical::Center),
+            )
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center)
+        });
+
+        content
+            .push_maybe(workers)
+            .push(elapsed_text)
+            .push(self.view_cancel_button())
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// The job's id, shortened, with a button to copy all of it.
+    fn view_job_id(&self, job_id: JobId) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        container(
+            row![
+                text(t!("job.id", id = job_id.short())).size(metrics.small),
+                button(text(t!("job.copy_id")).size(metrics.small))
+                    .on_press(LibreCardMessage::CopyJobId(job_id)),
+            ]
+            .spacing(metrics.tight_spacing)
+            .align_y(iced::alignment::Vertical::Center),
+        )
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center)
+        .into()
     }
 
-    fn view_copy_stage(&self, progress: &Progress) -> Element<LibreCardMessage> {
-        let title = text("Copying Files")
-            .size(28)
+    fn view_checksum_complete_stage(
+        &self,
+        report: &ChecksumReport,
+        fingerprint: &Digest,
+        manifest_digest: &str,
+    ) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("checksum.finished.title"))
+            .size(metrics.title)
             .width(Length::Fill)
             .align_x(iced::alignment::Horizontal::Center);
 
-        let progress_value = if progress.total == 0 {
-            0.0
+        let error_count = report.count_errors();
+        let total_files = report.total_files();
+
+        let (status, status_message) = if error_count == 0 {
+            (
+                Status::Success,
+                t!("checksum.success", total = format_count(total_files)),
+            )
         } else {
-            progress.completed as f32 / progress.total as f32
+            (
+                Status::Failure,
+                t!(
+                    "checksum.finished",
+                    failed = format_count(error_count),
+                    total = format_count(total_files)
+                ),
+            )
         };
 
-        let progress_bar = progress_bar(0.0..=1.0, progress_value)
+        let result_text = status_text(status, status_message, self.config.high_contrast)
+            .width(Length::Fill)
+            .size(metrics.emphasis)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let export_button = button(text(t!("checksum.export")).size(metrics.heading))
+            .on_press(LibreCardMessage::ExportChecksum(ManifestFormat::Csv))
             .width(Length::Fill)
-            .height(30);
+            .padding(metrics.button_padding);
 
-        let progress_text = text(format!(
-            "Progress: {} / {}",
-            progress.completed, progress.total
+        let fingerprint_text = text(t!(
+            "checksum.fingerprint",
+            fingerprint = format!("{:x}", fingerprint)
         ))
         .width(Length::Fill)
         .align_x(iced::alignment::Horizontal::Center);
 
-        column![title, progress_bar, progress_text,]
-            .spacing(20)
-            .padding(20)
+        let passes = report.verification_passes();
+        let method_text = text(match report.method {
+            _ if report.compared && passes > 1 => {
+                t!("checksum.method.compare_passes", passes = passes)
+            }
+            _ if report.compared => t!("checksum.method.compare"),
+            VerifyMethod::ReadBack if passes > 1 => {
+                t!("checksum.method.read_back_passes", passes = passes)
+            }
+            VerifyMethod::ReadBack => t!("checksum.method.read_back"),
+            VerifyMethod::Stream => t!("checksum.method.stream"),
+            VerifyMethod::Handle => t!("checksum.method.handle"),
+        })
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        // A job checked only as it was copied can still be read back from the drives
+        let level = report.verification_level();
+        let level_row = container(
+            row![self.view_verification_level(level)]
+                .push_maybe((level == VerificationLevel::Copy).then(|| {
+                    button(text(t!("verification_level.upgrade")))
+                        .on_press(LibreCardMessage::UpgradeVerification)
+                }))
+                .spacing(metrics.tight_spacing)
+                .align_y(iced::alignment::Vertical::Center),
+        )
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let manifest_digest_text = text(t!("checksum.manifest_digest", digest = manifest_digest))
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let mut content = column![
+            title,
+            result_text,
+            method_text,
+            level_row,
+            fingerprint_text,
+            manifest_digest_text
+        ]
+        .push_maybe(report.job_id.map(|job_id| self.view_job_id(job_id)));
+
+        // Which drive is bad, before any file-level detail
+        let timestamps = self.config.timestamp_format().unwrap_or_default();
+        for (index, summary) in report.per_destination_summary().into_iter().enumerate() {
+            let destination = self.job_destinations.get(index).map_or_else(
+                || (index + 1).to_string(),
+                |path| path.display().to_string(),
+            );
+            content = content.push(
+                status_text(
+                    if summary.passed() {
+                        Status::Success
+                    } else {
+                        Status::Failure
+                    },
+                    t!(
+                        "checksum.destination_summary",
+                        destination = destination,
+                        verified = format_count(summary.verified),
+                        failed = format_count(summary.failed),
+                        missing = format_count(summary.missing)
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+            // When each drive was done with, for the chain of custody
+            let times = report
+                .destination_times
+                .get(index)
+                .copied()
+                .unwrap_or_default();
+            let finished: Vec<_> = [
+                times
+                    .copied_at
+                    .map(|at| t!("checksum.copied_at", time = timestamps.format(at))),
+                times
+                    .verified_at
+                    .map(|at| t!("checksum.verified_at", time = timestamps.format(at))),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            if !finished.is_empty() {
+                content = content.push(
+                    text(finished.join(" · "))
+                        .size(metrics.small)
+                        .width(Length::Fill)
+                        .align_x(iced::alignment::Horizontal::Center),
+                );
+            }
+        }
+
+        if report.stopped_at_failure {
+            content = content.push(
+                status_text(
+                    Status::Failure,
+                    t!(
+                        "checksum.stopped_at_failure",
+                        total = format_count(report.total_files()),
+                        unchecked = format_count(report.unchecked_files)
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+            // The file it stopped at is the last one checked
+            if let Some(failed) = report.files.last() {
+                content = content.push(
+                    row![
+                        status_text(
+                            Status::Failure,
+                            t!("checksum.failed_file"),
+                            self.config.high_contrast,
+                        )
+                        .size(metrics.emphasis),
+                        path_text(&failed.source.0, metrics.emphasis),
+                    ]
+                    .spacing(5),
+                );
+            }
+        }
+
+        if let Some(baseline) = &report.baseline {
+            content = content.push(
+                text(t!(
+                    "checksum.baseline",
+                    count = format_count(baseline.files.len()),
+                    manifest = baseline.manifest.display()
+                ))
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        if !report.disabled_destinations.is_empty() {
+            let disabled: Vec<_> = report
+                .disabled_destinations
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            content = content.push(
+                text(t!("checksum.disabled", destinations = disabled.join(", ")))
+                    .size(metrics.detail)
+                    .width(Length::Fill)
+                    .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        let unstable = report.unstable_files().count();
+        if unstable > 0 {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!("checksum.unstable", count = format_count(unstable)),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        let salvaged: Vec<_> = report.files.iter().filter(|file| file.salvaged()).collect();
+        if !salvaged.is_empty() {
+            content = content.push(
+                status_text(
+                    Status::Failure,
+                    t!(
+                        "checksum.salvaged",
+                        count = format_count(salvaged.len()),
+                        lost = format_bytes(salvaged.iter().map(|file| file.bytes_lost()).sum())
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        let changes = &report.source_changes;
+        if !(changes.added.is_empty() && changes.removed.is_empty() && changes.modified.is_empty())
+        {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!(
+                        "checksum.source_changed",
+                        added = format_count(changes.added.len()),
+                        removed = format_count(changes.removed.len()),
+                        modified = format_count(changes.modified.len())
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        if !changes.changed_during_copy.is_empty() {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!(
+                        "copying.changed_during_copy",
+                        count = format_count(changes.changed_during_copy.len())
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        // A card with a single folder, or none, has nothing to break down
+        let groups = report.groups();
+        if groups.len() > 1 {
+            for group in groups {
+                let expanded = self.expanded_group.as_ref() == Some(&group.name);
+                let status = match group.failed {
+                    0 => Status::Success,
+                    _ => Status::Failure,
+                };
+                content = content.push(
+                    row![
+                        status_text(
+                            status,
+                            t!(
+                                "checksum.group",
+                                group = group.name,
+                                files = format_count(group.files),
+                                passed = format_count(group.passed),
+                                failed = format_count(group.failed)
+                            ),
+                            self.config.high_contrast,
+                        )
+                        .width(Length::Fill),
+                        button(text(if expanded {
+                            t!("checksum.group.hide")
+                        } else {
+                            t!("checksum.group.show")
+                        }))
+                        .on_press(LibreCardMessage::ToggleGroup(group.name.clone())),
+                    ]
+                    .spacing(metrics.tight_spacing)
+                    .align_y(iced::alignment::Alignment::Center),
+                );
+                if expanded {
+                    let files = report
+                        .files
+                        .iter()
+                        .map(|file| (report.relative_path(file), file.consistent()))
+                        .chain(report.skipped.iter().filter_map(|(file, reason)| {
+                            (*reason == SkipReason::CopyFailed).then_some((file.as_path(), false))
+                        }))
+                        .filter(|(file, _)| file_group(file) == group.name)
+                        .map(|(file, passed)| {
+                            let status = match passed {
+                                true => Status::Success,
+                                false => Status::Failure,
+                            };
+                            status_text(
+                                status,
+                                file.display().to_string(),
+                                self.config.high_contrast,
+                            )
+                            .size(metrics.small)
+                            .into()
+                        })
+                        .collect::<Vec<_>>();
+                    content = content.push(
+                        scrollable(column(files)).height(Length::Fixed(metrics.short_list_height)),
+                    );
+                }
+            }
+        }
+
+        // Which copies of each failed file differ, and what was found there
+        let mismatched: Vec<_> = report
+            .files
+            .iter()
+            .filter(|file| !file.consistent())
+            .collect();
+        if !mismatched.is_empty() {
+            let rows = mismatched.into_iter().map(|file| {
+                // A comparison that gave up early has no hash of the whole source
+                let source_hash = match file.stopped_at {
+                    None => format!("{:x}", file.source.1),
+                    Some(_) => t!("checksum.mismatch.not_read").into_owned(),
+                };
+                let mut entry = column![
+                    path_text(report.relative_path(file), metrics.detail),
+                    text(t!("checksum.mismatch.source", hash = source_hash)).size(metrics.small),
+                ]
+                .spacing(2);
+                let expected = file
+                    .destinations
+                    .iter()
+                    .filter(|(_, hash)| *hash != DestinationHash::NotExpected);
+                for ((path, matched), (_, hash)) in
+                    file.destination_matches().into_iter().zip(expected)
+                {
+                    let found = match hash {
+                        DestinationHash::Hashed(digest) => format!("{digest:x}"),
+                        DestinationHash::DiffersAt(offset) => {
+                            t!("checksum.mismatch.differs_at", offset = offset).into_owned()
+                        }
+                        _ => t!("checksum.mismatch.missing").into_owned(),
+                    };
+                    let status = match matched {
+                        true => Status::Success,
+                        false => Status::Failure,
+                    };
+                    entry = entry.push(
+                        status_text(
+                            status,
+                            t!(
+                                "checksum.mismatch.destination",
+                                path = path.display(),
+                                found = found
+                            ),
+                            self.config.high_contrast,
+                        )
+                        .size(metrics.small),
+                    );
+                }
+                entry.into()
+            });
+            content = content
+                .push(
+                    text(t!("checksum.mismatches"))
+                        .size(metrics.heading)
+                        .width(Length::Fill),
+                )
+                .push(
+                    scrollable(column(rows).spacing(metrics.tight_spacing))
+                        .height(Length::Fixed(metrics.list_height)),
+                );
+        }
+
+        for (index, missing) in report.missing_by_destination().into_iter().enumerate() {
+            if missing.is_empty() {
+                continue;
+            }
+            let destination = self.job_destinations.get(index).map_or_else(
+                || (index + 1).to_string(),
+                |path| path.display().to_string(),
+            );
+            let expanded = self.expanded_missing == Some(index);
+            content = content.push(
+                row![
+                    status_text(
+                        Status::Failure,
+                        t!(
+                            "checksum.missing",
+                            destination = destination,
+                            count = format_count(missing.len())
+                        ),
+                        self.config.high_contrast,
+                    )
+                    .width(Length::Fill),
+                    button(text(if expanded {
+                        t!("checksum.missing.hide")
+                    } else {
+                        t!("checksum.missing.show")
+                    }))
+                    .on_press(LibreCardMessage::ToggleMissingList(index)),
+                ]
+                .spacing(metrics.tight_spacing)
+                .align_y(iced::alignment::Alignment::Center),
+            );
+            if expanded {
+                content = content.push(
+                    scrollable(column(
+                        missing.iter().map(|path| path_text(path, metrics.small)),
+                    ))
+                    .height(Length::Fixed(metrics.short_list_height)),
+                );
+            }
+        }
+
+        if !report.extra_files.is_empty() {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!(
+                        "checksum.extra_files",
+                        count = format_count(report.extra_files.len())
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        if let Some(folders) = report
+            .folders
+            .as_ref()
+            .filter(|folders| !folders.is_empty())
+        {
+            content = content.push(
+                status_text(
+                    Status::Warning,
+                    t!(
+                        "checksum.folders",
+                        missing = format_count(folders.missing.len()),
+                        extra = format_count(folders.extra.len())
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        if !changes.unreliable_reads.is_empty() {
+            content = content.push(
+                status_text(
+                    Status::Failure,
+                    t!(
+                        "copying.unreliable_read",
+                        count = format_count(changes.unreliable_reads.len())
+                    ),
+                    self.config.high_contrast,
+                )
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center),
+            );
+        }
+
+        content = content.push(export_button).push(
+            row![
+                button(text(t!("checksum.export.certificate"))).on_press(
+                    LibreCardMessage::ExportChecksum(ManifestFormat::Certificate),
+                ),
+                button(text(t!("checksum.export.json")))
+                    .on_press(LibreCardMessage::ExportChecksum(ManifestFormat::Json)),
+            ]
+            .spacing(metrics.tight_spacing),
+        );
+
+        // A verified copy can stand in for the card for a drive that arrives late
+        if catch_up_origin(report).is_some() {
+            content = content
+                .push(button(text(t!("catch_up"))).on_press(LibreCardMessage::CatchUpDestination));
+        }
+
+        // Lists that standard tools can check, for algorithms they support
+        let mut lists = row![].spacing(metrics.tight_spacing);
+        if let Some(tag) = report.algorithm.tag_name() {
+            let tool = format!("{}sum", tag.to_lowercase());
+            lists = lists
+                .push(
+                    button(text(t!("checksum.export.gnu", tool = tool)))
+                        .on_press(LibreCardMessage::ExportChecksum(ManifestFormat::Gnu)),
+                )
+                .push(
+                    button(text(t!("checksum.export.bsd")))
+                        .on_press(LibreCardMessage::ExportChecksum(ManifestFormat::Bsd)),
+                );
+        }
+        if report.algorithm.mhl_name().is_some() {
+            lists = lists.push(
+                button(text(t!("checksum.export.mhl")))
+                    .on_press(LibreCardMessage::ExportChecksum(ManifestFormat::Mhl)),
+            );
+        }
+        content = content.push(lists);
+
+        let new_job_button = button(text(t!("checksum.new_job")).size(metrics.heading))
+            .on_press(LibreCardMessage::BackToInput)
+            .width(Length::Fill)
+            .padding(metrics.button_padding);
+
+        content
+            .push(new_job_button)
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
             .width(Length::Fill)
             .into()
     }
 
-    fn view_copy_complete_stage(&self, total_bytes_copied: u64) -> Element<LibreCardMessage> {
-        let title = text("Copy Complete")
-            .size(28)
+    fn view_reconcile_complete_stage(
+        &self,
+        report: &ContentReport,
+    ) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("reconcile.title"))
+            .size(metrics.title)
             .width(Length::Fill)
             .align_x(iced::alignment::Horizontal::Center);
 
-        let bytes_text = text(format!(
-            "Total Bytes Copied: {}",
-            human_bytes(total_bytes_copied as f64)
+        let summary_text = text(t!(
+            "reconcile.summary",
+            total = format_count(report.source_files),
+            algorithm = report.algorithm
         ))
         .width(Length::Fill)
         .align_x(iced::alignment::Horizontal::Center);
 
-        let checksum_button = button(text("Verify Checksum").size(20))
+        let mut content = column![title, summary_text];
+
+        for destination in &report.destinations {
+            let status = if destination.missing.is_empty() {
+                Status::Success
+            } else {
+                Status::Failure
+            };
+            content = content.push(status_text(
+                status,
+                t!(
+                    "reconcile.destination",
+                    destination = destination.destination.display(),
+                    in_place = format_count(destination.in_place),
+                    moved = format_count(destination.moved.len()),
+                    missing = format_count(destination.missing.len())
+                ),
+                self.config.high_contrast,
+            ));
+            if !destination.missing.is_empty() {
+                content = content.push(
+                    scrollable(column(
+                        destination
+                            .missing
+                            .iter()
+                            .map(|path| path_text(path, metrics.small)),
+                    ))
+                    .height(Length::Fixed(120.0)),
+                );
+            }
+        }
+
+        let back_button = button(text(t!("back")).size(metrics.heading))
+            .on_press(LibreCardMessage::BackToInput)
             .width(Length::Fill)
-            .padding(15)
-            .on_press(LibreCardMessage::StartChecksum);
+            .padding(metrics.button_padding);
 
-        column![title, bytes_text, checksum_button,]
-            .spacing(20)
-            .padding(20)
+        content
+            .push(back_button)
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
             .width(Length::Fill)
             .into()
     }
 
-    fn view_checksum_stage(&self, progress: &Progress) -> Element<LibreCardMessage> {
-        let title = text("Verifying File Integrity")
-            .size(28)
+    fn view_readability_complete_stage(
+        &self,
+        report: &ReadabilityReport,
+    ) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("readability.title"))
+            .size(metrics.title)
             .width(Length::Fill)
             .align_x(iced::alignment::Horizontal::Center);
 
-        let progress_value = if progress.total == 0 {
-            0.0
-        } else {
-            progress.completed as f32 / progress.total as f32
+        let troubled: Vec<_> = report.troubled().collect();
+        let (status, summary) = match troubled.len() {
+            0 => (
+                Status::Success,
+                t!(
+                    "readability.passed",
+                    total = format_count(report.files.len()),
+                    algorithm = report.algorithm
+                ),
+            ),
+            count => (
+                Status::Failure,
+                t!(
+                    "readability.failed",
+                    count = format_count(count),
+                    total = format_count(report.files.len())
+                ),
+            ),
         };
+        let summary_text = status_text(status, summary, self.config.high_contrast)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let mut content = column![title, summary_text];
+
+        if !troubled.is_empty() {
+            let rows = troubled.into_iter().map(|file| {
+                let reason = match (&file.first, &file.second) {
+                    (Err(error), _) | (_, Err(error)) => {
+                        t!("readability.read_failed", error = error)
+                    }
+                    _ => t!("readability.differed"),
+                };
+                row![
+                    path_text(&file.path, metrics.small),
+                    text(reason).size(metrics.small),
+                ]
+                .spacing(metrics.tight_spacing)
+                .into()
+            });
+            content = content.push(
+                scrollable(column(rows).spacing(4)).height(Length::Fixed(metrics.list_height)),
+            );
+        }
+
+        // Reading twice says nothing about a copy; the hashes only cover the card
+        content = content.push(text(t!("readability.cache_note")).size(metrics.small));
 
-        let progress_bar = progress_bar(0.0..=1.0, progress_value)
+        let export_button = button(text(t!("checksum.export")).size(metrics.heading))
+            .on_press(LibreCardMessage::ExportReadability(ManifestFormat::Csv))
             .width(Length::Fill)
-            .height(30);
+            .padding(metrics.button_padding);
+        content = content.push(export_button).push(
+            button(text(t!("readability.export.summary"))).on_press(
+                LibreCardMessage::ExportReadability(ManifestFormat::Certificate),
+            ),
+        );
+        if let Some(tag) = report.algorithm.tag_name() {
+            let tool = format!("{}sum", tag.to_lowercase());
+            content = content.push(
+                row![
+                    button(text(t!("checksum.export.gnu", tool = tool)))
+                        .on_press(LibreCardMessage::ExportReadability(ManifestFormat::Gnu)),
+                    button(text(t!("checksum.export.bsd")))
+                        .on_press(LibreCardMessage::ExportReadability(ManifestFormat::Bsd)),
+                ]
+                .spacing(metrics.tight_spacing),
+            );
+        }
 
-        let progress_text = text(format!(
-            "Progress: {} / {}",
-            progress.completed, progress.total
-        ))
-        .width(Length::Fill)
-        .align_x(iced::alignment::Horizontal::Center);
+        let back_button = button(text(t!("back")).size(metrics.heading))
+            .on_press(LibreCardMessage::BackToInput)
+            .width(Length::Fill)
+            .padding(metrics.button_padding);
 
-        column![title, progress_bar, progress_text,]
-            .spacing(20)
-            .padding(20)
+        content
+            .push(back_button)
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
             .width(Length::Fill)
             .into()
     }
 
-    fn view_checksum_complete_stage(&self, report: &ChecksumReport) -> Element<LibreCardMessage> {
-        let title = text("Checksum Verification Complete")
-            .size(28)
+    fn view_report_diff_stage<'a>(
+        &'a self,
+        first: &'a Path,
+        second: &'a Path,
+        diff: &'a ManifestDiff,
+        folder: bool,
+    ) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        // A checked folder reads the same as a second report, in its own words
+        let key = |compare: &'static str, verify: &'static str| match folder {
+            true => verify,
+            false => compare,
+        };
+
+        let title = text(t!(key("compare.title", "verify_manifest.title")))
+            .size(metrics.title)
             .width(Length::Fill)
             .align_x(iced::alignment::Horizontal::Center);
 
-        let error_count = report.count_errors();
-        let total_files = report.total_files();
+        let files = column![
+            row![
+                text(t!(key("compare.first", "verify_manifest.manifest"))),
+                path_text(first, metrics.detail)
+            ]
+            .spacing(metrics.tight_spacing),
+            row![
+                text(t!(key("compare.second", "verify_manifest.folder"))),
+                path_text(second, metrics.detail)
+            ]
+            .spacing(metrics.tight_spacing),
+        ]
+        .spacing(5);
 
-        let (status_message, status_color) = if error_count == 0 {
+        let high_contrast = self.config.high_contrast;
+        let (status, message) = if diff.is_empty() {
             (
-                format!("All {} files verified successfully!", total_files),
-                Color::from_rgb(0.0, 0.7, 0.0),
+                Status::Success,
+                t!(
+                    key("compare.identical", "verify_manifest.passed"),
+                    count = format_count(diff.matching)
+                ),
             )
         } else {
             (
-                format!(
-                    "WARNING: {} out of {} files failed verification!",
-                    error_count, total_files
+                Status::Failure,
+                t!(
+                    key("compare.matching", "verify_manifest.failed"),
+                    count = format_count(diff.matching)
                 ),
-                Color::from_rgb(0.9, 0.0, 0.0),
             )
         };
+        let mut content = column![
+            title,
+            files,
+            status_text(status, message, high_contrast)
+                .size(metrics.emphasis)
+                .width(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Center)
+        ];
+
+        if let Some(subfolder) = &diff.subfolder {
+            content = content.push(status_text(
+                Status::Warning,
+                t!("verify_manifest.partial", folder = subfolder),
+                high_contrast,
+            ));
+        }
+        if diff.algorithms_differ {
+            content = content.push(status_text(
+                Status::Warning,
+                t!("compare.algorithms_differ"),
+                high_contrast,
+            ));
+        }
+        for (altered, key) in diff.altered.into_iter().zip([
+            key("compare.first_altered", "verify_manifest.altered"),
+            "compare.second_altered",
+        ]) {
+            if altered {
+                content = content.push(status_text(Status::Warning, t!(key), high_contrast));
+            }
+        }
+
+        let sizes: Vec<String> = diff
+            .size_mismatches
+            .iter()
+            .map(|(path, first, second)| {
+                format!(
+                    "{path} ({} / {})",
+                    format_bytes(*first),
+                    format_bytes(*second)
+                )
+            })
+            .collect();
+        for (key, paths) in [
+            (
+                key("compare.only_in_first", "verify_manifest.missing"),
+                diff.only_in_first.as_slice(),
+            ),
+            (
+                key("compare.only_in_second", "verify_manifest.unlisted"),
+                diff.only_in_second.as_slice(),
+            ),
+            ("compare.hash_mismatches", diff.hash_mismatches.as_slice()),
+            ("compare.size_mismatches", sizes.as_slice()),
+        ] {
+            if paths.is_empty() {
+                continue;
+            }
+            content = content
+                .push(status_text(
+                    Status::Failure,
+                    t!(key, count = format_count(paths.len())),
+                    high_contrast,
+                ))
+                .push(
+                    scrollable(column(
+                        paths
+                            .iter()
+                            .map(|path| text(path.clone()).size(metrics.small).into()),
+                    ))
+                    .height(Length::Fixed(120.0)),
+                );
+        }
+
+        let export_button = button(text(t!("compare.export")))
+            .on_press_maybe((!diff.is_empty()).then_some(LibreCardMessage::ExportReportDiff));
+        let back_button = button(text(t!("back")).size(metrics.heading))
+            .on_press(LibreCardMessage::BackToInput)
+            .width(Length::Fill)
+            .padding(metrics.button_padding);
+
+        content
+            .push(export_button)
+            .push(back_button)
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn view_hash_file_stage<'a>(
+        &'a self,
+        path: &'a Path,
+        algorithm: HashAlgorithm,
+        progress: &Progress,
+        digest: Option<&Result<Digest, String>>,
+        expected: &'a str,
+    ) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("hash_file.title"))
+            .size(metrics.title)
+            .width(Length::Fill)
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let mut content = column![
+            title,
+            path_text(path, metrics.emphasis),
+            text(t!("hash_file.algorithm", algorithm = algorithm)),
+        ];
+
+        let digest = match digest {
+            None => {
+                return content
+                    .push(
+                        progress_bar(0.0..=1.0, progress.byte_fraction())
+                            .width(Length::Fill)
+                            .height(metrics.progress_bar_height),
+                    )
+                    .push(
+                        text(t!(
+                            "progress.bytes",
+                            completed = format_bytes(progress.done_bytes()),
+                            total = format_bytes(progress.total_bytes)
+                        ))
+                        .width(Length::Fill)
+                        .align_x(iced::alignment::Horizontal::Center),
+                    )
+                    .push(
+                        button(text(t!("hash_file.cancel")))
+                            .on_press(LibreCardMessage::CancelFileHash),
+                    )
+                    .spacing(metrics.spacing)
+                    .padding(metrics.padding)
+                    .width(Length::Fill)
+                    .into();
+            }
+            Some(Err(error)) => {
+                content = content.push(status_text(
+                    Status::Failure,
+                    error,
+                    self.config.high_contrast,
+                ));
+                None
+            }
+            Some(Ok(digest)) => Some(format!("{digest:x}")),
+        };
+
+        if let Some(digest) = digest {
+            // Lists often put the file name after the hash, so any word of the pasted text counts
+            let verdict = (!expected.trim().is_empty()).then(|| {
+                if expected
+                    .split_whitespace()
+                    .any(|word| word.eq_ignore_ascii_case(&digest))
+                {
+                    status_text(
+                        Status::Success,
+                        t!("hash_file.match"),
+                        self.config.high_contrast,
+                    )
+                } else {
+                    status_text(
+                        Status::Failure,
+                        t!("hash_file.mismatch"),
+                        self.config.high_contrast,
+                    )
+                }
+            });
+            content = content
+                .push(
+                    row![
+                        text(digest).font(iced::Font::MONOSPACE).width(Length::Fill),
+                        button(text(t!("hash_file.copy"))).on_press(LibreCardMessage::CopyFileHash),
+                    ]
+                    .spacing(metrics.tight_spacing)
+                    .align_y(iced::alignment::Alignment::Center),
+                )
+                .push(
+                    text_input(&t!("hash_file.expected"), expected)
+                        .on_input(LibreCardMessage::ExpectedHashChanged)
+                        .padding(metrics.field_padding),
+                )
+                .push_maybe(verdict.map(|verdict| verdict.size(metrics.emphasis)));
+        }
+
+        content
+            .push(
+                button(text(t!("back")).size(metrics.heading))
+                    .on_press(LibreCardMessage::BackToInput)
+                    .width(Length::Fill)
+                    .padding(metrics.button_padding),
+            )
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
+            .width(Length::Fill)
+            .into()
+    }
 
-        let status_text = text(status_message)
+    fn view_cancelled_stage(
+        &self,
+        completed: usize,
+        total: usize,
+    ) -> Element<'_, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("cancelled.title"))
+            .size(metrics.title)
             .width(Length::Fill)
-            .size(16)
-            .color(status_color)
             .align_x(iced::alignment::Horizontal::Center);
 
-        let export_button = button(text("Export Checksum Report").size(20))
-            .on_press(LibreCardMessage::ExportChecksum)
+        let summary_text = text(t!(
+            "cancelled",
+            completed = format_count(completed),
+            total = format_count(total)
+        ))
+        .width(Length::Fill)
+        .align_x(iced::alignment::Horizontal::Center);
+
+        let back_button = button(text(t!("back")).size(metrics.heading))
+            .on_press(LibreCardMessage::BackToInput)
+            .width(Length::Fill)
+            .padding(metrics.button_padding);
+
+        column![title, summary_text, back_button]
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// What the app was doing when it panicked during a job, with the details for a bug report.
+    fn view_recovery_stage<'a>(&'a self, report: &'a CrashReport) -> Element<'a, LibreCardMessage> {
+        let metrics = self.metrics();
+        let title = text(t!("crash.title"))
+            .size(metrics.title)
             .width(Length::Fill)
-            .padding(15);
+            .align_x(iced::alignment::Horizontal::Center);
+
+        let job = &report.job;
+        let mut details = column![text(t!("crash.when", when = format_recent_time(report.at)))]
+            .spacing(metrics.tight_spacing);
+        if let Some(job_id) = job.job_id {
+            details = details.push(self.view_job_id(job_id));
+        }
+        if let Some(source) = &report.source {
+            details = details.push(
+                row![text(t!("src_folder")), path_text(source, metrics.detail)]
+                    .spacing(metrics.tight_spacing),
+            );
+        }
+        for (index, destination) in job.destinations.iter().enumerate() {
+            if let Some(path) = &destination.path {
+                details = details.push(
+                    row![
+                        text(t!("dst_folder", index = index + 1)),
+                        path_text(path, metrics.detail)
+                    ]
+                    .spacing(metrics.tight_spacing),
+                );
+            }
+        }
+        details = details.push(text(t!(
+            "crash.progress",
+            completed = format_count(job.files_done),
+            total = format_count(job.files_total),
+            bytes = format_bytes(job.bytes_done),
+            total_bytes = format_bytes(job.bytes_total)
+        )));
+        details = details.push(text(t!("crash.left")));
+        details = details.push(
+            status_text(
+                Status::Failure,
+                t!("crash.message", message = &report.message),
+                self.config.high_contrast,
+            )
+            .size(metrics.small),
+        );
+        let backtrace = scrollable(text(&report.backtrace).size(metrics.small))
+            .height(Length::Fixed(metrics.short_list_height))
+            .width(Length::Fill);
+
+        let buttons = row![
+            button(text(t!("crash.copy")))
+                .on_press(LibreCardMessage::CopyCrashDetails)
+                .padding(metrics.button_padding),
+            button(text(t!("crash.continue")).size(metrics.heading))
+                .on_press(LibreCardMessage::DismissCrashReport)
+                .width(Length::Fill)
+                .padding(metrics.button_padding),
+        ]
+        .spacing(metrics.tight_spacing)
+        .align_y(iced::alignment::Alignment::Center);
 
-        column![title, status_text, export_button,]
-            .spacing(20)
-            .padding(20)
+        column![title, details, backtrace, buttons]
+            .spacing(metrics.spacing)
+            .padding(metrics.padding)
             .width(Length::Fill)
             .into()
     }
@@ -0,0 +1,131 @@
+use crate::i18n::format_clock;
+use futures::Stream;
+use std::collections::VecDeque;
+use std::fmt::{self, Write};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Events kept for the console; older ones are dropped.
+pub const CONSOLE_LIMIT: usize = 500;
+
+/// Events recorded before the GUI started listening, and the channel it takes them from.
+static EVENTS: Mutex<Option<mpsc::UnboundedReceiver<LogEntry>>> = Mutex::new(None);
+
+/// A tracing event as shown on the console.
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    pub at: SystemTime,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// Forwards every tracing event to the console.
+struct ConsoleLayer {
+    tx: mpsc::UnboundedSender<LogEntry>,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for ConsoleLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Message::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut message = Message::default();
+        event.record(&mut message);
+        // Fields of the spans the event is in, e.g. the id of the job that logged it
+        for span in ctx
+            .event_scope(event)
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+        {
+            if let Some(fields) = span.extensions().get::<Message>()
+                && !fields.0.is_empty()
+            {
+                message.0.push(' ');
+                message.0.push_str(&fields.0);
+            }
+        }
+        let _ = self.tx.send(LogEntry {
+            at: SystemTime::now(),
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            message: message.0,
+        });
+    }
+}
+
+/// The message of an event followed by its other fields as `name=value`, or the fields of a
+/// span.
+#[derive(Default)]
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        let _ = match field.name() {
+            "message" => write!(self.0, "{value:?}"),
+            name => write!(self.0, "{name}={value:?}"),
+        };
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.record_debug(field, &format_args!("{value}")),
+            _ => self.record_debug(field, &value),
+        }
+    }
+}
+
+/// Sets the console up as the global tracing subscriber. Events are kept until the GUI takes
+/// them with [`console_events`].
+pub fn init() {
+    let (tx, rx) = mpsc::unbounded_channel();
+    *EVENTS.lock().unwrap() = Some(rx);
+    let subscriber = tracing_subscriber::registry().with(ConsoleLayer { tx });
+    // Only fails if a subscriber is already set, which then gets the events instead
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+/// Emits each event recorded since [`init`]. Only the first listener gets them.
+pub fn console_events() -> impl Stream<Item = LogEntry> {
+    let rx = EVENTS.lock().unwrap().take();
+    futures::stream::unfold(rx, |rx| async move {
+        let mut rx = rx?;
+        let entry = rx.recv().await?;
+        Some((entry, Some(rx)))
+    })
+}
+
+/// Adds `entry` to the console, dropping the oldest event once it holds [`CONSOLE_LIMIT`].
+pub fn push_entry(console: &mut VecDeque<LogEntry>, entry: LogEntry) {
+    console.push_back(entry);
+    if console.len() > CONSOLE_LIMIT {
+        console.pop_front();
+    }
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:5} {}: {}",
+            format_clock(self.at),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
@@ -0,0 +1,161 @@
+use crate::paths;
+use crate::status_server::JobStatus;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::fmt::Write;
+use std::io;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+/// The running job, for the panic hook to save: its source and what the status server would
+/// report about it.
+static RUNNING: Mutex<Option<(Option<PathBuf>, JobStatus)>> = Mutex::new(None);
+
+/// Whether a report was saved for a panic in this run of the app.
+static SAVED: AtomicBool = AtomicBool::new(false);
+
+/// A panic during a job, saved by the panic hook so the next start can tell the user what was
+/// interrupted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub at: SystemTime,
+    pub version: String,
+    /// What the panic said, and where.
+    pub message: String,
+    pub backtrace: String,
+    pub source: Option<PathBuf>,
+    /// The job as it was when the app panicked.
+    pub job: JobStatus,
+}
+
+impl CrashReport {
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join("crash.json"))
+    }
+
+    /// The report of the last panic during a job, if one was saved and not dismissed.
+    pub fn load() -> Option<CrashReport> {
+        let content = std::fs::read_to_string(Self::path()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Written to a temporary file first, like the last job, as the app is going down.
+    fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let partial = path.with_extension("json.partial");
+        std::fs::write(
+            &partial,
+            serde_json::to_vec(self).map_err(io::Error::other)?,
+        )?;
+        std::fs::rename(partial, path)
+    }
+
+    /// Removes the saved report, once the user has seen it.
+    pub fn clear() {
+        if let Some(path) = Self::path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Everything in the report as plain text, for a bug report.
+    pub fn details(&self) -> String {
+        let mut details = format!("LibreCard {}\n", self.version);
+        let job = &self.job;
+        if let Some(job_id) = job.job_id {
+            let _ = writeln!(details, "Job: {job_id}");
+        }
+        if let Some(source) = &self.source {
+            let _ = writeln!(details, "Source: {}", source.display());
+        }
+        for (index, destination) in job.destinations.iter().enumerate() {
+            if let Some(path) = &destination.path {
+                let _ = writeln!(details, "Destination {}: {}", index + 1, path.display());
+            }
+        }
+        let _ = writeln!(
+            details,
+            "Stage: {:?}, {} of {} files, {} of {} bytes",
+            job.stage, job.files_done, job.files_total, job.bytes_done, job.bytes_total
+        );
+        if let Some(file) = &job.current_file {
+            let _ = writeln!(details, "Current file: {}", file.display());
+        }
+        let _ = write!(details, "Panic: {}\n\n{}", self.message, self.backtrace);
+        details
+    }
+}
+
+/// The message a panic was started with, if it was a string.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => (*message).to_owned(),
+        None => payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_else(|| "Box<dyn Any>".to_owned()),
+    }
+}
+
+/// Logs every panic with its backtrace, and saves a [`CrashReport`] for one during a job, before
+/// the default hook runs.
+pub fn install() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let mut message = panic_message(info.payload());
+        if let Some(location) = info.location() {
+            let _ = write!(message, " at {location}");
+        }
+        let backtrace = Backtrace::force_capture().to_string();
+        tracing::error!("Panicked: {message}\n{backtrace}");
+        // A panic while the job is being updated leaves it locked; that update is lost anyway
+        let running = RUNNING.try_lock().ok().and_then(|running| running.clone());
+        if let Some((source, job)) = running {
+            let report = CrashReport {
+                at: SystemTime::now(),
+                version: env!("CARGO_PKG_VERSION").to_owned(),
+                message,
+                backtrace,
+                source,
+                job,
+            };
+            match report.save() {
+                Ok(()) => SAVED.store(true, Ordering::Relaxed),
+                Err(e) => tracing::error!("Couldn't save the crash report: {e}"),
+            }
+        }
+        default(info);
+    }));
+}
+
+/// Keeps the running `job` for the panic hook, or `None` once no job runs. A report saved for a
+/// panic the app got through is removed when its job ends, as there is nothing to recover.
+pub fn track(job: Option<(Option<PathBuf>, JobStatus)>) {
+    let ended = job.is_none();
+    if let Ok(mut running) = RUNNING.lock() {
+        *running = job;
+    }
+    if ended && SAVED.swap(false, Ordering::Relaxed) {
+        CrashReport::clear();
+    }
+}
+
+/// Runs a job's `future`, turning a panic in it into the message `failed` makes of it. Otherwise
+/// the job would vanish with the panic, and the app would wait for it forever.
+pub async fn catch_panic<T>(
+    future: impl Future<Output = T>,
+    failed: impl FnOnce(String) -> T,
+) -> T {
+    match AssertUnwindSafe(future).catch_unwind().await {
+        Ok(output) => output,
+        Err(payload) => failed(panic_message(&*payload)),
+    }
+}
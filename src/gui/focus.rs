@@ -0,0 +1,273 @@
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer::{self, Quad};
+use iced::advanced::widget::operation::{Focusable, Operation};
+use iced::advanced::widget::{Tree, Widget, tree};
+use iced::advanced::{Clipboard, Shell, mouse};
+use iced::keyboard::{self, key};
+use iced::widget::{Button, Checkbox, button as button_style};
+use iced::{Border, Color, Element, Event, Length, Padding, Rectangle, Size, Theme, event};
+
+/// Widest the focus ring is drawn.
+const RING_WIDTH: f32 = 2.0;
+
+/// A button that can also be reached with Tab and pressed with Space or Enter. Built like
+/// [`iced::widget::button`].
+pub fn button<'a, Message: Clone + 'a>(
+    content: impl Into<Element<'a, Message>>,
+) -> FocusButton<'a, Message> {
+    FocusButton(Button::new(content))
+}
+
+/// A checkbox that can also be reached with Tab and toggled with Space or Enter. Built like
+/// [`iced::widget::checkbox`].
+pub fn checkbox<'a, Message: 'a>(
+    label: impl Into<String>,
+    is_checked: bool,
+) -> FocusCheckbox<'a, Message> {
+    FocusCheckbox(Checkbox::new(label, is_checked))
+}
+
+pub struct FocusButton<'a, Message>(Button<'a, Message>);
+
+impl<'a, Message: Clone + 'a> FocusButton<'a, Message> {
+    pub fn on_press(self, message: Message) -> Self {
+        FocusButton(self.0.on_press(message))
+    }
+
+    pub fn on_press_maybe(self, message: Option<Message>) -> Self {
+        FocusButton(self.0.on_press_maybe(message))
+    }
+
+    pub fn width(self, width: impl Into<Length>) -> Self {
+        FocusButton(self.0.width(width))
+    }
+
+    pub fn padding(self, padding: impl Into<Padding>) -> Self {
+        FocusButton(self.0.padding(padding))
+    }
+
+    pub fn style(
+        self,
+        style: impl Fn(&Theme, button_style::Status) -> button_style::Style + 'a,
+    ) -> Self {
+        FocusButton(self.0.style(style))
+    }
+}
+
+impl<'a, Message: Clone + 'a> From<FocusButton<'a, Message>> for Element<'a, Message> {
+    fn from(button: FocusButton<'a, Message>) -> Self {
+        Element::new(Focus::new(button.0))
+    }
+}
+
+pub struct FocusCheckbox<'a, Message>(Checkbox<'a, Message>);
+
+impl<'a, Message: 'a> FocusCheckbox<'a, Message> {
+    pub fn on_toggle(self, f: impl Fn(bool) -> Message + 'a) -> Self {
+        FocusCheckbox(self.0.on_toggle(f))
+    }
+}
+
+impl<'a, Message: 'a> From<FocusCheckbox<'a, Message>> for Element<'a, Message> {
+    fn from(checkbox: FocusCheckbox<'a, Message>) -> Self {
+        Element::new(Focus::new(checkbox.0))
+    }
+}
+
+/// Makes a widget that reacts to clicks focusable. Space or Enter clicks it while it has the
+/// focus, and a ring around it shows that it does.
+struct Focus<'a, Message> {
+    content: Element<'a, Message>,
+}
+
+impl<'a, Message> Focus<'a, Message> {
+    fn new(content: impl Into<Element<'a, Message>>) -> Self {
+        Focus {
+            content: content.into(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    focused: bool,
+}
+
+impl Focusable for State {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.focused = false;
+    }
+}
+
+impl<Message> Widget<Message, Theme, iced::Renderer> for Focus<'_, Message> {
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn size_hint(&self) -> Size<Length> {
+        self.content.as_widget().size_hint()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &iced::Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let node = self
+            .content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits);
+        layout::Node::with_children(node.size(), vec![node])
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            content_layout(layout),
+            cursor,
+            viewport,
+        );
+        if tree.state.downcast_ref::<State>().focused {
+            <iced::Renderer as renderer::Renderer>::fill_quad(
+                renderer,
+                Quad {
+                    bounds: layout.bounds().expand(RING_WIDTH),
+                    border: Border {
+                        color: theme.extended_palette().primary.strong.color,
+                        width: RING_WIDTH,
+                        radius: (2.0 + RING_WIDTH).into(),
+                    },
+                    ..Quad::default()
+                },
+                Color::TRANSPARENT,
+            );
+        }
+    }
+
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        operation.focusable(tree.state.downcast_mut::<State>(), None);
+        self.content.as_widget().operate(
+            &mut tree.children[0],
+            content_layout(layout),
+            renderer,
+            operation,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &iced::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        match &event {
+            // Clicking anywhere moves the focus away, like it does for text fields
+            Event::Mouse(mouse::Event::ButtonPressed(_)) => state.focused = false,
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key::Named::Space | key::Named::Enter),
+                ..
+            }) if state.focused => {
+                // A click in the middle of the widget, so it does whatever clicking it does
+                let center = mouse::Cursor::Available(layout.bounds().center());
+                for click in [
+                    mouse::Event::ButtonPressed(mouse::Button::Left),
+                    mouse::Event::ButtonReleased(mouse::Button::Left),
+                ] {
+                    let _ = self.content.as_widget_mut().on_event(
+                        &mut tree.children[0],
+                        Event::Mouse(click),
+                        content_layout(layout),
+                        center,
+                        renderer,
+                        clipboard,
+                        shell,
+                        viewport,
+                    );
+                }
+                return event::Status::Captured;
+            }
+            _ => {}
+        }
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            content_layout(layout),
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            content_layout(layout),
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+/// Layout of the wrapped widget, the only child of the node.
+fn content_layout(layout: Layout<'_>) -> Layout<'_> {
+    layout.children().next().unwrap()
+}
@@ -0,0 +1,62 @@
+use crate::config::{Config, WindowGeometry};
+use iced::{Point, Size, window};
+use std::sync::OnceLock;
+
+const MIN_WINDOW_SIZE: Size = Size::new(480.0, 360.0);
+
+/// Saved window position, read by [`restore_position`] once the monitor size is known.
+static SAVED_POSITION: OnceLock<Point> = OnceLock::new();
+
+/// Window settings restoring the geometry saved in the config.
+pub fn window_settings(config: &Config) -> window::Settings {
+    let mut settings = window::Settings::default();
+    if let Some(geometry) = config.window {
+        settings.size = Size::new(geometry.width, geometry.height).max(MIN_WINDOW_SIZE);
+        if let (Some(x), Some(y)) = (geometry.x, geometry.y) {
+            let _ = SAVED_POSITION.set(Point::new(x, y));
+            settings.position = window::Position::SpecificWith(restore_position);
+        }
+    }
+    settings
+}
+
+/// Clamps the saved position onto the current monitor, so a window last shown on a
+/// disconnected monitor doesn't open off-screen.
+fn restore_position(window: Size, monitor: Size) -> Point {
+    let saved = SAVED_POSITION.get().copied().unwrap_or(Point::ORIGIN);
+    Point::new(
+        saved.x.clamp(0.0, (monitor.width - window.width).max(0.0)),
+        saved
+            .y
+            .clamp(0.0, (monitor.height - window.height).max(0.0)),
+    )
+}
+
+/// Records a window event that changes the geometry to be saved on close.
+pub fn update_geometry(geometry: &mut Option<WindowGeometry>, event: &window::Event) {
+    let current = geometry.get_or_insert(WindowGeometry {
+        width: window::Settings::default().size.width,
+        height: window::Settings::default().size.height,
+        x: None,
+        y: None,
+    });
+    match event {
+        window::Event::Opened { position, size } => {
+            current.width = size.width;
+            current.height = size.height;
+            if let Some(position) = position {
+                current.x = Some(position.x);
+                current.y = Some(position.y);
+            }
+        }
+        window::Event::Resized(size) => {
+            current.width = size.width;
+            current.height = size.height;
+        }
+        window::Event::Moved(position) => {
+            current.x = Some(position.x);
+            current.y = Some(position.y);
+        }
+        _ => {}
+    }
+}
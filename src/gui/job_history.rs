@@ -0,0 +1,95 @@
+use crate::paths;
+use librecard_core::backend::{JobId, VerificationLevel};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Jobs kept in the history, newest first.
+const KEPT_JOBS: usize = 5;
+
+/// A destination row of the input stage as it was filled in for a job.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedDestination {
+    pub directory: PathBuf,
+    #[serde(default)]
+    pub label: String,
+    pub subroot: String,
+    /// File types, as typed, e.g. `jpg, arw`.
+    pub filter: String,
+    /// Disabled rows were left out of the job, but are restored with it.
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordedOutcome {
+    /// Copied, but not verified.
+    Copied { files: usize },
+    Verified {
+        files: usize,
+        failed: usize,
+        /// `None` for jobs recorded before the level was.
+        #[serde(default)]
+        level: Option<VerificationLevel>,
+    },
+    /// Stopped by the user after `completed` of `total` files.
+    Cancelled {
+        completed: usize,
+        total: usize,
+        /// Whether the copy was done and its verification was stopped.
+        verifying: bool,
+    },
+}
+
+/// What a finished job was run with, to run it again on the next card.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JobRecord {
+    #[serde(default)]
+    pub job_id: Option<JobId>,
+    pub source: PathBuf,
+    pub destinations: Vec<RecordedDestination>,
+    pub separate_sessions: bool,
+    pub outcome: RecordedOutcome,
+    pub finished_at: SystemTime,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct JobHistory {
+    /// Newest first.
+    pub jobs: Vec<JobRecord>,
+}
+
+impl JobHistory {
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join("job_history.json"))
+    }
+
+    /// The saved history; empty if there is none or it can't be read.
+    pub fn load() -> JobHistory {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec(self).map_err(io::Error::other)?)
+    }
+
+    /// Adds a job as the newest, dropping the oldest beyond the kept number.
+    pub fn record(&mut self, job: JobRecord) {
+        self.jobs.insert(0, job);
+        self.jobs.truncate(KEPT_JOBS);
+    }
+}
@@ -0,0 +1,40 @@
+use crate::paths;
+use librecard_core::backend::ChecksumReport;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+
+/// The most recent verification that completed, kept so its report outlives the app.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LastJob {
+    pub destinations: Vec<PathBuf>,
+    pub report: ChecksumReport,
+}
+
+impl LastJob {
+    fn path() -> Option<PathBuf> {
+        paths::data_dir().map(|dir| dir.join("last_job.json"))
+    }
+
+    /// The saved job, if there is one that can still be read.
+    pub fn load() -> Option<LastJob> {
+        let content = std::fs::read_to_string(Self::path()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Replaces the saved job. Written to a temporary file first, so a crash while writing
+    /// doesn't lose the previous one.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let partial = path.with_extension("json.partial");
+        std::fs::write(
+            &partial,
+            serde_json::to_vec(self).map_err(io::Error::other)?,
+        )?;
+        std::fs::rename(partial, path)
+    }
+}
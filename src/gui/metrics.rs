@@ -0,0 +1,72 @@
+/// Below this window height, the automatic layout switches to [`Metrics::COMPACT`]. A
+/// 1366×768 screen leaves less than that once the taskbar and title bar are counted.
+pub const COMPACT_BELOW_HEIGHT: f32 = 800.0;
+
+/// Text sizes and spacing shared by every stage view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Metrics {
+    pub title: f32,
+    /// Text of the main button of a stage.
+    pub heading: f32,
+    pub emphasis: f32,
+    /// Per-destination lines and file paths in lists.
+    pub detail: f32,
+    pub small: f32,
+    /// Between the sections of a stage.
+    pub spacing: f32,
+    /// Between the elements of a row or a prompt.
+    pub tight_spacing: f32,
+    /// Around a stage and its prompts.
+    pub padding: f32,
+    /// Inside the main button of a stage.
+    pub button_padding: f32,
+    /// Inside text fields.
+    pub field_padding: f32,
+    /// Scrolling lists of files and warnings.
+    pub list_height: f32,
+    /// Lists within a prompt or a result that shows several of them.
+    pub short_list_height: f32,
+    /// The destination list of the input stage, before it scrolls.
+    pub destination_list_height: f32,
+    pub progress_bar_height: f32,
+    /// Whether secondary information is collapsed behind a toggle.
+    pub collapse_details: bool,
+}
+
+impl Metrics {
+    pub const REGULAR: Metrics = Metrics {
+        title: 28.0,
+        heading: 20.0,
+        emphasis: 16.0,
+        detail: 14.0,
+        small: 12.0,
+        spacing: 20.0,
+        tight_spacing: 10.0,
+        padding: 20.0,
+        button_padding: 15.0,
+        field_padding: 10.0,
+        list_height: 200.0,
+        short_list_height: 150.0,
+        destination_list_height: 320.0,
+        progress_bar_height: 30.0,
+        collapse_details: false,
+    };
+
+    pub const COMPACT: Metrics = Metrics {
+        title: 20.0,
+        heading: 16.0,
+        emphasis: 14.0,
+        detail: 12.0,
+        small: 11.0,
+        spacing: 10.0,
+        tight_spacing: 6.0,
+        padding: 8.0,
+        button_padding: 8.0,
+        field_padding: 5.0,
+        list_height: 120.0,
+        short_list_height: 90.0,
+        destination_list_height: 200.0,
+        progress_bar_height: 20.0,
+        collapse_details: true,
+    };
+}
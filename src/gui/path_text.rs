@@ -0,0 +1,70 @@
+use iced::widget::{container, responsive, text, tooltip};
+use iced::{Element, Length};
+use std::path::Path;
+
+/// Average advance of a Latin character, relative to the font size. Wide characters, e.g. CJK,
+/// take a full em.
+const NARROW_CHAR_WIDTH: f32 = 0.6;
+
+/// Line height iced gives text by default, relative to the font size.
+const LINE_HEIGHT: f32 = 1.3;
+
+/// A path on one line that fits the width it's given by leaving out the middle, so both the
+/// drive and the file name stay visible, e.g. `/media/card/…/A001C014_230412.mov`. Hovering
+/// shows the full path.
+pub fn path_text<'a, Message: 'a>(path: &Path, size: f32) -> Element<'a, Message> {
+    let full = path.display().to_string();
+    let shown = full.clone();
+    let line = container(responsive(move |available| {
+        text(elide_middle(&shown, available.width / size))
+            .size(size)
+            .into()
+    }))
+    .width(Length::Fill)
+    .height((size * LINE_HEIGHT).ceil());
+    tooltip(
+        line,
+        container(text(full).size(size))
+            .padding(5)
+            .style(container::rounded_box),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+/// Width of a character in ems.
+fn char_width(c: char) -> f32 {
+    if c < '\u{1100}' {
+        NARROW_CHAR_WIDTH
+    } else {
+        1.0
+    }
+}
+
+/// Shortens `full` to about `width` ems by replacing its middle with `…`. Two thirds of the room
+/// go to the end, which holds the file name.
+fn elide_middle(full: &str, width: f32) -> String {
+    let chars: Vec<char> = full.chars().collect();
+    if chars.iter().map(|&c| char_width(c)).sum::<f32>() <= width {
+        return full.to_owned();
+    }
+    let room = (width - 1.0).max(0.0);
+
+    let mut tail_width = 0.0;
+    let mut tail_start = chars.len();
+    while tail_start > 0 && tail_width + char_width(chars[tail_start - 1]) <= room * 2.0 / 3.0 {
+        tail_start -= 1;
+        tail_width += char_width(chars[tail_start]);
+    }
+    let mut head_width = 0.0;
+    let mut head_end = 0;
+    while head_end < tail_start && head_width + char_width(chars[head_end]) <= room - tail_width {
+        head_width += char_width(chars[head_end]);
+        head_end += 1;
+    }
+
+    let mut shown: String = chars[..head_end].iter().collect();
+    shown.push('…');
+    shown.extend(&chars[tail_start..]);
+    shown
+}
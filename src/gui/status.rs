@@ -0,0 +1,40 @@
+use iced::Color;
+use iced::widget::{Text, text};
+use std::fmt::Display;
+
+/// Outcome conveyed by a status indicator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Warning,
+    Failure,
+}
+
+impl Status {
+    /// Symbol shown next to the message, so the status never relies on color alone.
+    pub fn symbol(self) -> char {
+        match self {
+            Status::Success => '✓',
+            Status::Warning => '⚠',
+            Status::Failure => '✗',
+        }
+    }
+
+    /// The high-contrast palette uses blue/orange, which stay distinguishable under the common
+    /// forms of color blindness, unlike green/red.
+    pub fn color(self, high_contrast: bool) -> Color {
+        match (self, high_contrast) {
+            (Status::Success, false) => Color::from_rgb(0.0, 0.7, 0.0),
+            (Status::Warning, false) => Color::from_rgb(0.85, 0.6, 0.0),
+            (Status::Failure, false) => Color::from_rgb(0.9, 0.0, 0.0),
+            (Status::Success, true) => Color::from_rgb(0.0, 0.35, 0.75),
+            (Status::Warning, true) => Color::from_rgb(0.5, 0.4, 0.0),
+            (Status::Failure, true) => Color::from_rgb(0.85, 0.3, 0.0),
+        }
+    }
+}
+
+/// Renders a status message prefixed with its symbol and colored by the active palette.
+pub fn status_text<'a>(status: Status, message: impl Display, high_contrast: bool) -> Text<'a> {
+    text(format!("{} {}", status.symbol(), message)).color(status.color(high_contrast))
+}
@@ -0,0 +1,120 @@
+use human_bytes::human_bytes;
+use librecard_core::backend::utc_date;
+use rust_i18n::t;
+use std::time::{Duration, SystemTime};
+
+/// Languages the UI is translated into, as (locale, native name).
+pub const LANGUAGES: &[(&str, &str)] = &[("en", "English"), ("zh-CN", "简体中文")];
+
+/// Switches the UI language. `None` follows the system language.
+pub fn apply_language(language: Option<&str>) {
+    let locale = language
+        .map(str::to_owned)
+        .or_else(system_language)
+        .unwrap_or_else(|| "en".to_owned());
+    rust_i18n::set_locale(&locale);
+}
+
+/// Picks the supported language closest to the system locale, e.g. `zh-Hans-CN` -> `zh-CN`.
+fn system_language() -> Option<String> {
+    let system = sys_locale::get_locale()?;
+    let language = system.split(['-', '_']).next()?;
+    LANGUAGES
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| locale.eq_ignore_ascii_case(&system))
+        .or_else(|| {
+            LANGUAGES
+                .iter()
+                .map(|(locale, _)| *locale)
+                .find(|locale| locale.split('-').next() == Some(language))
+        })
+        .map(str::to_owned)
+}
+
+/// Digit group and decimal separators for the current locale.
+fn separators() -> (char, char) {
+    let locale = rust_i18n::locale();
+    match locale.split('-').next().unwrap_or_default() {
+        "de" | "es" | "it" | "nl" | "pt" => ('.', ','),
+        "fr" | "ru" => ('\u{202F}', ','),
+        _ => (',', '.'),
+    }
+}
+
+/// Formats a count with the locale's digit grouping, e.g. `48,201`.
+pub fn format_count(count: usize) -> String {
+    let (group, _) = separators();
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() * 4 / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(group);
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Formats a byte size with the locale's decimal separator, e.g. `1.2 GB`.
+pub fn format_bytes(bytes: u64) -> String {
+    let (_, decimal) = separators();
+    human_bytes(bytes as f64).replace('.', &decimal.to_string())
+}
+
+/// Formats a duration as `H:MM:SS`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Formats the time of day of a point in time as `HH:MM:SS`, in UTC like [`format_time`].
+pub fn format_clock(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() % 86400);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Formats a point in time as `YYYY-MM-DD HH:MM UTC`.
+pub fn format_time(time: SystemTime) -> String {
+    let ((year, month, day), seconds) = utc_date(time);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        seconds / 3600,
+        seconds / 60 % 60
+    )
+}
+
+/// Formats an offset from UTC in seconds as `UTC+02:00`.
+pub fn format_utc_offset(offset: i64) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.abs() / 60;
+    format!("UTC{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Formats a point in time relative to today, e.g. `21:14 UTC yesterday`, or like
+/// [`format_time`] once it is older than that.
+pub fn format_recent_time(time: SystemTime) -> String {
+    let day = |time: SystemTime| {
+        time.duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() / 86400)
+    };
+    let (_, seconds) = utc_date(time);
+    let clock = format!("{:02}:{:02} UTC", seconds / 3600, seconds / 60 % 60);
+    match day(SystemTime::now()).checked_sub(day(time)) {
+        Some(0) => t!("time.today", time = clock).into_owned(),
+        Some(1) => t!("time.yesterday", time = clock).into_owned(),
+        _ => format_time(time),
+    }
+}
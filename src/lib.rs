@@ -0,0 +1,8 @@
+//! Copy and verification engine behind LibreCard, usable without the GUI.
+//!
+//! The jobs are async and expect a multi-threaded Tokio runtime with I/O and time enabled, as
+//! the app runs them on. [`backend::copy_dirs_blocking`] and [`backend::hash_dirs_blocking`] run
+//! them on a runtime of their own instead, for callers that have none; they refuse to run inside
+//! one. Nothing else in the crate starts a runtime.
+
+pub mod backend;
@@ -1,11 +1,22 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use crate::gui::LibreCardApp;
+use crate::config::Config;
+use crate::gui::{LibreCardApp, init_console, install_panic_hook, window_settings};
+use iced::Task;
 
-mod backend;
+mod config;
 mod gui;
+mod i18n;
+mod paths;
+mod status_server;
+mod update;
+
+rust_i18n::i18n!("locales", fallback = "en");
 
 fn main() -> iced::Result {
+    init_console();
+    install_panic_hook();
+
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
@@ -13,7 +24,20 @@ fn main() -> iced::Result {
 
     let _guard = runtime.enter();
 
+    let config = Config::load();
+
     iced::application("LibreCard", LibreCardApp::update, LibreCardApp::view)
         .subscription(LibreCardApp::subscription)
-        .run()
-}
\ No newline at end of file
+        .window(window_settings(&config))
+        // Closing is handled by the app so the window geometry can be saved first
+        .exit_on_close_request(false)
+        .run_with(|| {
+            let mut app = LibreCardApp::new(config);
+            let startup = Task::batch([
+                app.check_for_update(),
+                app.restart_status_server(),
+                app.check_restored_directories(),
+            ]);
+            (app, startup)
+        })
+}
@@ -0,0 +1,197 @@
+//! Where LibreCard keeps its files. They go in the platform's per-user folders, unless the app
+//! runs portable, e.g. from a thumb drive, in which case everything stays next to the executable.
+//! Also makes sense of the paths the user types.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Marker file next to the executable that turns on portable mode.
+const PORTABLE_MARKER: &str = "portable.toml";
+/// Folder next to the executable that holds everything in portable mode.
+const PORTABLE_DATA: &str = "LibreCardData";
+
+/// Folder the executable is in.
+pub fn executable_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.to_path_buf())
+}
+
+/// The portable data folder, if the app was started with `--portable` or next to a
+/// `portable.toml`. Decided once, so the mode can't change while the app runs.
+fn portable_dir() -> Option<&'static Path> {
+    static PORTABLE: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PORTABLE
+        .get_or_init(|| {
+            let dir = executable_dir()?;
+            let requested = std::env::args().skip(1).any(|arg| arg == "--portable");
+            (requested || dir.join(PORTABLE_MARKER).is_file()).then(|| dir.join(PORTABLE_DATA))
+        })
+        .as_deref()
+}
+
+/// Folder for the settings.
+pub fn config_dir() -> Option<PathBuf> {
+    match portable_dir() {
+        Some(dir) => Some(dir.to_path_buf()),
+        None => dirs::config_dir().map(|dir| dir.join("librecard")),
+    }
+}
+
+/// Folder for what the app keeps between runs besides the settings, e.g. the last report.
+pub fn data_dir() -> Option<PathBuf> {
+    match portable_dir() {
+        Some(dir) => Some(dir.to_path_buf()),
+        None => dirs::data_dir().map(|dir| dir.join("librecard")),
+    }
+}
+
+/// Turns a path the user typed or pasted into the one it stands for: surrounding quotes are
+/// dropped, a `file://` URL becomes its path, a leading `~` the home folder, and environment
+/// variables are filled in, `$VAR` and `${VAR}` or, on Windows, `%VAR%`. A variable that isn't
+/// set is left as typed. A relative path is made absolute against the working folder.
+pub fn expand_typed(input: &str) -> PathBuf {
+    let home = dirs::home_dir();
+    let expanded = expand_with(input, cfg!(windows), home.as_deref(), |name| {
+        std::env::var(name).ok()
+    });
+    std::path::absolute(&expanded).unwrap_or(expanded)
+}
+
+/// [`expand_typed`] with Windows rules if `windows`, short of making the path absolute.
+fn expand_with(
+    input: &str,
+    windows: bool,
+    home: Option<&Path>,
+    var: impl Fn(&str) -> Option<String>,
+) -> PathBuf {
+    let mut path = unquote(input.trim()).to_string();
+    if let Some(url) = path.strip_prefix("file://") {
+        path = url_path(url, windows);
+    }
+    path = match windows {
+        true => expand_percent_vars(&path, &var),
+        false => expand_dollar_vars(&path, &var),
+    };
+    let separators: &[char] = if windows { &['/', '\\'] } else { &['/'] };
+    if let (Some(home), Some(rest)) = (home, path.strip_prefix('~'))
+        && (rest.is_empty() || rest.starts_with(separators))
+    {
+        return match rest.trim_start_matches(separators) {
+            "" => home.to_path_buf(),
+            rest => home.join(rest),
+        };
+    }
+    PathBuf::from(path)
+}
+
+/// The text inside a pair of matching quotes around it, as a file manager copies a path.
+fn unquote(input: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = input
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    input
+}
+
+/// The path of a `file://` URL, given what follows the scheme. A host other than `localhost` is
+/// a share on Windows.
+fn url_path(url: &str, windows: bool) -> String {
+    let (host, path) = url.split_at(url.find('/').unwrap_or(url.len()));
+    let path = percent_decode(path);
+    match (windows, host) {
+        // `file:///C:/Footage` holds a drive letter after the slash
+        (true, "" | "localhost") => match path.get(2..3) {
+            Some(":") => path[1..].to_string(),
+            _ => path,
+        },
+        (true, host) => format!("//{host}{path}"),
+        (false, _) => path,
+    }
+}
+
+/// Decodes the `%XX` escapes of a URL. An escape that isn't one is kept as it is.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Fills in `$VAR` and `${VAR}`.
+fn expand_dollar_vars(text: &str, var: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, len) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        match var(name).filter(|_| !name.is_empty()) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[start..start + 1 + len]),
+        }
+        rest = &after[len..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Fills in `%VAR%`.
+fn expand_percent_vars(text: &str, var: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('%') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            expanded.push('%');
+            rest = after;
+            continue;
+        };
+        let name = &after[..end];
+        match var(name).filter(|_| !name.is_empty()) {
+            Some(value) => {
+                expanded.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            // The closing `%` may open the next variable
+            None => {
+                expanded.push('%');
+                expanded.push_str(name);
+                rest = &after[end..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
@@ -0,0 +1,200 @@
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{ACCESS_CONTROL_ALLOW_ORIGIN, CACHE_CONTROL, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use librecard_core::backend::JobId;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::io;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+/// Port the job status is served on when the settings don't name one.
+pub const DEFAULT_STATUS_PORT: u16 = 7345;
+
+/// What the app is doing, as served to other devices, e.g. a status display on the cart.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct JobStatus {
+    pub stage: JobStage,
+    /// The running job, or the one that finished last.
+    pub job_id: Option<JobId>,
+    /// The copy only simulates the job.
+    pub dry_run: bool,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    /// Average over the job so far.
+    pub bytes_per_second: f64,
+    /// Relative to the source.
+    pub current_file: Option<PathBuf>,
+    /// Empty unless copying.
+    pub destinations: Vec<DestinationStatus>,
+    /// Outcome of the last job that finished.
+    pub last_report: Option<ReportSummary>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JobStage {
+    #[default]
+    Idle,
+    Scanning,
+    Copying,
+    /// The copies are being synced to the destinations' drives.
+    Finalizing,
+    Verifying,
+    Hashing,
+    /// A job ended and its result is shown.
+    Finished,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DestinationStatus {
+    pub path: Option<PathBuf>,
+    pub state: DestinationStage,
+    /// Why the destination failed.
+    pub error: Option<String>,
+    pub bytes_written: u64,
+    pub bytes_total: u64,
+    /// Files written that aren't synced to the drive yet.
+    pub files_pending: usize,
+    /// Over the last few seconds.
+    pub bytes_per_second: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DestinationStage {
+    Writing,
+    Finalizing,
+    Done,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReportSummary {
+    pub outcome: ReportOutcome,
+    /// The status bar line, in the UI language.
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportOutcome {
+    Success,
+    Warning,
+    Failure,
+}
+
+/// A server answering on localhost with the latest [`JobStatus`] as JSON. Dropping it stops the
+/// server.
+#[derive(Debug)]
+pub struct StatusServer {
+    port: u16,
+    status: watch::Sender<JobStatus>,
+    stop: CancellationToken,
+}
+
+impl StatusServer {
+    /// Returns the handle and the server itself, which runs until the handle is dropped. The
+    /// server fails at once if `port` can't be bound.
+    pub fn new(port: u16) -> (StatusServer, impl Future<Output = io::Result<()>> + use<>) {
+        let (status, rx) = watch::channel(JobStatus::default());
+        let stop = CancellationToken::new();
+        let server = serve(port, rx, stop.clone());
+        (StatusServer { port, status, stop }, server)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Serves `status` from now on. Requests only ever see the latest.
+    pub fn publish(&self, status: JobStatus) {
+        self.status.send_replace(status);
+    }
+}
+
+impl Drop for StatusServer {
+    fn drop(&mut self) {
+        self.stop.cancel();
+    }
+}
+
+async fn serve(
+    port: u16,
+    status: watch::Receiver<JobStatus>,
+    stop: CancellationToken,
+) -> io::Result<()> {
+    // Other machines have to go through something the user set up, e.g. an SSH tunnel
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+    tracing::info!("Serving the job status at http://localhost:{port}/status");
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    // Usually out of file handles, which takes a moment to clear
+                    tracing::warn!("Failed to accept a status request: {e}");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+            _ = stop.cancelled() => return Ok(()),
+        };
+        let status = status.clone();
+        let stop = stop.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                let response = respond(&request, &status.borrow());
+                async move { Ok::<_, Infallible>(response) }
+            });
+            let connection = http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+            tokio::select! {
+                result = connection => {
+                    if let Err(e) = result {
+                        tracing::debug!("Status connection ended: {e}");
+                    }
+                }
+                _ = stop.cancelled() => {}
+            }
+        });
+    }
+}
+
+fn respond(request: &Request<Incoming>, status: &JobStatus) -> Response<Full<Bytes>> {
+    let (code, body) = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/" | "/status") => match serde_json::to_vec(status) {
+            Ok(json) => (StatusCode::OK, json),
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_body(&e.to_string()),
+            ),
+        },
+        (&Method::GET, _) => (StatusCode::NOT_FOUND, error_body("not found")),
+        _ => (
+            StatusCode::METHOD_NOT_ALLOWED,
+            error_body("only GET is served"),
+        ),
+    };
+    Response::builder()
+        .status(code)
+        .header(CONTENT_TYPE, "application/json")
+        .header(CACHE_CONTROL, "no-store")
+        // Lets a dashboard page served from elsewhere poll it
+        .header(ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(Full::new(Bytes::from(body)))
+        .expect("Static headers are valid")
+}
+
+fn error_body(error: &str) -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({ "error": error })).unwrap_or_default()
+}
@@ -0,0 +1,53 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/yikerman/LibreCard/releases/latest";
+
+/// A release newer than the running build.
+#[derive(Clone, Debug)]
+pub struct Release {
+    pub version: String,
+    /// Release page to download it from.
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct LatestRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+/// Asks GitHub for the latest release and returns it if it is newer than this build. Any failure,
+/// from no network to rate limiting, gives `None`: the check must never get in the way.
+pub async fn newer_release() -> Option<Release> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("LibreCard/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(10))
+        .build()
+        .ok()?;
+    let latest: LatestRelease = client
+        .get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    (version_numbers(&latest.tag_name)? > version_numbers(env!("CARGO_PKG_VERSION"))?).then(|| {
+        Release {
+            version: latest.tag_name.trim_start_matches('v').to_owned(),
+            url: latest.html_url,
+        }
+    })
+}
+
+/// The numbers of a version like `v1.2.0`, compared part by part. Pre-release suffixes are
+/// ignored, since only published releases are returned.
+fn version_numbers(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim_start_matches('v');
+    let release = version.split(['-', '+']).next()?;
+    release.split('.').map(|part| part.parse().ok()).collect()
+}
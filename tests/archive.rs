@@ -0,0 +1,134 @@
+//! Copies into ZIP and tar destinations, checked by extracting them again.
+
+mod common;
+
+use common::{completed, copy, write_files};
+use librecard_core::backend::{
+    CopyOptions, HashOptions, JobHandle, JobOutcome, Progress, SourcePlan, SpaceHandling, copy_dirs,
+};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::sync::watch;
+
+const FILES: &[(&str, &[u8])] = &[
+    ("DCIM/100CANON/IMG_0001.CR3", b"raw image data"),
+    ("DCIM/100CANON/IMG_0002.CR3", b"another raw image"),
+    ("PRIVATE/M4ROOT/CLIP/C0001.MP4", &[42; 3 * 1024 * 1024]),
+    ("EMPTY.TXT", b""),
+];
+
+fn expected() -> BTreeMap<String, Vec<u8>> {
+    FILES
+        .iter()
+        .map(|(path, content)| (path.to_string(), content.to_vec()))
+        .collect()
+}
+
+fn read_zip(path: &Path) -> BTreeMap<String, Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    (0..archive.len())
+        .map(|index| {
+            let mut entry = archive.by_index(index).unwrap();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            (entry.name().unwrap().into_owned(), content)
+        })
+        .collect()
+}
+
+fn read_tar(path: &Path) -> BTreeMap<String, Vec<u8>> {
+    let mut archive = tar::Archive::new(std::fs::File::open(path).unwrap());
+    archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().replace('\\', "/");
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            (path, content)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn archives_hold_the_source_files() {
+    let source = TempDir::new().unwrap();
+    write_files(source.path(), FILES);
+    let destination = TempDir::new().unwrap();
+    let destinations: Vec<PathBuf> = ["Proxies.zip", "Proxies.tar", "Folder"]
+        .iter()
+        .map(|name| destination.path().join(name))
+        .collect();
+    let options = CopyOptions {
+        verify: Some(HashOptions::default()),
+        ..Default::default()
+    };
+
+    let summary = completed(copy(source.path(), &destinations, options).await.unwrap());
+
+    let report = summary.report.expect("The copy was verified");
+    assert_eq!(report.count_errors(), 0);
+    assert_eq!(report.files.len(), FILES.len());
+    assert_eq!(read_zip(&destinations[0]), expected());
+    assert_eq!(read_tar(&destinations[1]), expected());
+    for (path, content) in FILES {
+        assert_eq!(std::fs::read(destinations[2].join(path)).unwrap(), *content);
+    }
+}
+
+/// Large enough that the job is still writing it when the test gets to stop it.
+const LONG_CLIP_SIZE: usize = 64 * 1024 * 1024;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stopping_mid_file_keeps_the_archives_from_before() {
+    let source = TempDir::new().unwrap();
+    let clip = vec![7; LONG_CLIP_SIZE];
+    write_files(
+        source.path(),
+        &[("A001.MOV", &clip), ("A002.MOV", b"short")],
+    );
+    let destination = TempDir::new().unwrap();
+    let destinations: Vec<PathBuf> = ["Proxies.zip", "Proxies.tar"]
+        .iter()
+        .map(|name| destination.path().join(name))
+        .collect();
+    for archive in &destinations {
+        std::fs::write(archive, b"last week's archive").unwrap();
+    }
+    let (tx, mut rx) = watch::channel(Progress::default());
+    let (warnings, _) = watch::channel(Vec::new());
+    let job = JobHandle::new(tx, warnings);
+    let stop = job.stop.clone();
+    let stop_mid_file = async move {
+        rx.wait_for(|progress| progress.current_file_bytes > 0)
+            .await
+            .unwrap();
+        stop.cancel();
+    };
+
+    let (outcome, ()) = tokio::join!(
+        copy_dirs(
+            source.path(),
+            &destinations,
+            &[],
+            SourcePlan::default(),
+            SpaceHandling::default(),
+            CopyOptions::default(),
+            job,
+        ),
+        stop_mid_file,
+    );
+
+    assert!(matches!(outcome.unwrap(), JobOutcome::Cancelled(_)));
+    for archive in &destinations {
+        assert_eq!(std::fs::read(archive).unwrap(), b"last week's archive");
+    }
+    let left: Vec<_> = std::fs::read_dir(destination.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(left.len(), destinations.len(), "{left:?}");
+}
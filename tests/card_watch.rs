@@ -0,0 +1,50 @@
+//! The card watcher picks out its card among the mounted volumes by label, serial or both.
+
+use librecard_core::backend::Volume;
+use std::path::PathBuf;
+
+fn card(label: &str, serial: Option<&str>) -> Volume {
+    Volume {
+        label: label.to_string(),
+        serial: serial.map(str::to_string),
+        mount_point: PathBuf::from("/media/card"),
+    }
+}
+
+#[test]
+fn a_label_matches_regardless_of_case() {
+    let volume = card("EOS_DIGITAL", Some("1234-ABCD"));
+
+    assert!(volume.matches("EOS_DIGITAL", ""));
+    assert!(volume.matches("eos_digital", ""));
+    assert!(volume.matches("  EOS_DIGITAL ", ""));
+    assert!(!volume.matches("EOS", ""));
+    assert!(!volume.matches("NIKON D850", ""));
+}
+
+#[test]
+fn a_serial_matches_regardless_of_case_and_dashes() {
+    let volume = card("EOS_DIGITAL", Some("1234-ABCD"));
+
+    assert!(volume.matches("", "1234-ABCD"));
+    assert!(volume.matches("", "1234abcd"));
+    assert!(!volume.matches("", "1234-ABCE"));
+    // Without a serial of its own, a volume can't be told to have the one asked for
+    assert!(!card("EOS_DIGITAL", None).matches("", "1234-ABCD"));
+}
+
+#[test]
+fn a_label_and_serial_must_both_match() {
+    let first = card("EOS_DIGITAL", Some("1234-ABCD"));
+    let second = card("EOS_DIGITAL", Some("5678-EF01"));
+
+    assert!(first.matches("EOS_DIGITAL", "1234-ABCD"));
+    assert!(!second.matches("EOS_DIGITAL", "1234-ABCD"));
+    assert!(!first.matches("NIKON D850", "1234-ABCD"));
+}
+
+#[test]
+fn nothing_matches_an_empty_label_and_serial() {
+    assert!(!card("", None).matches("", ""));
+    assert!(!card("EOS_DIGITAL", Some("1234-ABCD")).matches(" ", ""));
+}
@@ -0,0 +1,102 @@
+//! A source file written to after the card was listed is flagged, or copied again.
+
+mod common;
+
+use common::{completed, write_files};
+use librecard_core::backend::{
+    CopyOptions, CopySummary, JobHandle, Progress, SourcePlan, SpaceHandling, copy_dirs,
+};
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::sync::watch;
+
+/// Large enough that the job is still on it when the test gets to append to the next file.
+const FIRST_CLIP_SIZE: usize = 64 * 1024 * 1024;
+
+/// Copies a card of two clips, appending to the second while the first is copied, after the
+/// second was listed and before it is read.
+async fn copy_growing_card(options: CopyOptions) -> (CopySummary, TempDir) {
+    let source = TempDir::new().unwrap();
+    let first = vec![1; FIRST_CLIP_SIZE];
+    write_files(
+        source.path(),
+        &[("A001.MOV", &first), ("A002.MOV", b"recording")],
+    );
+    let destination = TempDir::new().unwrap();
+    let destinations = [destination.path().to_path_buf()];
+    let (tx, mut rx) = watch::channel(Progress::default());
+    let (warnings, _) = watch::channel(Vec::new());
+    let growing = source.path().join("A002.MOV");
+    let append = async move {
+        rx.wait_for(|progress| progress.current_file.as_deref() == Some(Path::new("A001.MOV")))
+            .await
+            .unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(growing)
+            .unwrap();
+        file.write_all(b" and more").unwrap();
+    };
+
+    let (outcome, ()) = tokio::join!(
+        copy_dirs(
+            source.path(),
+            &destinations,
+            &[],
+            SourcePlan::default(),
+            SpaceHandling::default(),
+            options,
+            JobHandle::new(tx, warnings),
+        ),
+        append,
+    );
+    (completed(outcome.unwrap()), destination)
+}
+
+fn grown(summary: &CopySummary) -> bool {
+    let file = summary
+        .files
+        .iter()
+        .find(|file| file.path == Path::new("A002.MOV"))
+        .unwrap();
+    file.changed_during_copy
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_file_that_grew_after_listing_is_flagged() {
+    let (summary, destination) = copy_growing_card(CopyOptions::default()).await;
+
+    assert!(grown(&summary));
+    assert!(
+        !summary.files[0].changed_during_copy,
+        "{:?}",
+        summary.files[0].path
+    );
+    assert_eq!(
+        std::fs::read(destination.path().join("A002.MOV")).unwrap(),
+        b"recording and more"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_file_that_grew_after_listing_is_copied_again() {
+    let options = CopyOptions {
+        recopy_changed: true,
+        ..Default::default()
+    };
+    let (summary, destination) = copy_growing_card(options).await;
+
+    // The change is caught as above; the copy made after it holds still is a clean one
+    assert!(!grown(&summary));
+    let copy = summary
+        .files
+        .iter()
+        .find(|file| file.path == Path::new("A002.MOV"))
+        .unwrap();
+    assert_eq!(copy.size, b"recording and more".len() as u64);
+    assert_eq!(
+        std::fs::read(destination.path().join("A002.MOV")).unwrap(),
+        b"recording and more"
+    );
+}
@@ -0,0 +1,61 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use librecard_core::backend::{
+    CopyOptions, CopySummary, JobHandle, JobOutcome, Progress, SourcePlan, SpaceHandling, copy_dirs,
+};
+use std::io;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::sync::watch;
+
+/// Copies `source` to `destinations` with `plan` and `options`, as the app does but without
+/// anyone watching the progress.
+pub async fn copy_with(
+    source: &Path,
+    destinations: &[PathBuf],
+    plan: SourcePlan,
+    options: CopyOptions,
+) -> io::Result<JobOutcome<CopySummary>> {
+    let (tx, _rx) = watch::channel(Progress::default());
+    let (warnings, _) = watch::channel(Vec::new());
+    copy_dirs(
+        source,
+        destinations,
+        &[],
+        plan,
+        SpaceHandling::default(),
+        options,
+        JobHandle::new(tx, warnings),
+    )
+    .await
+}
+
+pub async fn copy(
+    source: &Path,
+    destinations: &[PathBuf],
+    options: CopyOptions,
+) -> io::Result<JobOutcome<CopySummary>> {
+    copy_with(source, destinations, SourcePlan::default(), options).await
+}
+
+pub fn completed<T>(outcome: JobOutcome<T>) -> T {
+    match outcome {
+        JobOutcome::Completed(result) => result,
+        JobOutcome::Cancelled(_) => panic!("The job was cancelled"),
+    }
+}
+
+pub fn paths(dirs: &[TempDir]) -> Vec<PathBuf> {
+    dirs.iter().map(|dir| dir.path().to_path_buf()).collect()
+}
+
+/// Writes each of `files`, by path relative to `root`, with its content.
+pub fn write_files(root: &Path, files: &[(&str, &[u8])]) {
+    for (path, content) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+}
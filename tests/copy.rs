@@ -0,0 +1,87 @@
+//! End-to-end checks of `copy_dirs` on temp folders.
+
+mod common;
+
+use common::{completed, copy, paths};
+use librecard_core::backend::CopyOptions;
+use std::io;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A source with two clips, and two destinations, the second of which has a folder where the
+/// first clip goes.
+fn conflicting_destinations() -> (TempDir, Vec<TempDir>) {
+    let source = TempDir::new().unwrap();
+    std::fs::write(source.path().join("A001.MOV"), b"first clip").unwrap();
+    std::fs::write(source.path().join("A002.MOV"), b"second clip").unwrap();
+    let destinations = vec![TempDir::new().unwrap(), TempDir::new().unwrap()];
+    std::fs::create_dir(destinations[1].path().join("A001.MOV")).unwrap();
+    (source, destinations)
+}
+
+#[tokio::test]
+async fn continue_on_error_writes_the_other_destinations() {
+    let (source, dirs) = conflicting_destinations();
+    let destinations = paths(&dirs);
+    let options = CopyOptions {
+        continue_on_error: true,
+        ..Default::default()
+    };
+    let summary = completed(copy(source.path(), &destinations, options).await.unwrap());
+
+    assert_eq!(summary.failed.len(), 1);
+    let failure = &summary.failed[0];
+    assert_eq!(failure.path, Path::new("A001.MOV"));
+    assert_eq!(failure.destination.as_deref(), Some(&*destinations[1]));
+    assert_eq!(summary.failed_files(), 1);
+    assert_eq!(
+        std::fs::read(destinations[0].join("A001.MOV")).unwrap(),
+        b"first clip"
+    );
+    assert!(destinations[1].join("A001.MOV").is_dir());
+    for destination in &destinations {
+        assert_eq!(
+            std::fs::read(destination.join("A002.MOV")).unwrap(),
+            b"second clip"
+        );
+    }
+}
+
+#[tokio::test]
+async fn a_failure_ends_the_job_by_default() {
+    let (source, dirs) = conflicting_destinations();
+    let error = copy(source.path(), &paths(&dirs), CopyOptions::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::IsADirectory);
+}
+
+#[tokio::test]
+async fn a_folder_where_a_file_goes_fails_only_that_file() {
+    let source = TempDir::new().unwrap();
+    std::fs::write(source.path().join("FOO"), b"marker").unwrap();
+    std::fs::write(source.path().join("BAR"), b"other").unwrap();
+    let destination = TempDir::new().unwrap();
+    std::fs::create_dir(destination.path().join("FOO")).unwrap();
+    let options = CopyOptions {
+        continue_on_error: true,
+        ..Default::default()
+    };
+    let destinations = [destination.path().to_path_buf()];
+    let summary = completed(copy(source.path(), &destinations, options).await.unwrap());
+
+    assert_eq!(summary.failed.len(), 1);
+    assert_eq!(summary.failed[0].path, Path::new("FOO"));
+    assert!(
+        summary.failed[0]
+            .error
+            .contains("Destination has a directory where a file is expected"),
+        "{}",
+        summary.failed[0].error
+    );
+    assert!(destination.path().join("FOO").is_dir());
+    assert_eq!(
+        std::fs::read(destination.path().join("BAR")).unwrap(),
+        b"other"
+    );
+}
@@ -0,0 +1,105 @@
+//! The chunk-by-chunk progress a batch copy reports to a `CopyProgressSink`.
+
+use librecard_core::backend::{ChunkProgress, ChunkStatus, read_file_copy_batch};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+const MIB: usize = 1024 * 1024;
+
+/// What the sink was told about each chunk: its bytes, the file's size and the destinations.
+type Reported = Vec<(u64, u64, Vec<ChunkStatus>)>;
+
+fn recorder(reported: &Mutex<Reported>) -> impl Fn(&ChunkProgress<'_>) + Send + Sync + '_ {
+    move |chunk: &ChunkProgress<'_>| {
+        reported
+            .lock()
+            .unwrap()
+            .push((chunk.bytes, chunk.file_bytes, chunk.destinations.clone()));
+    }
+}
+
+#[tokio::test]
+async fn each_chunk_of_a_file_is_reported_in_order() {
+    let source = TempDir::new().unwrap();
+    let source_file = source.path().join("CLIP.MOV");
+    let size = 2 * MIB + MIB / 2;
+    std::fs::write(&source_file, vec![7; size]).unwrap();
+    let destinations = [TempDir::new().unwrap(), TempDir::new().unwrap()];
+    let dest_paths: Vec<PathBuf> = destinations
+        .iter()
+        .map(|dir| dir.path().join("CLIP.MOV"))
+        .collect();
+
+    let reported = Mutex::new(Vec::new());
+    let sink = recorder(&reported);
+    let copied = read_file_copy_batch(&source_file, dest_paths, Some(&sink))
+        .await
+        .unwrap();
+
+    assert_eq!(copied, size as u64);
+    let both = vec![ChunkStatus::Written, ChunkStatus::Written];
+    let size = size as u64;
+    assert_eq!(
+        reported.lock().unwrap().clone(),
+        vec![
+            (MIB as u64, size, both.clone()),
+            (MIB as u64, size, both.clone()),
+            ((MIB / 2) as u64, size, both),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn an_empty_file_has_no_chunks() {
+    let source = TempDir::new().unwrap();
+    let source_file = source.path().join("EMPTY");
+    std::fs::write(&source_file, b"").unwrap();
+    let destination = TempDir::new().unwrap();
+
+    let reported = Mutex::new(Vec::new());
+    let sink = recorder(&reported);
+    let dest_paths = vec![destination.path().join("EMPTY")];
+    read_file_copy_batch(&source_file, dest_paths, Some(&sink))
+        .await
+        .unwrap();
+
+    assert!(reported.lock().unwrap().clone().is_empty());
+}
+
+/// `/dev/full` fails every write, which lets a copy fail partway through on purpose.
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn the_chunk_a_write_fails_on_is_reported_before_the_error() {
+    let source = TempDir::new().unwrap();
+    let source_file = source.path().join("CLIP.MOV");
+    std::fs::write(&source_file, vec![7; 2 * MIB]).unwrap();
+    let destination = TempDir::new().unwrap();
+    let dest_paths = vec![
+        destination.path().join("CLIP.MOV"),
+        PathBuf::from("/dev/full"),
+    ];
+
+    let reported = Mutex::new(Vec::new());
+    let sink = recorder(&reported);
+    let error = read_file_copy_batch(&source_file, dest_paths, Some(&sink))
+        .await
+        .unwrap_err();
+
+    // The failure shows on the chunk the write was found to fail on, which a buffered write may
+    // only tell on the next one; every chunk before it went to both destinations
+    let reported = reported.lock().unwrap().clone();
+    let (failed, before) = reported.split_last().expect("The failed chunk is reported");
+    for (bytes, file_bytes, statuses) in before {
+        assert_eq!((*bytes, *file_bytes), (MIB as u64, 2 * MIB as u64));
+        assert_eq!(statuses, &[ChunkStatus::Written, ChunkStatus::Written]);
+    }
+    assert_eq!(error.kind(), std::io::ErrorKind::StorageFull);
+    let (_, file_bytes, statuses) = failed;
+    assert_eq!(*file_bytes, 2 * MIB as u64);
+    assert_eq!(statuses[0], ChunkStatus::Written);
+    match &statuses[1] {
+        ChunkStatus::Failed(message) => assert!(error.to_string().ends_with(message.as_str())),
+        status => panic!("Expected the second destination to fail, got {status:?}"),
+    }
+}
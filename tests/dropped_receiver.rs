@@ -0,0 +1,103 @@
+//! Jobs carry on when whoever watched their progress goes away.
+
+mod common;
+
+use common::write_files;
+use librecard_core::backend::{
+    CopyOptions, JobHandle, JobOutcome, Progress, SourcePlan, SpaceHandling, copy_dirs, hash_dirs,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::sync::watch;
+
+const FILE_COUNT: usize = 40;
+
+/// Writes the clips under `root` and returns their paths relative to it.
+fn source_files(root: &Path) -> Vec<PathBuf> {
+    let content = vec![9; 256 * 1024];
+    let names: Vec<String> = (0..FILE_COUNT)
+        .map(|index| format!("DCIM/C{index:04}.MP4"))
+        .collect();
+    let files: Vec<(&str, &[u8])> = names
+        .iter()
+        .map(|name| (name.as_str(), content.as_slice()))
+        .collect();
+    write_files(root, &files);
+    names.iter().map(PathBuf::from).collect()
+}
+
+/// Waits for the job to report progress once, then stops listening.
+async fn drop_after_first_update(mut rx: watch::Receiver<Progress>) {
+    let _ = rx.changed().await;
+    drop(rx);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_copy_finishes_after_its_receivers_are_dropped() {
+    let source = TempDir::new().unwrap();
+    source_files(source.path());
+    let destination = TempDir::new().unwrap();
+    let destinations = [destination.path().to_path_buf()];
+    let (tx, rx) = watch::channel(Progress::default());
+    let (warnings, warnings_rx) = watch::channel(Vec::new());
+    drop(warnings_rx);
+
+    let (outcome, ()) = tokio::join!(
+        copy_dirs(
+            source.path(),
+            &destinations,
+            &[],
+            SourcePlan::default(),
+            SpaceHandling::default(),
+            CopyOptions::default(),
+            JobHandle::new(tx, warnings),
+        ),
+        drop_after_first_update(rx),
+    );
+
+    match outcome.unwrap() {
+        JobOutcome::Completed(summary) => assert_eq!(summary.files.len(), FILE_COUNT),
+        JobOutcome::Cancelled(_) => panic!("The copy was cancelled"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_verification_finishes_after_its_receivers_are_dropped() {
+    let source = TempDir::new().unwrap();
+    let files = source_files(source.path());
+    let destination = TempDir::new().unwrap();
+    let destinations = [destination.path().to_path_buf()];
+    source_files(destination.path());
+    let (tx, rx) = watch::channel(Progress::default());
+    let (warnings, warnings_rx) = watch::channel(Vec::new());
+    drop(warnings_rx);
+    let (_workers_tx, workers) = watch::channel(2);
+    let salvaged = HashMap::new();
+
+    let (outcome, ()) = tokio::join!(
+        hash_dirs(
+            source.path(),
+            &destinations,
+            &[],
+            &files,
+            &salvaged,
+            Default::default(),
+            JobHandle {
+                workers,
+                ..JobHandle::new(tx, warnings)
+            },
+        ),
+        drop_after_first_update(rx),
+    );
+
+    match outcome.unwrap() {
+        JobOutcome::Completed(report) => {
+            assert_eq!(report.files.len(), FILE_COUNT);
+            assert_eq!(report.count_errors(), 0);
+            let (copy, _) = &report.files[0].destinations[0];
+            assert!(copy.starts_with(destination.path()));
+        }
+        JobOutcome::Cancelled(_) => panic!("The verification was cancelled"),
+    }
+}
@@ -0,0 +1,152 @@
+//! Zero-byte files are copied, verified by presence and size, and exported as such.
+
+mod common;
+
+use common::{completed, copy, paths, write_files};
+use librecard_core::backend::{
+    ChecksumReport, CopyOptions, DestinationHash, HashAlgorithm, HashOptions, JobHandle,
+    ManifestFormat, Progress, TimestampFormat, hash_dirs, read_manifest,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::sync::watch;
+
+const SHA256_OF_NOTHING: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+fn sha256() -> HashOptions {
+    HashOptions {
+        algorithm: HashAlgorithm::Sha256,
+        ..Default::default()
+    }
+}
+
+fn source_with_empty_file(root: &Path) {
+    write_files(
+        root,
+        &[
+            ("A001.MOV", b"clip"),
+            ("MISC/EMPTY.XML", b""),
+            ("EMPTY", b""),
+        ],
+    );
+}
+
+async fn verify(source: &Path, destinations: &[PathBuf]) -> ChecksumReport {
+    let files = ["A001.MOV", "MISC/EMPTY.XML", "EMPTY"].map(PathBuf::from);
+    let (tx, _rx) = watch::channel(Progress::default());
+    let (warnings, _) = watch::channel(Vec::new());
+    let (_workers_tx, workers) = watch::channel(2);
+    let outcome = hash_dirs(
+        source,
+        destinations,
+        &[],
+        &files,
+        &HashMap::new(),
+        sha256(),
+        JobHandle {
+            workers,
+            ..JobHandle::new(tx, warnings)
+        },
+    )
+    .await;
+    completed(outcome.unwrap())
+}
+
+fn file<'a>(report: &'a ChecksumReport, name: &str) -> &'a [(PathBuf, DestinationHash)] {
+    &report
+        .files
+        .iter()
+        .find(|file| file.source.0.ends_with(name))
+        .unwrap()
+        .destinations
+}
+
+#[tokio::test]
+async fn empty_files_are_copied_verified_and_exported() {
+    let source = TempDir::new().unwrap();
+    source_with_empty_file(source.path());
+    let dirs = [TempDir::new().unwrap(), TempDir::new().unwrap()];
+    let destinations = paths(&dirs);
+    let options = CopyOptions {
+        verify: Some(sha256()),
+        ..Default::default()
+    };
+
+    let summary = completed(copy(source.path(), &destinations, options).await.unwrap());
+
+    for destination in &destinations {
+        for name in ["MISC/EMPTY.XML", "EMPTY"] {
+            let copy = std::fs::metadata(destination.join(name)).unwrap();
+            assert!(copy.is_file() && copy.len() == 0, "{name}");
+        }
+    }
+    let report = summary.report.expect("The copy was verified");
+    assert_eq!(report.count_errors(), 0);
+    let empty = report.files.iter().find(|file| file.size == 0).unwrap();
+    assert!(
+        empty
+            .destinations
+            .iter()
+            .all(|(_, hash)| empty.empty_copy(hash))
+    );
+
+    let exports = TempDir::new().unwrap();
+    let csv = exports.path().join("report.csv");
+    report
+        .export(&csv, ManifestFormat::Csv, &TimestampFormat::default())
+        .unwrap();
+    let content = std::fs::read_to_string(&csv).unwrap();
+    assert!(content.contains("Present, 0 bytes"), "{content}");
+    assert!(
+        content.contains("# Empty files: 2, 2 present with 0 bytes at every destination"),
+        "{content}"
+    );
+
+    let json = exports.path().join("report.json");
+    report
+        .export(&json, ManifestFormat::Json, &TimestampFormat::default())
+        .unwrap();
+    assert!(
+        std::fs::read_to_string(&json)
+            .unwrap()
+            .contains("\"empty\"")
+    );
+
+    let gnu = exports.path().join("SHA256SUMS");
+    report
+        .export(&gnu, ManifestFormat::Gnu, &TimestampFormat::default())
+        .unwrap();
+    let manifest = read_manifest(&gnu).unwrap();
+    let entry = &manifest.entries["MISC/EMPTY.XML"];
+    assert_eq!(entry.hash, SHA256_OF_NOTHING);
+}
+
+#[tokio::test]
+async fn a_missing_or_filled_copy_of_an_empty_file_fails() {
+    let source = TempDir::new().unwrap();
+    source_with_empty_file(source.path());
+    let dirs = [TempDir::new().unwrap(), TempDir::new().unwrap()];
+    let destinations = paths(&dirs);
+    for destination in &destinations {
+        source_with_empty_file(destination);
+    }
+    std::fs::remove_file(destinations[0].join("EMPTY")).unwrap();
+    std::fs::write(destinations[1].join("EMPTY"), b"not empty").unwrap();
+
+    let report = verify(source.path(), &destinations).await;
+
+    assert_eq!(report.count_errors(), 1);
+    let empty = file(&report, "EMPTY");
+    assert_eq!(empty[0].1, DestinationHash::MissingAtDestination);
+    assert!(matches!(empty[1].1, DestinationHash::Hashed(_)));
+    let row = report
+        .files
+        .iter()
+        .find(|file| file.source.0.ends_with("EMPTY"))
+        .unwrap();
+    assert!(!row.empty_copy(&empty[1].1));
+    for (_, hash) in file(&report, "MISC/EMPTY.XML") {
+        assert!(matches!(hash, DestinationHash::Hashed(_)));
+    }
+}
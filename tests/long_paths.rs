@@ -0,0 +1,72 @@
+//! Folder trees whose paths run past the 260 characters Windows allows by default.
+
+mod common;
+
+use common::{completed, copy, write_files};
+use librecard_core::backend::{CopyOptions, HashOptions};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// A clip under folders that take its path past 300 characters wherever the test runs.
+fn deep_clip() -> String {
+    let folder = "CLIPS_FROM_THE_SECOND_UNIT_ON_LOCATION";
+    let mut path = [folder; 8].join("/");
+    path.push_str("/A001C001_240412_R1AB.MOV");
+    path
+}
+
+#[tokio::test]
+async fn a_path_longer_than_max_path_is_copied_and_verified() {
+    let source = TempDir::new().unwrap();
+    let clip = deep_clip();
+    write_files(source.path(), &[(&clip, b"deep clip")]);
+    let destination = TempDir::new().unwrap();
+    let destinations = [destination.path().to_path_buf()];
+    let options = CopyOptions {
+        verify: Some(HashOptions::default()),
+        ..Default::default()
+    };
+
+    let summary = completed(copy(source.path(), &destinations, options).await.unwrap());
+
+    let copy = destination.path().join(&clip);
+    assert!(copy.as_os_str().len() > 260);
+    assert_eq!(summary.files.len(), 1);
+    assert_eq!(summary.files[0].path, PathBuf::from(&clip));
+    assert_eq!(summary.report.unwrap().count_errors(), 0);
+    assert_eq!(std::fs::read(copy).unwrap(), b"deep clip");
+}
+
+#[cfg(windows)]
+mod windows {
+    use librecard_core::backend::long_path;
+    use std::path::Path;
+
+    #[test]
+    fn drive_paths_become_extended_length() {
+        assert_eq!(
+            long_path(Path::new(r"D:\Footage\DCIM\100CANON\IMG_0001.CR3")),
+            Path::new(r"\\?\D:\Footage\DCIM\100CANON\IMG_0001.CR3")
+        );
+    }
+
+    #[test]
+    fn extended_length_paths_are_normalized_first() {
+        assert_eq!(
+            long_path(Path::new(r"D:\Footage/DCIM\.\OLD\..\100CANON\IMG_0001.CR3")),
+            Path::new(r"\\?\D:\Footage\DCIM\100CANON\IMG_0001.CR3")
+        );
+    }
+
+    #[test]
+    fn relative_and_verbatim_paths_are_left_alone() {
+        for path in [
+            r"DCIM\100CANON",
+            r"D:DCIM\100CANON",
+            r"\\?\D:\Footage",
+            r"\\.\PhysicalDrive1",
+        ] {
+            assert_eq!(long_path(Path::new(path)), Path::new(path), "{path}");
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Lining up two manifests by path and telling what differs.
+
+use librecard_core::backend::{
+    Manifest, ManifestEntry, diff_manifests, normalize_manifest_path, read_manifest,
+};
+use tempfile::TempDir;
+
+const HASH_A: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+const HASH_B: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+const HASH_C: &str = "2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae";
+
+fn manifest(entries: &[(&str, &str, Option<u64>)]) -> Manifest {
+    Manifest {
+        algorithm: Some("sha256"),
+        entries: entries
+            .iter()
+            .map(|(path, hash, size)| {
+                let entry = ManifestEntry {
+                    hash: hash.to_string(),
+                    size: *size,
+                };
+                (normalize_manifest_path(path), entry)
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn paths_normalize_to_one_form() {
+    for (written, normalized) in [
+        ("DCIM/100CANON/IMG_0001.CR3", "DCIM/100CANON/IMG_0001.CR3"),
+        ("./DCIM/100CANON/IMG_0001.CR3", "DCIM/100CANON/IMG_0001.CR3"),
+        ("DCIM\\100CANON\\IMG_0001.CR3", "DCIM/100CANON/IMG_0001.CR3"),
+        (
+            "/DCIM//100CANON/./IMG_0001.CR3",
+            "DCIM/100CANON/IMG_0001.CR3",
+        ),
+        ("caf\u{e9}.MOV", "caf\u{e9}.MOV"),
+        ("cafe\u{301}.MOV", "caf\u{e9}.MOV"),
+    ] {
+        assert_eq!(normalize_manifest_path(written), normalized, "{written:?}");
+    }
+}
+
+#[test]
+fn differences_are_sorted_out_by_kind() {
+    let first = manifest(&[
+        ("DCIM/A.MOV", HASH_A, Some(10)),
+        ("DCIM/B.MOV", HASH_B, Some(20)),
+        ("DCIM/C.MOV", HASH_C, Some(30)),
+        ("DCIM/ONLY_FIRST.MOV", HASH_A, None),
+    ]);
+    let second = manifest(&[
+        ("DCIM\\A.MOV", HASH_A, Some(10)),
+        ("./DCIM/B.MOV", HASH_C, Some(20)),
+        ("DCIM/C.MOV", HASH_C, Some(31)),
+        ("DCIM/ONLY_SECOND.MOV", HASH_B, None),
+    ]);
+
+    let diff = diff_manifests(&first, &second);
+
+    assert_eq!(diff.matching, 1);
+    assert_eq!(diff.only_in_first, ["DCIM/ONLY_FIRST.MOV"]);
+    assert_eq!(diff.only_in_second, ["DCIM/ONLY_SECOND.MOV"]);
+    assert_eq!(diff.hash_mismatches, ["DCIM/B.MOV"]);
+    assert_eq!(diff.size_mismatches, [("DCIM/C.MOV".to_owned(), 30, 31)]);
+    assert!(!diff.algorithms_differ);
+    assert!(!diff.is_empty());
+}
+
+#[test]
+fn manifests_of_other_algorithms_only_compare_paths_and_sizes() {
+    let first = manifest(&[("A.MOV", HASH_A, Some(10))]);
+    let mut second = manifest(&[("A.MOV", "0123456789abcdef", Some(10))]);
+    second.algorithm = Some("xxh64");
+
+    let diff = diff_manifests(&first, &second);
+
+    assert!(diff.algorithms_differ);
+    assert!(diff.hash_mismatches.is_empty());
+    assert_eq!(diff.matching, 1);
+}
+
+#[test]
+fn checksum_lists_of_different_styles_line_up() {
+    let lists = TempDir::new().unwrap();
+    let gnu = lists.path().join("SHA256SUMS");
+    std::fs::write(
+        &gnu,
+        format!("{HASH_A}  ./DCIM/A.MOV\n{HASH_B} *DCIM/cafe\u{301}.MOV\n"),
+    )
+    .unwrap();
+    let bsd = lists.path().join("CHECKSUM.SHA256");
+    std::fs::write(
+        &bsd,
+        format!("SHA256 (DCIM/A.MOV) = {HASH_A}\nSHA256 (DCIM/caf\u{e9}.MOV) = {HASH_B}\n"),
+    )
+    .unwrap();
+
+    let diff = diff_manifests(&read_manifest(&gnu).unwrap(), &read_manifest(&bsd).unwrap());
+
+    assert!(diff.is_empty(), "{diff:?}");
+    assert_eq!(diff.matching, 2);
+}
@@ -0,0 +1,137 @@
+//! Modification windows are typed in local time and kept as points in time, which reports write
+//! in UTC. The tests run in Central European Time, daylight saving included.
+
+#![cfg(unix)]
+
+mod common;
+
+use common::{completed, copy_with, write_files};
+use librecard_core::backend::{
+    CopyOptions, ModifiedWindow, SourcePlan, TimestampFormat, local_utc_offset, parse_local_time,
+    utc_date,
+};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Once;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+/// Central European Time as a POSIX rule, so no time zone database is needed.
+const ZONE: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+
+/// Puts the process in [`ZONE`] before any test reads the local time.
+fn in_zone() {
+    static ZONE_SET: Once = Once::new();
+    // SAFETY: every test calls this first, and the Once keeps the others waiting until it is set
+    ZONE_SET.call_once(|| unsafe { std::env::set_var("TZ", ZONE) });
+}
+
+fn utc(text: &str) -> String {
+    TimestampFormat::default().format(parse_local_time(text).unwrap())
+}
+
+#[test]
+fn local_times_are_read_with_the_offset_of_their_date() {
+    in_zone();
+    assert_eq!(utc("2024-01-15 12:00"), "2024-01-15 11:00:00 UTC");
+    assert_eq!(utc("2024-07-15 12:00"), "2024-07-15 10:00:00 UTC");
+    assert_eq!(utc("2024-07-15T12:00:30"), "2024-07-15 10:00:30 UTC");
+    assert_eq!(utc("2024-04-12"), "2024-04-11 22:00:00 UTC");
+    assert_eq!(utc("  2024-12-31 23:59 "), "2024-12-31 22:59:00 UTC");
+}
+
+#[test]
+fn times_next_to_a_change_of_offset_take_the_right_one() {
+    in_zone();
+    // Clocks go from 02:00 to 03:00 on the last Sunday of March, and back on that of October
+    assert_eq!(utc("2024-03-31 01:59"), "2024-03-31 00:59:00 UTC");
+    assert_eq!(utc("2024-03-31 03:00"), "2024-03-31 01:00:00 UTC");
+    assert_eq!(utc("2024-10-27 01:59"), "2024-10-26 23:59:00 UTC");
+    assert_eq!(utc("2024-10-27 03:00"), "2024-10-27 02:00:00 UTC");
+
+    let spring = parse_local_time("2024-03-31 03:00").unwrap();
+    assert_eq!(local_utc_offset(spring), 2 * 3600);
+    assert_eq!(local_utc_offset(spring - Duration::from_secs(1)), 3600);
+}
+
+#[test]
+fn malformed_times_are_refused() {
+    in_zone();
+    for text in [
+        "",
+        "yesterday",
+        "2024-02-30",
+        "2023-02-29",
+        "2024-13-01",
+        "2024-00-10",
+        "2024-04-12 24:00",
+        "2024-04-12 18:60",
+        "2024-04-12 18",
+        "1969-12-31",
+    ] {
+        assert!(parse_local_time(text).is_err(), "{text:?}");
+    }
+    assert!(parse_local_time("2024-02-29").is_ok());
+}
+
+#[test]
+fn the_window_includes_its_start_and_excludes_its_end() {
+    in_zone();
+    let window = ModifiedWindow {
+        after: Some(parse_local_time("2024-04-12 18:00").unwrap()),
+        before: Some(parse_local_time("2024-04-13").unwrap()),
+    };
+    let start = window.after.unwrap();
+    let end = window.before.unwrap();
+
+    assert_eq!(utc_date(start), ((2024, 4, 12), 16 * 3600));
+    assert!(window.contains(Some(start)));
+    assert!(!window.contains(Some(start - Duration::from_secs(1))));
+    assert!(window.contains(Some(end - Duration::from_secs(1))));
+    assert!(!window.contains(Some(end)));
+    assert!(window.contains(None));
+    assert!(ModifiedWindow::default().is_unbounded());
+}
+
+#[tokio::test]
+async fn a_copy_takes_only_the_files_modified_in_the_window() {
+    in_zone();
+    let source = TempDir::new().unwrap();
+    write_files(
+        source.path(),
+        &[
+            ("A001.MOV", b"afternoon"),
+            ("A002.MOV", b"evening"),
+            ("A003.MOV", b"next day"),
+        ],
+    );
+    // 17:30, 18:30 and the next day's 09:00, local time
+    let noon_utc = SystemTime::UNIX_EPOCH + Duration::from_secs(1_712_923_200);
+    for (name, after_noon) in [("A001.MOV", 3.5), ("A002.MOV", 4.5), ("A003.MOV", 19.0)] {
+        let time = noon_utc + Duration::from_secs_f64(after_noon * 3600.0);
+        let file = File::options()
+            .write(true)
+            .open(source.path().join(name))
+            .unwrap();
+        file.set_modified(time).unwrap();
+    }
+    let plan = SourcePlan {
+        modified: ModifiedWindow {
+            after: Some(parse_local_time("2024-04-12 18:00").unwrap()),
+            before: Some(parse_local_time("2024-04-13").unwrap()),
+        },
+        ..Default::default()
+    };
+    let destination = TempDir::new().unwrap();
+    let destinations = [destination.path().to_path_buf()];
+
+    let summary = completed(
+        copy_with(source.path(), &destinations, plan, CopyOptions::default())
+            .await
+            .unwrap(),
+    );
+
+    let copied: Vec<&Path> = summary.files.iter().map(|file| &*file.path).collect();
+    assert_eq!(copied, [Path::new("A002.MOV")]);
+    assert!(!destination.path().join("A001.MOV").exists());
+}
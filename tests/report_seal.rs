@@ -0,0 +1,86 @@
+//! Exported reports carry a digest that importing them checks.
+
+mod common;
+
+use common::{completed, copy, write_files};
+use librecard_core::backend::{
+    ChecksumReport, CopyOptions, HashAlgorithm, HashOptions, ManifestFormat, ReportIntegrity,
+    TimestampFormat, read_manifest,
+};
+use std::path::Path;
+use tempfile::TempDir;
+
+async fn verified_report(source: &Path) -> ChecksumReport {
+    write_files(
+        source,
+        &[
+            ("A001.MOV", b"first clip"),
+            ("SUB/A002.MOV", b"second clip"),
+        ],
+    );
+    let destination = TempDir::new().unwrap();
+    let options = CopyOptions {
+        verify: Some(HashOptions {
+            algorithm: HashAlgorithm::Sha256,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let destinations = [destination.path().to_path_buf()];
+    let summary = completed(copy(source, &destinations, options).await.unwrap());
+    summary.report.expect("The copy was verified")
+}
+
+/// Flips the first hex digit of the first hash in the file, leaving its length alone.
+fn tamper(path: &Path, hash: &str) {
+    let content = std::fs::read_to_string(path).unwrap();
+    let at = content
+        .to_ascii_lowercase()
+        .find(hash)
+        .expect("The report holds the hash");
+    let mut bytes = content.into_bytes();
+    bytes[at] = if bytes[at] == b'0' { b'1' } else { b'0' };
+    std::fs::write(path, bytes).unwrap();
+}
+
+#[tokio::test]
+async fn sealed_reports_read_back_intact_and_tell_when_altered() {
+    let source = TempDir::new().unwrap();
+    let report = verified_report(source.path()).await;
+    let exports = TempDir::new().unwrap();
+
+    for (format, name) in [
+        (ManifestFormat::Csv, "report.csv"),
+        (ManifestFormat::Gnu, "SHA256SUMS"),
+        (ManifestFormat::Bsd, "CHECKSUM.SHA256"),
+    ] {
+        let path = exports.path().join(name);
+        report
+            .export(&path, format, &TimestampFormat::default())
+            .unwrap();
+
+        let manifest = read_manifest(&path).unwrap();
+        assert_eq!(manifest.integrity, ReportIntegrity::Intact, "{format:?}");
+        assert_eq!(manifest.entries.len(), 2, "{format:?}");
+
+        let hash = manifest.entries["A001.MOV"].hash.clone();
+        tamper(&path, &hash);
+        let manifest = read_manifest(&path).unwrap();
+        assert_eq!(manifest.integrity, ReportIntegrity::Altered, "{format:?}");
+    }
+}
+
+#[test]
+fn a_list_from_another_tool_is_unsealed() {
+    let exports = TempDir::new().unwrap();
+    let path = exports.path().join("SHA256SUMS");
+    std::fs::write(
+        &path,
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  EMPTY\n",
+    )
+    .unwrap();
+
+    let manifest = read_manifest(&path).unwrap();
+    assert_eq!(manifest.integrity, ReportIntegrity::Unsealed);
+    assert_eq!(manifest.entries.len(), 1);
+}
@@ -0,0 +1,122 @@
+//! Picking out the files that copied far slower than the rest of a job.
+
+use librecard_core::backend::{CopiedFile, slow_files, slow_transfers};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const MB: u64 = 1024 * 1024;
+
+fn copied(path: &str, size: u64, transfer_time: Option<Duration>) -> CopiedFile {
+    CopiedFile {
+        path: PathBuf::from(path),
+        size,
+        changed_during_copy: false,
+        unreliable_read: false,
+        kept_existing: 0,
+        duplicate_of: None,
+        skipped: None,
+        linked_to: None,
+        reconnected: false,
+        salvaged: None,
+        transfer_time,
+    }
+}
+
+#[test]
+fn a_transfer_far_below_the_median_is_slow() {
+    let second = Duration::from_secs(1);
+    let transfers = [
+        (100, second),
+        (90, second),
+        (110, second),
+        (100, second),
+        (20, second),
+    ];
+
+    let (median, slow) = slow_transfers(&transfers, 4.0).unwrap();
+
+    assert_eq!(median, 100.0);
+    assert_eq!(slow, [4]);
+}
+
+#[test]
+fn a_transfer_just_within_the_factor_is_not_slow() {
+    let second = Duration::from_secs(1);
+    let transfers = [(100, second); 4]
+        .into_iter()
+        .chain([(25, second)])
+        .collect::<Vec<_>>();
+
+    let (_, slow) = slow_transfers(&transfers, 4.0).unwrap();
+
+    assert!(slow.is_empty());
+}
+
+#[test]
+fn the_median_of_an_even_count_is_the_mean_of_the_middle_two() {
+    let second = Duration::from_secs(1);
+    let transfers = [
+        (10, second),
+        (100, second),
+        (200, second),
+        (300, second),
+        (400, second),
+        (500, second),
+    ];
+
+    let (median, slow) = slow_transfers(&transfers, 10.0).unwrap();
+
+    assert_eq!(median, 250.0);
+    assert_eq!(slow, [0]);
+}
+
+#[test]
+fn instant_transfers_count_towards_the_median_but_are_never_slow() {
+    let transfers = [
+        (100, Duration::ZERO),
+        (100, Duration::ZERO),
+        (100, Duration::ZERO),
+        (100, Duration::from_secs(1)),
+        (100, Duration::from_secs(100)),
+    ];
+
+    let (median, slow) = slow_transfers(&transfers, 4.0).unwrap();
+
+    assert!(median.is_infinite());
+    assert_eq!(slow, [3, 4]);
+}
+
+#[test]
+fn too_few_transfers_give_no_verdict() {
+    let transfers = [(100, Duration::from_secs(1)); 4];
+    assert_eq!(slow_transfers(&transfers, 4.0), None);
+}
+
+#[test]
+fn small_and_untimed_files_are_left_out() {
+    let second = Duration::from_secs(1);
+    let mut files: Vec<CopiedFile> = (0..5)
+        .map(|index| copied(&format!("A00{index}.MOV"), 80 * MB, Some(second)))
+        .collect();
+    files.push(copied("A005.MOV", 80 * MB, Some(Duration::from_secs(10))));
+    files.push(copied("THUMB.JPG", MB, Some(Duration::from_secs(10))));
+    files.push(copied("LINKED.MOV", 80 * MB, None));
+
+    let slow = slow_files(&files, 4.0);
+
+    assert_eq!(slow.len(), 1);
+    assert_eq!(slow[0].path, Path::new("A005.MOV"));
+    assert_eq!(slow[0].bytes_per_sec, 8 * MB);
+    assert_eq!(slow[0].median_bytes_per_sec, 80 * MB);
+}
+
+#[test]
+fn a_job_of_small_files_flags_nothing() {
+    let files: Vec<CopiedFile> = (0..10)
+        .map(|index| {
+            let time = Duration::from_millis(1 + 100 * index);
+            copied(&format!("IMG_{index}.JPG"), MB, Some(time))
+        })
+        .collect();
+    assert!(slow_files(&files, 4.0).is_empty());
+}
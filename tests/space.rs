@@ -0,0 +1,20 @@
+//! Checks of the space a copy is expected to need at its destinations.
+
+use librecard_core::backend::bytes_in_place;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+#[test]
+fn only_files_the_copy_keeps_are_in_place() {
+    let destination = TempDir::new().unwrap();
+    std::fs::write(destination.path().join("KEPT.MOV"), [0; 100]).unwrap();
+    std::fs::write(destination.path().join("SHORT.MOV"), [0; 40]).unwrap();
+    let files = vec![
+        (PathBuf::from("KEPT.MOV"), 100),
+        (PathBuf::from("SHORT.MOV"), 100),
+        (PathBuf::from("NEW.MOV"), 100),
+    ];
+
+    assert_eq!(bytes_in_place(destination.path(), &files, true), 100);
+    assert_eq!(bytes_in_place(destination.path(), &files, false), 0);
+}
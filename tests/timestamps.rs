@@ -0,0 +1,88 @@
+//! Copies keep the modification times of the files and folders they come from.
+
+mod common;
+
+use common::{completed, copy, paths, write_files};
+use librecard_core::backend::CopyOptions;
+use std::fs::File;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+const FILES: [&str; 3] = [
+    "DCIM/100CANON/IMG_0001.CR3",
+    "DCIM/100CANON/IMG_0002.CR3",
+    "PRIVATE/M4ROOT/CLIP/C0001.MP4",
+];
+
+const FOLDERS: [&str; 5] = [
+    "DCIM",
+    "DCIM/100CANON",
+    "PRIVATE",
+    "PRIVATE/M4ROOT",
+    "PRIVATE/M4ROOT/CLIP",
+];
+
+fn modified(path: &Path) -> SystemTime {
+    std::fs::metadata(path).unwrap().modified().unwrap()
+}
+
+/// Writes the card with every file and folder dated a different day in 2020, folders last so
+/// writing their files doesn't move their times.
+fn dated_source(root: &Path) {
+    let files: Vec<(&str, &[u8])> = FILES.iter().map(|file| (*file, &b"shot"[..])).collect();
+    write_files(root, &files);
+    let day = Duration::from_secs(24 * 60 * 60);
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_577_836_800);
+    for (index, path) in FILES.iter().chain(&FOLDERS).enumerate() {
+        let time = start + day * index as u32;
+        File::open(root.join(path))
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn files_and_folders_keep_their_modified_times() {
+    let source = TempDir::new().unwrap();
+    dated_source(source.path());
+    let dirs = [TempDir::new().unwrap(), TempDir::new().unwrap()];
+    let destinations = paths(&dirs);
+    let options = CopyOptions {
+        preserve_timestamps: true,
+        ..Default::default()
+    };
+
+    completed(copy(source.path(), &destinations, options).await.unwrap());
+
+    for destination in &destinations {
+        for path in FILES.iter().chain(&FOLDERS) {
+            assert_eq!(
+                modified(&destination.join(path)),
+                modified(&source.path().join(path)),
+                "{path}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn times_are_left_to_the_copy_by_default() {
+    let source = TempDir::new().unwrap();
+    dated_source(source.path());
+    let destination = TempDir::new().unwrap();
+    let destinations = [destination.path().to_path_buf()];
+
+    completed(
+        copy(source.path(), &destinations, CopyOptions::default())
+            .await
+            .unwrap(),
+    );
+
+    let file = FILES[0];
+    assert_ne!(
+        modified(&destination.path().join(file)),
+        modified(&source.path().join(file))
+    );
+}
@@ -0,0 +1,43 @@
+//! Destinations typed as shares (`\\server\share\...`), with no drive letter mapped.
+
+#![cfg(windows)]
+
+use librecard_core::backend::{is_network_path, long_path};
+use std::path::Path;
+
+#[test]
+fn share_paths_are_network_paths() {
+    for path in [
+        r"\\nas\archive",
+        r"\\nas\archive\2024\Shoot",
+        r"\\?\UNC\nas\archive\2024",
+    ] {
+        assert!(is_network_path(Path::new(path)), "{path}");
+    }
+}
+
+#[test]
+fn share_paths_become_extended_length() {
+    assert_eq!(
+        long_path(Path::new(r"\\nas\archive\2024\Shoot\A001.MOV")),
+        Path::new(r"\\?\UNC\nas\archive\2024\Shoot\A001.MOV")
+    );
+    assert_eq!(
+        long_path(Path::new(r"\\nas\archive")),
+        Path::new(r"\\?\UNC\nas\archive\")
+    );
+}
+
+#[test]
+fn share_paths_are_normalized_before_extending() {
+    assert_eq!(
+        long_path(Path::new(r"\\nas\archive\2024/Old\..\.\Shoot")),
+        Path::new(r"\\?\UNC\nas\archive\2024\Shoot")
+    );
+}
+
+#[test]
+fn extended_share_paths_are_left_alone() {
+    let path = Path::new(r"\\?\UNC\nas\archive\2024");
+    assert_eq!(long_path(path), path);
+}
@@ -0,0 +1,94 @@
+//! Names with accents, composed (NFC) or decomposed (NFD) as macOS writes them, through copy,
+//! verification and manifest import.
+
+mod common;
+
+use common::{completed, copy};
+use librecard_core::backend::{
+    CopyOptions, HashAlgorithm, HashOptions, ManifestFormat, Progress, TimestampFormat,
+    read_manifest, verify_manifest_folder,
+};
+use tempfile::TempDir;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+const COMPOSED: &str = "R\u{e9}sum\u{e9}/caf\u{e9}.MOV";
+const DECOMPOSED: &str = "Re\u{301}sume\u{301}/cafe\u{301}.MOV";
+
+fn sha256() -> HashOptions {
+    HashOptions {
+        algorithm: HashAlgorithm::Sha256,
+        ..Default::default()
+    }
+}
+
+/// Copies a card holding `name`, verifies it, exports the report to the destination, and checks
+/// the destination against it once as copied and once with the file renamed to `renamed`, as
+/// another file system may store it.
+async fn round_trip(name: &str, renamed: &str) {
+    let source = TempDir::new().unwrap();
+    let file = source.path().join(name);
+    std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+    std::fs::write(&file, b"accented clip").unwrap();
+    let destination = TempDir::new().unwrap();
+    let destinations = [destination.path().to_path_buf()];
+    let options = CopyOptions {
+        verify: Some(sha256()),
+        ..Default::default()
+    };
+
+    let summary = completed(copy(source.path(), &destinations, options).await.unwrap());
+    let report = summary.report.expect("The copy was verified");
+    assert_eq!(report.count_errors(), 0);
+    assert_eq!(
+        std::fs::read(destination.path().join(name)).unwrap(),
+        b"accented clip"
+    );
+
+    let manifest_path = destination.path().join("report.csv");
+    report
+        .export(
+            &manifest_path,
+            ManifestFormat::Csv,
+            &TimestampFormat::default(),
+        )
+        .unwrap();
+    let manifest = read_manifest(&manifest_path).unwrap();
+    let keys: Vec<&String> = manifest.entries.keys().collect();
+    assert_eq!(keys, [COMPOSED]);
+
+    for step in [name, renamed] {
+        if step != name {
+            std::fs::create_dir_all(destination.path().join(renamed).parent().unwrap()).unwrap();
+            std::fs::rename(
+                destination.path().join(name),
+                destination.path().join(renamed),
+            )
+            .unwrap();
+        }
+        let (tx, _rx) = watch::channel(Progress::default());
+        let diff = verify_manifest_folder(
+            &manifest_path,
+            &manifest,
+            None,
+            sha256(),
+            tx,
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+        let diff = completed(diff);
+        assert!(diff.is_empty(), "{step:?}: {diff:?}");
+        assert_eq!(diff.matching, 1);
+    }
+}
+
+#[tokio::test]
+async fn composed_names_round_trip() {
+    round_trip(COMPOSED, DECOMPOSED).await;
+}
+
+#[tokio::test]
+async fn decomposed_names_round_trip() {
+    round_trip(DECOMPOSED, COMPOSED).await;
+}